@@ -0,0 +1,289 @@
+//! Backfill of session history from Claude Code transcripts.
+//!
+//! Claude Code keeps a JSONL transcript of every session under
+//! `~/.claude/projects/<project>/`. Each line carries the `sessionId`, a timestamp
+//! and the working directory; assistant lines carry the model and token usage of
+//! the response. Lines are grouped by `sessionId` across files, since subagent
+//! and resumed transcripts repeat lines of their parent session, and responses
+//! are counted once by message id.
+
+use crate::database::{ImportedSession, UsageEntry};
+use crate::models::ModelType;
+use chrono::{DateTime, Local, NaiveDate, TimeDelta, Utc};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+/// The fields of a transcript line that sessions are rebuilt from
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TranscriptLine {
+    #[serde(default)]
+    session_id: Option<String>,
+    #[serde(default)]
+    timestamp: Option<String>,
+    #[serde(default)]
+    cwd: Option<String>,
+}
+
+/// Where Claude Code keeps transcripts: `$CLAUDE_CONFIG_DIR/projects`, or
+/// `~/.claude/projects` by default
+pub fn projects_dir() -> Option<PathBuf> {
+    match std::env::var_os("CLAUDE_CONFIG_DIR") {
+        Some(dir) if !dir.is_empty() => Some(PathBuf::from(dir).join("projects")),
+        _ => dirs::home_dir().map(|home| home.join(".claude").join("projects")),
+    }
+}
+
+/// Every `.jsonl` file below `root`, sorted by path
+///
+/// Symlinked directories are not followed.
+pub fn find_transcripts(root: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut dirs = vec![root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            let path = entry.path();
+            if file_type.is_dir() {
+                dirs.push(path);
+            } else if file_type.is_file() && path.extension().is_some_and(|e| e == "jsonl") {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+/// Everything read for one session so far
+#[derive(Default)]
+struct SessionBuilder {
+    timestamps: Vec<DateTime<Utc>>,
+    workspace_dir: Option<String>,
+    /// Project directory of the first transcript, for sessions without a `cwd`
+    project_dir: Option<String>,
+    usage: Vec<UsageEntry>,
+    message_ids: HashSet<String>,
+}
+
+impl SessionBuilder {
+    fn build(mut self, session_id: String, inactivity: TimeDelta) -> Option<ImportedSession> {
+        // Sessions without a response have no usage to import
+        if self.usage.is_empty() {
+            return None;
+        }
+        self.timestamps.sort();
+        self.usage.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+        let start = *self.timestamps.first()?;
+        let end = *self.timestamps.last()?;
+        let active = self
+            .timestamps
+            .windows(2)
+            .map(|pair| pair[1] - pair[0])
+            .filter(|gap| *gap <= inactivity)
+            .map(|gap| gap.num_seconds().max(0) as u64)
+            .sum();
+
+        let model_id = self
+            .usage
+            .iter()
+            .rev()
+            .filter_map(|e| e.model.as_deref())
+            .find(|m| *m != "<synthetic>")
+            .map(str::to_string);
+        let model_name = model_id
+            .as_deref()
+            .map(|id| match ModelType::from_name(id) {
+                ModelType::Unknown => id.to_string(),
+                model => model.canonical_name(),
+            });
+
+        Some(ImportedSession {
+            session_id,
+            start_time: start.with_timezone(&Local).to_rfc3339(),
+            last_updated: end.with_timezone(&Local).to_rfc3339(),
+            model_name,
+            model_id,
+            workspace_dir: self.workspace_dir.or(self.project_dir),
+            active_time_seconds: active,
+            usage: self.usage,
+        })
+    }
+}
+
+/// Rebuild the sessions in `files`, oldest first
+///
+/// Gaps between entries longer than `inactivity` do not count as active time.
+/// Sessions whose last entry is before `since` are left out.
+pub fn read_sessions(
+    files: &[PathBuf],
+    inactivity: TimeDelta,
+    since: Option<NaiveDate>,
+) -> Vec<ImportedSession> {
+    let mut builders: BTreeMap<String, SessionBuilder> = BTreeMap::new();
+    for path in files {
+        let Ok(file) = File::open(path) else {
+            continue;
+        };
+        // Transcripts are named after their session
+        let file_session = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        // Project directories are encoded absolute paths; subagent transcripts sit deeper
+        let project_dir = path
+            .parent()
+            .and_then(|p| p.file_name())
+            .map(|name| name.to_string_lossy())
+            .filter(|name| name.starts_with('-'))
+            .map(|name| decode_project_dir(&name));
+
+        for line in BufReader::new(file).lines().map_while(|l| l.ok()) {
+            let Ok(record) = serde_json::from_str::<TranscriptLine>(&line) else {
+                continue;
+            };
+            let Some(at) = record
+                .timestamp
+                .as_deref()
+                .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            else {
+                continue;
+            };
+            let session_id = record
+                .session_id
+                .filter(|id| !id.is_empty())
+                .unwrap_or_else(|| file_session.clone());
+            let builder = builders.entry(session_id.clone()).or_default();
+            builder.timestamps.push(at.with_timezone(&Utc));
+            if builder.workspace_dir.is_none() {
+                builder.workspace_dir = record.cwd.filter(|cwd| !cwd.is_empty());
+            }
+            if builder.project_dir.is_none() {
+                builder.project_dir = project_dir.clone();
+            }
+            if let Some(entry) = crate::blocks::entry_from_line(&line, &session_id) {
+                if builder.message_ids.insert(entry.message_id.clone()) {
+                    builder.usage.push(entry);
+                }
+            }
+        }
+    }
+
+    let mut sessions: Vec<_> = builders
+        .into_iter()
+        .filter_map(|(id, builder)| builder.build(id, inactivity))
+        .filter(|session| {
+            since.is_none_or(|since| {
+                DateTime::parse_from_rfc3339(&session.last_updated)
+                    .is_ok_and(|end| end.date_naive() >= since)
+            })
+        })
+        .collect();
+    sessions.sort_by(|a, b| a.start_time.cmp(&b.start_time));
+    sessions
+}
+
+/// Best-effort path of a project directory name: Claude Code replaces the
+/// separators (and dots) of the path with dashes, so dashes in names are lost
+fn decode_project_dir(name: &str) -> String {
+    name.replace('-', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    fn write_lines(path: &Path, lines: &[String]) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let mut file = File::create(path).unwrap();
+        for line in lines {
+            writeln!(file, "{}", line).unwrap();
+        }
+    }
+
+    fn user(session: &str, at: &str) -> String {
+        format!(
+            r#"{{"type":"user","sessionId":"{}","timestamp":"{}","cwd":"/src/app","message":{{"role":"user","content":"hi"}}}}"#,
+            session, at
+        )
+    }
+
+    fn assistant(session: &str, at: &str, id: &str, output: u32) -> String {
+        format!(
+            r#"{{"type":"assistant","sessionId":"{}","timestamp":"{}","cwd":"/src/app","message":{{"id":"{}","role":"assistant","model":"claude-sonnet-4-5-20250929","usage":{{"input_tokens":10,"output_tokens":{},"cache_read_input_tokens":100}}}}}}"#,
+            session, at, id, output
+        )
+    }
+
+    #[test]
+    fn test_read_sessions() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path().join("-src-app");
+        write_lines(
+            &project.join("s1.jsonl"),
+            &[
+                user("s1", "2026-10-01T09:00:00.000Z"),
+                assistant("s1", "2026-10-01T09:00:30.000Z", "msg_1", 50),
+                // Claude Code repeats the usage on every line of a response
+                assistant("s1", "2026-10-01T09:00:31.000Z", "msg_1", 50),
+                // Two hours idle: not active time
+                user("s1", "2026-10-01T11:00:31.000Z"),
+                assistant("s1", "2026-10-01T11:01:01.000Z", "msg_2", 70),
+                "not json".to_string(),
+            ],
+        );
+        // A subagent transcript belongs to its parent session
+        write_lines(
+            &project.join("s1").join("subagents").join("agent-1.jsonl"),
+            &[assistant("s1", "2026-10-01T09:30:00.000Z", "msg_3", 30)],
+        );
+        // A session without responses is skipped
+        write_lines(
+            &project.join("s2.jsonl"),
+            &[user("s2", "2026-10-02T09:00:00.000Z")],
+        );
+
+        let files = find_transcripts(temp_dir.path());
+        assert_eq!(files.len(), 3);
+
+        let sessions = read_sessions(&files, TimeDelta::minutes(60), None);
+        assert_eq!(sessions.len(), 1);
+        let session = &sessions[0];
+        assert_eq!(session.session_id, "s1");
+        assert_eq!(session.usage.len(), 3);
+        assert_eq!(session.tokens(), 3 * 110 + 150);
+        assert!(session.cost() > 0.0);
+        assert_eq!(session.model_name.as_deref(), Some("Sonnet 4.5"));
+        assert_eq!(
+            session.model_id.as_deref(),
+            Some("claude-sonnet-4-5-20250929")
+        );
+        assert_eq!(session.workspace_dir.as_deref(), Some("/src/app"));
+        // 09:00:00-09:30:00 and 11:00:31-11:01:01; the gap between is idle
+        assert_eq!(session.active_time_seconds, 1800 + 30);
+        let start = DateTime::parse_from_rfc3339(&session.start_time).unwrap();
+        let end = DateTime::parse_from_rfc3339(&session.last_updated).unwrap();
+        assert_eq!((end - start).num_seconds(), 2 * 3600 + 61);
+
+        let since = NaiveDate::from_ymd_opt(2026, 10, 5);
+        assert!(read_sessions(&files, TimeDelta::minutes(60), since).is_empty());
+    }
+
+    #[test]
+    fn test_decode_project_dir() {
+        assert_eq!(
+            decode_project_dir("-home-user-src-app"),
+            "/home/user/src/app"
+        );
+    }
+}
@@ -0,0 +1,564 @@
+//! Rolling 5-hour usage blocks.
+//!
+//! Claude subscriptions meter usage in 5-hour windows that start with the first
+//! message after the previous window has ended. Blocks are rebuilt from the token
+//! usage of every API response, recorded with its transcript timestamp in
+//! `usage_entries`: a block starts at the hour of the first response and covers
+//! every response in the following five hours; the first response after that
+//! starts the next block. Sessions recorded before usage entries existed count as
+//! one response at the time they were last active.
+//!
+//! The projection continues the block's average token rate since its first
+//! response until the reset, and compares the result with `[blocks] token_limit`,
+//! or the largest past block when no limit is configured.
+
+use crate::config::BlocksConfig;
+use crate::database::{SessionActivity, SqliteDatabase, UsageEntry};
+use crate::models::TranscriptEntry;
+use crate::pricing::{rates_for_model, TokenUsage};
+use crate::provider::{DataProvider, ProviderResult};
+use crate::utils::TranscriptTail;
+use chrono::{DateTime, DurationRound, Local, TimeDelta, Utc};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Length of a usage block.
+pub const BLOCK_HOURS: i64 = 5;
+
+/// Minimum time since a block's first response before its rate is projected.
+const MIN_PROJECTION_MINUTES: i64 = 5;
+
+/// Token usage at one point in time.
+#[derive(Debug, Clone)]
+struct Activity {
+    at: DateTime<Utc>,
+    session_id: String,
+    tokens: u64,
+    cost: f64,
+}
+
+/// One 5-hour usage window and the usage inside it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Block {
+    /// Hour of the first response
+    pub start: DateTime<Utc>,
+    pub first_activity: DateTime<Utc>,
+    pub last_activity: DateTime<Utc>,
+    pub tokens: u64,
+    pub cost: f64,
+    /// API responses (or sessions without usage entries) counted
+    pub responses: usize,
+    pub sessions: BTreeSet<String>,
+}
+
+/// Where an active block is heading if usage continues at its current rate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Projection {
+    /// Tokens at the reset
+    pub tokens: u64,
+    /// Cost at the reset
+    pub cost: f64,
+    /// Time until the limit is reached, when that happens before the reset
+    pub exhausts_in: Option<TimeDelta>,
+}
+
+impl Block {
+    fn starting(activity: Activity) -> Self {
+        let start = activity
+            .at
+            .duration_trunc(TimeDelta::hours(1))
+            .unwrap_or(activity.at);
+        let mut block = Block {
+            start,
+            first_activity: activity.at,
+            last_activity: activity.at,
+            tokens: 0,
+            cost: 0.0,
+            responses: 0,
+            sessions: BTreeSet::new(),
+        };
+        block.add(activity);
+        block
+    }
+
+    fn add(&mut self, activity: Activity) {
+        self.last_activity = self.last_activity.max(activity.at);
+        self.tokens += activity.tokens;
+        self.cost += activity.cost;
+        self.responses += 1;
+        self.sessions.insert(activity.session_id);
+    }
+
+    /// When the block resets.
+    pub fn end(&self) -> DateTime<Utc> {
+        self.start + TimeDelta::hours(BLOCK_HOURS)
+    }
+
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        now < self.end()
+    }
+
+    /// Time since the block started, up to its end.
+    pub fn elapsed(&self, now: DateTime<Utc>) -> TimeDelta {
+        (now.min(self.end()) - self.start).max(TimeDelta::zero())
+    }
+
+    /// Time until the block resets.
+    pub fn remaining(&self, now: DateTime<Utc>) -> TimeDelta {
+        (self.end() - now).max(TimeDelta::zero())
+    }
+
+    /// Usage at the reset if the average rate since the first response continues.
+    ///
+    /// Until the block has run for a few minutes the rate means little, so the
+    /// projection is the usage so far.
+    pub fn project(&self, now: DateTime<Utc>, limit: Option<u64>) -> Projection {
+        let active = now.min(self.end()) - self.first_activity;
+        let remaining_hours = self.remaining(now).num_seconds() as f64 / 3600.0;
+        let (token_rate, cost_rate) = if active >= TimeDelta::minutes(MIN_PROJECTION_MINUTES) {
+            let hours = active.num_seconds() as f64 / 3600.0;
+            (self.tokens as f64 / hours, self.cost / hours)
+        } else {
+            (0.0, 0.0)
+        };
+
+        let tokens = self.tokens + (token_rate * remaining_hours) as u64;
+        let exhausts_in = limit.filter(|limit| tokens >= *limit).map(|limit| {
+            if self.tokens >= limit {
+                TimeDelta::zero()
+            } else {
+                let hours = (limit - self.tokens) as f64 / token_rate;
+                TimeDelta::seconds((hours * 3600.0) as i64)
+            }
+        });
+
+        Projection {
+            tokens,
+            cost: self.cost + cost_rate * remaining_hours,
+            exhausts_in,
+        }
+    }
+}
+
+/// Groups activity into consecutive blocks.
+fn build_blocks(mut activity: Vec<Activity>) -> Vec<Block> {
+    activity.sort_by_key(|a| a.at);
+    let mut blocks: Vec<Block> = Vec::new();
+    for a in activity {
+        match blocks.last_mut() {
+            Some(block) if a.at < block.end() => block.add(a),
+            _ => blocks.push(Block::starting(a)),
+        }
+    }
+    blocks
+}
+
+fn parse_timestamp(timestamp: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// Formats a timestamp the way `usage_entries` stores it.
+fn storage_timestamp(at: DateTime<Utc>) -> String {
+    at.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string()
+}
+
+/// Builds the blocks with activity at or after `since`, oldest first.
+///
+/// A block already running at `since` is only counted from there.
+pub fn load_blocks(db: &SqliteDatabase, since: DateTime<Utc>) -> rusqlite::Result<Vec<Block>> {
+    let mut activity: Vec<Activity> = db
+        .get_usage_entries_since(&storage_timestamp(since))?
+        .into_iter()
+        .filter_map(|entry| {
+            Some(Activity {
+                at: parse_timestamp(&entry.timestamp)?,
+                tokens: entry.tokens(),
+                cost: entry.cost,
+                session_id: entry.session_id,
+            })
+        })
+        .collect();
+
+    // Session timestamps are local, so start a day early and filter precisely
+    let start_date = (since.with_timezone(&Local) - TimeDelta::days(1))
+        .format("%Y-%m-%d")
+        .to_string();
+    activity.extend(
+        db.get_session_activity_since(&start_date)?
+            .into_iter()
+            .filter(|session| !session.has_entries)
+            .filter_map(|session: SessionActivity| {
+                Some(Activity {
+                    at: parse_timestamp(&session.last_updated)?,
+                    tokens: session.tokens,
+                    cost: session.cost,
+                    session_id: session.session_id,
+                })
+            })
+            .filter(|a| a.at >= since),
+    );
+
+    Ok(build_blocks(activity))
+}
+
+/// The token limit of a block: the configured one, or the largest completed block.
+pub fn token_limit(blocks: &[Block], now: DateTime<Utc>, config: &BlocksConfig) -> Option<u64> {
+    config.token_limit.or_else(|| {
+        blocks
+            .iter()
+            .filter(|b| !b.is_active(now))
+            .map(|b| b.tokens)
+            .max()
+            .filter(|t| *t > 0)
+    })
+}
+
+/// Reads the usage of the responses among the last lines of a transcript.
+///
+/// Recording them with [`SqliteDatabase::record_usage_entries`] on every render keeps
+/// `usage_entries` complete without a cursor, since already recorded responses are
+/// ignored.
+pub fn transcript_entries(transcript: &TranscriptTail, session_id: &str) -> Vec<UsageEntry> {
+    let mut seen = HashSet::new();
+    transcript
+        .entries()
+        .iter()
+        .filter_map(|entry| usage_entry(entry, session_id))
+        .filter(|entry| seen.insert(entry.message_id.clone()))
+        .collect()
+}
+
+pub(crate) fn entry_from_line(line: &str, session_id: &str) -> Option<UsageEntry> {
+    usage_entry(
+        &serde_json::from_str::<TranscriptEntry>(line).ok()?,
+        session_id,
+    )
+}
+
+fn usage_entry(entry: &TranscriptEntry, session_id: &str) -> Option<UsageEntry> {
+    let message = &entry.message;
+    if message.role != "assistant" {
+        return None;
+    }
+    let usage = message.usage.as_ref()?;
+    let at = parse_timestamp(&entry.timestamp)?;
+    let timestamp = storage_timestamp(at);
+
+    let mut tokens = TokenUsage::default();
+    tokens.add(usage);
+    let cost = message
+        .model
+        .as_deref()
+        .and_then(rates_for_model)
+        .map_or(0.0, |rates| tokens.cost(&rates));
+
+    Some(UsageEntry {
+        message_id: message
+            .id
+            .clone()
+            .unwrap_or_else(|| format!("{}:{}", session_id, timestamp)),
+        session_id: session_id.to_string(),
+        timestamp,
+        model: message.model.clone(),
+        input_tokens: tokens.input,
+        output_tokens: tokens.output,
+        cache_write_tokens: tokens.cache_write_5m + tokens.cache_write_1h,
+        cache_read_tokens: tokens.cache_read,
+        cost,
+    })
+}
+
+/// Provider for the `{block_*}` variables of the active usage block.
+pub struct BlockProvider {
+    /// Path to the SQLite database (None if unavailable)
+    db_path: Option<PathBuf>,
+    config: BlocksConfig,
+}
+
+impl BlockProvider {
+    pub fn new(db_path: Option<PathBuf>, config: &BlocksConfig) -> Self {
+        Self {
+            db_path,
+            config: config.clone(),
+        }
+    }
+}
+
+impl DataProvider for BlockProvider {
+    fn name(&self) -> &str {
+        "blocks"
+    }
+
+    fn priority(&self) -> u32 {
+        50
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_millis(200)
+    }
+
+    fn is_available(&self) -> bool {
+        self.config.enabled
+    }
+
+    fn collect(&self) -> ProviderResult {
+        let mut vars: HashMap<String, String> = [
+            "block_elapsed",
+            "block_remaining",
+            "block_tokens",
+            "block_cost",
+            "block_projected_tokens",
+            "block_projected_cost",
+            "block_limit",
+            "block_exhausts",
+        ]
+        .into_iter()
+        .map(|key| (key.to_string(), String::new()))
+        .collect();
+
+        let Some(db) = self
+            .db_path
+            .as_deref()
+            .filter(|p| p.exists())
+            .and_then(|p| SqliteDatabase::new(p).ok())
+        else {
+            return Ok(vars);
+        };
+        let now = Utc::now();
+        let since = now - TimeDelta::days(self.config.history_days.max(1) as i64);
+        let Ok(blocks) = load_blocks(&db, since) else {
+            return Ok(vars);
+        };
+        let Some(block) = blocks.last().filter(|b| b.is_active(now)) else {
+            return Ok(vars);
+        };
+
+        let limit = token_limit(&blocks, now, &self.config);
+        let projection = block.project(now, limit);
+        vars.insert(
+            "block_elapsed".into(),
+            block.elapsed(now).num_seconds().to_string(),
+        );
+        vars.insert(
+            "block_remaining".into(),
+            block.remaining(now).num_seconds().to_string(),
+        );
+        vars.insert("block_tokens".into(), block.tokens.to_string());
+        vars.insert("block_cost".into(), format!("{:.2}", block.cost));
+        vars.insert(
+            "block_projected_tokens".into(),
+            projection.tokens.to_string(),
+        );
+        vars.insert(
+            "block_projected_cost".into(),
+            format!("{:.2}", projection.cost),
+        );
+        if let Some(limit) = limit {
+            vars.insert("block_limit".into(), limit.to_string());
+        }
+        if let Some(exhausts_in) = projection.exhausts_in {
+            vars.insert(
+                "block_exhausts".into(),
+                exhausts_in.num_seconds().to_string(),
+            );
+        }
+
+        Ok(vars)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        parse_timestamp(s).unwrap()
+    }
+
+    fn activity(s: &str, tokens: u64) -> Activity {
+        Activity {
+            at: at(s),
+            session_id: "s1".to_string(),
+            tokens,
+            cost: tokens as f64 / 1000.0,
+        }
+    }
+
+    #[test]
+    fn test_blocks_start_on_the_hour_and_last_five_hours() {
+        let blocks = build_blocks(vec![
+            activity("2026-03-02T13:59:00Z", 100),
+            activity("2026-03-02T09:41:00Z", 1000),
+            activity("2026-03-02T14:00:00Z", 10),
+            activity("2026-03-02T20:30:00Z", 5),
+        ]);
+
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0].start, at("2026-03-02T09:00:00Z"));
+        assert_eq!(blocks[0].end(), at("2026-03-02T14:00:00Z"));
+        assert_eq!(blocks[0].tokens, 1100);
+        assert_eq!(blocks[0].responses, 2);
+        // A response exactly at the reset starts the next block
+        assert_eq!(blocks[1].start, at("2026-03-02T14:00:00Z"));
+        assert_eq!(blocks[1].tokens, 10);
+        assert_eq!(blocks[2].start, at("2026-03-02T20:00:00Z"));
+    }
+
+    #[test]
+    fn test_block_elapsed_and_remaining() {
+        let block = build_blocks(vec![activity("2026-03-02T09:41:00Z", 10)]).remove(0);
+        let now = at("2026-03-02T11:15:00Z");
+
+        assert!(block.is_active(now));
+        assert_eq!(block.elapsed(now), TimeDelta::minutes(135));
+        assert_eq!(block.remaining(now), TimeDelta::minutes(165));
+
+        let later = at("2026-03-02T15:00:00Z");
+        assert!(!block.is_active(later));
+        assert_eq!(block.elapsed(later), TimeDelta::hours(5));
+        assert_eq!(block.remaining(later), TimeDelta::zero());
+    }
+
+    #[test]
+    fn test_block_projection() {
+        // 1M tokens in the first hour, four hours left
+        let block = build_blocks(vec![
+            activity("2026-03-02T09:00:00Z", 400_000),
+            activity("2026-03-02T09:50:00Z", 600_000),
+        ])
+        .remove(0);
+        let now = at("2026-03-02T10:00:00Z");
+
+        let projection = block.project(now, None);
+        assert_eq!(projection.tokens, 5_000_000);
+        assert!((projection.cost - 5000.0).abs() < 1e-6);
+        assert_eq!(projection.exhausts_in, None);
+
+        // Within the limit: no exhaustion
+        assert_eq!(block.project(now, Some(6_000_000)).exhausts_in, None);
+
+        // 3M limit reached two hours from now
+        assert_eq!(
+            block.project(now, Some(3_000_000)).exhausts_in,
+            Some(TimeDelta::hours(2))
+        );
+
+        // Already over the limit
+        assert_eq!(
+            block.project(now, Some(500_000)).exhausts_in,
+            Some(TimeDelta::zero())
+        );
+    }
+
+    #[test]
+    fn test_block_projection_needs_some_history() {
+        let block = build_blocks(vec![activity("2026-03-02T09:58:00Z", 50_000)]).remove(0);
+        let projection = block.project(at("2026-03-02T10:00:00Z"), Some(60_000));
+
+        assert_eq!(projection.tokens, 50_000);
+        assert_eq!(projection.exhausts_in, None);
+    }
+
+    #[test]
+    fn test_token_limit_defaults_to_largest_past_block() {
+        let blocks = build_blocks(vec![
+            activity("2026-03-01T09:00:00Z", 2_000),
+            activity("2026-03-01T18:00:00Z", 7_000),
+            activity("2026-03-02T09:00:00Z", 9_000),
+        ]);
+        let now = at("2026-03-02T10:00:00Z");
+
+        // The active block is not its own limit
+        assert_eq!(
+            token_limit(&blocks, now, &BlocksConfig::default()),
+            Some(7_000)
+        );
+
+        let config = BlocksConfig {
+            token_limit: Some(1_000),
+            ..BlocksConfig::default()
+        };
+        assert_eq!(token_limit(&blocks, now, &config), Some(1_000));
+        assert_eq!(
+            token_limit(&blocks[2..], now, &BlocksConfig::default()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_entry_from_line() {
+        let line = r#"{"message":{"id":"msg_1","model":"claude-sonnet-4-5","role":"assistant","usage":{"input_tokens":1000,"output_tokens":2000,"cache_read_input_tokens":10000,"cache_creation_input_tokens":400}},"timestamp":"2026-03-02T09:41:07.123+02:00"}"#;
+        let entry = entry_from_line(line, "s1").unwrap();
+
+        assert_eq!(entry.message_id, "msg_1");
+        assert_eq!(entry.timestamp, "2026-03-02T07:41:07.123Z");
+        assert_eq!(entry.tokens(), 13_400);
+        assert_eq!(entry.cache_write_tokens, 400);
+        // 1000 * $3 + 2000 * $15 + 10000 * $0.30 + 400 * $3.75, per million
+        assert!((entry.cost - 0.0375).abs() < 1e-9);
+
+        let user =
+            r#"{"message":{"role":"user","content":"hi"},"timestamp":"2026-03-02T09:41:07Z"}"#;
+        assert_eq!(entry_from_line(user, "s1"), None);
+
+        let no_id = r#"{"message":{"role":"assistant","usage":{"output_tokens":5}},"timestamp":"2026-03-02T09:41:07Z"}"#;
+        let entry = entry_from_line(no_id, "s1").unwrap();
+        assert_eq!(entry.message_id, "s1:2026-03-02T09:41:07.000Z");
+        assert_eq!(entry.cost, 0.0);
+    }
+
+    #[test]
+    fn test_load_blocks_from_entries_and_sessions() {
+        use crate::database::SessionUpdate;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let db = SqliteDatabase::new(&temp_dir.path().join("stats.db")).unwrap();
+
+        let now = Utc::now();
+        let entry = |id: &str, session: &str, ago_minutes: i64, tokens: u64| UsageEntry {
+            message_id: id.to_string(),
+            session_id: session.to_string(),
+            timestamp: storage_timestamp(now - TimeDelta::minutes(ago_minutes)),
+            output_tokens: tokens,
+            cost: 1.0,
+            ..UsageEntry::default()
+        };
+        let entries = [
+            entry("a", "with-entries", 30, 100),
+            entry("b", "with-entries", 20, 200),
+        ];
+        assert_eq!(db.record_usage_entries(&entries).unwrap(), 2);
+        // Recording the same responses again adds nothing
+        assert_eq!(db.record_usage_entries(&entries).unwrap(), 0);
+
+        // A session without usage entries counts at its last update (now)
+        db.update_session(
+            "no-entries",
+            SessionUpdate {
+                cost: 2.5,
+                lines_added: 0,
+                lines_removed: 0,
+                model_name: None,
+                workspace_dir: None,
+                device_id: None,
+                token_breakdown: None,
+                max_tokens_observed: None,
+                active_time_seconds: None,
+                last_activity: None,
+            },
+        )
+        .unwrap();
+
+        let blocks = load_blocks(&db, now - TimeDelta::days(1)).unwrap();
+        let block = blocks.last().unwrap();
+        assert!(block.is_active(Utc::now()));
+        assert_eq!(block.tokens, 300);
+        assert!((block.cost - 4.5).abs() < 1e-9);
+        assert_eq!(block.responses, 3);
+        assert_eq!(block.sessions.len(), 2);
+    }
+}
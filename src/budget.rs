@@ -0,0 +1,448 @@
+//! Spending budgets checked against recorded cost.
+//!
+//! Limits come from the `[budget]` config section. Global daily and weekly
+//! budgets are checked against `daily_stats`, monthly budgets against
+//! `monthly_stats`, and workspace budgets against the sessions recorded under
+//! that directory. [`evaluate`] reports a [`BudgetStatus`] for every configured
+//! limit; [`BudgetProvider`] exposes the most consumed one that applies to the
+//! current directory as the `budget_*` variables.
+
+use crate::config::{BudgetConfig, BudgetLimits};
+use crate::database::SqliteDatabase;
+use crate::provider::{DataProvider, ProviderResult};
+use chrono::{Datelike, Local, NaiveDate};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Period a budget limit applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BudgetPeriod {
+    Daily,
+    /// Monday to Sunday
+    Weekly,
+    /// Calendar month
+    Monthly,
+}
+
+impl BudgetPeriod {
+    pub fn label(&self) -> &'static str {
+        match self {
+            BudgetPeriod::Daily => "daily",
+            BudgetPeriod::Weekly => "weekly",
+            BudgetPeriod::Monthly => "monthly",
+        }
+    }
+
+    /// First day of the period containing `today`.
+    pub fn start(&self, today: NaiveDate) -> NaiveDate {
+        match self {
+            BudgetPeriod::Daily => today,
+            BudgetPeriod::Weekly => {
+                today - chrono::Duration::days(today.weekday().num_days_from_monday() as i64)
+            }
+            BudgetPeriod::Monthly => today.with_day(1).unwrap_or(today),
+        }
+    }
+}
+
+/// How far a budget has been consumed, relative to the configured thresholds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BudgetLevel {
+    Normal,
+    /// At or past `budget.warning_pct`
+    Warning,
+    /// At or past `budget.critical_pct`
+    Critical,
+}
+
+impl BudgetLevel {
+    pub fn from_pct(pct: f64, config: &BudgetConfig) -> Self {
+        if pct >= config.critical_pct {
+            BudgetLevel::Critical
+        } else if pct >= config.warning_pct {
+            BudgetLevel::Warning
+        } else {
+            BudgetLevel::Normal
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            BudgetLevel::Normal => "normal",
+            BudgetLevel::Warning => "warning",
+            BudgetLevel::Critical => "critical",
+        }
+    }
+}
+
+/// Spend against one configured limit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BudgetStatus {
+    /// Workspace key as configured (e.g. `~/work/client-a`), `None` for a global budget
+    pub workspace: Option<String>,
+    pub period: BudgetPeriod,
+    pub limit: f64,
+    pub spent: f64,
+}
+
+impl BudgetStatus {
+    /// Short name for the statusline: "monthly", or "client-a monthly" for a workspace.
+    pub fn name(&self) -> String {
+        match self.workspace.as_deref() {
+            Some(workspace) => {
+                let dir = Path::new(workspace.trim_end_matches('/'))
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or(workspace);
+                format!("{} {}", dir, self.period.label())
+            }
+            None => self.period.label().to_string(),
+        }
+    }
+
+    /// Amount left in USD; negative once the budget is exceeded.
+    pub fn remaining(&self) -> f64 {
+        self.limit - self.spent
+    }
+
+    /// Percentage of the limit spent (above 100 once exceeded).
+    pub fn pct(&self) -> f64 {
+        self.spent / self.limit * 100.0
+    }
+
+    pub fn level(&self, config: &BudgetConfig) -> BudgetLevel {
+        BudgetLevel::from_pct(self.pct(), config)
+    }
+}
+
+/// Check every configured budget.
+///
+/// Global budgets always apply. When `current_dir` is given, only workspace
+/// budgets covering it are checked; otherwise all of them are. Global budgets
+/// come first, followed by workspaces in key order.
+pub fn evaluate(
+    db: &SqliteDatabase,
+    config: &BudgetConfig,
+    current_dir: Option<&str>,
+) -> rusqlite::Result<Vec<BudgetStatus>> {
+    let today = Local::now().date_naive();
+    let computed = config.cost_source == "computed";
+    let mut statuses = Vec::new();
+
+    let global = BudgetLimits {
+        daily: config.daily,
+        weekly: config.weekly,
+        monthly: config.monthly,
+    };
+    for (period, limit) in periods(&global) {
+        let spent = match period {
+            BudgetPeriod::Monthly if computed => db.get_month_computed_cost()?.cost,
+            BudgetPeriod::Monthly => db.get_month_total()?,
+            _ => db.get_cost_since(&date_string(period.start(today)), computed)?,
+        };
+        statuses.push(BudgetStatus {
+            workspace: None,
+            period,
+            limit,
+            spent,
+        });
+    }
+
+    let mut workspaces: Vec<_> = config.workspaces.iter().collect();
+    workspaces.sort_by(|a, b| a.0.cmp(b.0));
+    for (key, limits) in workspaces {
+        let dir = expand_workspace(key);
+        if current_dir.is_some_and(|current| !Path::new(current).starts_with(&dir)) {
+            continue;
+        }
+        for (period, limit) in periods(limits) {
+            let spent = db.get_workspace_cost_since(
+                &dir.to_string_lossy(),
+                &date_string(period.start(today)),
+                computed,
+            )?;
+            statuses.push(BudgetStatus {
+                workspace: Some(key.clone()),
+                period,
+                limit,
+                spent,
+            });
+        }
+    }
+
+    Ok(statuses)
+}
+
+/// The budget with the highest percentage spent.
+pub fn tightest(statuses: &[BudgetStatus]) -> Option<&BudgetStatus> {
+    statuses.iter().max_by(|a, b| a.pct().total_cmp(&b.pct()))
+}
+
+/// Whether any global or workspace limit is configured.
+pub fn is_configured(config: &BudgetConfig) -> bool {
+    config.daily.is_some()
+        || config.weekly.is_some()
+        || config.monthly.is_some()
+        || !config.workspaces.is_empty()
+}
+
+/// The positive limits in `limits`, daily first. Zero or negative limits are ignored.
+fn periods(limits: &BudgetLimits) -> Vec<(BudgetPeriod, f64)> {
+    [
+        (BudgetPeriod::Daily, limits.daily),
+        (BudgetPeriod::Weekly, limits.weekly),
+        (BudgetPeriod::Monthly, limits.monthly),
+    ]
+    .into_iter()
+    .filter_map(|(period, limit)| limit.filter(|l| *l > 0.0).map(|l| (period, l)))
+    .collect()
+}
+
+fn date_string(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+/// Expand a leading `~` in a configured workspace path.
+fn expand_workspace(key: &str) -> PathBuf {
+    if key == "~" {
+        if let Some(home) = dirs::home_dir() {
+            return home;
+        }
+    } else if let Some(rest) = key.strip_prefix("~/") {
+        if let Some(home) = dirs::home_dir() {
+            return home.join(rest);
+        }
+    }
+    PathBuf::from(key)
+}
+
+/// Data provider that reports the tightest budget for the current directory.
+///
+/// Variables are returned with a `budget_` prefix. Every key is always present
+/// in the output HashMap; all values are empty when no budget is configured.
+/// Raw numeric values are returned so that formatting stays in the layout engine.
+pub struct BudgetProvider {
+    current_dir: String,
+    /// Path to the SQLite database (None if unavailable)
+    db_path: Option<PathBuf>,
+    config: BudgetConfig,
+}
+
+impl BudgetProvider {
+    pub fn new(current_dir: &str, db_path: Option<PathBuf>, config: &BudgetConfig) -> Self {
+        Self {
+            current_dir: current_dir.to_string(),
+            db_path,
+            config: config.clone(),
+        }
+    }
+}
+
+impl DataProvider for BudgetProvider {
+    fn name(&self) -> &str {
+        "budget"
+    }
+
+    fn priority(&self) -> u32 {
+        50
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_millis(200)
+    }
+
+    fn is_available(&self) -> bool {
+        is_configured(&self.config)
+    }
+
+    fn collect(&self) -> ProviderResult {
+        let mut vars: HashMap<String, String> = [
+            "budget_name",
+            "budget_spent",
+            "budget_limit",
+            "budget_remaining",
+            "budget_pct",
+            "budget_level",
+        ]
+        .into_iter()
+        .map(|key| (key.to_string(), String::new()))
+        .collect();
+
+        let db = self
+            .db_path
+            .as_deref()
+            .filter(|p| p.exists())
+            .and_then(|p| SqliteDatabase::new(p).ok());
+        let statuses = db
+            .and_then(|db| evaluate(&db, &self.config, Some(&self.current_dir)).ok())
+            .unwrap_or_default();
+
+        if let Some(status) = tightest(&statuses) {
+            vars.insert("budget_name".into(), status.name());
+            vars.insert("budget_spent".into(), format!("{:.2}", status.spent));
+            vars.insert("budget_limit".into(), format!("{:.2}", status.limit));
+            vars.insert(
+                "budget_remaining".into(),
+                format!("{:.2}", status.remaining()),
+            );
+            vars.insert("budget_pct".into(), format!("{:.0}", status.pct()));
+            vars.insert(
+                "budget_level".into(),
+                status.level(&self.config).label().to_string(),
+            );
+        }
+
+        Ok(vars)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::SessionUpdate;
+    use tempfile::TempDir;
+
+    fn status(
+        workspace: Option<&str>,
+        period: BudgetPeriod,
+        limit: f64,
+        spent: f64,
+    ) -> BudgetStatus {
+        BudgetStatus {
+            workspace: workspace.map(str::to_string),
+            period,
+            limit,
+            spent,
+        }
+    }
+
+    fn record(db: &SqliteDatabase, session_id: &str, cost: f64, workspace: &str) {
+        db.update_session(
+            session_id,
+            SessionUpdate {
+                cost,
+                lines_added: 0,
+                lines_removed: 0,
+                model_name: None,
+                workspace_dir: Some(workspace.to_string()),
+                device_id: None,
+                token_breakdown: None,
+                max_tokens_observed: None,
+                active_time_seconds: None,
+                last_activity: None,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_period_start() {
+        // 2026-10-15 is a Thursday
+        let today = NaiveDate::from_ymd_opt(2026, 10, 15).unwrap();
+        assert_eq!(BudgetPeriod::Daily.start(today), today);
+        assert_eq!(
+            BudgetPeriod::Weekly.start(today),
+            NaiveDate::from_ymd_opt(2026, 10, 12).unwrap()
+        );
+        assert_eq!(
+            BudgetPeriod::Monthly.start(today),
+            NaiveDate::from_ymd_opt(2026, 10, 1).unwrap()
+        );
+
+        let monday = NaiveDate::from_ymd_opt(2026, 10, 12).unwrap();
+        assert_eq!(BudgetPeriod::Weekly.start(monday), monday);
+    }
+
+    #[test]
+    fn test_status_values_and_level() {
+        let config = BudgetConfig::default();
+
+        let monthly = status(None, BudgetPeriod::Monthly, 300.0, 240.0);
+        assert_eq!(monthly.name(), "monthly");
+        assert_eq!(monthly.remaining(), 60.0);
+        assert_eq!(monthly.pct(), 80.0);
+        assert_eq!(monthly.level(&config), BudgetLevel::Warning);
+
+        let over = status(Some("~/work/client-a/"), BudgetPeriod::Daily, 10.0, 12.5);
+        assert_eq!(over.name(), "client-a daily");
+        assert_eq!(over.remaining(), -2.5);
+        assert_eq!(over.level(&config), BudgetLevel::Critical);
+
+        assert_eq!(BudgetLevel::from_pct(74.9, &config), BudgetLevel::Normal);
+        assert_eq!(BudgetLevel::from_pct(90.0, &config), BudgetLevel::Critical);
+    }
+
+    #[test]
+    fn test_tightest_budget() {
+        let statuses = vec![
+            status(None, BudgetPeriod::Daily, 20.0, 5.0),
+            status(None, BudgetPeriod::Monthly, 300.0, 210.0),
+            status(Some("/work"), BudgetPeriod::Weekly, 50.0, 20.0),
+        ];
+        assert_eq!(tightest(&statuses).unwrap().period, BudgetPeriod::Monthly);
+        assert!(tightest(&[]).is_none());
+    }
+
+    #[test]
+    fn test_periods_skip_unset_and_non_positive_limits() {
+        let limits = BudgetLimits {
+            daily: Some(0.0),
+            weekly: None,
+            monthly: Some(100.0),
+        };
+        assert_eq!(periods(&limits), vec![(BudgetPeriod::Monthly, 100.0)]);
+    }
+
+    #[test]
+    fn test_evaluate_global_and_workspace_budgets() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = SqliteDatabase::new(&temp_dir.path().join("test.db")).unwrap();
+        record(&db, "a", 3.0, "/work/client-a");
+        record(&db, "b", 5.0, "/work/client-b/api");
+
+        let mut config = BudgetConfig {
+            daily: Some(10.0),
+            monthly: Some(100.0),
+            ..Default::default()
+        };
+        for (key, monthly) in [("/work/client-a", 3.5), ("/work/client-b", 50.0)] {
+            config.workspaces.insert(
+                key.to_string(),
+                BudgetLimits {
+                    monthly: Some(monthly),
+                    ..Default::default()
+                },
+            );
+        }
+
+        let all = evaluate(&db, &config, None).unwrap();
+        let summary: Vec<_> = all.iter().map(|s| (s.name(), s.spent)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("daily".to_string(), 8.0),
+                ("monthly".to_string(), 8.0),
+                ("client-a monthly".to_string(), 3.0),
+                ("client-b monthly".to_string(), 5.0),
+            ]
+        );
+
+        let in_b = evaluate(&db, &config, Some("/work/client-b/api/src")).unwrap();
+        assert_eq!(in_b.len(), 3);
+        assert_eq!(tightest(&in_b).unwrap().name(), "daily");
+
+        let in_a = evaluate(&db, &config, Some("/work/client-a")).unwrap();
+        assert_eq!(tightest(&in_a).unwrap().name(), "client-a monthly");
+    }
+
+    #[test]
+    fn test_provider_without_budgets() {
+        let provider = BudgetProvider::new("/tmp", None, &BudgetConfig::default());
+        assert_eq!(provider.name(), "budget");
+        assert!(!provider.is_available());
+        let vars = provider.collect().unwrap();
+        assert_eq!(vars.len(), 6);
+        assert!(vars.values().all(|v| v.is_empty()));
+    }
+}
@@ -0,0 +1,147 @@
+//! `blocks` subcommand handler: past and active 5-hour usage blocks (text or JSON).
+
+use crate::error::Result;
+
+/// List the usage blocks of the last `days` days, oldest first
+pub(crate) fn show_blocks_report(days: u32, json_output: bool) -> Result<()> {
+    use crate::blocks::{load_blocks, token_limit, BLOCK_HOURS};
+    use crate::display::{format_duration, Colors};
+    use crate::layout::format_token_count;
+    use chrono::{Local, TimeDelta, Utc};
+    use serde_json::json;
+
+    let config = crate::config::get_config();
+    let now = Utc::now();
+    let history_days = days.max(config.blocks.history_days).max(1);
+
+    let db_path = crate::stats::StatsData::get_sqlite_path()?;
+    let db = crate::database::SqliteDatabase::new(&db_path)?;
+    // Load the full history so the default limit matches the statusline's
+    let all_blocks = load_blocks(&db, now - TimeDelta::days(history_days as i64))?;
+    let limit = token_limit(&all_blocks, now, &config.blocks);
+    let since = now - TimeDelta::days(days.max(1) as i64);
+    let blocks: Vec<_> = all_blocks.iter().filter(|b| b.end() > since).collect();
+    let active = blocks.last().filter(|b| b.is_active(now));
+    let projection = active.map(|b| b.project(now, limit));
+
+    if json_output {
+        let list: Vec<_> = blocks
+            .iter()
+            .map(|block| {
+                json!({
+                    "start": block.start.with_timezone(&Local).to_rfc3339(),
+                    "end": block.end().with_timezone(&Local).to_rfc3339(),
+                    "last_activity": block.last_activity.with_timezone(&Local).to_rfc3339(),
+                    "active": block.is_active(now),
+                    "tokens": block.tokens,
+                    "cost": block.cost,
+                    "responses": block.responses,
+                    "sessions": block.sessions.len(),
+                })
+            })
+            .collect();
+        let active = active.zip(projection).map(|(block, projection)| {
+            json!({
+                "elapsed_seconds": block.elapsed(now).num_seconds(),
+                "remaining_seconds": block.remaining(now).num_seconds(),
+                "projected_tokens": projection.tokens,
+                "projected_cost": projection.cost,
+                "exhausts_in_seconds": projection.exhausts_in.map(|d| d.num_seconds()),
+            })
+        });
+        let output = json!({
+            "block_hours": BLOCK_HOURS,
+            "token_limit": limit,
+            "blocks": list,
+            "active": active,
+        });
+        println!("{}", serde_json::to_string(&output)?);
+        return Ok(());
+    }
+
+    if blocks.is_empty() {
+        println!("No usage blocks in the last {} days.", days);
+        println!();
+        println!("Blocks are built from the token usage in transcripts, recorded as the");
+        println!("statusline renders. Past sessions without recorded usage count once, at");
+        println!("the time they were last active.");
+        return Ok(());
+    }
+
+    println!(
+        "Usage Blocks ({}-hour windows, last {} days)",
+        BLOCK_HOURS, days
+    );
+    println!();
+    // The share of the limit is only shown when there is one
+    let limit_column = |value: &str| match limit {
+        Some(_) => format!("  {:>6}", value),
+        None => String::new(),
+    };
+    println!(
+        "{:<16}  {:<5}  {:>8}  {:>10}  {:>8}{}",
+        "Start",
+        "End",
+        "Tokens",
+        "Cost",
+        "Sessions",
+        limit_column("Limit")
+    );
+    println!("{}", "-".repeat(if limit.is_some() { 61 } else { 53 }));
+    for block in &blocks {
+        let pct = limit
+            .filter(|l| *l > 0)
+            .map(|l| format!("{:.0}%", block.tokens as f64 / l as f64 * 100.0))
+            .unwrap_or_default();
+        println!(
+            "{:<16}  {:<5}  {:>8}  {:>10}  {:>8}{}{}",
+            block.start.with_timezone(&Local).format("%Y-%m-%d %H:%M"),
+            block.end().with_timezone(&Local).format("%H:%M"),
+            format_token_count(block.tokens),
+            format!("${:.2}", block.cost),
+            block.sessions.len(),
+            limit_column(&pct),
+            if block.is_active(now) { "  active" } else { "" }
+        );
+    }
+
+    if let (Some(block), Some(projection)) = (active, projection) {
+        println!();
+        println!(
+            "Active block: {} elapsed, resets in {} (at {})",
+            format_duration(block.elapsed(now).num_seconds() as u64),
+            format_duration(block.remaining(now).num_seconds() as u64),
+            block.end().with_timezone(&Local).format("%H:%M")
+        );
+        println!(
+            "Projected at reset: {} tokens, ${:.2}",
+            format_token_count(projection.tokens),
+            projection.cost
+        );
+        match (limit, projection.exhausts_in) {
+            (Some(limit), Some(exhausts_in)) => println!(
+                "{}Limit of {} tokens reached in {}, before the reset{}",
+                Colors::red(),
+                format_token_count(limit),
+                format_duration(exhausts_in.num_seconds() as u64),
+                Colors::reset()
+            ),
+            (Some(limit), None) => {
+                println!("Within the limit of {} tokens", format_token_count(limit))
+            }
+            (None, _) => {}
+        }
+    }
+    if config.blocks.token_limit.is_none() {
+        if let Some(limit) = limit {
+            println!();
+            println!(
+                "Limit: largest block of the last {} days ({} tokens); set [blocks] token_limit to override",
+                history_days,
+                format_token_count(limit)
+            );
+        }
+    }
+
+    Ok(())
+}
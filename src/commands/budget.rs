@@ -0,0 +1,105 @@
+//! `budget` subcommand handler: spend against configured budgets (text or JSON).
+
+use crate::error::Result;
+use crate::BudgetAction;
+
+/// Handle budget command invocations
+pub(crate) fn handle_budget_command(action: BudgetAction) -> Result<()> {
+    match action {
+        BudgetAction::Status { json } => show_budget_status(json),
+    }
+}
+
+/// Print a table of every configured budget
+fn show_budget_status(json_output: bool) -> Result<()> {
+    use crate::budget::{evaluate, is_configured};
+    use crate::display::Colors;
+    use serde_json::json;
+
+    let config = crate::config::get_config();
+    let budget = &config.budget;
+
+    let statuses = if is_configured(budget) {
+        let db_path = crate::stats::StatsData::get_sqlite_path()?;
+        let db = crate::database::SqliteDatabase::new(&db_path)?;
+        evaluate(&db, budget, None)?
+    } else {
+        Vec::new()
+    };
+
+    if json_output {
+        let budgets: Vec<_> = statuses
+            .iter()
+            .map(|status| {
+                json!({
+                    "workspace": status.workspace,
+                    "period": status.period.label(),
+                    "limit": status.limit,
+                    "spent": status.spent,
+                    "remaining": status.remaining(),
+                    "pct": status.pct(),
+                    "level": status.level(budget).label(),
+                })
+            })
+            .collect();
+        let output = json!({
+            "cost_source": budget.cost_source,
+            "budgets": budgets,
+        });
+        println!("{}", serde_json::to_string(&output)?);
+        return Ok(());
+    }
+
+    if statuses.is_empty() {
+        println!("No budgets configured.");
+        println!();
+        println!("Add limits to the [budget] section of your config file, e.g.:");
+        println!();
+        println!("  [budget]");
+        println!("  monthly = 300.0");
+        println!();
+        println!("Run `statusline generate-config` for a full example.");
+        return Ok(());
+    }
+
+    let bar_width = config.display.progress_bar_width;
+    let scope_width = statuses
+        .iter()
+        .map(|s| s.workspace.as_deref().map_or(3, |w| w.chars().count()))
+        .max()
+        .unwrap_or(3)
+        .max("Budget".len());
+
+    println!("Spending Budgets ({} cost)", budget.cost_source);
+    println!();
+    println!(
+        "{:<scope_width$}  {:<7}  {:>10}  {:>10}  {:>10}  {:>5}",
+        "Budget", "Period", "Spent", "Limit", "Remaining", "Used"
+    );
+    println!("{}", "-".repeat(scope_width + 52));
+    for status in &statuses {
+        let scope =
+            crate::utils::sanitize_for_terminal(status.workspace.as_deref().unwrap_or("all"));
+        let pct = status.pct();
+        let remaining = status.remaining();
+        let remaining = if remaining < 0.0 {
+            format!("-${:.2}", -remaining)
+        } else {
+            format!("${:.2}", remaining)
+        };
+        println!(
+            "{:<scope_width$}  {:<7}  {:>10}  {:>10}  {:>10}  {}{:>4.0}% {}{}",
+            scope,
+            status.period.label(),
+            format!("${:.2}", status.spent),
+            format!("${:.2}", status.limit),
+            remaining,
+            Colors::budget_color(pct),
+            pct,
+            crate::display::format_raw_bar(pct, bar_width),
+            Colors::reset()
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,119 @@
+//! `export` and `import-data` subcommand handlers: move the stats database
+//! between machines as NDJSON or JSON (CSV for spreadsheets).
+
+use crate::database::{SqliteDatabase, EXPORT_TABLES};
+use crate::error::{Result, StatuslineError};
+use crate::export::{is_export_table, Export};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Export `tables` (all of them when empty) to `output`, or stdout
+pub(crate) fn export_data(format: &str, tables: &[String], output: Option<PathBuf>) -> Result<()> {
+    let tables: Vec<&str> = if tables.is_empty() {
+        EXPORT_TABLES.iter().map(|(name, _)| *name).collect()
+    } else {
+        tables.iter().map(String::as_str).collect()
+    };
+
+    let db_path = crate::stats::StatsData::get_sqlite_path()?;
+    let db = SqliteDatabase::new(&db_path)?;
+    let export = Export::from_database(&db, &tables)?;
+    let content = match format {
+        "json" => export.to_json(),
+        "csv" => export.to_csv(),
+        _ => export.to_ndjson(),
+    };
+
+    match output {
+        Some(path) => {
+            std::fs::write(&path, content)?;
+            let rows: usize = export.tables.iter().map(|t| t.rows.len()).sum();
+            println!(
+                "Exported {} rows from {} tables to {}",
+                rows,
+                export.tables.len(),
+                path.display()
+            );
+        }
+        None => print!("{}", content),
+    }
+    Ok(())
+}
+
+/// Add the rows of the export in `file` ("-" for stdin) to the database
+pub(crate) fn import_data(file: &Path, tables: &[String], replace: bool) -> Result<()> {
+    let content = if file == Path::new("-") {
+        let mut content = String::new();
+        std::io::stdin().read_to_string(&mut content)?;
+        content
+    } else {
+        std::fs::read_to_string(file).map_err(|e| {
+            StatuslineError::other(format!("Could not read {}: {}", file.display(), e))
+        })?
+    };
+    let export = Export::parse(&content)?;
+
+    let (known, unknown): (Vec<_>, Vec<_>) = export
+        .tables
+        .into_iter()
+        .filter(|dump| tables.is_empty() || tables.contains(&dump.table))
+        .partition(|dump| is_export_table(&dump.table));
+
+    let db_path = crate::stats::StatsData::get_sqlite_path()?;
+    let db = SqliteDatabase::new(&db_path)?;
+    let results = db.import_tables(&known, replace)?;
+
+    println!(
+        "Export from {} (format v{}, schema v{})",
+        export.header.exported_at, export.header.version, export.header.schema_version
+    );
+    for result in &results {
+        let mut line = format!("  {:<24}{:>7} added", result.table, result.inserted);
+        if replace {
+            line.push_str(&format!(", {} replaced", result.replaced));
+        } else {
+            line.push_str(&format!(", {} already present", result.skipped));
+        }
+        println!("{}", line);
+        if !result.differing.is_empty() {
+            eprintln!(
+                "Warning: {} {} in {} have other totals in the export; kept {} and dropped {}: {}",
+                result.differing.len(),
+                if result.table == "daily_stats" {
+                    "days"
+                } else {
+                    "months"
+                },
+                result.table,
+                if replace {
+                    "the export's"
+                } else {
+                    "this database's"
+                },
+                if replace {
+                    "this database's"
+                } else {
+                    "the export's"
+                },
+                result.differing.join(", ")
+            );
+        }
+        if !result.unknown_columns.is_empty() {
+            println!(
+                "  {:<24}left out columns this version lacks: {}",
+                "",
+                result.unknown_columns.join(", ")
+            );
+        }
+    }
+    for dump in &unknown {
+        println!(
+            "  {:<24}skipped: not a table this version imports",
+            dump.table
+        );
+    }
+    if results.is_empty() && unknown.is_empty() {
+        println!("Nothing to import.");
+    }
+    Ok(())
+}
@@ -0,0 +1,161 @@
+//! `forecast` subcommand handler: projected spend and token usage (text or JSON).
+
+use crate::error::Result;
+
+/// Show today's and this month's forecast with the typical day for each weekday
+pub(crate) fn show_forecast_report(json_output: bool) -> Result<()> {
+    use crate::display::Colors;
+    use crate::forecast::{load_history, project};
+    use crate::layout::format_token_count;
+    use chrono::{Datelike, Weekday};
+    use serde_json::json;
+
+    let config = crate::config::get_config();
+    let forecast_config = &config.forecast;
+    let now = chrono::Local::now().naive_local();
+    let today = now.date();
+
+    let db_path = crate::stats::StatsData::get_sqlite_path()?;
+    let db = crate::database::SqliteDatabase::new(&db_path)?;
+    let (cost_history, token_history) = load_history(&db, today, forecast_config)?;
+    // No live session here, so the forecast rests on history alone
+    let cost = project(&cost_history, now, None, forecast_config);
+    let tokens = project(&token_history, now, None, forecast_config);
+    let confidence = cost.confidence();
+
+    let weekdays = [
+        Weekday::Mon,
+        Weekday::Tue,
+        Weekday::Wed,
+        Weekday::Thu,
+        Weekday::Fri,
+        Weekday::Sat,
+        Weekday::Sun,
+    ];
+    let typical: Vec<_> = weekdays
+        .iter()
+        .map(|w| {
+            (
+                w,
+                cost_history.typical(*w, today, forecast_config.ewma_alpha),
+            )
+        })
+        .collect();
+
+    if json_output {
+        let forecast = |f: &crate::forecast::Forecast| {
+            json!({
+                "today_so_far": f.today_so_far,
+                "today": f.today,
+                "month_so_far": f.month_so_far,
+                "month": f.month,
+            })
+        };
+        let weekdays: serde_json::Map<_, _> = typical
+            .iter()
+            .filter_map(|(w, t)| t.map(|t| (w.to_string(), json!(t.value))))
+            .collect();
+        let output = json!({
+            "date": today.format("%Y-%m-%d").to_string(),
+            "cost": forecast(&cost),
+            "tokens": forecast(&tokens),
+            "confidence": confidence.label(),
+            "typical_by_weekday": weekdays,
+        });
+        println!("{}", serde_json::to_string(&output)?);
+        return Ok(());
+    }
+
+    let title = format!("Spend Forecast for {}", today.format("%A %Y-%m-%d"));
+    println!("{}", title);
+    println!("{}", "=".repeat(title.len()));
+    println!();
+    println!("{:<14} {:>12} {:>12}", "", "So far", "Forecast");
+    println!(
+        "{:<14} {:>12} {:>12}",
+        "Today",
+        format!("${:.2}", cost.today_so_far),
+        format!("${:.2}", cost.today)
+    );
+    println!(
+        "{:<14} {:>12} {:>12}",
+        "This month",
+        format!("${:.2}", cost.month_so_far),
+        format!("${:.2}", cost.month)
+    );
+    println!(
+        "{:<14} {:>12} {:>12}",
+        "Tokens today",
+        format_token_count(tokens.today_so_far as u64),
+        format_token_count(tokens.today as u64)
+    );
+    println!(
+        "{:<14} {:>12} {:>12}",
+        "Tokens month",
+        format_token_count(tokens.month_so_far as u64),
+        format_token_count(tokens.month as u64)
+    );
+    println!();
+
+    match cost.typical {
+        Some(t) if t.weekday_samples >= 2 => println!(
+            "Typical {}: ${:.2} ({} past {}s, ±{:.0}%)",
+            today.weekday(),
+            t.value,
+            t.weekday_samples,
+            today.weekday(),
+            t.variation * 100.0
+        ),
+        Some(t) => println!(
+            "Typical day: ${:.2} (moving average of {} days, ±{:.0}%)",
+            t.value,
+            t.days,
+            t.variation * 100.0
+        ),
+        None => {
+            println!("No history yet: the forecast assumes the rest of the month is like today")
+        }
+    }
+    println!("Confidence: {} {}", confidence.icon(), confidence.label());
+
+    if typical.iter().any(|(_, t)| t.is_some()) {
+        println!();
+        println!("Typical day by weekday:");
+        for (weekday, t) in &typical {
+            if let Some(t) = t {
+                println!("  {}  ${:.2}", weekday, t.value);
+            }
+        }
+    }
+
+    // Compare against the global budgets, when configured
+    let budgets = [
+        ("daily", config.budget.daily, cost.today),
+        ("monthly", config.budget.monthly, cost.month),
+    ];
+    if budgets.iter().any(|(_, limit, _)| limit.is_some()) {
+        println!();
+        println!("Budgets:");
+        for (name, limit, projected) in budgets {
+            let Some(limit) = limit.filter(|l| *l > 0.0) else {
+                continue;
+            };
+            let status = if projected > limit {
+                format!("over by ${:.2}", projected - limit)
+            } else {
+                format!("${:.2} to spare", limit - projected)
+            };
+            println!(
+                "  {:<8} ${:.2} limit, {}forecast ${:.2}: {}{}",
+                name,
+                limit,
+                Colors::budget_color(projected / limit * 100.0),
+                projected,
+                status,
+                Colors::reset()
+            );
+        }
+    }
+
+    Ok(())
+}
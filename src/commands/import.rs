@@ -0,0 +1,114 @@
+//! `import` subcommand handler: backfill session history from Claude Code
+//! transcripts.
+
+use crate::error::{Result, StatuslineError};
+use crate::ImportAction;
+use chrono::{NaiveDate, TimeDelta};
+use std::path::PathBuf;
+
+/// Handle import command invocations
+pub(crate) fn handle_import_command(action: ImportAction) -> Result<()> {
+    match action {
+        ImportAction::Transcripts {
+            since,
+            dry_run,
+            projects_dir,
+        } => import_transcripts(since, dry_run, projects_dir),
+    }
+}
+
+/// Import the sessions found in the transcripts below `projects_dir`
+fn import_transcripts(
+    since: Option<NaiveDate>,
+    dry_run: bool,
+    projects_dir: Option<PathBuf>,
+) -> Result<()> {
+    use crate::backfill::{find_transcripts, projects_dir as default_projects_dir, read_sessions};
+    use crate::layout::format_token_count;
+
+    let root = projects_dir
+        .or_else(default_projects_dir)
+        .ok_or_else(|| StatuslineError::other("Could not determine the home directory"))?;
+    if !root.is_dir() {
+        return Err(StatuslineError::other(format!(
+            "Transcript directory not found: {}",
+            root.display()
+        )));
+    }
+
+    let config = crate::config::get_config();
+    let inactivity = TimeDelta::minutes(config.burn_rate.inactivity_threshold_minutes as i64);
+    let files = find_transcripts(&root);
+    let sessions = read_sessions(&files, inactivity, since);
+    println!(
+        "Scanned {} transcripts in {}",
+        files.len(),
+        crate::utils::shorten_path(&root.to_string_lossy())
+    );
+    if sessions.is_empty() {
+        println!("No sessions with token usage found.");
+        return Ok(());
+    }
+
+    let db_path = crate::stats::StatsData::get_sqlite_path()?;
+    let db = crate::database::SqliteDatabase::new(&db_path)?;
+
+    if dry_run {
+        let pruned_before = db.sessions_pruned_before()?;
+        let mut new_sessions = Vec::new();
+        for session in &sessions {
+            let pruned = pruned_before
+                .as_deref()
+                .is_some_and(|cutoff| session.last_updated.as_str() < cutoff);
+            if !pruned && !db.session_exists(&session.session_id)? {
+                new_sessions.push(session);
+            }
+        }
+        for session in &new_sessions {
+            println!(
+                "  {}  {:<8}  {:<12}  {:>9}  {:>7}  {}",
+                &session.start_time[..10],
+                session.session_id.chars().take(8).collect::<String>(),
+                session.model_name.as_deref().unwrap_or("-"),
+                format!("${:.2}", session.cost()),
+                format_token_count(session.tokens()),
+                session
+                    .workspace_dir
+                    .as_deref()
+                    .map(crate::utils::shorten_path)
+                    .unwrap_or_default()
+            );
+        }
+        let cost: f64 = new_sessions.iter().map(|s| s.cost()).sum();
+        println!(
+            "Would import {} new sessions (${:.2}); {} already recorded or pruned. Nothing was written.",
+            new_sessions.len(),
+            cost,
+            sessions.len() - new_sessions.len()
+        );
+        return Ok(());
+    }
+
+    let (imported, entries) = db.import_transcript_sessions(&sessions)?;
+    let cost: f64 = sessions.iter().map(|s| s.cost()).sum();
+    println!(
+        "Found {} sessions from {} to {} (${:.2} priced from tokens)",
+        sessions.len(),
+        &sessions[0].start_time[..10],
+        sessions
+            .iter()
+            .map(|s| &s.last_updated[..10])
+            .max()
+            .unwrap_or_default(),
+        cost
+    );
+    println!(
+        "Imported {} new sessions; {} already recorded or pruned",
+        imported,
+        sessions.len() - imported
+    );
+    if entries > 0 {
+        println!("Recorded {} responses for usage blocks", entries);
+    }
+    Ok(())
+}
@@ -9,7 +9,6 @@ use crate::Cli;
 
 /// Handle `--list-vars` CLI flag: run all providers and print variables grouped by source.
 pub(crate) fn handle_list_vars(cli: &Cli) -> Result<()> {
-    use crate::provider::ProviderOrchestrator;
     use std::collections::BTreeMap;
 
     // Read JSON from stdin (needed for session/cost/model data)
@@ -30,59 +29,15 @@ pub(crate) fn handle_list_vars(cli: &Cli) -> Result<()> {
                 .unwrap_or_else(|| "~".to_string())
         });
 
-    let full_config = crate::config::get_config();
-
-    // --- Run providers via orchestrator ---
-    let mut orchestrator = ProviderOrchestrator::new();
-
-    // Register GitProvider
-    orchestrator.register(Box::new(crate::git_provider::GitProvider::new(
-        &current_dir,
-    )));
-
-    // Register StatsProvider
-    let total_cost = input
-        .cost
-        .as_ref()
-        .and_then(|c| c.total_cost_usd)
-        .unwrap_or(0.0);
-    let lines_added = input
-        .cost
-        .as_ref()
-        .and_then(|c| c.total_lines_added)
-        .unwrap_or(0);
-    let lines_removed = input
-        .cost
-        .as_ref()
-        .and_then(|c| c.total_lines_removed)
-        .unwrap_or(0);
     let daily_total = {
         let data = crate::stats::get_or_load_stats_data();
         let today = chrono::Local::now().format("%Y-%m-%d").to_string();
         data.daily.get(&today).map(|d| d.total_cost).unwrap_or(0.0)
     };
-    let db_path = crate::stats::StatsData::get_sqlite_path()
-        .ok()
-        .map(|p| p.display().to_string());
-    orchestrator.register(Box::new(crate::stats::StatsProvider::new(
-        input.session_id.clone(),
-        total_cost,
-        daily_total,
-        lines_added,
-        lines_removed,
-        input.transcript.clone(),
-        db_path,
-    )));
-
-    // Register GsdProvider
-    let cwd = std::path::Path::new(&current_dir);
-    orchestrator.register(Box::new(crate::gsd::GsdProvider::new(
-        &full_config.gsd,
-        cwd,
-    )));
-
-    // Collect all provider variables
-    let provider_vars = orchestrator.collect_all();
+
+    // --- Run providers via orchestrator (same set as the main render path) ---
+    let provider_vars =
+        crate::render::collect_provider_vars(&input, &current_dir, daily_total, true);
 
     // --- Build core variables (not from providers) ---
     let mut core_vars: BTreeMap<String, String> = BTreeMap::new();
@@ -135,18 +90,78 @@ pub(crate) fn handle_list_vars(cli: &Cli) -> Result<()> {
         core_vars.insert("daily_total".into(), format!("${:.2}", daily_total));
     }
 
+    // Claude Code metadata (raw values)
+    if let Some(version) = input.version.as_deref() {
+        core_vars.insert(
+            "claude_version".into(),
+            format!("v{}", crate::utils::sanitize_for_terminal(version)),
+        );
+    }
+    if let Some(style) = input.output_style.as_ref().and_then(|s| s.name.as_deref()) {
+        core_vars.insert(
+            "output_style".into(),
+            crate::utils::sanitize_for_terminal(style),
+        );
+    }
+    if let Some(id) = input.model.as_ref().and_then(|m| m.id.as_deref()) {
+        core_vars.insert("model_id".into(), crate::utils::sanitize_for_terminal(id));
+    }
+    if let Some(project_dir) = input
+        .workspace
+        .as_ref()
+        .and_then(|w| w.project_dir.as_deref())
+    {
+        core_vars.insert(
+            "project_dir".into(),
+            crate::utils::sanitize_for_terminal(&crate::utils::shorten_path(project_dir)),
+        );
+    }
+    if let Some(cost_data) = &input.cost {
+        if let Some(ms) = cost_data.total_duration_ms {
+            core_vars.insert("wall_time".into(), format!("{}s", ms / 1000));
+        }
+        if let Some(ms) = cost_data.total_api_duration_ms {
+            core_vars.insert("api_time".into(), format!("{}s", ms / 1000));
+        }
+        if let Some(pct) = cost_data.api_time_percentage() {
+            core_vars.insert("api_pct".into(), format!("{:.0}%", pct));
+        }
+    }
+    core_vars.insert(
+        "time".into(),
+        chrono::Local::now().format("%H:%M").to_string(),
+    );
+    if input.exceeds_200k_tokens == Some(true) {
+        core_vars.insert("exceeds_200k".into(), ">200k".into());
+    }
+
     // --- Print results grouped by provider ---
     // First, categorise provider vars by prefix
     let mut git_vars: BTreeMap<String, String> = BTreeMap::new();
+    let mut vcs_vars: BTreeMap<String, String> = BTreeMap::new();
     let mut stats_vars: BTreeMap<String, String> = BTreeMap::new();
+    let mut budget_vars: BTreeMap<String, String> = BTreeMap::new();
+    let mut forecast_vars: BTreeMap<String, String> = BTreeMap::new();
+    let mut block_vars: BTreeMap<String, String> = BTreeMap::new();
+    let mut limit_vars: BTreeMap<String, String> = BTreeMap::new();
     let mut gsd_vars: BTreeMap<String, String> = BTreeMap::new();
     let mut other_vars: BTreeMap<String, String> = BTreeMap::new();
 
     for (key, value) in &provider_vars {
         if key.starts_with("git") {
             git_vars.insert(key.clone(), value.clone());
+        } else if key == "vcs" || key.starts_with("vcs_") {
+            vcs_vars.insert(key.clone(), value.clone());
         } else if key.starts_with("stats_") {
             stats_vars.insert(key.clone(), value.clone());
+        } else if key.starts_with("budget_") {
+            budget_vars.insert(key.clone(), value.clone());
+        } else if key.starts_with("forecast_") {
+            forecast_vars.insert(key.clone(), value.clone());
+        } else if key.starts_with("block_") {
+            block_vars.insert(key.clone(), value.clone());
+        } else if key.starts_with("limit_") || key == "api_errors" {
+            limit_vars.insert(key.clone(), value.clone());
         } else if key.starts_with("gsd_") {
             gsd_vars.insert(key.clone(), value.clone());
         } else {
@@ -178,7 +193,12 @@ pub(crate) fn handle_list_vars(cli: &Cli) -> Result<()> {
     println!();
 
     print_group("git", &git_vars);
+    print_group("vcs", &vcs_vars);
     print_group("stats", &stats_vars);
+    print_group("budget", &budget_vars);
+    print_group("forecast", &forecast_vars);
+    print_group("blocks", &block_vars);
+    print_group("limits", &limit_vars);
     print_group("gsd", &gsd_vars);
     if !other_vars.is_empty() {
         print_group("other", &other_vars);
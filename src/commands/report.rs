@@ -0,0 +1,43 @@
+//! `report` subcommand handler: usage totals by period, model, project or device
+//! (table, JSON, CSV or markdown).
+
+use crate::error::Result;
+use chrono::NaiveDate;
+
+/// Print the usage report grouped by `by` in the given `format`
+pub(crate) fn show_usage_report(
+    by: &str,
+    since: Option<NaiveDate>,
+    until: Option<NaiveDate>,
+    format: &str,
+) -> Result<()> {
+    use crate::error::StatuslineError;
+    use crate::report::{build_report, Grouping};
+
+    // clap restricts both values, so these only fail if the lists drift apart
+    let grouping = Grouping::parse(by)
+        .ok_or_else(|| StatuslineError::other(format!("Unknown report grouping: {}", by)))?;
+    if let (Some(since), Some(until)) = (since, until) {
+        if since > until {
+            return Err(StatuslineError::other(format!(
+                "--since {} is after --until {}",
+                since, until
+            )));
+        }
+    }
+
+    let db_path = crate::stats::StatsData::get_sqlite_path()?;
+    let db = crate::database::SqliteDatabase::new(&db_path)?;
+    let report = build_report(&db, grouping, since, until)?;
+
+    match format {
+        "json" => println!("{}", serde_json::to_string(&report.to_json())?),
+        "csv" => println!("{}", report.to_csv()),
+        "markdown" => println!("{}", report.to_markdown()),
+        _ if report.rows.is_empty() => {
+            println!("No activity recorded for this range.");
+        }
+        _ => println!("{}", report.to_table()),
+    }
+    Ok(())
+}
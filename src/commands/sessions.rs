@@ -0,0 +1,373 @@
+//! `sessions` subcommand handler: browse live sessions and archived work periods
+//! (text or JSON).
+
+use crate::database::{SessionFilter, SessionSummary, SqliteDatabase};
+use crate::error::{Result, StatuslineError};
+use crate::SessionsAction;
+use chrono::{DateTime, Local, TimeDelta};
+use serde_json::json;
+
+/// Burn rate modes, in the order `show` lists them
+const BURN_RATE_MODES: [&str; 3] = ["wall_clock", "active_time", "auto_reset"];
+
+/// Handle sessions command invocations
+pub(crate) fn handle_sessions_command(action: SessionsAction) -> Result<()> {
+    let db_path = crate::stats::StatsData::get_sqlite_path()?;
+    let db = SqliteDatabase::new(&db_path)?;
+
+    match action {
+        SessionsAction::List {
+            workspace,
+            model,
+            since,
+            until,
+            archived,
+            limit,
+            page,
+            json,
+        } => {
+            let filter = SessionFilter {
+                workspace,
+                model,
+                since: since.map(|d| d.to_string()),
+                until: until.map(|d| d.to_string()),
+                archived,
+                ..SessionFilter::default()
+            };
+            list_sessions(&db, &filter, limit.max(1), page.max(1), json)
+        }
+        SessionsAction::Show { session_id, json } => show_session(&db, &session_id, json),
+        SessionsAction::Active { json } => show_active_sessions(&db, json),
+    }
+}
+
+/// Print one page of the sessions matching `filter`, most recently active first
+fn list_sessions(
+    db: &SqliteDatabase,
+    filter: &SessionFilter,
+    limit: usize,
+    page: usize,
+    json_output: bool,
+) -> Result<()> {
+    let total = db.count_sessions_matching(filter)?;
+    let offset = (page - 1) * limit;
+    let sessions = db.find_sessions(filter, Some(limit), offset)?;
+
+    if json_output {
+        let output = json!({
+            "total": total,
+            "page": page,
+            "limit": limit,
+            "sessions": sessions.iter().map(session_json).collect::<Vec<_>>(),
+        });
+        println!("{}", serde_json::to_string(&output)?);
+        return Ok(());
+    }
+
+    if sessions.is_empty() {
+        if total > 0 {
+            println!(
+                "No sessions on page {} ({} matching sessions).",
+                page, total
+            );
+        } else {
+            println!("No matching sessions.");
+        }
+        return Ok(());
+    }
+
+    print_session_table(&sessions);
+    println!();
+    let shown = format!("{}-{} of {}", offset + 1, offset + sessions.len(), total);
+    if offset + sessions.len() < total {
+        println!("Showing {} (--page {} for more)", shown, page + 1);
+    } else {
+        println!("Showing {}", shown);
+    }
+    Ok(())
+}
+
+/// Print every row recorded for one session: the live session and its archived
+/// work periods
+fn show_session(db: &SqliteDatabase, session_id: &str, json_output: bool) -> Result<()> {
+    let filter = SessionFilter {
+        session_id: Some(session_id.to_string()),
+        archived: true,
+        ..SessionFilter::default()
+    };
+    let rows = db.find_sessions(&filter, None, 0)?;
+
+    // The ID may be abbreviated, as in the list, as long as it is unambiguous
+    let mut ids: Vec<&str> = rows.iter().map(|r| r.session_id.as_str()).collect();
+    ids.sort_unstable();
+    ids.dedup();
+    match ids.len() {
+        0 => {
+            return Err(StatuslineError::other(format!(
+                "No session matching '{}'",
+                session_id
+            )))
+        }
+        1 => {}
+        _ => {
+            return Err(StatuslineError::other(format!(
+                "'{}' matches {} sessions: {}",
+                session_id,
+                ids.len(),
+                ids.join(", ")
+            )))
+        }
+    }
+
+    let (archived, live): (Vec<_>, Vec<_>) = rows.iter().partition(|r| r.archived);
+    let config = crate::config::get_config();
+    let burn_rate = &config.burn_rate;
+
+    if json_output {
+        let output = json!({
+            "session": live.first().map(|s| session_json(s)),
+            "archived": archived.iter().map(|s| session_json(s)).collect::<Vec<_>>(),
+            "burn_rate_mode": burn_rate.mode,
+        });
+        println!("{}", serde_json::to_string(&output)?);
+        return Ok(());
+    }
+
+    let Some(session) = live.first().or(archived.first()) else {
+        return Ok(());
+    };
+    println!("Session {}", session.session_id);
+    if live.is_empty() {
+        println!("(no longer live; only archived work periods remain)");
+    }
+    println!();
+    let field = |label: &str, value: String| println!("  {:<13}{}", label, value);
+    let unknown = || "-".to_string();
+    field(
+        "Workspace:",
+        session
+            .workspace_dir
+            .as_deref()
+            .map_or_else(unknown, crate::utils::shorten_path),
+    );
+    field("Model:", session.model_name.clone().unwrap_or_else(unknown));
+    field("Device:", session.device_id.clone().unwrap_or_else(unknown));
+    field("Started:", local_time(&session.start_time));
+    let ago = parse_time(&session.last_updated)
+        .map(|t| {
+            let seconds = (Local::now() - t).num_seconds().max(0) as u64;
+            format!(" ({} ago)", crate::display::format_duration(seconds))
+        })
+        .unwrap_or_default();
+    field(
+        "Last update:",
+        format!("{}{}", local_time(&session.last_updated), ago),
+    );
+    field("Wall time:", wall_time(session));
+    field(
+        "Active time:",
+        match session.active_time_seconds.filter(|t| *t > 0) {
+            Some(t) => crate::display::format_duration(t),
+            None => "not recorded (only tracked in active_time mode)".to_string(),
+        },
+    );
+
+    let t = &session.totals;
+    let computed = if t.computed_cost > 0.0 {
+        format!(" (priced from tokens: ${:.2})", t.computed_cost)
+    } else {
+        String::new()
+    };
+    field("Cost:", format!("${:.2}{}", t.cost, computed));
+    field("Lines:", format!("+{} -{}", t.lines_added, t.lines_removed));
+    if !session.archived {
+        use crate::layout::format_token_count;
+        field(
+            "Tokens:",
+            format!(
+                "{} total: {} input, {} output, {} cache read, {} cache write",
+                format_token_count(t.tokens()),
+                format_token_count(t.input_tokens),
+                format_token_count(t.output_tokens),
+                format_token_count(t.cache_read_tokens),
+                format_token_count(t.cache_creation_tokens)
+            ),
+        );
+    }
+
+    println!();
+    println!("  Burn rate at the last update:");
+    for mode in BURN_RATE_MODES {
+        let rate = session
+            .burn_rate(mode, burn_rate.min_duration_seconds)
+            .map(|r| format!("${:.2}/hr", r))
+            .unwrap_or_else(|| "-".to_string());
+        let configured = if *mode == burn_rate.mode {
+            "  (configured)"
+        } else {
+            ""
+        };
+        println!("    {:<13}{:>10}{}", mode, rate, configured);
+    }
+
+    if !archived.is_empty() {
+        println!();
+        println!("  Archived work periods (auto_reset):");
+        for period in &archived {
+            println!(
+                "    {} - {}  {:>7}  ${:.2}  +{} -{}",
+                local_time(&period.start_time),
+                local_time(&period.last_updated),
+                wall_time(period),
+                period.totals.cost,
+                period.totals.lines_added,
+                period.totals.lines_removed
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Print the sessions updated within the burn rate inactivity threshold
+fn show_active_sessions(db: &SqliteDatabase, json_output: bool) -> Result<()> {
+    let config = crate::config::get_config();
+    let threshold = TimeDelta::minutes(config.burn_rate.inactivity_threshold_minutes as i64);
+    let cutoff = Local::now() - threshold;
+
+    let filter = SessionFilter {
+        since: Some(cutoff.format("%Y-%m-%d").to_string()),
+        ..SessionFilter::default()
+    };
+    let sessions: Vec<_> = db
+        .find_sessions(&filter, None, 0)?
+        .into_iter()
+        .filter(|s| parse_time(&s.last_updated).is_some_and(|t| t >= cutoff))
+        .collect();
+
+    if json_output {
+        let output = json!({
+            "inactivity_threshold_minutes": config.burn_rate.inactivity_threshold_minutes,
+            "sessions": sessions.iter().map(session_json).collect::<Vec<_>>(),
+        });
+        println!("{}", serde_json::to_string(&output)?);
+        return Ok(());
+    }
+
+    if sessions.is_empty() {
+        println!(
+            "No sessions updated in the last {} minutes.",
+            config.burn_rate.inactivity_threshold_minutes
+        );
+        return Ok(());
+    }
+    print_session_table(&sessions);
+    Ok(())
+}
+
+/// Print sessions as a table with abbreviated IDs
+fn print_session_table(sessions: &[SessionSummary]) {
+    use crate::layout::format_token_count;
+
+    println!(
+        "{:<10}  {:<16}  {:>7}  {:>7}  {:<14}  {:>8}  {:>7}  Workspace",
+        "Session", "Last update", "Wall", "Active", "Model", "Cost", "Tokens"
+    );
+    println!("{}", "-".repeat(96));
+    for session in sessions {
+        let id: String = session.session_id.chars().take(8).collect();
+        let model: String = session
+            .model_name
+            .as_deref()
+            .unwrap_or("-")
+            .chars()
+            .take(14)
+            .collect();
+        println!(
+            "{:<10}  {:<16}  {:>7}  {:>7}  {:<14}  {:>8}  {:>7}  {}",
+            if session.archived {
+                format!("{}*", id)
+            } else {
+                id
+            },
+            local_time(&session.last_updated),
+            wall_time(session),
+            session
+                .active_time_seconds
+                .filter(|t| *t > 0)
+                .map(crate::display::format_duration)
+                .unwrap_or_else(|| "-".to_string()),
+            model,
+            format!("${:.2}", session.totals.cost),
+            if session.archived {
+                "-".to_string()
+            } else {
+                format_token_count(session.totals.tokens())
+            },
+            session
+                .workspace_dir
+                .as_deref()
+                .map(crate::utils::shorten_path)
+                .unwrap_or_default()
+        );
+    }
+    if sessions.iter().any(|s| s.archived) {
+        println!();
+        println!("* archived work period (auto_reset mode)");
+    }
+}
+
+fn session_json(session: &SessionSummary) -> serde_json::Value {
+    let config = crate::config::get_config();
+    let min_duration = config.burn_rate.min_duration_seconds;
+    let t = &session.totals;
+    let burn_rate: serde_json::Map<_, _> = BURN_RATE_MODES
+        .iter()
+        .map(|mode| {
+            (
+                mode.to_string(),
+                json!(session.burn_rate(mode, min_duration)),
+            )
+        })
+        .collect();
+    json!({
+        "session_id": session.session_id,
+        "archived": session.archived,
+        "start_time": session.start_time,
+        "last_updated": session.last_updated,
+        "wall_time_seconds": session.wall_time_seconds(),
+        "active_time_seconds": session.active_time_seconds,
+        "model": session.model_name,
+        "workspace": session.workspace_dir,
+        "device_id": session.device_id,
+        "cost": t.cost,
+        "computed_cost": t.computed_cost,
+        "lines_added": t.lines_added,
+        "lines_removed": t.lines_removed,
+        "input_tokens": t.input_tokens,
+        "output_tokens": t.output_tokens,
+        "cache_read_tokens": t.cache_read_tokens,
+        "cache_creation_tokens": t.cache_creation_tokens,
+        "total_tokens": t.tokens(),
+        "burn_rate": burn_rate,
+    })
+}
+
+fn parse_time(timestamp: &str) -> Option<DateTime<Local>> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|t| t.with_timezone(&Local))
+}
+
+/// A stored timestamp in local time, or as stored when it does not parse
+fn local_time(timestamp: &str) -> String {
+    parse_time(timestamp)
+        .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|| timestamp.to_string())
+}
+
+fn wall_time(session: &SessionSummary) -> String {
+    session
+        .wall_time_seconds()
+        .map(crate::display::format_duration)
+        .unwrap_or_else(|| "-".to_string())
+}
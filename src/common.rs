@@ -6,7 +6,8 @@
 use crate::error::Result;
 use chrono::Local;
 use sha2::{Digest, Sha256};
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
 
 /// Gets the application data directory using XDG Base Directory specification.
 ///
@@ -64,6 +65,57 @@ pub fn get_config_dir() -> PathBuf {
     base_dir.join("claudia-statusline")
 }
 
+/// Gets a cache directory of the application, such as
+/// `~/.cache/claudia-statusline/git-status/` for `name = "git-status"`.
+///
+/// Returns `None` when the platform has no cache directory.
+pub fn get_cache_dir(name: &str) -> Option<PathBuf> {
+    Some(dirs::cache_dir()?.join("claudia-statusline").join(name))
+}
+
+/// Creates a directory, and any missing parents, with owner-only permissions
+/// (0o700 on Unix).
+pub fn create_private_dir(dir: &Path) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        fs::DirBuilder::new()
+            .mode(0o700)
+            .recursive(true)
+            .create(dir)
+    }
+
+    #[cfg(not(unix))]
+    {
+        fs::create_dir_all(dir)
+    }
+}
+
+/// Writes a cache file atomically (temp file + rename), creating its directory
+/// with [`create_private_dir`].
+///
+/// Concurrent statusline processes never see a partly written file.
+pub fn write_cache_file(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        create_private_dir(dir)?;
+    }
+    let tmp = path.with_extension(format!("tmp.{}", std::process::id()));
+    fs::write(&tmp, contents)?;
+    if let Err(e) = fs::rename(&tmp, path) {
+        let _ = fs::remove_file(&tmp);
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Names a cache file after a path: the first 64 bits of the SHA-256 of the
+/// path, in hex, followed by `suffix`.
+pub fn cache_file_name(path: &Path, suffix: &str) -> String {
+    let digest = Sha256::digest(path.to_string_lossy().as_bytes());
+    let hex: String = digest[..8].iter().map(|b| format!("{:02x}", b)).collect();
+    format!("{}{}", hex, suffix)
+}
+
 /// Gets the current timestamp in ISO 8601 format.
 ///
 /// # Example
@@ -261,4 +313,25 @@ mod tests {
         let device_id2 = get_device_id();
         assert_eq!(device_id, device_id2);
     }
+
+    #[test]
+    fn test_write_cache_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().join("cache").join("kind");
+        let path = dir.join(cache_file_name(Path::new("/src/app"), ".json"));
+        assert_eq!(path.file_name().unwrap().len(), 16 + ".json".len());
+
+        write_cache_file(&path, b"one").unwrap();
+        write_cache_file(&path, b"two").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"two");
+        // No temp files are left behind
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&dir).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o700);
+        }
+    }
 }
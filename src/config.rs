@@ -24,6 +24,18 @@ pub struct Config {
     /// Cost thresholds configuration
     pub cost: CostConfig,
 
+    /// Model pricing for token-derived cost
+    pub pricing: PricingConfig,
+
+    /// Spending budgets
+    pub budget: BudgetConfig,
+
+    /// Spend and token forecasting
+    pub forecast: ForecastConfig,
+
+    /// Rolling 5-hour usage blocks
+    pub blocks: BlocksConfig,
+
     /// Database configuration
     pub database: DatabaseConfig,
 
@@ -250,6 +262,140 @@ pub struct CostConfig {
     pub medium_threshold: f64,
 }
 
+/// Model pricing configuration for computing cost from token usage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PricingConfig {
+    /// Show `{cost_divergence}` when the computed session cost differs from the
+    /// cost reported by Claude Code by at least this percentage
+    pub divergence_threshold_pct: f64,
+
+    /// Per-model rates that override or extend the built-in pricing table.
+    /// Keys are matched against the model id or display name (case-insensitive,
+    /// dots and spaces treated as dashes); the longest matching key wins.
+    ///
+    /// Example in config.toml:
+    /// ```toml
+    /// [pricing.models.sonnet-4-5]
+    /// input = 3.0
+    /// output = 15.0
+    /// ```
+    #[serde(skip_serializing_if = "std::collections::HashMap::is_empty")]
+    pub models: std::collections::HashMap<String, ModelPricing>,
+}
+
+/// Spending budget configuration
+///
+/// Global limits are checked against `daily_stats` (daily, weekly) and
+/// `monthly_stats` (monthly); workspace limits against the sessions recorded
+/// under that directory. Unset limits are not tracked.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BudgetConfig {
+    /// Limit for today's spend across all sessions in USD
+    pub daily: Option<f64>,
+
+    /// Limit for this week's spend (Monday to Sunday) in USD
+    pub weekly: Option<f64>,
+
+    /// Limit for this calendar month's spend in USD
+    pub monthly: Option<f64>,
+
+    /// Percentage of a budget at which it is shown in the warning color
+    pub warning_pct: f64,
+
+    /// Percentage of a budget at which it is shown in the critical color
+    pub critical_pct: f64,
+
+    /// Cost counted against budgets: "reported" (Claude Code's cost) or
+    /// "computed" (cost priced from transcript token usage)
+    pub cost_source: String,
+
+    /// Per-workspace limits keyed by directory (`~` is expanded). A workspace
+    /// budget covers sessions in that directory and any directory below it.
+    ///
+    /// Example in config.toml:
+    /// ```toml
+    /// [budget.workspaces."~/work/client-a"]
+    /// monthly = 150.0
+    /// ```
+    #[serde(skip_serializing_if = "std::collections::HashMap::is_empty")]
+    pub workspaces: std::collections::HashMap<String, BudgetLimits>,
+}
+
+/// Spend forecasting configuration
+///
+/// Forecasts combine the current session's burn rate with the typical spend for
+/// the same weekday in `daily_stats`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ForecastConfig {
+    /// Enable the `{forecast_*}` variables
+    pub enabled: bool,
+
+    /// Days of `daily_stats` history used to estimate a typical day
+    pub history_days: u32,
+
+    /// Smoothing factor (0.0-1.0) for the moving average used when there is not
+    /// yet enough history for the same weekday; higher favours recent days
+    pub ewma_alpha: f64,
+
+    /// Hours ahead the current burn rate is assumed to continue today
+    pub burn_horizon_hours: f64,
+}
+
+/// Rolling 5-hour usage block configuration
+///
+/// Blocks are rebuilt from the timestamped token usage recorded from transcripts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BlocksConfig {
+    /// Enable the `{block_*}` variables and record usage for `statusline blocks`
+    pub enabled: bool,
+
+    /// Tokens a block may use before it is exhausted; when unset, the largest
+    /// past block within `history_days` is used
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_limit: Option<u64>,
+
+    /// Days of past blocks searched for the largest block
+    pub history_days: u32,
+}
+
+/// Daily, weekly and monthly limits for one workspace in USD
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BudgetLimits {
+    pub daily: Option<f64>,
+    pub weekly: Option<f64>,
+    pub monthly: Option<f64>,
+}
+
+/// Prices for one model in USD per million tokens
+///
+/// Cache prices that are not set are derived from `input` with the standard
+/// multipliers: 1.25x for 5-minute writes, 2x for 1-hour writes, 0.1x for reads.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelPricing {
+    /// Input tokens
+    pub input: f64,
+
+    /// Output tokens
+    pub output: f64,
+
+    /// Cache writes with a 5-minute TTL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_write_5m: Option<f64>,
+
+    /// Cache writes with a 1-hour TTL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_write_1h: Option<f64>,
+
+    /// Cache reads
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_read: Option<f64>,
+}
+
 /// Database configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
@@ -270,7 +416,8 @@ pub struct DatabaseConfig {
     #[serde(skip_serializing)]
     pub json_backup: bool,
 
-    /// Retention period for session data in days (0 = keep forever)
+    /// Retention period for session data, usage entries and transcript errors in days
+    /// (0 = keep forever); usage entries are kept for at least `blocks.history_days`
     pub retention_days_sessions: Option<u32>,
 
     /// Retention period for daily stats in days (0 = keep forever)
@@ -326,8 +473,14 @@ pub struct TranscriptConfig {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct GitConfig {
-    /// Timeout for git operations in milliseconds
+    /// Timeout for git operations (and jj/hg commands) in milliseconds
     pub timeout_ms: u32,
+
+    /// Maximum age in seconds of a cached git status or diff before it is recomputed
+    ///
+    /// The cache is also invalidated when the index, HEAD, refs or directory mtimes
+    /// change; the max age catches in-place edits to tracked files. 0 disables caching.
+    pub cache_max_age_seconds: u64,
 }
 
 /// Burn rate calculation configuration
@@ -417,6 +570,32 @@ pub struct BurnRateConfig {
 /// | `{dir_short}` | `app` | Just the directory name |
 /// | `{git}` | `main +2 ~1` | Full git info |
 /// | `{git_branch}` | `main` | Branch name only |
+/// | `{git_status}` | `+2 ~1` | Change counts only |
+/// | `{git_root}` | `~/src/monorepo` | Repository root |
+/// | `{git_subdir}` | `crates/foo` | Path below the repository root |
+/// | `{git_ahead}` | `2` | Commits ahead of upstream |
+/// | `{git_behind}` | `1` | Commits behind upstream |
+/// | `{git_stash}` | `3` | Stash entries |
+/// | `{git_upstream}` | `origin/main` | Upstream branch |
+/// | `{git_detached}` | `a1b2c3d` | Detached HEAD short SHA |
+/// | `{git_state}` | `REBASE 3/7 !2` | In-progress operation and conflicts |
+/// | `{git_commit}` | `a1b2c3d` | HEAD commit short hash |
+/// | `{git_commit_subject}` | `Fix parser edge case` | HEAD commit subject |
+/// | `{git_commit_author}` | `Jane Doe` | HEAD commit author |
+/// | `{git_commit_age}` | `12m ago` | Time since the HEAD commit |
+/// | `{git_tag}` | `v1.4.0` | Nearest tag |
+/// | `{git_diff}` | `+42 -7` | Uncommitted lines per git |
+/// | `{git_diff_added}` | `42` | Uncommitted lines added |
+/// | `{git_diff_removed}` | `7` | Uncommitted lines removed |
+/// | `{git_diff_files}` | `3` | Files with uncommitted changes |
+/// | `{git_diff_staged}` | `+30 -5` | Staged line changes |
+/// | `{git_diff_unstaged}` | `+12 -2` | Unstaged line changes |
+/// | `{vcs}` | `jj` | VCS of the repository (git, jj, hg) |
+/// | `{vcs_branch}` | `main` | Branch or bookmark |
+/// | `{vcs_change}` | `kxqzvoyl` | jj change id or commit hash |
+/// | `{vcs_status}` | `+2 ~1 ?3` | Change counts |
+/// | `{vcs_conflicts}` | `2` | Conflicted files |
+/// | `{vcs_root}` | `~/src/app` | Repository root |
 /// | `{context}` | `75% [=====>----]` | Context bar with percentage |
 /// | `{context_pct}` | `75` | Just the percentage number |
 /// | `{context_tokens}` | `150k/200k` | Token counts |
@@ -427,11 +606,42 @@ pub struct BurnRateConfig {
 /// | `{cost}` | `$12.50` | Session cost |
 /// | `{burn_rate}` | `$3.50/hr` | Cost per hour |
 /// | `{daily_total}` | `$45.00` | Today's total cost |
+/// | `{cost_computed}` | `$11.80` | Session cost priced from token usage |
+/// | `{cost_computed_today}` | `$42.10` | Today's token-derived cost |
+/// | `{cost_computed_month}` | `$310.45` | This month's token-derived cost |
+/// | `{cost_divergence}` | `+15%` | Computed vs reported cost, when beyond the threshold |
+/// | `{cache_saved}` | `$4.20` | Session savings from prompt caching vs uncached |
+/// | `{cache_saved_today}` | `$18.75` | Today's savings from prompt caching |
+/// | `{budget_remaining}` | `$60.00` | Amount left in the tightest spending budget |
+/// | `{budget_pct}` | `80%` | Percentage of the tightest budget spent |
+/// | `{budget_bar}` | `[========>-]` | Progress bar of the tightest budget |
+/// | `{budget_name}` | `monthly` | Which budget the `{budget_*}` variables describe |
+/// | `{forecast_today}` | `$18.40` | Projected spend for today |
+/// | `{forecast_month}` | `$412.00` | Projected spend for this month |
+/// | `{forecast_confidence}` | `medium` | Confidence in the forecast (low/medium/high) |
+/// | `{block_elapsed}` | `2h15m` | Time since the current 5-hour usage block started |
+/// | `{block_remaining}` | `2h45m` | Time until the current usage block resets |
+/// | `{block_tokens}` | `3.4M` | Tokens used in the current usage block |
+/// | `{block_cost}` | `$12.40` | Cost of the current usage block |
+/// | `{block_exhausts}` | `1h20m` | Time until the block limit is reached, when projected before the reset |
+/// | `{limit_reset}` | `1h56m` | Time until a usage limit resets, when one is in effect |
+/// | `{limit_reset_at}` | `15:00` | When the usage limit resets |
+/// | `{api_errors}` | `2` | API errors in this session's transcript |
 /// | `{lines}` | `+50 -10` | Lines changed |
 /// | `{token_rate}` | `12.5 tok/s` | Token processing rate (combined format) |
 /// | `{token_rate_only}` | `12.5 tok/s` | Token rate only |
 /// | `{token_session_total}` | `1.5K` | Session token total |
 /// | `{token_daily_total}` | `day: 25K` | Daily token total |
+/// | `{wall_time}` | `12m` | Wall-clock session time (from Claude Code) |
+/// | `{api_time}` | `3m` | Time spent waiting on the API |
+/// | `{api_pct}` | `25%` | API time as a share of wall time |
+/// | `{time}` | `14:05` | Local clock time |
+/// | `{claude_version}` | `v1.0.80` | Claude Code version |
+/// | `{output_style}` | `Explanatory` | Active output style |
+/// | `{model_id}` | `claude-opus-4-1` | Model identifier |
+/// | `{project_dir}` | `~/projects/app` | Project root (shortened) |
+/// | `{project_name}` | `app` | Project root basename |
+/// | `{exceeds_200k}` | `>200k` | Last request exceeded 200k tokens |
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct LayoutConfig {
@@ -466,6 +676,104 @@ pub struct LayoutConfig {
     /// When false, unknown variables render as empty string.
     #[serde(default = "default_true")]
     pub show_unknown_vars: bool,
+
+    /// Terminal columns each line must fit in: a number (0 = no limit, the
+    /// default), or `"auto"` for the `COLUMNS` environment variable
+    ///
+    /// Lines that are too wide collapse and then drop segments, lowest
+    /// `priority` first (see [`ComponentsConfig`]).
+    pub width: LayoutWidth,
+
+    /// Repeated between a line's left group and its right-aligned group, split
+    /// by `{fill}`, to pad the line to the target width (default: " ")
+    pub fill: String,
+
+    /// Render each `{sep}`-delimited segment as a block on the background of
+    /// its component (the theme's `*_bg` colors), joined by transition glyphs
+    pub powerline: bool,
+
+    /// Powerline transition glyphs: "arrow" (default) and "rounded" need a
+    /// Nerd Font or powerline-patched font, "ascii" works in any terminal
+    pub powerline_style: String,
+}
+
+impl LayoutConfig {
+    /// Whether a format or a non-default preset replaces the built-in statusline
+    pub fn is_custom(&self) -> bool {
+        !self.format.is_empty() || self.preset.to_lowercase() != "default"
+    }
+
+    /// Columns the statusline has to fit in, or `None` for unlimited
+    ///
+    /// `COLUMNS` is only read with `width = "auto"`: shells do not export it, and
+    /// a value inherited from elsewhere says nothing about where the line is shown.
+    pub fn target_width(&self) -> Option<usize> {
+        match self.width {
+            LayoutWidth::Columns(0) => None,
+            LayoutWidth::Columns(columns) => Some(columns),
+            LayoutWidth::Auto => env::var("COLUMNS")
+                .ok()
+                .and_then(|v| v.trim().parse::<usize>().ok())
+                .filter(|&columns| columns > 0),
+        }
+    }
+}
+
+/// `[layout] width`: a number of columns, or `"auto"` for the `COLUMNS`
+/// environment variable
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutWidth {
+    Columns(usize),
+    Auto,
+}
+
+impl Default for LayoutWidth {
+    fn default() -> Self {
+        LayoutWidth::Columns(0)
+    }
+}
+
+impl std::str::FromStr for LayoutWidth {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        if s.trim().eq_ignore_ascii_case("auto") {
+            return Ok(LayoutWidth::Auto);
+        }
+        s.trim()
+            .parse()
+            .map(LayoutWidth::Columns)
+            .map_err(|_| format!("width must be a number of columns or \"auto\", not {:?}", s))
+    }
+}
+
+impl Serialize for LayoutWidth {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        match self {
+            LayoutWidth::Columns(columns) => serializer.serialize_u64(*columns as u64),
+            LayoutWidth::Auto => serializer.serialize_str("auto"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for LayoutWidth {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Columns(usize),
+            Name(String),
+        }
+        match Raw::deserialize(deserializer)? {
+            Raw::Columns(columns) => Ok(LayoutWidth::Columns(columns)),
+            Raw::Name(name) => name.parse().map_err(serde::de::Error::custom),
+        }
+    }
 }
 
 /// Per-component configuration for fine-grained customization
@@ -503,13 +811,22 @@ pub struct DirectoryComponentConfig {
 
     /// Override theme color (empty = use theme)
     pub color: String,
+
+    /// Priority when the line is too wide (default: 80); lower priorities collapse
+    /// or drop first
+    pub priority: u8,
+
+    /// Template the segment shows once collapsed (default: "{dir_short}")
+    pub compact: String,
 }
 
 /// Git component configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct GitComponentConfig {
-    /// Format: "full" (default), "branch", "status"
+    /// Format: "full" (default), "branch", "status", "tracking", "full_tracking"
+    ///
+    /// The `*tracking` formats append ahead/behind/stash counts (e.g. `↑2 ↓1 ≡3`).
     pub format: String,
 
     /// When to show: "always" (default), "dirty", "never"
@@ -517,6 +834,13 @@ pub struct GitComponentConfig {
 
     /// Override theme color (empty = use theme)
     pub color: String,
+
+    /// Priority when the line is too wide (default: 60); lower priorities collapse
+    /// or drop first
+    pub priority: u8,
+
+    /// Template the segment shows once collapsed (default: "{git_branch}")
+    pub compact: String,
 }
 
 /// Context component configuration
@@ -531,6 +855,13 @@ pub struct ContextComponentConfig {
 
     /// Show token counts
     pub show_tokens: bool,
+
+    /// Priority when the line is too wide (default: 70); lower priorities collapse
+    /// or drop first
+    pub priority: u8,
+
+    /// Template the segment shows once collapsed (default: "{context_pct}")
+    pub compact: String,
 }
 
 /// Cost component configuration
@@ -542,6 +873,13 @@ pub struct CostComponentConfig {
 
     /// Override theme color (empty = use theme)
     pub color: String,
+
+    /// Priority when the line is too wide (default: 50); lower priorities collapse
+    /// or drop first
+    pub priority: u8,
+
+    /// Template the segment shows once collapsed (default: "{cost_short}")
+    pub compact: String,
 }
 
 /// Model component configuration
@@ -558,6 +896,13 @@ pub struct ModelComponentConfig {
 
     /// Override theme color (empty = use theme)
     pub color: String,
+
+    /// Priority when the line is too wide (default: 90); lower priorities collapse
+    /// or drop first
+    pub priority: u8,
+
+    /// Template the segment shows once collapsed (default: empty = never collapses)
+    pub compact: String,
 }
 
 /// Token rate component configuration
@@ -596,6 +941,13 @@ pub struct TokenRateComponentConfig {
 
     /// Override theme color (empty = use theme)
     pub color: String,
+
+    /// Priority when the line is too wide (default: 30); lower priorities collapse
+    /// or drop first
+    pub priority: u8,
+
+    /// Template the segment shows once collapsed (default: "{token_rate_only}")
+    pub compact: String,
 }
 
 impl Default for LayoutConfig {
@@ -606,6 +958,10 @@ impl Default for LayoutConfig {
             separator: " \u{2022} ".to_string(), // " • "
             components: ComponentsConfig::default(),
             show_unknown_vars: true,
+            width: LayoutWidth::default(),
+            fill: " ".to_string(),
+            powerline: false,
+            powerline_style: "arrow".to_string(),
         }
     }
 }
@@ -616,6 +972,8 @@ impl Default for DirectoryComponentConfig {
             format: "short".to_string(),
             max_length: 0,
             color: String::new(),
+            priority: 80,
+            compact: "{dir_short}".to_string(),
         }
     }
 }
@@ -626,6 +984,8 @@ impl Default for GitComponentConfig {
             format: "full".to_string(),
             show_when: "always".to_string(),
             color: String::new(),
+            priority: 60,
+            compact: "{git_branch}".to_string(),
         }
     }
 }
@@ -636,6 +996,8 @@ impl Default for ContextComponentConfig {
             format: "full".to_string(),
             bar_width: None,
             show_tokens: true,
+            priority: 70,
+            compact: "{context_pct}".to_string(),
         }
     }
 }
@@ -645,6 +1007,8 @@ impl Default for CostComponentConfig {
         Self {
             format: "full".to_string(),
             color: String::new(),
+            priority: 50,
+            compact: "{cost_short}".to_string(),
         }
     }
 }
@@ -654,6 +1018,8 @@ impl Default for ModelComponentConfig {
         Self {
             format: "abbreviation".to_string(),
             color: String::new(),
+            priority: 90,
+            compact: String::new(),
         }
     }
 }
@@ -666,6 +1032,8 @@ impl Default for TokenRateComponentConfig {
             show_session_total: false,
             show_daily_total: false,
             color: String::new(),
+            priority: 30,
+            compact: "{token_rate_only}".to_string(),
         }
     }
 }
@@ -885,6 +1253,50 @@ impl Default for CostConfig {
     }
 }
 
+impl Default for PricingConfig {
+    fn default() -> Self {
+        PricingConfig {
+            divergence_threshold_pct: 10.0,
+            models: std::collections::HashMap::new(),
+        }
+    }
+}
+
+impl Default for BudgetConfig {
+    fn default() -> Self {
+        BudgetConfig {
+            daily: None,
+            weekly: None,
+            monthly: None,
+            warning_pct: 75.0,
+            critical_pct: 90.0,
+            cost_source: "reported".to_string(),
+            workspaces: std::collections::HashMap::new(),
+        }
+    }
+}
+
+impl Default for ForecastConfig {
+    fn default() -> Self {
+        ForecastConfig {
+            enabled: true,
+            history_days: 56,
+            ewma_alpha: 0.3,
+            burn_horizon_hours: 2.0,
+        }
+    }
+}
+
+impl Default for BlocksConfig {
+    fn default() -> Self {
+        BlocksConfig {
+            enabled: true,
+            token_limit: None,
+            history_days: 30,
+        }
+    }
+}
+
 impl Default for DatabaseConfig {
     fn default() -> Self {
         DatabaseConfig {
@@ -953,6 +1365,7 @@ impl Default for GitConfig {
     fn default() -> Self {
         GitConfig {
             timeout_ms: 200, // 200ms default timeout for git operations
+            cache_max_age_seconds: 10,
         }
     }
 }
@@ -1208,6 +1621,65 @@ learning_confidence_threshold = 0.7
 low_threshold = 5.0      # Green below this
 medium_threshold = 20.0  # Yellow between low and medium, red above
 
+[pricing]
+# Cost computed from transcript token usage ({cost_computed}) is flagged with
+# {cost_divergence} when it differs from Claude Code's reported cost by this much
+divergence_threshold_pct = 10.0
+
+# Override or add model rates in USD per million tokens. Keys match model ids
+# and names by substring, longest first. Cache rates default to 1.25x (5m write),
+# 2x (1h write) and 0.1x (read) of the input rate.
+# [pricing.models.sonnet-4-5]
+# input = 3.0
+# output = 15.0
+# cache_write_5m = 3.75
+# cache_write_1h = 6.0
+# cache_read = 0.3
+
+[budget]
+# Spending limits in USD shown by {budget_remaining}, {budget_pct}, {budget_bar}
+# and `statusline budget status`. Unset limits are not tracked.
+# daily = 20.0
+# weekly = 80.0
+# monthly = 300.0   # Calendar month
+
+# Percentage of a budget at which it turns to the theme's budget_warning and
+# budget_critical colors
+warning_pct = 75.0
+critical_pct = 90.0
+
+# Cost counted against budgets: "reported" (Claude Code) or "computed" (token pricing)
+cost_source = "reported"
+
+# Limits for one workspace, covering sessions in that directory and below it
+# [budget.workspaces."~/work/client-a"]
+# monthly = 150.0
+
+[forecast]
+# End-of-day and end-of-month projections ({forecast_today}, {forecast_month})
+enabled = true
+
+# Days of history used to estimate a typical day for each weekday
+history_days = 56
+
+# Smoothing for the moving average used until a weekday has enough history
+ewma_alpha = 0.3
+
+# Hours the current burn rate is assumed to continue today
+burn_horizon_hours = 2.0
+
+[blocks]
+# Rolling 5-hour usage blocks ({block_elapsed}, {block_remaining}, {block_tokens},
+# {block_cost}) and `statusline blocks`
+enabled = true
+
+# Tokens per block before it is exhausted, for {block_exhausts}. When unset, the
+# largest block in the last history_days is used.
+# token_limit = 20000000
+
+# Days of past blocks searched for the largest block
+history_days = 30
+
 [database]
 # Database connection settings
 busy_timeout_ms = 10000
@@ -1254,6 +1726,7 @@ backoff_factor = 2.0
 [git]
 # Git operation settings
 timeout_ms = 200  # Timeout for git operations
+cache_max_age_seconds = 10  # Reuse git status/diff across refreshes for up to N seconds (0 = disabled)
 
 [burn_rate]
 # Burn rate calculation mode
@@ -1378,6 +1851,13 @@ fn build_config() -> Config {
             config.display.theme = theme;
         }
 
+        // Override layout width from environment (--width sets it)
+        if let Ok(val) = env::var("STATUSLINE_WIDTH") {
+            if let Ok(width) = val.parse::<LayoutWidth>() {
+                config.layout.width = width;
+            }
+        }
+
         // Override show_context_tokens from environment if set (for testing)
         if let Ok(val) = env::var("STATUSLINE_SHOW_CONTEXT_TOKENS") {
             config.display.show_context_tokens = val == "true" || val == "1";
@@ -1536,6 +2016,60 @@ mod tests {
         assert!(example.contains("window_size"));
     }
 
+    #[test]
+    fn test_pricing_config_models() {
+        let toml = "[pricing]\ndivergence_threshold_pct = 25.0\n\n\
+                    [pricing.models.opus-4-5]\ninput = 5.0\noutput = 25.0\ncache_read = 0.4\n";
+        let config: Config = toml::from_str(toml).expect("pricing config should parse");
+        assert_eq!(config.pricing.divergence_threshold_pct, 25.0);
+        let opus = &config.pricing.models["opus-4-5"];
+        assert_eq!((opus.input, opus.output), (5.0, 25.0));
+        assert_eq!(opus.cache_read, Some(0.4));
+        assert_eq!(opus.cache_write_5m, None);
+
+        let default = Config::default();
+        assert_eq!(default.pricing.divergence_threshold_pct, 10.0);
+        assert!(default.pricing.models.is_empty());
+    }
+
+    #[test]
+    fn test_budget_config() {
+        let toml = "[budget]\nmonthly = 300.0\ncost_source = \"computed\"\n\n\
+                    [budget.workspaces.\"~/work/client-a\"]\ndaily = 10.0\n";
+        let config: Config = toml::from_str(toml).expect("budget config should parse");
+        assert_eq!(config.budget.monthly, Some(300.0));
+        assert_eq!(config.budget.daily, None);
+        assert_eq!(config.budget.cost_source, "computed");
+        assert_eq!(config.budget.warning_pct, 75.0);
+        let client = &config.budget.workspaces["~/work/client-a"];
+        assert_eq!(client.daily, Some(10.0));
+        assert_eq!(client.monthly, None);
+
+        let default = Config::default();
+        assert_eq!(default.budget.critical_pct, 90.0);
+        assert!(default.budget.workspaces.is_empty());
+    }
+
+    #[test]
+    fn test_forecast_config() {
+        let toml = "[forecast]\nhistory_days = 28\nburn_horizon_hours = 1.5\n";
+        let config: Config = toml::from_str(toml).expect("forecast config should parse");
+        assert_eq!(config.forecast.history_days, 28);
+        assert_eq!(config.forecast.burn_horizon_hours, 1.5);
+        assert!(config.forecast.enabled);
+        assert_eq!(config.forecast.ewma_alpha, 0.3);
+    }
+
+    #[test]
+    fn test_blocks_config() {
+        let toml = "[blocks]\ntoken_limit = 5000000\n";
+        let config: Config = toml::from_str(toml).expect("blocks config should parse");
+        assert_eq!(config.blocks.token_limit, Some(5_000_000));
+        assert_eq!(config.blocks.history_days, 30);
+        assert!(config.blocks.enabled);
+        assert_eq!(Config::default().blocks.token_limit, None);
+    }
+
     #[test]
     fn test_database_config_default_json_backup_false() {
         // D-03: the v3.0.0 default for json_backup is false.
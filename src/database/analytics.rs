@@ -1,5 +1,6 @@
-use super::SqliteDatabase;
-use rusqlite::Result;
+use super::{SessionFilter, SessionSummary, SqliteDatabase, UsageTotals};
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Result, Row};
 
 /// Session data with model name for rebuilding learned context windows
 #[derive(Debug)]
@@ -146,4 +147,149 @@ impl SqliteDatabase {
 
         Ok(sessions)
     }
+
+    /// Get daily totals between `since` and `until` (YYYY-MM-DD, inclusive), oldest first
+    ///
+    /// `sessions` is the number of sessions active on each day.
+    pub fn get_daily_totals(
+        &self,
+        since: Option<&str>,
+        until: Option<&str>,
+    ) -> Result<Vec<(String, UsageTotals)>> {
+        self.get_period_totals("daily_stats", "date", since, until)
+    }
+
+    /// Get monthly totals between `since` and `until` (YYYY-MM, inclusive), oldest first
+    pub fn get_monthly_totals(
+        &self,
+        since: Option<&str>,
+        until: Option<&str>,
+    ) -> Result<Vec<(String, UsageTotals)>> {
+        self.get_period_totals("monthly_stats", "month", since, until)
+    }
+
+    fn get_period_totals(
+        &self,
+        table: &str,
+        key: &str,
+        since: Option<&str>,
+        until: Option<&str>,
+    ) -> Result<Vec<(String, UsageTotals)>> {
+        let conn = self.get_connection()?;
+        // Table and column names come from the two callers above, never from input
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {key}, COALESCE(session_count, 0), COALESCE(total_cost, 0.0),
+                    COALESCE(computed_cost, 0.0), COALESCE(total_lines_added, 0),
+                    COALESCE(total_lines_removed, 0), COALESCE(total_input_tokens, 0),
+                    COALESCE(total_output_tokens, 0), COALESCE(total_cache_read_tokens, 0),
+                    COALESCE(total_cache_creation_tokens, 0)
+             FROM {table}
+             WHERE (?1 IS NULL OR {key} >= ?1) AND (?2 IS NULL OR {key} <= ?2)
+             ORDER BY {key}"
+        ))?;
+        let rows = stmt.query_map(params![since, until], |row| {
+            Ok((row.get(0)?, usage_totals(row, 1)?))
+        })?;
+        rows.collect()
+    }
+
+    /// Get the sessions matching `filter`, most recently active first
+    ///
+    /// Pages with `limit` (all when None) and `offset`.
+    pub fn find_sessions(
+        &self,
+        filter: &SessionFilter,
+        limit: Option<usize>,
+        offset: usize,
+    ) -> Result<Vec<SessionSummary>> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT * FROM ({FILTERED_SESSIONS}) ORDER BY last_updated DESC LIMIT ?8 OFFSET ?9"
+        ))?;
+        let mut values = session_filter_values(filter);
+        // SQLite reads a negative limit as no limit
+        values.push(Value::Integer(limit.map_or(-1, |l| l as i64)));
+        values.push(Value::Integer(offset as i64));
+        let rows = stmt.query_map(params_from_iter(values), |row| {
+            let active_time: Option<i64> = row.get(6)?;
+            Ok(SessionSummary {
+                session_id: row.get(0)?,
+                start_time: row.get(1)?,
+                last_updated: row.get(2)?,
+                model_name: row.get(3)?,
+                workspace_dir: row.get(4)?,
+                device_id: row.get(5)?,
+                active_time_seconds: active_time.map(|t| t.max(0) as u64),
+                archived: row.get(7)?,
+                totals: usage_totals(row, 8)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// Count the sessions matching `filter`, for paging through [`Self::find_sessions`]
+    pub fn count_sessions_matching(&self, filter: &SessionFilter) -> Result<usize> {
+        let conn = self.get_connection()?;
+        let count: i64 = conn.query_row(
+            &format!("SELECT COUNT(*) FROM ({FILTERED_SESSIONS})"),
+            params_from_iter(session_filter_values(filter)),
+            |row| row.get(0),
+        )?;
+        Ok(count.max(0) as usize)
+    }
+}
+
+/// Live sessions and archived work periods matching the filter parameters ?1-?7
+///
+/// Compares prefixes with substr() rather than LIKE so '_' and '%' match literally.
+const FILTERED_SESSIONS: &str = "
+    SELECT * FROM (
+        SELECT session_id, start_time, last_updated, model_name, workspace_dir, device_id,
+               active_time_seconds, 0 AS archived,
+               1, COALESCE(cost, 0.0), COALESCE(computed_cost, 0.0),
+               COALESCE(lines_added, 0), COALESCE(lines_removed, 0),
+               COALESCE(total_input_tokens, 0), COALESCE(total_output_tokens, 0),
+               COALESCE(total_cache_read_tokens, 0), COALESCE(total_cache_creation_tokens, 0)
+        FROM sessions
+        UNION ALL
+        SELECT session_id, start_time, end_time, model_name, workspace_dir, device_id,
+               active_time_seconds, 1,
+               1, cost, 0.0, lines_added, lines_removed, 0, 0, 0, 0
+        FROM session_archive WHERE ?7
+    )
+    WHERE (?1 IS NULL OR substr(session_id, 1, length(?1)) = ?1)
+      AND (?2 IS NULL OR workspace_dir = ?2 OR substr(workspace_dir, 1, length(?3)) = ?3)
+      AND (?4 IS NULL OR instr(lower(model_name), lower(?4)) > 0)
+      AND (?5 IS NULL OR substr(last_updated, 1, 10) >= ?5)
+      AND (?6 IS NULL OR substr(last_updated, 1, 10) <= ?6)";
+
+/// Values of the filter parameters ?1-?7 of `FILTERED_SESSIONS`
+fn session_filter_values(filter: &SessionFilter) -> Vec<Value> {
+    let text = |v: Option<&str>| v.map_or(Value::Null, |v| Value::Text(v.to_string()));
+    let workspace = filter.workspace.as_deref().map(|w| w.trim_end_matches('/'));
+    vec![
+        text(filter.session_id.as_deref()),
+        text(workspace),
+        text(workspace.map(|w| format!("{}/", w)).as_deref()),
+        text(filter.model.as_deref()),
+        text(filter.since.as_deref()),
+        text(filter.until.as_deref()),
+        Value::Integer(filter.archived as i64),
+    ]
+}
+
+/// Read the session count, cost, line and token columns starting at `first`
+fn usage_totals(row: &Row, first: usize) -> Result<UsageTotals> {
+    let count = |i: usize| -> Result<u64> { Ok(row.get::<_, i64>(first + i)?.max(0) as u64) };
+    Ok(UsageTotals {
+        sessions: count(0)?,
+        cost: row.get(first + 1)?,
+        computed_cost: row.get(first + 2)?,
+        lines_added: count(3)?,
+        lines_removed: count(4)?,
+        input_tokens: count(5)?,
+        output_tokens: count(6)?,
+        cache_read_tokens: count(7)?,
+        cache_creation_tokens: count(8)?,
+    })
 }
@@ -1,6 +1,6 @@
-use super::SqliteDatabase;
+use super::{ComputedCost, SqliteDatabase};
 use crate::common::current_date;
-use rusqlite::{params, Result};
+use rusqlite::{params, Result, Transaction};
 
 impl SqliteDatabase {
     /// Get today's total cost
@@ -18,6 +18,59 @@ impl SqliteDatabase {
         Ok(total)
     }
 
+    /// Get today's token-derived cost and cache savings across all sessions
+    pub fn get_today_computed_cost(&self) -> Result<ComputedCost> {
+        let conn = self.get_connection()?;
+        let today = current_date();
+        let total = conn
+            .query_row(
+                "SELECT COALESCE(computed_cost, 0.0), COALESCE(cache_saved, 0.0),
+                        COALESCE(cache_write_cost, 0.0)
+                 FROM daily_stats WHERE date = ?1",
+                params![&today],
+                |row| {
+                    Ok(ComputedCost {
+                        cost: row.get(0)?,
+                        cache_saved: row.get(1)?,
+                        cache_write_cost: row.get(2)?,
+                    })
+                },
+            )
+            .unwrap_or_default();
+        Ok(total)
+    }
+
+    /// Get the total cost recorded from `start_date` (YYYY-MM-DD) through today
+    ///
+    /// Sums the token-derived `computed_cost` instead of the reported cost when
+    /// `computed` is set.
+    pub fn get_cost_since(&self, start_date: &str, computed: bool) -> Result<f64> {
+        let conn = self.get_connection()?;
+        let sql = if computed {
+            "SELECT COALESCE(SUM(computed_cost), 0.0) FROM daily_stats WHERE date >= ?1"
+        } else {
+            "SELECT COALESCE(SUM(total_cost), 0.0) FROM daily_stats WHERE date >= ?1"
+        };
+        conn.query_row(sql, params![start_date], |row| row.get(0))
+    }
+
+    /// Get daily cost and total token usage from `start_date` (YYYY-MM-DD) onwards,
+    /// oldest first. Days without activity have no row.
+    pub fn get_daily_history(&self, start_date: &str) -> Result<Vec<(String, f64, u64)>> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT date, COALESCE(total_cost, 0.0),
+                    COALESCE(total_input_tokens, 0) + COALESCE(total_output_tokens, 0) +
+                    COALESCE(total_cache_read_tokens, 0) + COALESCE(total_cache_creation_tokens, 0)
+             FROM daily_stats WHERE date >= ?1 ORDER BY date",
+        )?;
+        let rows = stmt.query_map(params![start_date], |row| {
+            let tokens: i64 = row.get(2)?;
+            Ok((row.get(0)?, row.get(1)?, tokens.max(0) as u64))
+        })?;
+        rows.collect()
+    }
+
     /// Get today's total token usage (sum of all token types)
     ///
     /// Returns the aggregate token count for the current day across all sessions.
@@ -72,6 +125,16 @@ impl SqliteDatabase {
     ) -> Result<()> {
         let mut conn = self.get_connection()?;
         let tx = conn.transaction()?;
+        Self::import_daily_tx(&tx, daily)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// `import_daily` within an open transaction
+    pub(super) fn import_daily_tx(
+        tx: &Transaction,
+        daily: &std::collections::HashMap<String, crate::stats::DailyStats>,
+    ) -> Result<()> {
         for (date, stats) in daily.iter() {
             tx.execute(
                 "INSERT OR IGNORE INTO daily_stats (date, total_cost, total_lines_added, total_lines_removed, session_count)
@@ -85,7 +148,6 @@ impl SqliteDatabase {
                 ],
             )?;
         }
-        tx.commit()?;
         Ok(())
     }
 
@@ -0,0 +1,87 @@
+use super::schema::SessionError;
+use super::SqliteDatabase;
+use crate::retry::{retry_if_retryable, RetryConfig};
+use rusqlite::{params, OptionalExtension, Result};
+
+impl SqliteDatabase {
+    /// Record usage limits and API errors, ignoring ones that were already recorded
+    ///
+    /// Errors are keyed by session, timestamp and kind, so re-reading the same
+    /// transcript lines on every render adds nothing. Returns the number of new rows.
+    pub fn record_session_errors(&self, errors: &[SessionError]) -> Result<usize> {
+        if errors.is_empty() {
+            return Ok(0);
+        }
+        let retry_config = RetryConfig::for_db_ops();
+
+        retry_if_retryable(&retry_config, || {
+            let mut conn = self.get_connection()?;
+            let tx = conn.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+
+            let mut inserted = 0;
+            {
+                let mut stmt = tx.prepare(
+                    "INSERT OR IGNORE INTO session_errors
+                        (session_id, timestamp, kind, message, reset_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                )?;
+                for error in errors {
+                    inserted += stmt.execute(params![
+                        error.session_id,
+                        error.timestamp,
+                        error.kind,
+                        error.message,
+                        error.reset_at,
+                    ])?;
+                }
+            }
+
+            tx.commit()?;
+            Ok(inserted)
+        })
+        .map_err(|e| match e {
+            crate::error::StatuslineError::Database(db_err) => db_err,
+            _ => rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_BUSY),
+                Some(e.to_string()),
+            ),
+        })
+    }
+
+    /// Count the errors of `kind` ("usage_limit" or "api_error") recorded for a session
+    pub fn count_session_errors(&self, session_id: &str, kind: &str) -> Result<u64> {
+        let conn = self.get_connection()?;
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM session_errors WHERE session_id = ?1 AND kind = ?2",
+            params![session_id, kind],
+            |row| row.get(0),
+        )?;
+        Ok(count.max(0) as u64)
+    }
+
+    /// Get the most recent usage limit, in any session, that resets after `now`
+    /// (UTC RFC3339)
+    ///
+    /// Usage limits apply to the whole account, so a limit hit in one session
+    /// holds for every other session too.
+    pub fn get_active_usage_limit(&self, now: &str) -> Result<Option<SessionError>> {
+        let conn = self.get_connection()?;
+        conn.query_row(
+            "SELECT session_id, timestamp, kind, COALESCE(message, ''), reset_at
+             FROM session_errors
+             WHERE kind = 'usage_limit' AND reset_at > ?1
+             ORDER BY timestamp DESC LIMIT 1",
+            params![now],
+            |row| {
+                Ok(SessionError {
+                    session_id: row.get(0)?,
+                    timestamp: row.get(1)?,
+                    kind: row.get(2)?,
+                    message: row.get(3)?,
+                    reset_at: row.get(4)?,
+                })
+            },
+        )
+        .optional()
+    }
+}
@@ -0,0 +1,220 @@
+use super::schema::{TableDump, TableImport};
+use super::SqliteDatabase;
+use crate::retry::{retry_if_retryable, RetryConfig};
+use rusqlite::types::Value;
+use rusqlite::{params_from_iter, Connection, Result, Transaction};
+
+/// Tables carried by `export` and `import-data`, with the columns that identify a row
+///
+/// Archived work periods are identified by their span rather than by the local
+/// `id`, which means nothing in another database.
+pub const EXPORT_TABLES: [(&str, &[&str]); 9] = [
+    ("sessions", &["session_id"]),
+    ("session_archive", &["session_id", "start_time", "end_time"]),
+    ("daily_stats", &["date"]),
+    ("monthly_stats", &["month"]),
+    ("learned_context_windows", &["model_name"]),
+    ("usage_entries", &["message_id"]),
+    ("session_errors", &["session_id", "timestamp", "kind"]),
+    ("session_daily_costs", &["session_id", "date"]),
+    ("meta", &["key"]),
+];
+
+fn key_columns(table: &str) -> Result<&'static [&'static str]> {
+    EXPORT_TABLES
+        .iter()
+        .find(|(name, _)| *name == table)
+        .map(|(_, keys)| *keys)
+        .ok_or_else(|| misuse(format!("Table {} cannot be exported", table)))
+}
+
+fn misuse(message: String) -> rusqlite::Error {
+    rusqlite::Error::SqliteFailure(
+        rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_MISUSE),
+        Some(message),
+    )
+}
+
+/// Columns of `table` in schema order, leaving out `session_archive.id`
+fn table_columns(conn: &Connection, table: &str) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare("SELECT name FROM pragma_table_info(?1) ORDER BY cid")?;
+    let columns = stmt
+        .query_map([table], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>>>()?;
+    Ok(columns
+        .into_iter()
+        .filter(|column| !(table == "session_archive" && column == "id"))
+        .collect())
+}
+
+fn quoted(columns: &[&str]) -> String {
+    columns
+        .iter()
+        .map(|c| format!("\"{}\"", c))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl SqliteDatabase {
+    /// Every row of one of the [`EXPORT_TABLES`], ordered by its key columns
+    pub fn export_table(&self, table: &str) -> Result<TableDump> {
+        let keys = key_columns(table)?;
+        let conn = self.get_connection()?;
+        let columns = table_columns(&conn, table)?;
+
+        // Table and column names come from EXPORT_TABLES and the schema, never input
+        let names: Vec<&str> = columns.iter().map(String::as_str).collect();
+        let sql = format!(
+            "SELECT {} FROM {} ORDER BY {}",
+            quoted(&names),
+            table,
+            quoted(keys)
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt
+            .query_map([], |row| {
+                (0..columns.len())
+                    .map(|i| row.get::<_, Value>(i))
+                    .collect::<Result<Vec<_>>>()
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(TableDump {
+            table: table.to_string(),
+            columns,
+            rows,
+        })
+    }
+
+    /// Highest schema migration applied to this database
+    pub fn schema_version(&self) -> Result<u32> {
+        let conn = self.get_connection()?;
+        conn.query_row(
+            "SELECT COALESCE(MAX(version), 0) FROM schema_migrations",
+            [],
+            |row| row.get(0),
+        )
+    }
+
+    /// Add exported rows to the database, all in one transaction
+    ///
+    /// Rows whose key is already present are skipped, or overwritten when
+    /// `replace` is set, so importing the same export twice changes nothing.
+    /// Daily and monthly totals are not added up either; the days and months
+    /// whose totals differ are reported in [`TableImport::differing`].
+    /// Columns this schema does not have (exports from a newer version) are
+    /// left out, and columns the export lacks (older versions) get their defaults.
+    pub fn import_tables(&self, dumps: &[TableDump], replace: bool) -> Result<Vec<TableImport>> {
+        let retry_config = RetryConfig::for_db_ops();
+
+        retry_if_retryable(&retry_config, || {
+            let mut conn = self.get_connection()?;
+            let tx = conn.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+            let results = dumps
+                .iter()
+                .map(|dump| Self::import_table_tx(&tx, dump, replace))
+                .collect::<Result<Vec<_>>>()?;
+            tx.commit()?;
+            Ok(results)
+        })
+        .map_err(|e| match e {
+            crate::error::StatuslineError::Database(db_err) => db_err,
+            _ => rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_BUSY),
+                Some(e.to_string()),
+            ),
+        })
+    }
+
+    fn import_table_tx(tx: &Transaction, dump: &TableDump, replace: bool) -> Result<TableImport> {
+        let keys = key_columns(&dump.table)?;
+        if dump.rows.is_empty() {
+            return Ok(TableImport {
+                table: dump.table.clone(),
+                ..TableImport::default()
+            });
+        }
+        let known = table_columns(tx, &dump.table)?;
+        let (used, unknown): (Vec<usize>, Vec<usize>) =
+            (0..dump.columns.len()).partition(|&i| known.contains(&dump.columns[i]));
+        let key_indexes = keys
+            .iter()
+            .map(|key| {
+                dump.columns.iter().position(|c| c == key).ok_or_else(|| {
+                    misuse(format!("The {} rows have no {} column", dump.table, key))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // Only column names of the schema reach the SQL; values are bound
+        let names: Vec<&str> = used.iter().map(|&i| dump.columns[i].as_str()).collect();
+        let matches = keys
+            .iter()
+            .enumerate()
+            .map(|(i, key)| format!("\"{}\" IS ?{}", key, i + 1))
+            .collect::<Vec<_>>()
+            .join(" AND ");
+        let exists_sql = format!(
+            "SELECT EXISTS(SELECT 1 FROM {} WHERE {})",
+            dump.table, matches
+        );
+        let delete_sql = format!("DELETE FROM {} WHERE {}", dump.table, matches);
+        let select_sql = format!(
+            "SELECT {} FROM {} WHERE {}",
+            quoted(&names),
+            dump.table,
+            matches
+        );
+        let totals = matches!(dump.table.as_str(), "daily_stats" | "monthly_stats");
+        let insert_sql = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            dump.table,
+            quoted(&names),
+            (1..=names.len())
+                .map(|i| format!("?{}", i))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+
+        let mut result = TableImport {
+            table: dump.table.clone(),
+            unknown_columns: unknown.iter().map(|&i| dump.columns[i].clone()).collect(),
+            ..TableImport::default()
+        };
+        let value = |row: &[Value], i: usize| row.get(i).cloned().unwrap_or(Value::Null);
+        for row in &dump.rows {
+            let key: Vec<Value> = key_indexes.iter().map(|&i| value(row, i)).collect();
+            let exists: bool = tx
+                .prepare_cached(&exists_sql)?
+                .query_row(params_from_iter(&key), |r| r.get(0))?;
+            if exists {
+                if totals {
+                    let current =
+                        tx.prepare_cached(&select_sql)?
+                            .query_row(params_from_iter(&key), |r| {
+                                (0..names.len())
+                                    .map(|i| r.get::<_, Value>(i))
+                                    .collect::<Result<Vec<_>>>()
+                            })?;
+                    if used.iter().zip(&current).any(|(&i, v)| value(row, i) != *v) {
+                        if let Value::Text(period) = &key[0] {
+                            result.differing.push(period.clone());
+                        }
+                    }
+                }
+                if !replace {
+                    result.skipped += 1;
+                    continue;
+                }
+                tx.prepare_cached(&delete_sql)?
+                    .execute(params_from_iter(&key))?;
+                result.replaced += 1;
+            } else {
+                result.inserted += 1;
+            }
+            tx.prepare_cached(&insert_sql)?
+                .execute(params_from_iter(used.iter().map(|&i| value(row, i))))?;
+        }
+        Ok(result)
+    }
+}
@@ -0,0 +1,227 @@
+use super::schema::{ImportedSession, UsageTotals};
+use super::SqliteDatabase;
+use crate::retry::{retry_if_retryable, RetryConfig};
+use crate::stats::{DailyStats, MonthlyStats, SessionStats};
+use chrono::{DateTime, Local};
+use rusqlite::{params, Connection, OptionalExtension, Result, Transaction};
+use std::collections::{BTreeMap, HashMap};
+
+impl SqliteDatabase {
+    /// Import sessions rebuilt from transcripts, with their usage entries
+    ///
+    /// Sessions already in the database are left alone, since the statusline's own
+    /// record of them is more complete. New sessions and their days and months are
+    /// created like the JSON migration creates them (`import_sessions`,
+    /// `import_daily`, `import_monthly`). Transcripts carry no reported cost, so
+    /// the token usage of a new session goes to the computed cost and token columns
+    /// of the days and months of its responses. Importing the same transcripts
+    /// again changes nothing, also after `db-maintain` pruned their sessions: those
+    /// are skipped (see `sessions_pruned_before`).
+    ///
+    /// Returns the number of new sessions and of new usage entries.
+    pub fn import_transcript_sessions(
+        &self,
+        sessions: &[ImportedSession],
+    ) -> Result<(usize, usize)> {
+        let retry_config = RetryConfig::for_db_ops();
+
+        retry_if_retryable(&retry_config, || {
+            let mut conn = self.get_connection()?;
+            let tx = conn.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+
+            let pruned_before = Self::pruned_before(&tx)?;
+            let mut imported = 0;
+            let mut entries = 0;
+            for session in sessions {
+                if pruned_before
+                    .as_deref()
+                    .is_some_and(|cutoff| session.last_updated.as_str() < cutoff)
+                {
+                    continue;
+                }
+                if Self::import_session_tx(&tx, session)? {
+                    imported += 1;
+                }
+                entries += Self::record_usage_entries_tx(&tx, &session.usage)?;
+            }
+
+            tx.commit()?;
+            Ok((imported, entries))
+        })
+        .map_err(|e| match e {
+            crate::error::StatuslineError::Database(db_err) => db_err,
+            _ => rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_BUSY),
+                Some(e.to_string()),
+            ),
+        })
+    }
+
+    /// Insert one session and add its usage to the aggregates; false when it already existed
+    fn import_session_tx(tx: &Transaction, session: &ImportedSession) -> Result<bool> {
+        let exists: bool = tx.query_row(
+            "SELECT EXISTS(SELECT 1 FROM sessions WHERE session_id = ?1)",
+            params![session.session_id],
+            |row| row.get(0),
+        )?;
+        if exists {
+            return Ok(false);
+        }
+
+        let stats = SessionStats {
+            last_updated: session.last_updated.clone(),
+            cost: 0.0,
+            lines_added: 0,
+            lines_removed: 0,
+            start_time: Some(session.start_time.clone()),
+            max_tokens_observed: None,
+            active_time_seconds: Some(session.active_time_seconds),
+            last_activity: Some(session.last_updated.clone()),
+        };
+        Self::import_sessions_tx(tx, &HashMap::from([(session.session_id.clone(), stats)]))?;
+
+        let sum = |f: fn(&super::UsageEntry) -> u64| -> i64 {
+            session.usage.iter().map(f).sum::<u64>() as i64
+        };
+        tx.execute(
+            "UPDATE sessions SET
+                computed_cost = ?2, model_name = ?3, model_id = ?4, workspace_dir = ?5,
+                total_input_tokens = ?6, total_output_tokens = ?7, total_cache_read_tokens = ?8,
+                total_cache_creation_tokens = ?9
+             WHERE session_id = ?1",
+            params![
+                session.session_id,
+                session.cost(),
+                session.model_name,
+                session.model_id,
+                session.workspace_dir,
+                sum(|e| e.input_tokens),
+                sum(|e| e.output_tokens),
+                sum(|e| e.cache_read_tokens),
+                sum(|e| e.cache_write_tokens),
+            ],
+        )?;
+
+        // Usage is added to the local day and month of each response
+        let mut days: BTreeMap<String, UsageTotals> = BTreeMap::new();
+        for entry in &session.usage {
+            let Ok(at) = DateTime::parse_from_rfc3339(&entry.timestamp) else {
+                continue;
+            };
+            days.entry(at.with_timezone(&Local).format("%Y-%m-%d").to_string())
+                .or_default()
+                .add(&UsageTotals {
+                    computed_cost: entry.cost,
+                    input_tokens: entry.input_tokens,
+                    output_tokens: entry.output_tokens,
+                    cache_read_tokens: entry.cache_read_tokens,
+                    cache_creation_tokens: entry.cache_write_tokens,
+                    ..UsageTotals::default()
+                });
+        }
+        let mut months: BTreeMap<String, UsageTotals> = BTreeMap::new();
+        for (day, totals) in &days {
+            months.entry(day[..7].to_string()).or_default().add(totals);
+        }
+
+        // Create the rows that are missing, then add the session to every row
+        Self::import_daily_tx(
+            tx,
+            &days
+                .keys()
+                .map(|day| {
+                    let stats = DailyStats {
+                        total_cost: 0.0,
+                        sessions: Vec::new(),
+                        lines_added: 0,
+                        lines_removed: 0,
+                    };
+                    (day.clone(), stats)
+                })
+                .collect(),
+        )?;
+        Self::import_monthly_tx(
+            tx,
+            &months
+                .keys()
+                .map(|month| {
+                    let stats = MonthlyStats {
+                        total_cost: 0.0,
+                        sessions: 0,
+                        lines_added: 0,
+                        lines_removed: 0,
+                    };
+                    (month.clone(), stats)
+                })
+                .collect(),
+        )?;
+        for (table, key, periods) in [
+            ("daily_stats", "date", &days),
+            ("monthly_stats", "month", &months),
+        ] {
+            // Table and column names are the literals above, never input
+            let sql = format!(
+                "UPDATE {table} SET
+                    computed_cost = COALESCE(computed_cost, 0.0) + ?2,
+                    session_count = COALESCE(session_count, 0) + 1,
+                    total_input_tokens = COALESCE(total_input_tokens, 0) + ?3,
+                    total_output_tokens = COALESCE(total_output_tokens, 0) + ?4,
+                    total_cache_read_tokens = COALESCE(total_cache_read_tokens, 0) + ?5,
+                    total_cache_creation_tokens = COALESCE(total_cache_creation_tokens, 0) + ?6
+                 WHERE {key} = ?1"
+            );
+            for (period, totals) in periods {
+                tx.execute(
+                    &sql,
+                    params![
+                        period,
+                        totals.computed_cost,
+                        totals.input_tokens as i64,
+                        totals.output_tokens as i64,
+                        totals.cache_read_tokens as i64,
+                        totals.cache_creation_tokens as i64,
+                    ],
+                )?;
+            }
+        }
+
+        // Per-day cost of the session, for workspace budgets
+        for (day, totals) in &days {
+            tx.execute(
+                "INSERT OR IGNORE INTO session_daily_costs (session_id, date, computed_cost)
+                 VALUES (?1, ?2, ?3)",
+                params![session.session_id, day, totals.computed_cost],
+            )?;
+        }
+        Ok(true)
+    }
+
+    /// Check whether a session is recorded
+    pub fn session_exists(&self, session_id: &str) -> Result<bool> {
+        let conn = self.get_connection()?;
+        conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM sessions WHERE session_id = ?1)",
+            params![session_id],
+            |row| row.get(0),
+        )
+    }
+
+    /// Get the time (UTC, `%Y-%m-%dT%H:%M:%S`) before which `db-maintain` has pruned
+    /// sessions, if it ever did
+    ///
+    /// A pruned session's usage is still in the daily and monthly stats, so it must
+    /// not be imported again.
+    pub fn sessions_pruned_before(&self) -> Result<Option<String>> {
+        let conn = self.get_connection()?;
+        Self::pruned_before(&conn)
+    }
+
+    fn pruned_before(conn: &Connection) -> Result<Option<String>> {
+        conn.query_row(
+            "SELECT value FROM meta WHERE key = 'sessions_pruned_before'",
+            [],
+            |row| row.get(0),
+        )
+        .optional()
+    }
+}
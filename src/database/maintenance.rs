@@ -64,6 +64,33 @@ pub fn perform_maintenance(
                 params![cutoff_str],
             )?;
             records_pruned += deleted;
+
+            // Imports skip what was pruned, since it is still in the daily and monthly stats
+            conn.execute(
+                "INSERT INTO meta (key, value) VALUES ('sessions_pruned_before', ?1)
+                 ON CONFLICT(key) DO UPDATE SET value = max(value, excluded.value)",
+                params![cutoff_str],
+            )?;
+
+            // Transcript errors go with their sessions (migration v11)
+            if table_exists(&conn, "session_errors")? {
+                let deleted = conn.execute(
+                    "DELETE FROM session_errors WHERE timestamp < ?1",
+                    params![cutoff_str],
+                )?;
+                records_pruned += deleted;
+            }
+
+            // Usage entries (migration v10) too, but blocks still need `history_days` of them
+            if table_exists(&conn, "usage_entries")? {
+                let days_usage = days_sessions.max(config.blocks.history_days);
+                let cutoff = now - Duration::days(days_usage as i64);
+                let deleted = conn.execute(
+                    "DELETE FROM usage_entries WHERE timestamp < ?1",
+                    params![cutoff.format("%Y-%m-%dT%H:%M:%S").to_string()],
+                )?;
+                records_pruned += deleted;
+            }
         }
 
         // Prune old daily stats
@@ -76,6 +103,15 @@ pub fn perform_maintenance(
                 params![cutoff_str],
             )?;
             records_pruned += deleted;
+
+            // Databases not yet migrated to v12 have no per-session daily costs
+            if table_exists(&conn, "session_daily_costs")? {
+                let deleted = conn.execute(
+                    "DELETE FROM session_daily_costs WHERE date < ?1",
+                    params![cutoff_str],
+                )?;
+                records_pruned += deleted;
+            }
         }
 
         // Prune old monthly stats
@@ -127,6 +163,15 @@ pub fn perform_maintenance(
     })
 }
 
+/// Check whether `name` is a table in the database
+fn table_exists(conn: &Connection, name: &str) -> Result<bool> {
+    conn.query_row(
+        "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = ?1",
+        params![name],
+        |row| row.get(0),
+    )
+}
+
 /// Check if VACUUM should be performed
 fn should_vacuum(conn: &Connection) -> Result<bool> {
     use chrono::Utc;
@@ -7,11 +7,15 @@ use std::sync::{Mutex, MutexGuard, OnceLock};
 mod analytics;
 mod context;
 mod daily;
+mod errors;
+mod export;
+mod import;
 mod maintenance;
 mod monthly;
 mod schema;
 mod session;
 mod sync;
+mod usage;
 
 #[cfg(test)]
 mod tests;
@@ -21,10 +25,16 @@ mod tests;
 // Some items are only consumed via the library crate (not the binary directly).
 #[allow(unused_imports)]
 pub use analytics::SessionWithModel;
+pub use export::EXPORT_TABLES;
 pub use maintenance::perform_maintenance;
 #[allow(unused_imports)]
 pub use maintenance::MaintenanceResult;
-pub use schema::{SessionUpdate, SCHEMA};
+#[allow(unused_imports)]
+pub use schema::TableImport;
+pub use schema::{
+    ComputedCost, ImportedSession, SessionActivity, SessionError, SessionFilter, SessionMetadata,
+    SessionSummary, SessionUpdate, TableDump, UsageEntry, UsageTotals, SCHEMA,
+};
 
 // Track which database files have been migrated to avoid redundant migration checks
 static MIGRATED_DBS: OnceLock<Mutex<HashSet<PathBuf>>> = OnceLock::new();
@@ -131,11 +141,12 @@ impl SqliteDatabase {
                     .map_err(crate::error::StatuslineError::Database)?;
                 tx.execute_batch(SCHEMA)
                     .map_err(crate::error::StatuslineError::Database)?;
-                // Mark as fully migrated (v6 includes daily/monthly token tracking).
+                // Mark as fully migrated (v12 includes token-derived cost, cache savings, usage entries,
+                // session errors and per-day session costs).
                 tx.execute(
                     "INSERT OR IGNORE INTO schema_migrations (version, applied_at, checksum, description, execution_time_ms)
-                     VALUES (?1, ?2, '', 'New database with complete schema (v6)', 0)",
-                    params![6, chrono::Local::now().to_rfc3339()],
+                     VALUES (?1, ?2, '', 'New database with complete schema (v12)', 0)",
+                    params![12, chrono::Local::now().to_rfc3339()],
                 )
                 .map_err(crate::error::StatuslineError::Database)?;
                 tx.commit().map_err(crate::error::StatuslineError::Database)?;
@@ -1,6 +1,6 @@
-use super::SqliteDatabase;
+use super::{ComputedCost, SqliteDatabase};
 use crate::common::current_month;
-use rusqlite::{params, Result};
+use rusqlite::{params, Result, Transaction};
 
 impl SqliteDatabase {
     /// Import legacy monthly aggregates from a JSON-to-SQLite migration (#52).
@@ -13,6 +13,16 @@ impl SqliteDatabase {
     ) -> Result<()> {
         let mut conn = self.get_connection()?;
         let tx = conn.transaction()?;
+        Self::import_monthly_tx(&tx, monthly)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// `import_monthly` within an open transaction
+    pub(super) fn import_monthly_tx(
+        tx: &Transaction,
+        monthly: &std::collections::HashMap<String, crate::stats::MonthlyStats>,
+    ) -> Result<()> {
         for (month, stats) in monthly.iter() {
             tx.execute(
                 "INSERT OR IGNORE INTO monthly_stats (month, total_cost, total_lines_added, total_lines_removed, session_count)
@@ -26,7 +36,6 @@ impl SqliteDatabase {
                 ],
             )?;
         }
-        tx.commit()?;
         Ok(())
     }
 
@@ -61,6 +70,28 @@ impl SqliteDatabase {
         Ok(total)
     }
 
+    /// Get current month's token-derived cost and cache savings across all sessions
+    pub fn get_month_computed_cost(&self) -> Result<ComputedCost> {
+        let conn = self.get_connection()?;
+        let month = current_month();
+        let total = conn
+            .query_row(
+                "SELECT COALESCE(computed_cost, 0.0), COALESCE(cache_saved, 0.0),
+                        COALESCE(cache_write_cost, 0.0)
+                 FROM monthly_stats WHERE month = ?1",
+                params![&month],
+                |row| {
+                    Ok(ComputedCost {
+                        cost: row.get(0)?,
+                        cache_saved: row.get(1)?,
+                        cache_write_cost: row.get(2)?,
+                    })
+                },
+            )
+            .unwrap_or_default();
+        Ok(total)
+    }
+
     /// Get all monthly stats from the database
     pub fn get_all_monthly_stats(
         &self,
@@ -1,5 +1,5 @@
 pub const SCHEMA: &str = r#"
--- Sessions table (includes all migration v3, v4, v5, v6 columns and session_archive table)
+-- Sessions table (includes all migration v3, v4, v5, v6, v7, v8, v9 columns and session_archive table)
 CREATE TABLE IF NOT EXISTS sessions (
     session_id TEXT PRIMARY KEY,
     start_time TEXT NOT NULL,
@@ -17,10 +17,19 @@ CREATE TABLE IF NOT EXISTS sessions (
     total_cache_read_tokens INTEGER DEFAULT 0,
     total_cache_creation_tokens INTEGER DEFAULT 0,
     active_time_seconds INTEGER DEFAULT 0,
-    last_activity TEXT
+    last_activity TEXT,
+    model_id TEXT,
+    project_dir TEXT,
+    claude_version TEXT,
+    output_style TEXT,
+    total_duration_ms INTEGER,
+    total_api_duration_ms INTEGER,
+    computed_cost REAL DEFAULT 0.0,
+    cache_saved REAL DEFAULT 0.0,
+    cache_write_cost REAL DEFAULT 0.0
 );
 
--- Daily aggregates (materialized for performance, includes v6 token columns)
+-- Daily aggregates (materialized for performance, includes v6 token and v8/v9 computed cost columns)
 CREATE TABLE IF NOT EXISTS daily_stats (
     date TEXT PRIMARY KEY,
     total_cost REAL DEFAULT 0.0,
@@ -31,10 +40,13 @@ CREATE TABLE IF NOT EXISTS daily_stats (
     total_input_tokens INTEGER DEFAULT 0,
     total_output_tokens INTEGER DEFAULT 0,
     total_cache_read_tokens INTEGER DEFAULT 0,
-    total_cache_creation_tokens INTEGER DEFAULT 0
+    total_cache_creation_tokens INTEGER DEFAULT 0,
+    computed_cost REAL DEFAULT 0.0,
+    cache_saved REAL DEFAULT 0.0,
+    cache_write_cost REAL DEFAULT 0.0
 );
 
--- Monthly aggregates (includes v6 token columns)
+-- Monthly aggregates (includes v6 token and v8/v9 computed cost columns)
 CREATE TABLE IF NOT EXISTS monthly_stats (
     month TEXT PRIMARY KEY,
     total_cost REAL DEFAULT 0.0,
@@ -45,7 +57,10 @@ CREATE TABLE IF NOT EXISTS monthly_stats (
     total_input_tokens INTEGER DEFAULT 0,
     total_output_tokens INTEGER DEFAULT 0,
     total_cache_read_tokens INTEGER DEFAULT 0,
-    total_cache_creation_tokens INTEGER DEFAULT 0
+    total_cache_creation_tokens INTEGER DEFAULT 0,
+    computed_cost REAL DEFAULT 0.0,
+    cache_saved REAL DEFAULT 0.0,
+    cache_write_cost REAL DEFAULT 0.0
 );
 
 -- Learned context windows table (migration v4)
@@ -85,6 +100,7 @@ CREATE INDEX IF NOT EXISTS idx_sessions_cost ON sessions(cost DESC);
 CREATE INDEX IF NOT EXISTS idx_sessions_model_name ON sessions(model_name);
 CREATE INDEX IF NOT EXISTS idx_sessions_workspace ON sessions(workspace_dir);
 CREATE INDEX IF NOT EXISTS idx_sessions_device ON sessions(device_id);
+CREATE INDEX IF NOT EXISTS idx_sessions_project ON sessions(project_dir);
 CREATE INDEX IF NOT EXISTS idx_learned_confidence ON learned_context_windows(confidence_score DESC);
 CREATE INDEX IF NOT EXISTS idx_daily_date_cost ON daily_stats(date DESC, total_cost DESC);
 CREATE INDEX IF NOT EXISTS idx_daily_device ON daily_stats(device_id);
@@ -127,6 +143,44 @@ CREATE TABLE IF NOT EXISTS session_archive (
 -- Indexes for session_archive
 CREATE INDEX IF NOT EXISTS idx_archive_session ON session_archive(session_id);
 CREATE INDEX IF NOT EXISTS idx_archive_date ON session_archive(DATE(archived_at));
+
+-- Per-response token usage for usage blocks (migration v10)
+CREATE TABLE IF NOT EXISTS usage_entries (
+    message_id TEXT PRIMARY KEY,
+    session_id TEXT NOT NULL,
+    timestamp TEXT NOT NULL,
+    model TEXT,
+    input_tokens INTEGER DEFAULT 0,
+    output_tokens INTEGER DEFAULT 0,
+    cache_write_tokens INTEGER DEFAULT 0,
+    cache_read_tokens INTEGER DEFAULT 0,
+    cost REAL DEFAULT 0.0
+);
+
+CREATE INDEX IF NOT EXISTS idx_usage_entries_timestamp ON usage_entries(timestamp);
+
+-- Usage limits and API errors found in session transcripts (migration v11)
+CREATE TABLE IF NOT EXISTS session_errors (
+    session_id TEXT NOT NULL,
+    timestamp TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    message TEXT,
+    reset_at TEXT,
+    PRIMARY KEY (session_id, timestamp, kind)
+);
+
+CREATE INDEX IF NOT EXISTS idx_session_errors_kind ON session_errors(kind, reset_at);
+
+-- Cost each session added per day, for per-directory budgets (migration v12)
+CREATE TABLE IF NOT EXISTS session_daily_costs (
+    session_id TEXT NOT NULL,
+    date TEXT NOT NULL,
+    cost REAL DEFAULT 0.0,
+    computed_cost REAL DEFAULT 0.0,
+    PRIMARY KEY (session_id, date)
+);
+
+CREATE INDEX IF NOT EXISTS idx_session_daily_costs_date ON session_daily_costs(date);
 "#;
 
 /// Parameters for updating a session in the database
@@ -144,6 +198,287 @@ pub struct SessionUpdate {
     pub last_activity: Option<String>,
 }
 
+/// Claude Code metadata for a session (migration v7 columns)
+///
+/// Stored separately from [`SessionUpdate`] because it is descriptive rather than
+/// cumulative: values simply overwrite what was previously recorded, and fields
+/// that are `None` leave the stored value untouched.
+#[derive(Clone, Debug, Default)]
+pub struct SessionMetadata {
+    pub model_id: Option<String>,
+    pub project_dir: Option<String>,
+    pub claude_version: Option<String>,
+    pub output_style: Option<String>,
+    pub total_duration_ms: Option<u64>,
+    pub total_api_duration_ms: Option<u64>,
+}
+
+/// Cost figures priced from token usage (migration v8/v9 columns)
+///
+/// Stored per session and added to the daily/monthly aggregates by
+/// `SqliteDatabase::record_computed_cost`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ComputedCost {
+    /// Token usage priced with the model rates
+    pub cost: f64,
+    /// Uncached cost minus actual cost (negative when cache writes did not pay off)
+    pub cache_saved: f64,
+    /// Spent on cache writes
+    pub cache_write_cost: f64,
+}
+
+/// Token usage of one API response, timestamped for usage blocks (migration v10)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UsageEntry {
+    /// API message id (or a session/timestamp key when the transcript has none)
+    pub message_id: String,
+    pub session_id: String,
+    /// UTC RFC3339 timestamp with millisecond precision, so entries sort as text
+    pub timestamp: String,
+    pub model: Option<String>,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_write_tokens: u64,
+    pub cache_read_tokens: u64,
+    /// Usage priced with the model rates, 0.0 for models without a price
+    pub cost: f64,
+}
+
+impl UsageEntry {
+    /// Total tokens of all types
+    pub fn tokens(&self) -> u64 {
+        self.input_tokens + self.output_tokens + self.cache_write_tokens + self.cache_read_tokens
+    }
+}
+
+/// A usage limit or API error found in a session transcript (migration v11)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SessionError {
+    pub session_id: String,
+    /// UTC RFC3339 timestamp with millisecond precision
+    pub timestamp: String,
+    /// "usage_limit" or "api_error"
+    pub kind: String,
+    pub message: String,
+    /// When a usage limit resets (UTC RFC3339), if known
+    pub reset_at: Option<String>,
+}
+
+impl SessionError {
+    /// Record an error read from the transcript of `session_id`
+    pub fn from_transcript(session_id: &str, error: &crate::utils::TranscriptError) -> Self {
+        use chrono::SecondsFormat;
+        Self {
+            session_id: session_id.to_string(),
+            timestamp: error.timestamp.to_rfc3339_opts(SecondsFormat::Millis, true),
+            kind: error.kind.as_str().to_string(),
+            message: error.message.clone(),
+            reset_at: error
+                .reset_at
+                .map(|t| t.to_rfc3339_opts(SecondsFormat::Millis, true)),
+        }
+    }
+}
+
+/// A session rebuilt from a Claude Code transcript, for `import_transcript_sessions`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ImportedSession {
+    pub session_id: String,
+    /// First transcript entry (local RFC3339, like live sessions)
+    pub start_time: String,
+    /// Last transcript entry (local RFC3339)
+    pub last_updated: String,
+    pub model_name: Option<String>,
+    pub model_id: Option<String>,
+    pub workspace_dir: Option<String>,
+    pub active_time_seconds: u64,
+    /// Token usage of every response, oldest first
+    pub usage: Vec<UsageEntry>,
+}
+
+impl ImportedSession {
+    /// Token usage priced with the model rates; transcripts carry no reported cost
+    pub fn cost(&self) -> f64 {
+        self.usage.iter().map(|e| e.cost).sum()
+    }
+
+    /// Total tokens of all types
+    pub fn tokens(&self) -> u64 {
+        self.usage.iter().map(UsageEntry::tokens).sum()
+    }
+}
+
+/// Session span and totals, for usage blocks covering sessions without usage entries
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SessionActivity {
+    pub session_id: String,
+    pub start_time: String,
+    pub last_updated: String,
+    /// Token-derived cost when recorded, otherwise the reported cost
+    pub cost: f64,
+    pub tokens: u64,
+    /// Whether the session has rows in `usage_entries`
+    pub has_entries: bool,
+}
+
+/// Cost, line and token totals of a reporting period or a group of sessions
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UsageTotals {
+    pub sessions: u64,
+    /// Cost reported by Claude Code
+    pub cost: f64,
+    /// Cost priced from token usage
+    pub computed_cost: f64,
+    pub lines_added: u64,
+    pub lines_removed: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cache_creation_tokens: u64,
+}
+
+impl UsageTotals {
+    /// Total tokens of all types
+    pub fn tokens(&self) -> u64 {
+        self.input_tokens + self.output_tokens + self.cache_read_tokens + self.cache_creation_tokens
+    }
+
+    /// Add another set of totals to this one
+    pub fn add(&mut self, other: &UsageTotals) {
+        self.sessions += other.sessions;
+        self.cost += other.cost;
+        self.computed_cost += other.computed_cost;
+        self.lines_added += other.lines_added;
+        self.lines_removed += other.lines_removed;
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_read_tokens += other.cache_read_tokens;
+        self.cache_creation_tokens += other.cache_creation_tokens;
+    }
+}
+
+/// A live session or an archived work period with its totals (`sessions` is 1)
+///
+/// Work periods archived by `auto_reset` mode carry no token or computed cost
+/// columns, so those totals are 0.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SessionSummary {
+    pub session_id: String,
+    pub start_time: String,
+    /// Last update of a live session, or the end of an archived work period
+    pub last_updated: String,
+    pub model_name: Option<String>,
+    pub workspace_dir: Option<String>,
+    pub device_id: Option<String>,
+    /// Recorded in `active_time` burn rate mode only
+    pub active_time_seconds: Option<u64>,
+    /// Whether this row comes from `session_archive`
+    pub archived: bool,
+    pub totals: UsageTotals,
+}
+
+impl SessionSummary {
+    /// Seconds from the start to the last update
+    pub fn wall_time_seconds(&self) -> Option<u64> {
+        let start = chrono::DateTime::parse_from_rfc3339(&self.start_time).ok()?;
+        let end = chrono::DateTime::parse_from_rfc3339(&self.last_updated).ok()?;
+        Some((end - start).num_seconds().max(0) as u64)
+    }
+
+    /// Cost per hour as `{burn_rate}` showed it at the last update in `mode`
+    ///
+    /// `wall_clock` and `auto_reset` divide by the wall time (`auto_reset` restarts
+    /// it after inactivity), `active_time` by the recorded active time. Like the
+    /// statusline, durations up to `min_duration_seconds` give no rate.
+    pub fn burn_rate(&self, mode: &str, min_duration_seconds: u64) -> Option<f64> {
+        let duration = match mode {
+            "active_time" => self.active_time_seconds.filter(|t| *t > 0)?,
+            _ => self.wall_time_seconds()?,
+        };
+        (duration > min_duration_seconds && self.totals.cost > 0.0)
+            .then(|| self.totals.cost * 3600.0 / duration as f64)
+    }
+}
+
+/// Which sessions [`SqliteDatabase::find_sessions`](super::SqliteDatabase::find_sessions) returns
+#[derive(Clone, Debug, Default)]
+pub struct SessionFilter {
+    /// Session ID or the start of one
+    pub session_id: Option<String>,
+    /// Workspace directory; sessions in its subdirectories match too
+    pub workspace: Option<String>,
+    /// Part of the model name, ignoring case
+    pub model: Option<String>,
+    /// First day of last activity (YYYY-MM-DD)
+    pub since: Option<String>,
+    /// Last day of last activity (YYYY-MM-DD)
+    pub until: Option<String>,
+    /// Include work periods archived by `auto_reset` mode
+    pub archived: bool,
+}
+
+/// The rows of one table, for `export` and `import-data`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TableDump {
+    pub table: String,
+    pub columns: Vec<String>,
+    /// One value per column
+    pub rows: Vec<Vec<rusqlite::types::Value>>,
+}
+
+/// What [`SqliteDatabase::import_tables`](super::SqliteDatabase::import_tables) did with one table
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TableImport {
+    pub table: String,
+    pub inserted: usize,
+    /// Rows that overwrote an existing row (only when replacing)
+    pub replaced: usize,
+    /// Rows left out because the database already had them
+    pub skipped: usize,
+    /// Days or months (`daily_stats`, `monthly_stats`) both databases have with
+    /// different totals: the database keeps its own, or takes the export's when
+    /// replacing, as the totals cannot be told apart to add them up
+    pub differing: Vec<String>,
+    /// Columns of the export that this schema version does not have
+    pub unknown_columns: Vec<String>,
+}
+
+impl ComputedCost {
+    /// Price a transcript's token usage with the configured rates
+    pub fn from_usage(usage: &crate::pricing::TranscriptUsage) -> Self {
+        let savings = usage.cache_savings();
+        Self {
+            cost: usage.cost(),
+            cache_saved: savings.saved,
+            cache_write_cost: savings.write_cost,
+        }
+    }
+}
+
+impl SessionMetadata {
+    /// Extract the persisted metadata fields from a Claude Code input payload
+    pub fn from_input(input: &crate::models::StatuslineInput) -> Self {
+        Self {
+            model_id: input.model.as_ref().and_then(|m| m.id.clone()),
+            project_dir: input.workspace.as_ref().and_then(|w| w.project_dir.clone()),
+            claude_version: input.version.clone(),
+            output_style: input.output_style.as_ref().and_then(|s| s.name.clone()),
+            total_duration_ms: input.cost.as_ref().and_then(|c| c.total_duration_ms),
+            total_api_duration_ms: input.cost.as_ref().and_then(|c| c.total_api_duration_ms),
+        }
+    }
+
+    /// Returns true when there is nothing to store
+    pub fn is_empty(&self) -> bool {
+        self.model_id.is_none()
+            && self.project_dir.is_none()
+            && self.claude_version.is_none()
+            && self.output_style.is_none()
+            && self.total_duration_ms.is_none()
+            && self.total_api_duration_ms.is_none()
+    }
+}
+
 impl SessionUpdate {
     /// Create a new SessionUpdate with default values for the new burn rate tracking fields
     #[allow(dead_code)]
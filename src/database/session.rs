@@ -1,4 +1,4 @@
-use super::schema::SessionUpdate;
+use super::schema::{ComputedCost, SessionMetadata, SessionUpdate};
 use super::SqliteDatabase;
 use crate::common::{current_date, current_month, current_timestamp};
 use crate::retry::{retry_if_retryable, RetryConfig};
@@ -74,6 +74,211 @@ impl SqliteDatabase {
         })
     }
 
+    /// Record Claude Code metadata (model id, project root, version, timing) for a session
+    ///
+    /// Only touches existing rows: the session is created by `update_session`, so this
+    /// must be called after it. `None` fields keep their previously stored value.
+    pub fn update_session_metadata(
+        &self,
+        session_id: &str,
+        metadata: &SessionMetadata,
+    ) -> Result<()> {
+        if metadata.is_empty() {
+            return Ok(());
+        }
+
+        let retry_config = RetryConfig::for_db_ops();
+
+        retry_if_retryable(&retry_config, || {
+            let conn = self.get_connection()?;
+            conn.execute(
+                "UPDATE sessions SET
+                    model_id = COALESCE(?2, model_id),
+                    project_dir = COALESCE(?3, project_dir),
+                    claude_version = COALESCE(?4, claude_version),
+                    output_style = COALESCE(?5, output_style),
+                    total_duration_ms = COALESCE(?6, total_duration_ms),
+                    total_api_duration_ms = COALESCE(?7, total_api_duration_ms)
+                 WHERE session_id = ?1",
+                params![
+                    session_id,
+                    metadata.model_id.as_deref(),
+                    metadata.project_dir.as_deref(),
+                    metadata.claude_version.as_deref(),
+                    metadata.output_style.as_deref(),
+                    metadata.total_duration_ms.map(|t| t as i64),
+                    metadata.total_api_duration_ms.map(|t| t as i64),
+                ],
+            )?;
+            Ok(())
+        })
+        .map_err(|e| match e {
+            crate::error::StatuslineError::Database(db_err) => db_err,
+            _ => rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_BUSY),
+                Some(e.to_string()),
+            ),
+        })
+    }
+
+    /// Record the token-derived cost and cache savings of a session and add the change
+    /// to today's and this month's aggregates
+    ///
+    /// Only touches existing session rows (created by `update_session`). The cost is
+    /// re-priced from the whole transcript on every call, so it also goes down (e.g.
+    /// after a `[pricing]` rate is lowered); the aggregates follow by the same delta.
+    pub fn record_computed_cost(&self, session_id: &str, computed: &ComputedCost) -> Result<()> {
+        let retry_config = RetryConfig::for_db_ops();
+
+        retry_if_retryable(&retry_config, || {
+            let mut conn = self.get_connection()?;
+            let tx = conn.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+
+            let old = tx
+                .query_row(
+                    "SELECT COALESCE(computed_cost, 0.0), COALESCE(cache_saved, 0.0),
+                            COALESCE(cache_write_cost, 0.0)
+                     FROM sessions WHERE session_id = ?1",
+                    params![session_id],
+                    |row| {
+                        Ok(ComputedCost {
+                            cost: row.get(0)?,
+                            cache_saved: row.get(1)?,
+                            cache_write_cost: row.get(2)?,
+                        })
+                    },
+                )
+                .optional()?;
+
+            if let Some(old) = old.filter(|old| old != computed) {
+                let cost_delta = computed.cost - old.cost;
+                let saved_delta = computed.cache_saved - old.cache_saved;
+                let write_delta = computed.cache_write_cost - old.cache_write_cost;
+
+                tx.execute(
+                    "UPDATE sessions SET computed_cost = ?2, cache_saved = ?3, cache_write_cost = ?4
+                     WHERE session_id = ?1",
+                    params![
+                        session_id,
+                        computed.cost,
+                        computed.cache_saved,
+                        computed.cache_write_cost
+                    ],
+                )?;
+                tx.execute(
+                    "INSERT INTO daily_stats (date, computed_cost, cache_saved, cache_write_cost)
+                     VALUES (?1, ?2, ?3, ?4)
+                     ON CONFLICT(date) DO UPDATE SET
+                        computed_cost = COALESCE(computed_cost, 0.0) + ?2,
+                        cache_saved = COALESCE(cache_saved, 0.0) + ?3,
+                        cache_write_cost = COALESCE(cache_write_cost, 0.0) + ?4",
+                    params![current_date(), cost_delta, saved_delta, write_delta],
+                )?;
+                tx.execute(
+                    "INSERT INTO monthly_stats (month, computed_cost, cache_saved, cache_write_cost)
+                     VALUES (?1, ?2, ?3, ?4)
+                     ON CONFLICT(month) DO UPDATE SET
+                        computed_cost = COALESCE(computed_cost, 0.0) + ?2,
+                        cache_saved = COALESCE(cache_saved, 0.0) + ?3,
+                        cache_write_cost = COALESCE(cache_write_cost, 0.0) + ?4",
+                    params![current_month(), cost_delta, saved_delta, write_delta],
+                )?;
+                tx.execute(
+                    "INSERT INTO session_daily_costs (session_id, date, computed_cost)
+                     VALUES (?1, ?2, ?3)
+                     ON CONFLICT(session_id, date) DO UPDATE SET
+                        computed_cost = COALESCE(computed_cost, 0.0) + ?3",
+                    params![session_id, current_date(), cost_delta],
+                )?;
+            }
+
+            tx.commit()?;
+            Ok(())
+        })
+        .map_err(|e| match e {
+            crate::error::StatuslineError::Database(db_err) => db_err,
+            _ => rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_BUSY),
+                Some(e.to_string()),
+            ),
+        })
+    }
+
+    /// Get the token-derived cost and cache savings recorded for a session
+    #[allow(dead_code)] // Public API - used by library consumers
+    pub fn get_session_computed_cost(&self, session_id: &str) -> Option<ComputedCost> {
+        let conn = self.get_connection().ok()?;
+        conn.query_row(
+            "SELECT COALESCE(computed_cost, 0.0), COALESCE(cache_saved, 0.0),
+                    COALESCE(cache_write_cost, 0.0)
+             FROM sessions WHERE session_id = ?1",
+            params![session_id],
+            |row| {
+                Ok(ComputedCost {
+                    cost: row.get(0)?,
+                    cache_saved: row.get(1)?,
+                    cache_write_cost: row.get(2)?,
+                })
+            },
+        )
+        .ok()
+    }
+
+    /// Get the cost that sessions in `workspace` or any directory below it added from
+    /// `start_date` (YYYY-MM-DD) through today
+    ///
+    /// Only the days inside the period count, so a long session started before it
+    /// contributes what it spent since. Sums the token-derived `computed_cost`
+    /// instead of the reported cost when `computed` is set.
+    pub fn get_workspace_cost_since(
+        &self,
+        workspace: &str,
+        start_date: &str,
+        computed: bool,
+    ) -> Result<f64> {
+        let conn = self.get_connection()?;
+        let workspace = workspace.trim_end_matches('/');
+        let prefix = format!("{}/", workspace);
+        // Compare prefixes with substr() rather than LIKE so '_' and '%' in paths match literally
+        let sql = if computed {
+            "SELECT COALESCE(SUM(d.computed_cost), 0.0)
+             FROM session_daily_costs d JOIN sessions s ON s.session_id = d.session_id
+             WHERE (s.workspace_dir = ?1 OR substr(s.workspace_dir, 1, length(?2)) = ?2)
+               AND d.date >= ?3"
+        } else {
+            "SELECT COALESCE(SUM(d.cost), 0.0)
+             FROM session_daily_costs d JOIN sessions s ON s.session_id = d.session_id
+             WHERE (s.workspace_dir = ?1 OR substr(s.workspace_dir, 1, length(?2)) = ?2)
+               AND d.date >= ?3"
+        };
+        conn.query_row(sql, params![workspace, prefix, start_date], |row| {
+            row.get(0)
+        })
+    }
+
+    /// Get Claude Code metadata recorded for a session
+    #[allow(dead_code)] // Public API - used by library consumers
+    pub fn get_session_metadata(&self, session_id: &str) -> Option<SessionMetadata> {
+        let conn = self.get_connection().ok()?;
+        conn.query_row(
+            "SELECT model_id, project_dir, claude_version, output_style,
+                    total_duration_ms, total_api_duration_ms
+             FROM sessions WHERE session_id = ?1",
+            params![session_id],
+            |row| {
+                Ok(SessionMetadata {
+                    model_id: row.get(0)?,
+                    project_dir: row.get(1)?,
+                    claude_version: row.get(2)?,
+                    output_style: row.get(3)?,
+                    total_duration_ms: row.get::<_, Option<i64>>(4)?.map(|t| t as u64),
+                    total_api_duration_ms: row.get::<_, Option<i64>>(5)?.map(|t| t as u64),
+                })
+            },
+        )
+        .ok()
+    }
+
     /// Archive a session to session_archive table (for auto_reset mode)
     /// This preserves the work period history before resetting the session counters
     fn archive_session(tx: &Transaction, session_id: &str) -> Result<()> {
@@ -508,6 +713,13 @@ impl SqliteDatabase {
                     input_tokens_delta, output_tokens_delta, cache_read_tokens_delta, cache_creation_tokens_delta],
         )?;
 
+        // The session's share of today, for costs by workspace over a period
+        tx.execute(
+            "INSERT INTO session_daily_costs (session_id, date, cost) VALUES (?1, ?2, ?3)
+             ON CONFLICT(session_id, date) DO UPDATE SET cost = COALESCE(cost, 0.0) + ?3",
+            params![session_id, &today, cost_delta],
+        )?;
+
         // Update monthly stats atomically with delta values
         // Note: session_count is SET (not incremented) to the actual count of distinct sessions
         tx.execute(
@@ -689,7 +901,16 @@ impl SqliteDatabase {
     ) -> Result<()> {
         let mut conn = self.get_connection()?;
         let tx = conn.transaction()?;
+        Self::import_sessions_tx(&tx, sessions)?;
+        tx.commit()?;
+        Ok(())
+    }
 
+    /// `import_sessions` within an open transaction
+    pub(super) fn import_sessions_tx(
+        tx: &Transaction,
+        sessions: &std::collections::HashMap<String, crate::stats::SessionStats>,
+    ) -> Result<()> {
         for (session_id, session) in sessions.iter() {
             // Insert session (don't use UPSERT, just INSERT as this is initial import)
             tx.execute(
@@ -707,8 +928,6 @@ impl SqliteDatabase {
                 ],
             )?;
         }
-
-        tx.commit()?;
         Ok(())
     }
 }
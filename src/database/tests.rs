@@ -37,6 +37,28 @@ fn test_public_api_surface() {
         last_activity: None,
     };
 
+    // SessionMetadata -- must be constructable via Default
+    let _metadata = super::SessionMetadata::default();
+
+    // ComputedCost -- must be constructable via Default
+    let _computed = super::ComputedCost::default();
+
+    // UsageEntry / SessionActivity -- must be constructable via Default
+    let _entry = super::UsageEntry::default();
+    let _activity = super::SessionActivity::default();
+
+    // SessionError -- must be constructable via Default
+    let _error = super::SessionError::default();
+
+    // UsageTotals / SessionSummary -- must be constructable via Default
+    let _totals = super::UsageTotals::default();
+    let _summary = super::SessionSummary::default();
+    let _filter = super::SessionFilter::default();
+    let _imported = super::ImportedSession::default();
+    let _dump = super::TableDump::default();
+    let _table_import = super::TableImport::default();
+    let _: &[(&str, &[&str])] = &super::EXPORT_TABLES;
+
     // MaintenanceResult -- must be constructable with all fields
     let _result = super::MaintenanceResult {
         checkpoint_done: false,
@@ -128,6 +150,794 @@ fn test_session_update() {
     );
 }
 
+#[test]
+fn test_session_metadata_update() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let db = SqliteDatabase::new(&db_path).unwrap();
+
+    db.update_session(
+        "meta-session",
+        SessionUpdate {
+            cost: 1.0,
+            lines_added: 0,
+            lines_removed: 0,
+            model_name: Some("Opus".to_string()),
+            workspace_dir: None,
+            device_id: None,
+            token_breakdown: None,
+            max_tokens_observed: None,
+            active_time_seconds: None,
+            last_activity: None,
+        },
+    )
+    .unwrap();
+
+    db.update_session_metadata(
+        "meta-session",
+        &SessionMetadata {
+            model_id: Some("claude-opus-4-1".to_string()),
+            project_dir: Some("/home/user/project".to_string()),
+            claude_version: Some("1.0.80".to_string()),
+            output_style: Some("default".to_string()),
+            total_duration_ms: Some(45_000),
+            total_api_duration_ms: Some(2_300),
+        },
+    )
+    .unwrap();
+
+    // A partial update must not clear previously stored values
+    db.update_session_metadata(
+        "meta-session",
+        &SessionMetadata {
+            total_duration_ms: Some(60_000),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let stored = db.get_session_metadata("meta-session").unwrap();
+    assert_eq!(stored.model_id.as_deref(), Some("claude-opus-4-1"));
+    assert_eq!(stored.project_dir.as_deref(), Some("/home/user/project"));
+    assert_eq!(stored.claude_version.as_deref(), Some("1.0.80"));
+    assert_eq!(stored.output_style.as_deref(), Some("default"));
+    assert_eq!(stored.total_duration_ms, Some(60_000));
+    assert_eq!(stored.total_api_duration_ms, Some(2_300));
+
+    // Unknown sessions are left alone (no row is created)
+    db.update_session_metadata(
+        "missing-session",
+        &SessionMetadata {
+            claude_version: Some("1.0.80".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert!(db.get_session_metadata("missing-session").is_none());
+}
+
+#[test]
+fn test_record_computed_cost_aggregates_delta() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let db = SqliteDatabase::new(&db_path).unwrap();
+
+    for session_id in ["cost-a", "cost-b"] {
+        db.update_session(
+            session_id,
+            SessionUpdate {
+                cost: 1.0,
+                lines_added: 0,
+                lines_removed: 0,
+                model_name: None,
+                workspace_dir: None,
+                device_id: None,
+                token_breakdown: None,
+                max_tokens_observed: None,
+                active_time_seconds: None,
+                last_activity: None,
+            },
+        )
+        .unwrap();
+    }
+
+    let computed = |cost: f64, cache_saved: f64, cache_write_cost: f64| ComputedCost {
+        cost,
+        cache_saved,
+        cache_write_cost,
+    };
+    db.record_computed_cost("cost-a", &computed(0.50, 0.20, 0.10))
+        .unwrap();
+    db.record_computed_cost("cost-a", &computed(1.25, 0.90, 0.30))
+        .unwrap();
+    db.record_computed_cost("cost-b", &computed(2.00, -0.40, 0.50))
+        .unwrap();
+    // A lower cost is subtracted again
+    db.record_computed_cost("cost-b", &computed(0.10, 0.0, 0.0))
+        .unwrap();
+    // Unknown sessions are not created
+    db.record_computed_cost("missing-session", &computed(9.0, 1.0, 1.0))
+        .unwrap();
+
+    assert_eq!(
+        db.get_session_computed_cost("cost-a"),
+        Some(computed(1.25, 0.90, 0.30))
+    );
+    assert_eq!(db.get_session_computed_cost("cost-b").unwrap().cost, 0.10);
+    assert_eq!(db.get_session_computed_cost("missing-session"), None);
+
+    for total in [
+        db.get_today_computed_cost().unwrap(),
+        db.get_month_computed_cost().unwrap(),
+    ] {
+        assert!((total.cost - 1.35).abs() < 1e-9);
+        assert!((total.cache_saved - 0.90).abs() < 1e-9);
+        assert!((total.cache_write_cost - 0.30).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn test_record_computed_cost_follows_lowered_rate() {
+    use crate::pricing::{ModelRates, TokenUsage};
+
+    let temp_dir = TempDir::new().unwrap();
+    let db = SqliteDatabase::new(&temp_dir.path().join("test.db")).unwrap();
+    db.update_session(
+        "repriced",
+        SessionUpdate {
+            cost: 1.0,
+            lines_added: 0,
+            lines_removed: 0,
+            model_name: None,
+            workspace_dir: None,
+            device_id: None,
+            token_breakdown: None,
+            max_tokens_observed: None,
+            active_time_seconds: None,
+            last_activity: None,
+        },
+    )
+    .unwrap();
+
+    let usage = TokenUsage {
+        input: 100_000,
+        output: 20_000,
+        cache_write_5m: 50_000,
+        cache_read: 400_000,
+        ..Default::default()
+    };
+    let priced = |rates: ModelRates| ComputedCost {
+        cost: usage.cost(&rates),
+        cache_saved: usage.cache_savings(&rates).saved,
+        cache_write_cost: usage.cache_write_cost(&rates),
+    };
+    let before = priced(ModelRates::from_base(15.0, 75.0));
+    let after = priced(ModelRates::from_base(5.0, 25.0));
+    assert!(after.cost < before.cost);
+
+    db.record_computed_cost("repriced", &before).unwrap();
+    db.record_computed_cost("repriced", &after).unwrap();
+
+    assert_eq!(db.get_session_computed_cost("repriced"), Some(after));
+    for total in [
+        db.get_today_computed_cost().unwrap(),
+        db.get_month_computed_cost().unwrap(),
+    ] {
+        assert!((total.cost - after.cost).abs() < 1e-9);
+        assert!((total.cache_saved - after.cache_saved).abs() < 1e-9);
+        assert!((total.cache_write_cost - after.cache_write_cost).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn test_cost_since_and_workspace_cost() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let db = SqliteDatabase::new(&db_path).unwrap();
+
+    for (session_id, cost, workspace) in [
+        ("ws-root", 1.0, "/work/client_a"),
+        ("ws-sub", 2.0, "/work/client_a/api"),
+        ("ws-sibling", 4.0, "/work/client_ab"),
+        ("ws-other", 8.0, "/home/me"),
+    ] {
+        db.update_session(
+            session_id,
+            SessionUpdate {
+                cost,
+                lines_added: 0,
+                lines_removed: 0,
+                model_name: None,
+                workspace_dir: Some(workspace.to_string()),
+                device_id: None,
+                token_breakdown: None,
+                max_tokens_observed: None,
+                active_time_seconds: None,
+                last_activity: None,
+            },
+        )
+        .unwrap();
+    }
+    db.record_computed_cost(
+        "ws-sub",
+        &ComputedCost {
+            cost: 0.5,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let today = current_date();
+    assert_eq!(db.get_cost_since(&today, false).unwrap(), 15.0);
+    assert_eq!(db.get_cost_since(&today, true).unwrap(), 0.5);
+    assert_eq!(db.get_cost_since("9999-01-01", false).unwrap(), 0.0);
+
+    // Sessions in the workspace and below it, but not in a sibling sharing the prefix
+    assert_eq!(
+        db.get_workspace_cost_since("/work/client_a/", &today, false)
+            .unwrap(),
+        3.0
+    );
+    assert_eq!(
+        db.get_workspace_cost_since("/work/client_a", &today, true)
+            .unwrap(),
+        0.5
+    );
+    assert_eq!(
+        db.get_workspace_cost_since("/work/client_a", "9999-01-01", false)
+            .unwrap(),
+        0.0
+    );
+
+    // A session that started before the period only counts what it spent since
+    db.get_connection()
+        .unwrap()
+        .execute(
+            "UPDATE session_daily_costs SET date = '2000-01-31' WHERE session_id = 'ws-root'",
+            [],
+        )
+        .unwrap();
+    db.update_session(
+        "ws-root",
+        SessionUpdate {
+            cost: 1.5,
+            lines_added: 0,
+            lines_removed: 0,
+            model_name: None,
+            workspace_dir: Some("/work/client_a".to_string()),
+            device_id: None,
+            token_breakdown: None,
+            max_tokens_observed: None,
+            active_time_seconds: None,
+            last_activity: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        db.get_workspace_cost_since("/work/client_a", &today, false)
+            .unwrap(),
+        2.5
+    );
+    assert_eq!(
+        db.get_workspace_cost_since("/work/client_a", "2000-01-01", false)
+            .unwrap(),
+        3.5
+    );
+}
+
+#[test]
+fn test_daily_history() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let db = SqliteDatabase::new(&db_path).unwrap();
+    {
+        let conn = db.get_connection().unwrap();
+        for (date, cost, input) in [
+            ("2026-10-02", 3.0, 200),
+            ("2026-09-30", 1.0, 100),
+            ("2026-09-01", 9.0, 900),
+        ] {
+            conn.execute(
+                "INSERT INTO daily_stats (date, total_cost, total_input_tokens, total_output_tokens)
+                 VALUES (?1, ?2, ?3, 50)",
+                rusqlite::params![date, cost, input],
+            )
+            .unwrap();
+        }
+    }
+
+    assert_eq!(
+        db.get_daily_history("2026-09-15").unwrap(),
+        vec![
+            ("2026-09-30".to_string(), 1.0, 150),
+            ("2026-10-02".to_string(), 3.0, 250),
+        ]
+    );
+}
+
+#[test]
+fn test_report_totals() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let db = SqliteDatabase::new(&db_path).unwrap();
+    {
+        let conn = db.get_connection().unwrap();
+        for (date, cost, input) in [
+            ("2026-10-02", 3.0, 200),
+            ("2026-09-30", 1.0, 100),
+            ("2026-09-01", 9.0, 900),
+        ] {
+            conn.execute(
+                "INSERT INTO daily_stats (date, total_cost, session_count, total_input_tokens)
+                 VALUES (?1, ?2, 2, ?3)",
+                rusqlite::params![date, cost, input],
+            )
+            .unwrap();
+        }
+        conn.execute(
+            "INSERT INTO monthly_stats (month, total_cost, session_count) VALUES ('2026-09', 10.0, 4)",
+            [],
+        )
+        .unwrap();
+    }
+
+    let days = db
+        .get_daily_totals(Some("2026-09-15"), Some("2026-10-01"))
+        .unwrap();
+    assert_eq!(days.len(), 1);
+    assert_eq!(days[0].0, "2026-09-30");
+    assert_eq!(days[0].1.cost, 1.0);
+    assert_eq!(days[0].1.sessions, 2);
+    assert_eq!(days[0].1.input_tokens, 100);
+    assert_eq!(db.get_daily_totals(None, None).unwrap().len(), 3);
+
+    let months = db.get_monthly_totals(Some("2026-09"), None).unwrap();
+    assert_eq!(months.len(), 1);
+    assert_eq!(months[0].1.sessions, 4);
+
+    let update = SessionUpdate {
+        cost: 2.5,
+        lines_added: 10,
+        lines_removed: 3,
+        model_name: Some("Sonnet 4.5".to_string()),
+        workspace_dir: Some("/src/app".to_string()),
+        device_id: None,
+        token_breakdown: Some(crate::models::TokenBreakdown {
+            input_tokens: 1000,
+            output_tokens: 200,
+            cache_read_tokens: 5000,
+            cache_creation_tokens: 300,
+        }),
+        max_tokens_observed: None,
+        active_time_seconds: None,
+        last_activity: None,
+    };
+    db.update_session("session-a", update).unwrap();
+
+    let today = current_date();
+    let filter = SessionFilter {
+        since: Some(today.clone()),
+        until: Some(today),
+        ..SessionFilter::default()
+    };
+    let sessions = db.find_sessions(&filter, None, 0).unwrap();
+    assert_eq!(sessions.len(), 1);
+    let session = &sessions[0];
+    assert_eq!(session.model_name.as_deref(), Some("Sonnet 4.5"));
+    assert_eq!(session.workspace_dir.as_deref(), Some("/src/app"));
+    assert_eq!(session.totals.sessions, 1);
+    assert_eq!(session.totals.cost, 2.5);
+    assert_eq!(session.totals.lines_added, 10);
+    assert_eq!(session.totals.tokens(), 6500);
+    let filter = SessionFilter {
+        until: Some("2000-01-01".to_string()),
+        ..SessionFilter::default()
+    };
+    assert!(db.find_sessions(&filter, None, 0).unwrap().is_empty());
+}
+
+#[test]
+fn test_find_sessions() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let db = SqliteDatabase::new(&db_path).unwrap();
+    {
+        let conn = db.get_connection().unwrap();
+        for (id, start, updated, model, workspace, active) in [
+            (
+                "aaa-1",
+                "2026-10-01T09:00:00+00:00",
+                "2026-10-01T11:00:00+00:00",
+                "Opus 4.1",
+                "/work/app",
+                1800,
+            ),
+            (
+                "aab-2",
+                "2026-10-02T09:00:00+00:00",
+                "2026-10-02T09:30:00+00:00",
+                "Sonnet 4.5",
+                "/work/app/api",
+                0,
+            ),
+            (
+                "bbb-3",
+                "2026-10-03T09:00:00+00:00",
+                "2026-10-03T10:00:00+00:00",
+                "Sonnet 4.5",
+                "/work/app_old",
+                0,
+            ),
+        ] {
+            conn.execute(
+                "INSERT INTO sessions (session_id, start_time, last_updated, cost, model_name,
+                                       workspace_dir, active_time_seconds, total_input_tokens)
+                 VALUES (?1, ?2, ?3, 4.0, ?4, ?5, ?6, 100)",
+                rusqlite::params![id, start, updated, model, workspace, active],
+            )
+            .unwrap();
+        }
+        conn.execute(
+            "INSERT INTO session_archive (session_id, start_time, end_time, archived_at, cost,
+                                          lines_added, lines_removed, model_name, workspace_dir)
+             VALUES ('aaa-1', '2026-09-30T09:00:00+00:00', '2026-09-30T10:00:00+00:00',
+                     '2026-10-01T09:00:00+00:00', 2.0, 5, 1, 'Opus 4.1', '/work/app')",
+            [],
+        )
+        .unwrap();
+    }
+
+    let ids = |filter: &SessionFilter| -> Vec<String> {
+        db.find_sessions(filter, None, 0)
+            .unwrap()
+            .into_iter()
+            .map(|s| s.session_id)
+            .collect()
+    };
+
+    // Most recently updated first; archived work periods only on request
+    let all = SessionFilter::default();
+    assert_eq!(ids(&all), vec!["bbb-3", "aab-2", "aaa-1"]);
+    assert_eq!(db.count_sessions_matching(&all).unwrap(), 3);
+    let archived = SessionFilter {
+        archived: true,
+        ..SessionFilter::default()
+    };
+    assert_eq!(db.count_sessions_matching(&archived).unwrap(), 4);
+
+    // Paging
+    let page: Vec<_> = db
+        .find_sessions(&archived, Some(2), 2)
+        .unwrap()
+        .into_iter()
+        .map(|s| (s.session_id, s.archived))
+        .collect();
+    assert_eq!(
+        page,
+        vec![("aaa-1".to_string(), false), ("aaa-1".to_string(), true)]
+    );
+
+    // The workspace matches subdirectories but not siblings sharing the prefix
+    let filter = SessionFilter {
+        workspace: Some("/work/app/".to_string()),
+        ..SessionFilter::default()
+    };
+    assert_eq!(ids(&filter), vec!["aab-2", "aaa-1"]);
+
+    let filter = SessionFilter {
+        model: Some("sonnet".to_string()),
+        since: Some("2026-10-03".to_string()),
+        ..SessionFilter::default()
+    };
+    assert_eq!(ids(&filter), vec!["bbb-3"]);
+
+    let filter = SessionFilter {
+        session_id: Some("aa".to_string()),
+        until: Some("2026-10-01".to_string()),
+        archived: true,
+        ..SessionFilter::default()
+    };
+    let rows = db.find_sessions(&filter, None, 0).unwrap();
+    assert_eq!(rows.len(), 2);
+    let archived_row = &rows[1];
+    assert!(archived_row.archived);
+    assert_eq!(archived_row.totals.cost, 2.0);
+    assert_eq!(archived_row.totals.lines_added, 5);
+    assert_eq!(archived_row.totals.tokens(), 0);
+
+    // Burn rate per mode, as shown at the last update
+    let live = &rows[0];
+    assert_eq!(live.wall_time_seconds(), Some(7200));
+    assert_eq!(live.burn_rate("wall_clock", 60), Some(2.0));
+    assert_eq!(live.burn_rate("auto_reset", 60), Some(2.0));
+    assert_eq!(live.burn_rate("active_time", 60), Some(8.0));
+    assert_eq!(live.burn_rate("wall_clock", 7200), None);
+    assert_eq!(archived_row.burn_rate("active_time", 60), None);
+}
+
+#[test]
+fn test_export_import_tables() {
+    use rusqlite::types::Value;
+
+    let temp_dir = TempDir::new().unwrap();
+    let source = SqliteDatabase::new(&temp_dir.path().join("source.db")).unwrap();
+    source
+        .update_session(
+            "session-a",
+            SessionUpdate {
+                cost: 2.5,
+                lines_added: 10,
+                lines_removed: 3,
+                model_name: Some("Opus 4.1".to_string()),
+                workspace_dir: Some("/src/app".to_string()),
+                device_id: None,
+                token_breakdown: None,
+                max_tokens_observed: Some(120_000),
+                active_time_seconds: None,
+                last_activity: None,
+            },
+        )
+        .unwrap();
+    source
+        .record_session_errors(&[SessionError {
+            session_id: "session-a".to_string(),
+            timestamp: "2026-10-17T12:00:00.000Z".to_string(),
+            kind: "api_error".to_string(),
+            message: "overloaded".to_string(),
+            reset_at: None,
+        }])
+        .unwrap();
+    {
+        let conn = Connection::open(temp_dir.path().join("source.db")).unwrap();
+        conn.execute(
+            "INSERT INTO session_archive (session_id, start_time, end_time, archived_at, cost, lines_added, lines_removed)
+             VALUES ('session-a', '2026-10-16T09:00:00+00:00', '2026-10-16T10:00:00+00:00', '2026-10-16T12:00:00+00:00', 1.25, 4, 1)",
+            [],
+        )
+        .unwrap();
+    }
+
+    let tables: Vec<&str> = EXPORT_TABLES.iter().map(|(name, _)| *name).collect();
+    let export = |db: &SqliteDatabase| -> Vec<TableDump> {
+        tables
+            .iter()
+            .map(|table| db.export_table(table).unwrap())
+            .collect()
+    };
+    let cost_of = |db: &SqliteDatabase, session_id: &str| {
+        let filter = SessionFilter {
+            session_id: Some(session_id.to_string()),
+            ..SessionFilter::default()
+        };
+        db.find_sessions(&filter, None, 0).unwrap()[0].totals.cost
+    };
+    let dumps = export(&source);
+    let archive = &dumps[1];
+    assert_eq!(archive.table, "session_archive");
+    assert_eq!(archive.rows.len(), 1);
+    // The local row id is not exported
+    assert!(!archive.columns.contains(&"id".to_string()));
+    assert!(source.export_table("sync_meta").is_err());
+    assert_eq!(source.schema_version().unwrap(), 12);
+
+    let target = SqliteDatabase::new(&temp_dir.path().join("target.db")).unwrap();
+    let results = target.import_tables(&dumps, false).unwrap();
+    assert_eq!(results[0].inserted, 1);
+    assert_eq!(results[1].inserted, 1);
+    assert_eq!(export(&target), dumps);
+
+    // Importing again keeps what is there, unless replacing
+    let mut changed = dumps[0].clone();
+    let cost = changed.columns.iter().position(|c| c == "cost").unwrap();
+    changed.rows[0][cost] = Value::Real(9.0);
+    let results = target
+        .import_tables(std::slice::from_ref(&changed), false)
+        .unwrap();
+    assert_eq!((results[0].inserted, results[0].skipped), (0, 1));
+    assert_eq!(cost_of(&target, "session-a"), 2.5);
+    let results = target.import_tables(&[changed], true).unwrap();
+    assert_eq!(results[0].replaced, 1);
+    assert_eq!(cost_of(&target, "session-a"), 9.0);
+
+    // Days with other totals are not added up, but reported
+    let mut daily = dumps[2].clone();
+    assert_eq!(daily.table, "daily_stats");
+    let results = target
+        .import_tables(std::slice::from_ref(&daily), false)
+        .unwrap();
+    assert!(results[0].differing.is_empty());
+    let total = daily
+        .columns
+        .iter()
+        .position(|c| c == "total_cost")
+        .unwrap();
+    daily.rows[0][total] = Value::Real(7.0);
+    let date = match &daily.rows[0][0] {
+        Value::Text(date) => date.clone(),
+        other => panic!("unexpected date {:?}", other),
+    };
+    let results = target.import_tables(&[daily], false).unwrap();
+    assert_eq!(results[0].skipped, 1);
+    assert_eq!(results[0].differing, vec![date]);
+
+    // Rows from other schema versions: unknown columns are left out, missing
+    // ones get their defaults
+    let other = TableDump {
+        table: "sessions".to_string(),
+        columns: vec![
+            "session_id".to_string(),
+            "start_time".to_string(),
+            "last_updated".to_string(),
+            "future_column".to_string(),
+        ],
+        rows: vec![vec![
+            Value::Text("session-b".to_string()),
+            Value::Text("2026-10-17T09:00:00+00:00".to_string()),
+            Value::Text("2026-10-17T10:00:00+00:00".to_string()),
+            Value::Integer(1),
+        ]],
+    };
+    let results = target.import_tables(&[other], false).unwrap();
+    assert_eq!(results[0].inserted, 1);
+    assert_eq!(
+        results[0].unknown_columns,
+        vec!["future_column".to_string()]
+    );
+    assert_eq!(cost_of(&target, "session-b"), 0.0);
+}
+
+#[test]
+fn test_import_transcript_sessions() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let db = SqliteDatabase::new(&db_path).unwrap();
+
+    let entry = |id: &str, session: &str, timestamp: &str| UsageEntry {
+        message_id: id.to_string(),
+        session_id: session.to_string(),
+        timestamp: timestamp.to_string(),
+        model: Some("claude-sonnet-4-5".to_string()),
+        input_tokens: 100,
+        output_tokens: 10,
+        cost: 0.25,
+        ..UsageEntry::default()
+    };
+    let session = |id: &str, usage: Vec<UsageEntry>| ImportedSession {
+        session_id: id.to_string(),
+        start_time: "2026-09-01T12:00:00+00:00".to_string(),
+        last_updated: "2026-09-02T12:00:00+00:00".to_string(),
+        model_name: Some("Sonnet 4.5".to_string()),
+        model_id: Some("claude-sonnet-4-5".to_string()),
+        workspace_dir: Some("/src/app".to_string()),
+        active_time_seconds: 600,
+        usage,
+    };
+    // Mid-day UTC, so the local dates are the same in any time zone
+    let sessions = [
+        session(
+            "imported-a",
+            vec![
+                entry("msg_1", "imported-a", "2026-09-01T12:00:00.000Z"),
+                entry("msg_2", "imported-a", "2026-09-02T12:00:00.000Z"),
+            ],
+        ),
+        session(
+            "imported-b",
+            vec![entry("msg_3", "imported-b", "2026-09-02T12:00:00.000Z")],
+        ),
+    ];
+
+    assert!(!db.session_exists("imported-a").unwrap());
+    assert_eq!(db.import_transcript_sessions(&sessions).unwrap(), (2, 3));
+    assert!(db.session_exists("imported-a").unwrap());
+    // Importing again adds nothing
+    assert_eq!(db.import_transcript_sessions(&sessions).unwrap(), (0, 0));
+
+    let days = db.get_daily_totals(None, None).unwrap();
+    assert_eq!(days.len(), 2);
+    assert_eq!(days[0].0, "2026-09-01");
+    assert_eq!(days[0].1.sessions, 1);
+    assert_eq!(days[1].1.sessions, 2);
+    // Transcripts carry no reported cost; the priced usage is the computed cost
+    assert_eq!(days[1].1.cost, 0.0);
+    assert_eq!(days[1].1.computed_cost, 0.5);
+    assert_eq!(days[1].1.input_tokens, 200);
+    let months = db.get_monthly_totals(None, None).unwrap();
+    assert_eq!(months.len(), 1);
+    assert_eq!(months[0].1.sessions, 2);
+    assert_eq!(months[0].1.cost, 0.0);
+    assert_eq!(months[0].1.computed_cost, 0.75);
+    // Workspace budgets see the usage on the days it happened
+    assert_eq!(
+        db.get_workspace_cost_since("/src/app", "2026-09-02", true)
+            .unwrap(),
+        0.5
+    );
+
+    let imported = db
+        .find_sessions(&SessionFilter::default(), None, 0)
+        .unwrap();
+    let a = imported
+        .iter()
+        .find(|s| s.session_id == "imported-a")
+        .unwrap();
+    assert_eq!(a.totals.cost, 0.0);
+    assert_eq!(a.totals.computed_cost, 0.5);
+    assert_eq!(a.totals.tokens(), 220);
+    assert_eq!(a.active_time_seconds, Some(600));
+    assert_eq!(a.model_name.as_deref(), Some("Sonnet 4.5"));
+    assert_eq!(
+        db.get_usage_entries_since("2026-01-01T00:00:00.000Z")
+            .unwrap()
+            .len(),
+        3
+    );
+}
+
+#[test]
+fn test_usage_entries() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let db = SqliteDatabase::new(&db_path).unwrap();
+
+    let entry = |id: &str, timestamp: &str, output: u64| UsageEntry {
+        message_id: id.to_string(),
+        session_id: "session-a".to_string(),
+        timestamp: timestamp.to_string(),
+        model: Some("claude-sonnet-4-5".to_string()),
+        output_tokens: output,
+        cost: 0.5,
+        ..UsageEntry::default()
+    };
+    let recorded = db
+        .record_usage_entries(&[
+            entry("msg_2", "2026-10-02T10:00:00.000Z", 20),
+            entry("msg_1", "2026-10-01T10:00:00.000Z", 10),
+            entry("msg_0", "2026-09-01T10:00:00.000Z", 5),
+        ])
+        .unwrap();
+    assert_eq!(recorded, 3);
+
+    // Re-recording keeps the first row for a message id
+    let recorded = db
+        .record_usage_entries(&[
+            entry("msg_2", "2026-10-02T10:00:00.000Z", 999),
+            entry("msg_3", "2026-10-03T10:00:00.000Z", 30),
+        ])
+        .unwrap();
+    assert_eq!(recorded, 1);
+
+    let entries = db
+        .get_usage_entries_since("2026-09-15T00:00:00.000Z")
+        .unwrap();
+    let ids: Vec<_> = entries.iter().map(|e| e.message_id.as_str()).collect();
+    assert_eq!(ids, vec!["msg_1", "msg_2", "msg_3"]);
+    assert_eq!(entries[1].output_tokens, 20);
+    assert_eq!(entries[1].tokens(), 20);
+
+    // Sessions report whether their usage is in usage_entries
+    let update = SessionUpdate {
+        cost: 1.0,
+        lines_added: 0,
+        lines_removed: 0,
+        model_name: None,
+        workspace_dir: None,
+        device_id: None,
+        token_breakdown: None,
+        max_tokens_observed: None,
+        active_time_seconds: None,
+        last_activity: None,
+    };
+    db.update_session("session-a", update.clone()).unwrap();
+    db.update_session("session-b", update).unwrap();
+    let activity = db.get_session_activity_since(&current_date()).unwrap();
+    assert_eq!(activity.len(), 2);
+    for session in &activity {
+        assert_eq!(session.has_entries, session.session_id == "session-a");
+        assert_eq!(session.cost, 1.0);
+    }
+}
+
 #[test]
 fn test_session_update_delta_calculation() {
     // This test verifies the critical bug fix where costs were being accumulated
@@ -1350,3 +2160,60 @@ fn test_reset_session_max_tokens_nonexistent_session() {
         "Reset on non-existent session should succeed"
     );
 }
+
+#[test]
+fn test_session_errors() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let db = SqliteDatabase::new(&db_path).unwrap();
+
+    let error = |session: &str, timestamp: &str, kind: &str, reset_at: Option<&str>| SessionError {
+        session_id: session.to_string(),
+        timestamp: timestamp.to_string(),
+        kind: kind.to_string(),
+        message: "5-hour limit reached".to_string(),
+        reset_at: reset_at.map(str::to_string),
+    };
+    let errors = [
+        error(
+            "session-a",
+            "2026-10-17T08:00:00.000Z",
+            "usage_limit",
+            Some("2026-10-17T10:00:00.000Z"),
+        ),
+        error(
+            "session-a",
+            "2026-10-17T12:00:00.000Z",
+            "usage_limit",
+            Some("2026-10-17T15:00:00.000Z"),
+        ),
+        error("session-a", "2026-10-17T12:01:00.000Z", "api_error", None),
+        error("session-b", "2026-10-17T12:02:00.000Z", "api_error", None),
+    ];
+    assert_eq!(db.record_session_errors(&errors).unwrap(), 4);
+    // Re-reading the same transcript lines records nothing new
+    assert_eq!(db.record_session_errors(&errors).unwrap(), 0);
+
+    assert_eq!(
+        db.count_session_errors("session-a", "api_error").unwrap(),
+        1
+    );
+    assert_eq!(
+        db.count_session_errors("session-a", "usage_limit").unwrap(),
+        2
+    );
+    assert_eq!(
+        db.count_session_errors("session-c", "api_error").unwrap(),
+        0
+    );
+
+    let active = db
+        .get_active_usage_limit("2026-10-17T13:00:00.000Z")
+        .unwrap()
+        .unwrap();
+    assert_eq!(active.reset_at.as_deref(), Some("2026-10-17T15:00:00.000Z"));
+    assert!(db
+        .get_active_usage_limit("2026-10-17T16:00:00.000Z")
+        .unwrap()
+        .is_none());
+}
@@ -0,0 +1,116 @@
+use super::schema::{SessionActivity, UsageEntry};
+use super::SqliteDatabase;
+use crate::retry::{retry_if_retryable, RetryConfig};
+use rusqlite::{params, Result, Transaction};
+
+impl SqliteDatabase {
+    /// Record per-response token usage, ignoring entries that were already recorded
+    ///
+    /// Transcripts are re-read on every render, so the same response is offered many
+    /// times; the message id primary key keeps it to one row. Returns the number of
+    /// new rows.
+    pub fn record_usage_entries(&self, entries: &[UsageEntry]) -> Result<usize> {
+        if entries.is_empty() {
+            return Ok(0);
+        }
+        let retry_config = RetryConfig::for_db_ops();
+
+        retry_if_retryable(&retry_config, || {
+            let mut conn = self.get_connection()?;
+            let tx = conn.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+            let inserted = Self::record_usage_entries_tx(&tx, entries)?;
+            tx.commit()?;
+            Ok(inserted)
+        })
+        .map_err(|e| match e {
+            crate::error::StatuslineError::Database(db_err) => db_err,
+            _ => rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_BUSY),
+                Some(e.to_string()),
+            ),
+        })
+    }
+
+    /// `record_usage_entries` within an open transaction
+    pub(super) fn record_usage_entries_tx(
+        tx: &Transaction,
+        entries: &[UsageEntry],
+    ) -> Result<usize> {
+        let mut stmt = tx.prepare_cached(
+            "INSERT OR IGNORE INTO usage_entries
+                (message_id, session_id, timestamp, model, input_tokens, output_tokens,
+                 cache_write_tokens, cache_read_tokens, cost)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        )?;
+        let mut inserted = 0;
+        for entry in entries {
+            inserted += stmt.execute(params![
+                entry.message_id,
+                entry.session_id,
+                entry.timestamp,
+                entry.model,
+                entry.input_tokens as i64,
+                entry.output_tokens as i64,
+                entry.cache_write_tokens as i64,
+                entry.cache_read_tokens as i64,
+                entry.cost,
+            ])?;
+        }
+        Ok(inserted)
+    }
+
+    /// Get usage entries at or after `since` (UTC RFC3339), oldest first
+    pub fn get_usage_entries_since(&self, since: &str) -> Result<Vec<UsageEntry>> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT message_id, session_id, timestamp, model, COALESCE(input_tokens, 0),
+                    COALESCE(output_tokens, 0), COALESCE(cache_write_tokens, 0),
+                    COALESCE(cache_read_tokens, 0), COALESCE(cost, 0.0)
+             FROM usage_entries WHERE timestamp >= ?1 ORDER BY timestamp",
+        )?;
+        let rows = stmt.query_map(params![since], |row| {
+            let tokens = |i: usize| row.get::<_, i64>(i).map(|t| t.max(0) as u64);
+            Ok(UsageEntry {
+                message_id: row.get(0)?,
+                session_id: row.get(1)?,
+                timestamp: row.get(2)?,
+                model: row.get(3)?,
+                input_tokens: tokens(4)?,
+                output_tokens: tokens(5)?,
+                cache_write_tokens: tokens(6)?,
+                cache_read_tokens: tokens(7)?,
+                cost: row.get(8)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// Get the span and totals of sessions last active on or after `start_date`
+    /// (YYYY-MM-DD)
+    pub fn get_session_activity_since(&self, start_date: &str) -> Result<Vec<SessionActivity>> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT s.session_id, s.start_time, s.last_updated,
+                    CASE WHEN COALESCE(s.computed_cost, 0.0) > 0.0
+                         THEN s.computed_cost ELSE COALESCE(s.cost, 0.0) END,
+                    COALESCE(s.total_input_tokens, 0) + COALESCE(s.total_output_tokens, 0) +
+                    COALESCE(s.total_cache_read_tokens, 0) + COALESCE(s.total_cache_creation_tokens, 0),
+                    EXISTS (SELECT 1 FROM usage_entries u WHERE u.session_id = s.session_id)
+             FROM sessions s
+             WHERE substr(s.last_updated, 1, 10) >= ?1
+             ORDER BY s.start_time",
+        )?;
+        let rows = stmt.query_map(params![start_date], |row| {
+            let tokens: i64 = row.get(4)?;
+            Ok(SessionActivity {
+                session_id: row.get(0)?,
+                start_time: row.get(1)?,
+                last_updated: row.get(2)?,
+                cost: row.get(3)?,
+                tokens: tokens.max(0) as u64,
+                has_entries: row.get(5)?,
+            })
+        })?;
+        rows.collect()
+    }
+}
@@ -3,10 +3,14 @@
 //! This module handles the visual formatting of the statusline output,
 //! including colors, progress bars, and layout.
 
+use std::collections::HashMap;
+
 use crate::config;
-use crate::git::{format_git_info, get_git_status};
-use crate::layout::{LayoutRenderer, VariableBuilder};
-use crate::models::{ContextUsage, Cost, ModelType};
+use crate::git::{
+    format_git_counts, format_git_info, format_git_state, format_git_tracking, get_git_status,
+};
+use crate::layout::{LayoutRenderer, Powerline, VariableBuilder};
+use crate::models::{ContextUsage, Cost, ModelType, StatuslineInput};
 use crate::theme::{get_theme_manager, Theme};
 use crate::utils::{calculate_context_usage, parse_duration, sanitize_for_terminal, shorten_path};
 
@@ -202,6 +206,15 @@ impl Colors {
         theme.resolve_color(&theme.colors.git_branch)
     }
 
+    /// Get in-progress git operation color from theme
+    pub fn git_state() -> String {
+        if !Self::enabled() {
+            return String::new();
+        }
+        let theme = get_current_theme();
+        theme.resolve_color(&theme.colors.git_state)
+    }
+
     /// Get duration color from theme
     pub fn duration() -> String {
         if !Self::enabled() {
@@ -211,6 +224,24 @@ impl Colors {
         theme.resolve_color(&theme.colors.duration)
     }
 
+    /// Get usage limit countdown color from theme
+    pub fn limit_reset() -> String {
+        if !Self::enabled() {
+            return String::new();
+        }
+        let theme = get_current_theme();
+        theme.resolve_color(&theme.colors.limit_reset)
+    }
+
+    /// Get API error count color from theme
+    pub fn api_error() -> String {
+        if !Self::enabled() {
+            return String::new();
+        }
+        let theme = get_current_theme();
+        theme.resolve_color(&theme.colors.api_error)
+    }
+
     /// Get lines added color from theme
     pub fn lines_added() -> String {
         if !Self::enabled() {
@@ -264,8 +295,28 @@ impl Colors {
             theme.resolve_color(&theme.colors.context_normal)
         }
     }
+
+    /// Get budget color based on percentage spent and the budget thresholds
+    pub fn budget_color(percentage: f64) -> String {
+        if !Self::enabled() {
+            return String::new();
+        }
+        let theme = get_current_theme();
+        let config = config::get_config();
+
+        match crate::budget::BudgetLevel::from_pct(percentage, &config.budget) {
+            crate::budget::BudgetLevel::Critical => {
+                theme.resolve_color(&theme.colors.budget_critical)
+            }
+            crate::budget::BudgetLevel::Warning => {
+                theme.resolve_color(&theme.colors.budget_warning)
+            }
+            crate::budget::BudgetLevel::Normal => theme.resolve_color(&theme.colors.budget_normal),
+        }
+    }
 }
 
+#[allow(dead_code)] // Public API - used by library consumers
 pub fn format_output(
     current_dir: &str,
     model_name: Option<&str>,
@@ -282,11 +333,116 @@ pub fn format_output(
         cost,
         daily_total,
         session_id,
+        None,
+        &config.display,
+    )
+}
+
+/// Extra context available when rendering a full Claude Code input.
+struct RenderContext<'a> {
+    /// The parsed stdin payload
+    input: &'a StatuslineInput,
+    /// Variables collected by the data providers (see `render::collect_provider_vars`)
+    provider_vars: &'a HashMap<String, String>,
+}
+
+/// Print the statusline for a parsed Claude Code input.
+///
+/// Equivalent to [`format_output`], but with access to the full input payload and
+/// to the variables already collected by the data providers. Git information is
+/// taken from `provider_vars` instead of running `git status` again, and layout
+/// variables sourced from the input ({claude_version}, {output_style}, {model_id},
+/// {project_dir}, {exceeds_200k}) and from providers ({stats_*}, {gsd_*}) are
+/// populated as well.
+pub fn format_input(
+    input: &StatuslineInput,
+    current_dir: &str,
+    daily_total: f64,
+    provider_vars: &HashMap<String, String>,
+) {
+    let config = config::get_config();
+    let context = RenderContext {
+        input,
+        provider_vars,
+    };
+    format_output_with_config(
+        current_dir,
+        input.model.as_ref().and_then(|m| m.display_name.as_deref()),
+        input.transcript.as_deref(),
+        input.cost.as_ref(),
+        daily_total,
+        input.session_id.as_deref(),
+        Some(&context),
         &config.display,
     )
 }
 
+/// Git values used by the layout engine, all sanitized and color-free except `info`
+/// and `state`.
+#[derive(Debug, PartialEq)]
+struct GitParts {
+    /// Full colored summary (branch plus change counts)
+    info: String,
+    /// Branch name as reported by git
+    branch: String,
+    /// Change counts only (e.g. "+2 ~1"), empty when clean
+    status: String,
+    /// Ahead/behind/stash summary (e.g. "↑2 ≡1"), empty when in sync
+    tracking: String,
+    /// Colored in-progress operation (e.g. "REBASE 3/7 !2"), empty when idle
+    state: String,
+}
+
+/// Resolve git layout values.
+///
+/// Uses provider-collected variables when available, otherwise runs `git status`.
+/// Returns `None` outside a git repository.
+fn resolve_git_parts(
+    current_dir: &str,
+    provider_vars: Option<&HashMap<String, String>>,
+) -> Option<GitParts> {
+    match provider_vars {
+        Some(vars) => {
+            let branch = vars.get("git_branch")?;
+            let count = |key: &str| {
+                vars.get(key)
+                    .and_then(|v| v.parse::<usize>().ok())
+                    .unwrap_or(0)
+            };
+            Some(GitParts {
+                info: vars.get("git").cloned().unwrap_or_default(),
+                branch: branch.clone(),
+                status: vars.get("git_status").cloned().unwrap_or_default(),
+                tracking: format_git_tracking(
+                    count("git_ahead"),
+                    count("git_behind"),
+                    count("git_stash"),
+                ),
+                state: vars.get("git_state").cloned().unwrap_or_default(),
+            })
+        }
+        None => {
+            let git_status = get_git_status(current_dir)?;
+            Some(GitParts {
+                info: format_git_info(&git_status).trim_start().to_string(),
+                branch: sanitize_for_terminal(&git_status.branch),
+                status: format_git_counts(&git_status),
+                tracking: format_git_tracking(
+                    git_status.ahead,
+                    git_status.behind,
+                    git_status.stash,
+                ),
+                state: match format_git_state(&git_status) {
+                    state if state.is_empty() => state,
+                    state => format!("{}{}{}", Colors::git_state(), state, Colors::reset()),
+                },
+            })
+        }
+    }
+}
+
 /// Format output with explicit display configuration (returns String)
+#[allow(clippy::too_many_arguments)]
 fn format_statusline_string(
     current_dir: &str,
     model_name: Option<&str>,
@@ -294,6 +450,7 @@ fn format_statusline_string(
     cost: Option<&Cost>,
     daily_total: f64,
     session_id: Option<&str>,
+    provider_vars: Option<&HashMap<String, String>>,
     display_config: &config::DisplayConfig,
 ) -> String {
     log::debug!(
@@ -337,11 +494,9 @@ fn format_statusline_string(
 
     // 2. Git status
     if display_config.show_git {
-        if let Some(git_status) = get_git_status(current_dir) {
-            let git_info = format_git_info(&git_status);
-            if !git_info.is_empty() {
-                // Trim leading space from git_info (legacy format)
-                parts.push(git_info.trim_start().to_string());
+        if let Some(git) = resolve_git_parts(current_dir, provider_vars) {
+            if !git.info.is_empty() {
+                parts.push(git.info);
             }
         }
     }
@@ -517,6 +672,7 @@ fn format_statusline_string(
 ///
 /// This function builds all component variables and renders them
 /// using the user's layout configuration (preset or custom format).
+#[allow(clippy::too_many_arguments)]
 fn format_statusline_with_layout(
     current_dir: &str,
     model_name: Option<&str>,
@@ -524,6 +680,7 @@ fn format_statusline_with_layout(
     cost: Option<&Cost>,
     daily_total: f64,
     session_id: Option<&str>,
+    context: Option<&RenderContext>,
     layout_config: &config::LayoutConfig,
 ) -> String {
     let full_config = config::get_config();
@@ -549,39 +706,29 @@ fn format_statusline_with_layout(
     );
 
     // Git status (with component config)
-    if let Some(git_status) = get_git_status(current_dir) {
-        let git_info = format_git_info(&git_status);
-        let branch = sanitize_for_terminal(&git_status.branch);
-        let is_dirty = git_status.added > 0
-            || git_status.modified > 0
-            || git_status.deleted > 0
-            || git_status.untracked > 0;
-
-        // Build status-only string (without branch)
-        let status_parts: Vec<String> = [
-            (git_status.added > 0).then(|| format!("+{}", git_status.added)),
-            (git_status.modified > 0).then(|| format!("~{}", git_status.modified)),
-            (git_status.deleted > 0).then(|| format!("-{}", git_status.deleted)),
-            (git_status.untracked > 0).then(|| format!("?{}", git_status.untracked)),
-        ]
-        .into_iter()
-        .flatten()
-        .collect();
-        let status_only = status_parts.join(" ");
+    let provider_vars = context.map(|c| c.provider_vars);
+    if let Some(git) = resolve_git_parts(current_dir, provider_vars) {
+        let is_dirty = !git.status.is_empty();
 
         builder = builder.git_with_config(
-            git_info.trim_start(),
-            Some(&branch),
-            if status_only.is_empty() {
+            &git.info,
+            Some(&git.branch),
+            if git.status.is_empty() {
                 None
             } else {
-                Some(&status_only)
+                Some(&git.status)
+            },
+            if git.tracking.is_empty() {
+                None
+            } else {
+                Some(&git.tracking)
             },
             is_dirty,
             &Colors::green(),
             &reset,
             &components.git,
         );
+        builder = builder.set("git_state", git.state);
     }
 
     // Context usage (with component config)
@@ -672,6 +819,167 @@ fn format_statusline_with_layout(
         }
     }
 
+    // Cost and cache savings computed from token usage, from the raw stats provider values
+    if let Some(vars) = provider_vars {
+        let value = |key: &str| vars.get(key).and_then(|v| v.parse::<f64>().ok());
+        let session = value("stats_cost_computed");
+        builder = builder.computed_cost(
+            session,
+            value("stats_cost_computed_today"),
+            value("stats_cost_computed_month"),
+            value("stats_cost_divergence"),
+            &get_cost_color(session.unwrap_or(0.0)),
+            &Colors::yellow(),
+            &reset,
+        );
+        builder = builder.cache_saved(
+            value("stats_cache_saved"),
+            value("stats_cache_saved_today"),
+            &Colors::green(),
+            &Colors::red(),
+            &reset,
+        );
+
+        // End-of-day and end-of-month forecasts, colored against the matching budget
+        let budget = &full_config.budget;
+        let forecast_color = |value: Option<f64>, limit: Option<f64>| match (value, limit) {
+            (Some(value), Some(limit)) if limit > 0.0 => {
+                Colors::budget_color(value / limit * 100.0)
+            }
+            _ => Colors::light_gray(),
+        };
+        let (today, month) = (value("forecast_today"), value("forecast_month"));
+        builder = builder.forecast(
+            today,
+            month,
+            value("forecast_tokens_today").map(|t| t as u64),
+            value("forecast_tokens_month").map(|t| t as u64),
+            &forecast_color(today, budget.daily),
+            &forecast_color(month, budget.monthly),
+            &Colors::light_gray(),
+            &reset,
+        );
+
+        // Active 5-hour usage block, colored by its projected share of the block limit
+        if let (Some(elapsed), Some(remaining), Some(tokens), Some(cost)) = (
+            value("block_elapsed"),
+            value("block_remaining"),
+            value("block_tokens"),
+            value("block_cost"),
+        ) {
+            let projected = value("block_projected_tokens").zip(value("block_projected_cost"));
+            let usage_color = match (projected, value("block_limit")) {
+                (Some((projected, _)), Some(limit)) if limit > 0.0 => {
+                    Colors::budget_color(projected / limit * 100.0)
+                }
+                _ => Colors::light_gray(),
+            };
+            let exhausts = value("block_exhausts").map(|s| format_duration(s as u64));
+            builder = builder.block(
+                &format_duration(elapsed as u64),
+                &format_duration(remaining as u64),
+                tokens as u64,
+                cost,
+                projected.map(|(tokens, cost)| (tokens as u64, cost)),
+                exhausts.as_deref(),
+                &Colors::duration(),
+                &usage_color,
+                &Colors::red(),
+                &reset,
+            );
+        }
+
+        // Usage limit countdown and API errors found in the transcript
+        let limit_reset = value("limit_reset").map(|s| format_duration(s as u64));
+        builder = builder.rate_limits(
+            limit_reset.as_deref(),
+            vars.get("limit_reset_at").map(String::as_str),
+            value("api_errors").map(|c| c as u64),
+            &Colors::limit_reset(),
+            &Colors::api_error(),
+            &reset,
+        );
+
+        // Tightest spending budget for this directory
+        if let (Some(spent), Some(limit)) = (value("budget_spent"), value("budget_limit")) {
+            let pct = spent / limit * 100.0;
+            builder = builder.budget(
+                vars.get("budget_name").map(String::as_str).unwrap_or(""),
+                spent,
+                limit,
+                &format_raw_bar(pct, full_config.display.progress_bar_width),
+                &Colors::budget_color(pct),
+                &reset,
+            );
+        }
+    }
+
+    // API vs wall-clock time (reported by Claude Code)
+    if let Some(cost_data) = cost {
+        let wall_time = cost_data
+            .total_duration_ms
+            .map(|ms| format_duration(ms / 1000));
+        let api_time = cost_data
+            .total_api_duration_ms
+            .map(|ms| format_duration(ms / 1000));
+        builder = builder.api_timing(
+            wall_time.as_deref(),
+            api_time.as_deref(),
+            cost_data.api_time_percentage(),
+            &Colors::duration(),
+            &reset,
+        );
+    }
+
+    // Local clock time
+    builder = builder.clock(
+        &chrono::Local::now().format("%H:%M").to_string(),
+        &Colors::light_gray(),
+        &reset,
+    );
+
+    // Claude Code session metadata (version, output style, model id, project root)
+    if let Some(input) = context.map(|c| c.input) {
+        let version = input.version.as_deref().map(sanitize_for_terminal);
+        let output_style = input
+            .output_style
+            .as_ref()
+            .and_then(|s| s.name.as_deref())
+            .map(sanitize_for_terminal);
+        let model_id = input
+            .model
+            .as_ref()
+            .and_then(|m| m.id.as_deref())
+            .map(sanitize_for_terminal);
+        builder = builder.claude_code(
+            version.as_deref(),
+            output_style.as_deref(),
+            model_id.as_deref(),
+            &Colors::light_gray(),
+            &reset,
+        );
+
+        if let Some(project_dir) = input
+            .workspace
+            .as_ref()
+            .and_then(|w| w.project_dir.as_deref())
+        {
+            let short_project = sanitize_for_terminal(&shorten_path(project_dir));
+            let project_name = std::path::Path::new(project_dir)
+                .file_name()
+                .and_then(|n| n.to_str())
+                .map(sanitize_for_terminal)
+                .unwrap_or_default();
+            builder = builder.project(&short_project, &project_name, &Colors::directory(), &reset);
+        }
+
+        builder = builder.exceeds_200k(
+            input.exceeds_200k_tokens.unwrap_or(false),
+            &Colors::context_color(100.0),
+            &reset,
+        );
+    }
+
     // Token rate (with component config)
     // Uses rolling window if configured, otherwise session average
     // Now respects rate_display config (output_only, input_only, both)
@@ -704,10 +1012,23 @@ fn format_statusline_with_layout(
         }
     }
 
+    // Remaining provider variables ({stats_*}, {gsd_*}, {git_status}); values built
+    // above take precedence so existing variables render exactly as before.
+    if let Some(vars) = provider_vars {
+        builder = builder.extend_missing(vars);
+    }
+
     // Build variables and render
     let variables = builder.build();
-    let renderer = LayoutRenderer::from_config(layout_config);
-    renderer.render(&variables)
+    let mut renderer = LayoutRenderer::from_config(layout_config);
+    if layout_config.powerline {
+        let theme = Colors::enabled().then(get_current_theme);
+        renderer = renderer.with_powerline(Powerline::new(
+            &layout_config.powerline_style,
+            theme.as_ref(),
+        ));
+    }
+    renderer.render_to_width(&variables, layout_config.target_width())
 }
 
 /// Render the statusline from a pre-collected variable map using the
@@ -728,6 +1049,7 @@ pub fn render_with_vars(
 }
 
 /// Format output with explicit display configuration (prints to stdout)
+#[allow(clippy::too_many_arguments)]
 fn format_output_with_config(
     current_dir: &str,
     model_name: Option<&str>,
@@ -735,13 +1057,13 @@ fn format_output_with_config(
     cost: Option<&Cost>,
     daily_total: f64,
     session_id: Option<&str>,
+    context: Option<&RenderContext>,
     display_config: &config::DisplayConfig,
 ) {
     let full_config = config::get_config();
 
     // Check if custom layout is configured (non-empty format OR non-default preset)
-    let use_layout_system = !full_config.layout.format.is_empty()
-        || full_config.layout.preset.to_lowercase() != "default";
+    let use_layout_system = full_config.layout.is_custom();
 
     let output = if use_layout_system {
         format_statusline_with_layout(
@@ -751,6 +1073,7 @@ fn format_output_with_config(
             cost,
             daily_total,
             session_id,
+            context,
             &full_config.layout,
         )
     } else {
@@ -761,6 +1084,7 @@ fn format_output_with_config(
             cost,
             daily_total,
             session_id,
+            context.map(|c| c.provider_vars),
             display_config,
         )
     };
@@ -788,12 +1112,13 @@ pub fn format_output_to_string(
         cost,
         daily_total,
         session_id,
+        None,
         &config.display,
     )
 }
 
 /// Generate just the raw progress bar without colors (e.g., "[====>-----]")
-fn format_raw_bar(percentage: f64, width: usize) -> String {
+pub(crate) fn format_raw_bar(percentage: f64, width: usize) -> String {
     let filled_ratio = percentage / 100.0;
     let filled = (filled_ratio * width as f64).round() as usize;
     let filled = filled.min(width);
@@ -922,7 +1247,7 @@ fn get_cost_color(cost: f64) -> String {
     Colors::cost_color(cost)
 }
 
-fn format_duration(seconds: u64) -> String {
+pub(crate) fn format_duration(seconds: u64) -> String {
     if seconds < 60 {
         format!("{}s", seconds)
     } else if seconds < 3600 {
@@ -1346,6 +1671,7 @@ mod tests {
             total_cost_usd: Some(0.50),
             total_lines_added: None,
             total_lines_removed: None,
+            ..Default::default()
         };
 
         // The burn rate calculation happens in format_output
@@ -1441,6 +1767,75 @@ mod tests {
         assert_eq!(sanitized_model, "claude--opus");
     }
 
+    #[test]
+    fn test_resolve_git_parts_uses_provider_vars() {
+        let mut vars = HashMap::new();
+        vars.insert("git".to_string(), "main +1".to_string());
+        vars.insert("git_branch".to_string(), "main".to_string());
+        vars.insert("git_status".to_string(), "+1".to_string());
+        vars.insert("git_ahead".to_string(), "2".to_string());
+        vars.insert("git_stash".to_string(), "1".to_string());
+
+        // Provider vars are used as-is; the directory is never inspected
+        let parts = resolve_git_parts("/definitely/not/a/repo", Some(&vars));
+        assert_eq!(
+            parts,
+            Some(GitParts {
+                info: "main +1".to_string(),
+                branch: "main".to_string(),
+                status: "+1".to_string(),
+                tracking: "↑2 ≡1".to_string(),
+                state: String::new(),
+            })
+        );
+
+        // No git_branch means the git provider produced nothing
+        let empty = HashMap::new();
+        assert_eq!(
+            resolve_git_parts("/definitely/not/a/repo", Some(&empty)),
+            None
+        );
+        assert_eq!(resolve_git_parts("/definitely/not/a/repo", None), None);
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_layout_format_conditions() {
+        // Colored values, so the conditions have to look past the escape sequences
+        set_color_override(Some(true));
+        let input = StatuslineInput::default();
+        let layout = config::LayoutConfig {
+            format: "[{if budget_pct >= 80}over{elif budget_pct >= 50}close{else}ok{endif}]"
+                .to_string(),
+            width: crate::config::LayoutWidth::Columns(200),
+            ..config::LayoutConfig::default()
+        };
+        let render = |spent: &str| {
+            let vars = HashMap::from([
+                ("budget_spent".to_string(), spent.to_string()),
+                ("budget_limit".to_string(), "10".to_string()),
+            ]);
+            let context = RenderContext {
+                input: &input,
+                provider_vars: &vars,
+            };
+            format_statusline_with_layout(
+                "/definitely/not/a/repo",
+                None,
+                None,
+                None,
+                0.0,
+                None,
+                Some(&context),
+                &layout,
+            )
+        };
+        assert_eq!(render("9"), "[over]");
+        assert_eq!(render("6"), "[close]");
+        assert_eq!(render("1"), "[ok]");
+        set_color_override(None);
+    }
+
     #[test]
     fn test_token_rate_time_unit_conversion() {
         // Test that time_unit config produces correct rate multipliers and units
@@ -0,0 +1,409 @@
+//! Portable exports of the stats database.
+//!
+//! An export starts with a versioned header (format name, export format version,
+//! schema version of the database it came from) followed by the rows of each
+//! table as JSON objects keyed by column name. NDJSON puts the header and every
+//! row on a line of its own, so exports diff cleanly; JSON nests the same rows
+//! under `rows`. Because rows carry their column names, an export can be read by
+//! any later schema version: columns it lacks get their defaults, and columns
+//! the reading database does not know are left out. CSV lists each table as a
+//! section for spreadsheets and cannot be imported.
+
+use crate::database::{SqliteDatabase, TableDump, EXPORT_TABLES};
+use crate::error::{Result, StatuslineError};
+use rusqlite::types::Value;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value as Json};
+
+/// Name in the header of every export
+pub const EXPORT_FORMAT: &str = "claudia-statusline-export";
+
+/// Version of the export layout; bumped when a change would mislead older readers
+pub const EXPORT_VERSION: u32 = 1;
+
+/// The first line of an NDJSON export, and the top-level fields of a JSON one
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportHeader {
+    pub format: String,
+    pub version: u32,
+    /// Schema version of the database the export was taken from
+    pub schema_version: u32,
+    pub exported_at: String,
+    pub tables: Vec<String>,
+}
+
+/// The header and rows of an export
+#[derive(Debug, Clone, PartialEq)]
+pub struct Export {
+    pub header: ExportHeader,
+    pub tables: Vec<TableDump>,
+}
+
+/// Rows of each table as JSON objects, in the order read
+type TableRows = Vec<(String, Vec<Map<String, Json>>)>;
+
+/// One data line of an NDJSON export
+#[derive(Serialize, Deserialize)]
+struct NdjsonRow {
+    table: String,
+    row: Map<String, Json>,
+}
+
+/// A JSON export: the header fields with the rows of each table
+#[derive(Serialize, Deserialize)]
+struct JsonExport {
+    #[serde(flatten)]
+    header: ExportHeader,
+    rows: Map<String, Json>,
+}
+
+impl Export {
+    /// Read `tables` (names from [`EXPORT_TABLES`]) from the database
+    pub fn from_database(db: &SqliteDatabase, tables: &[&str]) -> Result<Self> {
+        let dumps = tables
+            .iter()
+            .map(|table| db.export_table(table))
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(Self {
+            header: ExportHeader {
+                format: EXPORT_FORMAT.to_string(),
+                version: EXPORT_VERSION,
+                schema_version: db.schema_version()?,
+                exported_at: chrono::Local::now().to_rfc3339(),
+                tables: tables.iter().map(|t| t.to_string()).collect(),
+            },
+            tables: dumps,
+        })
+    }
+
+    pub fn to_ndjson(&self) -> String {
+        let mut out = serde_json::to_string(&self.header).unwrap_or_default();
+        out.push('\n');
+        for dump in &self.tables {
+            for row in row_objects(dump) {
+                let line = NdjsonRow {
+                    table: dump.table.clone(),
+                    row,
+                };
+                out.push_str(&serde_json::to_string(&line).unwrap_or_default());
+                out.push('\n');
+            }
+        }
+        out
+    }
+
+    pub fn to_json(&self) -> String {
+        let export = JsonExport {
+            header: self.header.clone(),
+            rows: self
+                .tables
+                .iter()
+                .map(|dump| {
+                    let rows = row_objects(dump).into_iter().map(Json::Object).collect();
+                    (dump.table.clone(), Json::Array(rows))
+                })
+                .collect(),
+        };
+        serde_json::to_string_pretty(&export).unwrap_or_default() + "\n"
+    }
+
+    /// Each table as a `# table: name` comment, a header row and its rows
+    pub fn to_csv(&self) -> String {
+        let mut out = format!(
+            "# {} v{} (schema v{}), exported {}\n",
+            self.header.format,
+            self.header.version,
+            self.header.schema_version,
+            self.header.exported_at
+        );
+        for dump in &self.tables {
+            out.push_str(&format!("\n# table: {}\n", dump.table));
+            let header: Vec<_> = dump
+                .columns
+                .iter()
+                .map(|c| crate::report::csv_field(c))
+                .collect();
+            out.push_str(&header.join(","));
+            out.push('\n');
+            for row in &dump.rows {
+                let fields: Vec<_> = row
+                    .iter()
+                    .map(|value| match value {
+                        Value::Null => String::new(),
+                        Value::Integer(i) => i.to_string(),
+                        Value::Real(f) => f.to_string(),
+                        Value::Text(s) => crate::report::csv_field(s),
+                        Value::Blob(_) => String::new(),
+                    })
+                    .collect();
+                out.push_str(&fields.join(","));
+                out.push('\n');
+            }
+        }
+        out
+    }
+
+    /// Read an NDJSON or JSON export
+    ///
+    /// Fails for files that are not exports and for exports written in a newer
+    /// export format version than this build understands.
+    pub fn parse(content: &str) -> Result<Self> {
+        let (header, tables) = match serde_json::from_str::<JsonExport>(content) {
+            Ok(export) => {
+                check_header(&export.header)?;
+                let tables = export
+                    .rows
+                    .into_iter()
+                    .map(|(table, rows)| {
+                        let rows = match rows {
+                            Json::Array(rows) => rows
+                                .into_iter()
+                                .filter_map(|row| match row {
+                                    Json::Object(row) => Some(row),
+                                    _ => None,
+                                })
+                                .collect(),
+                            _ => Vec::new(),
+                        };
+                        (table, rows)
+                    })
+                    .collect();
+                (export.header, tables)
+            }
+            Err(_) => parse_ndjson(content)?,
+        };
+
+        // Tables in the order of the header, then any it does not list; NDJSON
+        // has no lines for empty tables
+        let mut ordered: TableRows = tables;
+        for table in &header.tables {
+            if !ordered.iter().any(|(name, _)| name == table) {
+                ordered.push((table.clone(), Vec::new()));
+            }
+        }
+        ordered.sort_by_key(|(table, _)| {
+            header
+                .tables
+                .iter()
+                .position(|t| t == table)
+                .unwrap_or(usize::MAX)
+        });
+        let tables = ordered
+            .into_iter()
+            .map(|(table, rows)| table_dump(table, rows))
+            .collect();
+        Ok(Self { header, tables })
+    }
+}
+
+fn parse_ndjson(content: &str) -> Result<(ExportHeader, TableRows)> {
+    let mut lines = content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+    let header = lines
+        .next()
+        .and_then(|(_, line)| serde_json::from_str::<ExportHeader>(line).ok())
+        .ok_or_else(|| StatuslineError::other("Not a statusline export (no export header)"))?;
+    check_header(&header)?;
+
+    let mut tables: TableRows = Vec::new();
+    for (index, line) in lines {
+        let row: NdjsonRow = serde_json::from_str(line).map_err(|e| {
+            StatuslineError::other(format!("Line {} of the export: {}", index + 1, e))
+        })?;
+        match tables.iter_mut().find(|(table, _)| *table == row.table) {
+            Some((_, rows)) => rows.push(row.row),
+            None => tables.push((row.table, vec![row.row])),
+        }
+    }
+    Ok((header, tables))
+}
+
+fn check_header(header: &ExportHeader) -> Result<()> {
+    if header.format != EXPORT_FORMAT {
+        return Err(StatuslineError::other(format!(
+            "Not a statusline export (format '{}')",
+            header.format
+        )));
+    }
+    if header.version > EXPORT_VERSION {
+        return Err(StatuslineError::other(format!(
+            "The export uses format version {}, but this statusline reads up to version {}; upgrade to import it",
+            header.version, EXPORT_VERSION
+        )));
+    }
+    Ok(())
+}
+
+/// Whether `table` is one that exports carry
+pub fn is_export_table(table: &str) -> bool {
+    EXPORT_TABLES.iter().any(|(name, _)| *name == table)
+}
+
+fn row_objects(dump: &TableDump) -> Vec<Map<String, Json>> {
+    dump.rows
+        .iter()
+        .map(|row| {
+            dump.columns
+                .iter()
+                .cloned()
+                .zip(row.iter().map(to_json))
+                .collect()
+        })
+        .collect()
+}
+
+/// Rows of one table; columns are those of its rows, in the order first seen
+fn table_dump(table: String, rows: Vec<Map<String, Json>>) -> TableDump {
+    let mut columns: Vec<String> = Vec::new();
+    for row in &rows {
+        for column in row.keys() {
+            if !columns.contains(column) {
+                columns.push(column.clone());
+            }
+        }
+    }
+    let rows = rows
+        .iter()
+        .map(|row| {
+            columns
+                .iter()
+                .map(|column| row.get(column).map_or(Value::Null, from_json))
+                .collect()
+        })
+        .collect();
+    TableDump {
+        table,
+        columns,
+        rows,
+    }
+}
+
+fn to_json(value: &Value) -> Json {
+    match value {
+        Value::Null => Json::Null,
+        Value::Integer(i) => Json::from(*i),
+        Value::Real(f) => serde_json::Number::from_f64(*f).map_or(Json::Null, Json::Number),
+        Value::Text(s) => Json::String(s.clone()),
+        // No exported column holds blobs
+        Value::Blob(_) => Json::Null,
+    }
+}
+
+fn from_json(value: &Json) -> Value {
+    match value {
+        Json::Null => Value::Null,
+        Json::Bool(b) => Value::Integer(*b as i64),
+        Json::Number(n) => match n.as_i64() {
+            Some(i) => Value::Integer(i),
+            None => n.as_f64().map_or(Value::Null, Value::Real),
+        },
+        Json::String(s) => Value::Text(s.clone()),
+        other => Value::Text(other.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn export() -> Export {
+        Export {
+            header: ExportHeader {
+                format: EXPORT_FORMAT.to_string(),
+                version: EXPORT_VERSION,
+                schema_version: 11,
+                exported_at: "2026-10-17T12:00:00+00:00".to_string(),
+                tables: vec!["sessions".to_string(), "meta".to_string()],
+            },
+            tables: vec![
+                TableDump {
+                    table: "sessions".to_string(),
+                    columns: vec![
+                        "session_id".to_string(),
+                        "cost".to_string(),
+                        "lines_added".to_string(),
+                        "model_name".to_string(),
+                    ],
+                    rows: vec![
+                        vec![
+                            Value::Text("s1".to_string()),
+                            Value::Real(1.0),
+                            Value::Integer(12),
+                            Value::Text("Opus, \"4\"".to_string()),
+                        ],
+                        vec![
+                            Value::Text("s2".to_string()),
+                            Value::Real(0.1 + 0.2),
+                            Value::Integer(0),
+                            Value::Null,
+                        ],
+                    ],
+                },
+                TableDump {
+                    table: "meta".to_string(),
+                    columns: vec!["key".to_string(), "value".to_string()],
+                    rows: vec![vec![
+                        Value::Text("created_at".to_string()),
+                        Value::Text("2026-01-01".to_string()),
+                    ]],
+                },
+            ],
+        }
+    }
+
+    /// The same tables with columns in the sorted order of JSON objects
+    fn sorted(export: &Export) -> Export {
+        let tables = export
+            .tables
+            .iter()
+            .map(|dump| table_dump(dump.table.clone(), row_objects(dump)))
+            .collect();
+        Export {
+            header: export.header.clone(),
+            tables,
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let export = export();
+        let ndjson = export.to_ndjson();
+        assert_eq!(ndjson.lines().count(), 4);
+        assert!(ndjson.starts_with(r#"{"format":"claudia-statusline-export","version":1,"#));
+        assert_eq!(Export::parse(&ndjson).unwrap(), sorted(&export));
+        assert_eq!(Export::parse(&export.to_json()).unwrap(), sorted(&export));
+    }
+
+    #[test]
+    fn test_csv() {
+        let csv = export().to_csv();
+        assert!(csv.contains(
+            "# table: sessions\nsession_id,cost,lines_added,model_name\ns1,1,12,\"Opus, \"\"4\"\"\"\n"
+        ));
+        assert!(csv.contains("# table: meta\nkey,value\ncreated_at,2026-01-01\n"));
+    }
+
+    #[test]
+    fn test_parse_versions() {
+        // Header fields this version does not know are ignored
+        let older = concat!(
+            r#"{"format":"claudia-statusline-export","version":1,"schema_version":9,"exported_at":"x","tables":["meta"],"device":"a"}"#,
+            "\n",
+            r#"{"table":"meta","row":{"key":"k","value":"v"}}"#,
+            "\n"
+        );
+        let parsed = Export::parse(older).unwrap();
+        assert_eq!(parsed.header.schema_version, 9);
+        assert_eq!(parsed.tables[0].rows.len(), 1);
+
+        let newer = older.replacen(r#""version":1"#, r#""version":2"#, 1);
+        assert!(Export::parse(&newer)
+            .unwrap_err()
+            .to_string()
+            .contains("format version 2"));
+        assert!(Export::parse("session_id,cost\n").is_err());
+        assert!(Export::parse(&older.replace("claudia-statusline-export", "other")).is_err());
+    }
+}
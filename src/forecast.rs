@@ -0,0 +1,561 @@
+//! Spend and token forecasting from `daily_stats` history.
+//!
+//! A typical day is estimated per weekday: the mean of the same weekday over the
+//! last `history_days`, or an exponentially weighted moving average (EWMA) of all
+//! recent days while a weekday has fewer than two samples. Days without a
+//! `daily_stats` row count as zero.
+//!
+//! The end-of-day forecast adds to today's total whichever is larger: what is left
+//! of a typical day, or the current burn rate continued for `burn_horizon_hours`.
+//! The end-of-month forecast adds a typical day for every remaining day of the month.
+
+use crate::config::ForecastConfig;
+use crate::database::SqliteDatabase;
+use crate::provider::{DataProvider, ProviderResult};
+use chrono::{Datelike, Days, Local, NaiveDate, NaiveDateTime, Timelike, Weekday};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Samples of a weekday needed before its own average is used instead of the EWMA.
+const MIN_WEEKDAY_SAMPLES: usize = 2;
+
+/// How much a forecast can be trusted, from the amount and spread of history.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Confidence {
+    Low,
+    Medium,
+    High,
+}
+
+impl Confidence {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Confidence::Low => "low",
+            Confidence::Medium => "medium",
+            Confidence::High => "high",
+        }
+    }
+
+    pub fn icon(&self) -> &'static str {
+        match self {
+            Confidence::Low => "○",
+            Confidence::Medium => "◐",
+            Confidence::High => "●",
+        }
+    }
+}
+
+/// Typical daily amount estimated from history.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TypicalDay {
+    pub value: f64,
+    /// Past days of the same weekday in the window; below 2 the value is an EWMA of all days
+    pub weekday_samples: usize,
+    /// Days of history in the window
+    pub days: usize,
+    /// Coefficient of variation (standard deviation / mean) of the days used
+    pub variation: f64,
+}
+
+impl TypicalDay {
+    pub fn confidence(&self) -> Confidence {
+        if self.weekday_samples >= 4 && self.variation <= 0.5 {
+            Confidence::High
+        } else if (self.weekday_samples >= MIN_WEEKDAY_SAMPLES || self.days >= 7)
+            && self.variation <= 1.0
+        {
+            Confidence::Medium
+        } else {
+            Confidence::Low
+        }
+    }
+}
+
+/// Daily totals of one quantity (cost or tokens).
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    days: BTreeMap<NaiveDate, f64>,
+    history_days: u64,
+}
+
+impl History {
+    pub fn new(days: BTreeMap<NaiveDate, f64>, history_days: u32) -> Self {
+        Self {
+            days,
+            history_days: history_days as u64,
+        }
+    }
+
+    /// Amount recorded on `date` (zero without a row).
+    pub fn on(&self, date: NaiveDate) -> f64 {
+        self.days.get(&date).copied().unwrap_or(0.0)
+    }
+
+    /// Total from `start` through `end`, inclusive.
+    pub fn sum(&self, start: NaiveDate, end: NaiveDate) -> f64 {
+        self.days.range(start..=end).map(|(_, v)| v).sum()
+    }
+
+    /// Typical amount for `weekday`, from the days before `today`.
+    ///
+    /// The window starts at the first recorded day or `history_days` ago, whichever
+    /// is later, so days before the statusline was installed are not counted as idle.
+    pub fn typical(&self, weekday: Weekday, today: NaiveDate, alpha: f64) -> Option<TypicalDay> {
+        let first = *self.days.keys().next()?;
+        let start = today
+            .checked_sub_days(Days::new(self.history_days))
+            .map_or(first, |d| d.max(first));
+        let past: Vec<(NaiveDate, f64)> = start
+            .iter_days()
+            .take_while(|d| *d < today)
+            .map(|d| (d, self.on(d)))
+            .collect();
+        if past.is_empty() {
+            return None;
+        }
+
+        let same: Vec<f64> = past
+            .iter()
+            .filter(|(d, _)| d.weekday() == weekday)
+            .map(|(_, v)| *v)
+            .collect();
+        let (value, variation) = if same.len() >= MIN_WEEKDAY_SAMPLES {
+            (mean(&same), variation(&same))
+        } else {
+            let all: Vec<f64> = past.iter().map(|(_, v)| *v).collect();
+            let alpha = alpha.clamp(0.0, 1.0);
+            let ewma = all[1..]
+                .iter()
+                .fold(all[0], |avg, v| alpha * v + (1.0 - alpha) * avg);
+            (ewma, variation(&all))
+        };
+
+        Some(TypicalDay {
+            value,
+            weekday_samples: same.len(),
+            days: past.len(),
+            variation,
+        })
+    }
+}
+
+/// End-of-day and end-of-month projection for one quantity.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Forecast {
+    pub today_so_far: f64,
+    pub today: f64,
+    pub month_so_far: f64,
+    pub month: f64,
+    /// Typical amount for today's weekday (None without history)
+    pub typical: Option<TypicalDay>,
+}
+
+impl Forecast {
+    pub fn confidence(&self) -> Confidence {
+        self.typical
+            .map_or(Confidence::Low, |typical| typical.confidence())
+    }
+}
+
+/// Project the rest of today and the month from `history` and the current burn rate.
+pub fn project(
+    history: &History,
+    now: NaiveDateTime,
+    burn_per_hour: Option<f64>,
+    config: &ForecastConfig,
+) -> Forecast {
+    let today = now.date();
+    let today_so_far = history.on(today);
+    let typical = history.typical(today.weekday(), today, config.ewma_alpha);
+
+    let hours_left = 24.0 - now.num_seconds_from_midnight() as f64 / 3600.0;
+    let from_history = typical.map(|t| (t.value - today_so_far).max(0.0));
+    let from_burn = burn_per_hour
+        .filter(|rate| *rate > 0.0)
+        .map(|rate| rate * hours_left.min(config.burn_horizon_hours.max(0.0)));
+    let remaining = match (from_history, from_burn) {
+        (Some(h), Some(b)) => h.max(b),
+        (h, b) => h.or(b).unwrap_or(0.0),
+    };
+    let forecast_today = today_so_far + remaining;
+
+    let month_start = today.with_day(1).unwrap_or(today);
+    let month_so_far = history.sum(month_start, today);
+
+    // One typical value per weekday; without history every remaining day is like today
+    let typical_by_weekday: HashMap<Weekday, f64> = [
+        Weekday::Mon,
+        Weekday::Tue,
+        Weekday::Wed,
+        Weekday::Thu,
+        Weekday::Fri,
+        Weekday::Sat,
+        Weekday::Sun,
+    ]
+    .into_iter()
+    .filter_map(|w| {
+        history
+            .typical(w, today, config.ewma_alpha)
+            .map(|t| (w, t.value))
+    })
+    .collect();
+    let rest_of_month: f64 = today
+        .iter_days()
+        .skip(1)
+        .take_while(|d| d.month() == today.month())
+        .map(|d| {
+            typical_by_weekday
+                .get(&d.weekday())
+                .copied()
+                .unwrap_or(forecast_today)
+        })
+        .sum();
+
+    Forecast {
+        today_so_far,
+        today: forecast_today,
+        month_so_far,
+        month: month_so_far - today_so_far + forecast_today + rest_of_month,
+        typical,
+    }
+}
+
+/// Cost and token forecasts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpendForecast {
+    pub cost: Forecast,
+    pub tokens: Forecast,
+}
+
+/// Load cost and token history covering both the configured window and the current month.
+pub fn load_history(
+    db: &SqliteDatabase,
+    today: NaiveDate,
+    config: &ForecastConfig,
+) -> rusqlite::Result<(History, History)> {
+    let month_start = today.with_day(1).unwrap_or(today);
+    let since = today
+        .checked_sub_days(Days::new(config.history_days as u64))
+        .map_or(month_start, |d| d.min(month_start));
+
+    let mut cost = BTreeMap::new();
+    let mut tokens = BTreeMap::new();
+    for (date, day_cost, day_tokens) in
+        db.get_daily_history(&since.format("%Y-%m-%d").to_string())?
+    {
+        if let Ok(date) = NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
+            cost.insert(date, day_cost);
+            tokens.insert(date, day_tokens as f64);
+        }
+    }
+    Ok((
+        History::new(cost, config.history_days),
+        History::new(tokens, config.history_days),
+    ))
+}
+
+/// Forecast cost and tokens as of `now`, given the current burn rates per hour.
+pub fn forecast(
+    db: &SqliteDatabase,
+    now: NaiveDateTime,
+    cost_per_hour: Option<f64>,
+    tokens_per_hour: Option<f64>,
+    config: &ForecastConfig,
+) -> rusqlite::Result<SpendForecast> {
+    let (cost, tokens) = load_history(db, now.date(), config)?;
+    Ok(SpendForecast {
+        cost: project(&cost, now, cost_per_hour, config),
+        tokens: project(&tokens, now, tokens_per_hour, config),
+    })
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+/// Standard deviation relative to the mean (0 when the mean is 0).
+fn variation(values: &[f64]) -> f64 {
+    let mean = mean(values);
+    if mean <= 0.0 {
+        return 0.0;
+    }
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64;
+    variance.sqrt() / mean
+}
+
+/// Data provider that produces the end-of-day and end-of-month forecasts.
+///
+/// Variables are returned with a `forecast_` prefix. Every key is always present
+/// in the output HashMap; unavailable values use empty strings. Raw numeric values
+/// are returned so that formatting stays in the layout engine.
+pub struct ForecastProvider {
+    /// Current session ID, for the burn rates
+    session_id: Option<String>,
+    /// Total cost of the current session in USD
+    session_cost: f64,
+    /// Path to the SQLite database (None if unavailable)
+    db_path: Option<PathBuf>,
+    config: ForecastConfig,
+}
+
+impl ForecastProvider {
+    pub fn new(
+        session_id: Option<String>,
+        session_cost: f64,
+        db_path: Option<PathBuf>,
+        config: &ForecastConfig,
+    ) -> Self {
+        Self {
+            session_id,
+            session_cost,
+            db_path,
+            config: config.clone(),
+        }
+    }
+
+    /// Cost and token burn rates per hour of the current session, once it has run
+    /// for the configured burn rate minimum.
+    fn burn_rates(&self, db: &SqliteDatabase) -> (Option<f64>, Option<f64>) {
+        let min_duration = crate::config::get_config().burn_rate.min_duration_seconds;
+        let Some(session_id) = self.session_id.as_deref() else {
+            return (None, None);
+        };
+        let Some(hours) = crate::stats::get_session_duration_by_mode(session_id)
+            .filter(|d| *d > min_duration)
+            .map(|d| d as f64 / 3600.0)
+        else {
+            return (None, None);
+        };
+
+        let cost = Some(self.session_cost / hours).filter(|r| *r > 0.0);
+        let tokens = db
+            .get_session_token_breakdown(session_id)
+            .map(|(input, output, cache_read, cache_creation)| {
+                (input as f64 + output as f64 + cache_read as f64 + cache_creation as f64) / hours
+            })
+            .filter(|r| *r > 0.0);
+        (cost, tokens)
+    }
+}
+
+impl DataProvider for ForecastProvider {
+    fn name(&self) -> &str {
+        "forecast"
+    }
+
+    fn priority(&self) -> u32 {
+        50
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_millis(200)
+    }
+
+    fn is_available(&self) -> bool {
+        self.config.enabled
+    }
+
+    fn collect(&self) -> ProviderResult {
+        let mut vars: HashMap<String, String> = [
+            "forecast_today",
+            "forecast_month",
+            "forecast_tokens_today",
+            "forecast_tokens_month",
+            "forecast_confidence",
+            "forecast_confidence_icon",
+        ]
+        .into_iter()
+        .map(|key| (key.to_string(), String::new()))
+        .collect();
+
+        let Some(db) = self
+            .db_path
+            .as_deref()
+            .filter(|p| p.exists())
+            .and_then(|p| SqliteDatabase::new(p).ok())
+        else {
+            return Ok(vars);
+        };
+        let (cost_rate, token_rate) = self.burn_rates(&db);
+        let Ok(forecast) = forecast(
+            &db,
+            Local::now().naive_local(),
+            cost_rate,
+            token_rate,
+            &self.config,
+        ) else {
+            return Ok(vars);
+        };
+
+        if forecast.cost.month > 0.0 {
+            vars.insert(
+                "forecast_today".into(),
+                format!("{:.2}", forecast.cost.today),
+            );
+            vars.insert(
+                "forecast_month".into(),
+                format!("{:.2}", forecast.cost.month),
+            );
+            let confidence = forecast.cost.confidence();
+            vars.insert("forecast_confidence".into(), confidence.label().into());
+            vars.insert("forecast_confidence_icon".into(), confidence.icon().into());
+        }
+        if forecast.tokens.month > 0.0 {
+            vars.insert(
+                "forecast_tokens_today".into(),
+                format!("{:.0}", forecast.tokens.today),
+            );
+            vars.insert(
+                "forecast_tokens_month".into(),
+                format!("{:.0}", forecast.tokens.month),
+            );
+        }
+
+        Ok(vars)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn at(s: &str, hour: u32) -> NaiveDateTime {
+        date(s).and_hms_opt(hour, 0, 0).unwrap()
+    }
+
+    fn history(days: &[(&str, f64)]) -> History {
+        History::new(days.iter().map(|(d, v)| (date(d), *v)).collect(), 56)
+    }
+
+    #[test]
+    fn test_typical_uses_same_weekday_average() {
+        // Thursdays 2026-10-01 and 2026-10-08 at $10 and $20; other days $2
+        let mut days: Vec<(String, f64)> = Vec::new();
+        for d in date("2026-10-01").iter_days().take(14) {
+            let value = match d.day() {
+                1 => 10.0,
+                8 => 20.0,
+                _ => 2.0,
+            };
+            days.push((d.format("%Y-%m-%d").to_string(), value));
+        }
+        let days: Vec<(&str, f64)> = days.iter().map(|(d, v)| (d.as_str(), *v)).collect();
+        let history = history(&days);
+
+        let thursday = history
+            .typical(Weekday::Thu, date("2026-10-15"), 0.3)
+            .unwrap();
+        assert_eq!(thursday.value, 15.0);
+        assert_eq!(thursday.weekday_samples, 2);
+        assert_eq!(thursday.days, 14);
+        assert_eq!(thursday.confidence(), Confidence::Medium);
+
+        let friday = history
+            .typical(Weekday::Fri, date("2026-10-15"), 0.3)
+            .unwrap();
+        assert_eq!(friday.value, 2.0);
+    }
+
+    #[test]
+    fn test_typical_falls_back_to_ewma_and_counts_idle_days() {
+        // Monday $10, Tuesday idle (no row), Wednesday $4
+        let history = history(&[("2026-10-12", 10.0), ("2026-10-14", 4.0)]);
+        let typical = history
+            .typical(Weekday::Thu, date("2026-10-15"), 0.5)
+            .unwrap();
+        // 10 -> 0.5*0 + 0.5*10 = 5 -> 0.5*4 + 0.5*5 = 4.5
+        assert_eq!(typical.value, 4.5);
+        assert_eq!(typical.weekday_samples, 0);
+        assert_eq!(typical.days, 3);
+        assert_eq!(typical.confidence(), Confidence::Low);
+
+        assert!(history
+            .typical(Weekday::Mon, date("2026-10-12"), 0.5)
+            .is_none());
+    }
+
+    #[test]
+    fn test_typical_respects_history_window() {
+        let history = History::new(
+            [(date("2026-09-01"), 100.0), (date("2026-10-14"), 2.0)]
+                .into_iter()
+                .collect(),
+            1,
+        );
+        let typical = history
+            .typical(Weekday::Thu, date("2026-10-15"), 0.3)
+            .unwrap();
+        assert_eq!(typical.days, 1);
+        assert_eq!(typical.value, 2.0);
+    }
+
+    #[test]
+    fn test_project_takes_larger_of_history_and_burn_rate() {
+        let config = ForecastConfig::default();
+        let history = history(&[("2026-10-14", 10.0), ("2026-10-15", 4.0)]);
+        let now = at("2026-10-15", 12);
+
+        // Typical day $10 with $4 spent: $6 remaining from history
+        let forecast = project(&history, now, None, &config);
+        assert_eq!(forecast.today_so_far, 4.0);
+        assert_eq!(forecast.today, 10.0);
+
+        // $5/h for the 2 hour horizon beats the history estimate
+        let forecast = project(&history, now, Some(5.0), &config);
+        assert_eq!(forecast.today, 14.0);
+
+        // Near midnight the burn rate only has the rest of the day left
+        let forecast = project(&history, at("2026-10-15", 23), Some(5.0), &config);
+        assert_eq!(forecast.today, 10.0);
+    }
+
+    #[test]
+    fn test_project_month() {
+        let config = ForecastConfig::default();
+        // One day of history at $3 before today (2026-10-29, Thursday)
+        let with_history = history(&[("2026-10-28", 3.0), ("2026-10-29", 1.0)]);
+        let forecast = project(&with_history, at("2026-10-29", 20), None, &config);
+
+        assert_eq!(forecast.month_so_far, 4.0);
+        assert_eq!(forecast.today, 3.0);
+        // Oct 28 ($3) + today ($3 forecast) + Oct 30, 31 at the $3 typical day
+        assert_eq!(forecast.month, 12.0);
+
+        // Without any history the rest of the month repeats today's projection
+        let today_only = history(&[("2026-10-30", 2.0)]);
+        let forecast = project(&today_only, at("2026-10-30", 12), Some(1.0), &config);
+        assert_eq!(forecast.today, 4.0);
+        assert_eq!(forecast.month, 8.0);
+        assert_eq!(forecast.confidence(), Confidence::Low);
+    }
+
+    #[test]
+    fn test_confidence_levels() {
+        let typical = |weekday_samples, days, variation| TypicalDay {
+            value: 1.0,
+            weekday_samples,
+            days,
+            variation,
+        };
+        assert_eq!(typical(6, 42, 0.3).confidence(), Confidence::High);
+        assert_eq!(typical(6, 42, 0.8).confidence(), Confidence::Medium);
+        assert_eq!(typical(1, 7, 0.8).confidence(), Confidence::Medium);
+        assert_eq!(typical(1, 5, 0.2).confidence(), Confidence::Low);
+        assert_eq!(typical(6, 42, 1.5).confidence(), Confidence::Low);
+    }
+
+    #[test]
+    fn test_provider_without_database() {
+        let provider = ForecastProvider::new(None, 0.0, None, &ForecastConfig::default());
+        assert_eq!(provider.name(), "forecast");
+        assert!(provider.is_available());
+        let vars = provider.collect().unwrap();
+        assert_eq!(vars.len(), 6);
+        assert!(vars.values().all(|v| v.is_empty()));
+    }
+}
@@ -6,24 +6,229 @@
 use crate::common::validate_path_security;
 use crate::display::Colors;
 use crate::error::{Result, StatuslineError};
+use crate::git_cache;
 use crate::git_utils;
 use crate::utils::sanitize_for_terminal;
-use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 /// Git repository status information.
 ///
-/// Contains the current branch name and counts of different types of file changes.
-#[derive(Debug, Default)]
+/// Contains the current branch name, counts of different types of file changes and
+/// upstream tracking information.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct GitStatus {
     pub branch: String,
     pub added: usize,
     pub modified: usize,
     pub deleted: usize,
     pub untracked: usize,
+    /// Commits on the local branch that are not on its upstream
+    pub ahead: usize,
+    /// Commits on the upstream that are not on the local branch
+    pub behind: usize,
+    /// Upstream branch name (e.g. `origin/main`), if one is configured
+    pub upstream: Option<String>,
+    /// Number of entries in the stash
+    pub stash: usize,
+    /// Abbreviated commit SHA when HEAD is detached
+    pub detached_sha: Option<String>,
+    /// Operation in progress (rebase, merge, ...), if any
+    pub operation: Option<GitOperationState>,
+    /// Paths with unresolved merge conflicts (also counted in `modified`)
+    pub unmerged: usize,
 }
 
-/// Validates that a path is a git repository directory
-fn validate_git_directory(dir: &str) -> Result<PathBuf> {
+/// Multi-step git operation that leaves the repository in an unusual state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GitOperation {
+    Rebase,
+    /// `git am` applying a mailbox
+    Am,
+    Merge,
+    CherryPick,
+    Revert,
+    Bisect,
+}
+
+impl GitOperation {
+    /// Short uppercase label shown in the statusline.
+    pub fn label(&self) -> &'static str {
+        match self {
+            GitOperation::Rebase => "REBASE",
+            GitOperation::Am => "AM",
+            GitOperation::Merge => "MERGE",
+            GitOperation::CherryPick => "CHERRY-PICK",
+            GitOperation::Revert => "REVERT",
+            GitOperation::Bisect => "BISECT",
+        }
+    }
+}
+
+/// An in-progress operation and, for rebases and `git am`, its step progress.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GitOperationState {
+    pub operation: GitOperation,
+    /// Current step and total steps (e.g. `(3, 7)`), when git records them
+    pub progress: Option<(usize, usize)>,
+}
+
+/// Length of abbreviated commit SHAs shown for a detached HEAD.
+pub(crate) const SHORT_SHA_LEN: usize = 7;
+
+/// Location of a git repository discovered from a working directory.
+///
+/// Produced by [`discover_repository`]. Paths are absolute; when discovery started
+/// from a canonicalized directory they are canonical as well.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitRepo {
+    /// Top-level directory of the working tree
+    pub work_tree: PathBuf,
+    /// The repository's git directory (`.git`, the target of a `.git` file, or `GIT_DIR`)
+    ///
+    /// For linked worktrees this is the per-worktree directory
+    /// (e.g. `<main>/.git/worktrees/<name>`), which holds `HEAD` and the index.
+    pub git_dir: PathBuf,
+    /// Directory shared by all worktrees (refs, objects, stash); equals `git_dir`
+    /// for ordinary repositories and submodules.
+    pub common_dir: PathBuf,
+    /// Starting directory relative to `work_tree` (empty at the repository root)
+    pub subdir: PathBuf,
+}
+
+/// Discovers the git repository containing `dir`, mirroring git's own lookup.
+///
+/// - Honours `GIT_DIR` / `GIT_WORK_TREE` when set (relative values resolve against `dir`,
+///   as they would for a git process started there). With `GIT_DIR` but no
+///   `GIT_WORK_TREE`, `dir` itself is the top of the working tree.
+/// - Otherwise walks up from `dir` to the nearest `.git`, stopping at any directory listed
+///   in `GIT_CEILING_DIRECTORIES`.
+/// - A `.git` *file* (linked worktrees, submodules) is followed via its `gitdir:` line.
+///
+/// Returns `None` when `dir` is not inside a repository working tree.
+pub fn discover_repository(dir: &Path) -> Option<GitRepo> {
+    discover_repository_with_env(
+        dir,
+        std::env::var_os("GIT_DIR"),
+        std::env::var_os("GIT_WORK_TREE"),
+        std::env::var_os("GIT_CEILING_DIRECTORIES"),
+    )
+}
+
+/// [`discover_repository`] with the relevant environment passed explicitly (testable).
+fn discover_repository_with_env(
+    dir: &Path,
+    git_dir_env: Option<OsString>,
+    work_tree_env: Option<OsString>,
+    ceilings_env: Option<OsString>,
+) -> Option<GitRepo> {
+    let non_empty = |v: Option<OsString>| v.filter(|v| !v.is_empty()).map(PathBuf::from);
+
+    if let Some(git_dir) = non_empty(git_dir_env) {
+        let git_dir = absolutize(dir, &git_dir);
+        if !is_git_dir(&git_dir) {
+            return None;
+        }
+        let work_tree = non_empty(work_tree_env)
+            .map(|wt| absolutize(dir, &wt))
+            .unwrap_or_else(|| dir.to_path_buf());
+        let subdir = dir.strip_prefix(&work_tree).ok()?.to_path_buf();
+        let common_dir = resolve_common_dir(&git_dir);
+        return Some(GitRepo {
+            work_tree,
+            git_dir,
+            common_dir,
+            subdir,
+        });
+    }
+
+    let ceilings: Vec<PathBuf> = ceilings_env
+        .map(|v| {
+            std::env::split_paths(&v)
+                .filter(|p| p.is_absolute())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    for candidate in dir.ancestors() {
+        if let Some(git_dir) = resolve_dot_git(&candidate.join(".git")) {
+            let common_dir = resolve_common_dir(&git_dir);
+            let work_tree = non_empty(work_tree_env)
+                .map(|wt| absolutize(dir, &wt))
+                .unwrap_or_else(|| candidate.to_path_buf());
+            let subdir = dir.strip_prefix(&work_tree).ok()?.to_path_buf();
+            return Some(GitRepo {
+                work_tree,
+                git_dir,
+                common_dir,
+                subdir,
+            });
+        }
+
+        // Ceiling directories may contain a repository themselves, but the
+        // search never continues above them.
+        if ceilings.iter().any(|c| c == candidate) {
+            break;
+        }
+    }
+
+    None
+}
+
+/// Resolves a `.git` entry to its git directory.
+///
+/// A directory is used as-is; a file must contain a `gitdir: <path>` line, where a
+/// relative path is resolved against the file's directory.
+fn resolve_dot_git(dot_git: &Path) -> Option<PathBuf> {
+    if dot_git.is_dir() {
+        return is_git_dir(dot_git).then(|| dot_git.to_path_buf());
+    }
+    if !dot_git.is_file() {
+        return None;
+    }
+
+    let contents = std::fs::read_to_string(dot_git).ok()?;
+    let target = contents
+        .lines()
+        .find_map(|line| line.strip_prefix("gitdir:"))
+        .map(str::trim)
+        .filter(|t| !t.is_empty())?;
+    let git_dir = absolutize(dot_git.parent()?, Path::new(target));
+    is_git_dir(&git_dir).then_some(git_dir)
+}
+
+/// Resolves the common directory of a (possibly linked-worktree) git directory.
+fn resolve_common_dir(git_dir: &Path) -> PathBuf {
+    std::fs::read_to_string(git_dir.join("commondir"))
+        .ok()
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty())
+        .map(|c| absolutize(git_dir, Path::new(&c)))
+        .filter(|c| c.is_dir())
+        .unwrap_or_else(|| git_dir.to_path_buf())
+}
+
+/// Minimal git directory check (git itself requires `HEAD` to be present).
+fn is_git_dir(path: &Path) -> bool {
+    path.is_dir() && path.join("HEAD").is_file()
+}
+
+/// Joins relative paths onto `base` and normalizes the result when possible.
+fn absolutize(base: &Path, path: &Path) -> PathBuf {
+    let joined = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        base.join(path)
+    };
+    std::fs::canonicalize(&joined).unwrap_or(joined)
+}
+
+/// Validates that a path is inside a git repository working tree.
+///
+/// Returns the canonicalized path together with the discovered repository.
+fn validate_git_directory(dir: &str) -> Result<(PathBuf, GitRepo)> {
     // Use common validation first
     let canonical_path = validate_path_security(dir)?;
 
@@ -35,12 +240,103 @@ fn validate_git_directory(dir: &str) -> Result<PathBuf> {
         )));
     }
 
-    // Check if it's a git repository by looking for .git directory
-    if !canonical_path.join(".git").exists() {
-        return Err(StatuslineError::git("Not a git repository"));
+    // Check that it's inside a git repository (walks up, follows .git files, GIT_DIR)
+    let repo = discover_repository(&canonical_path)
+        .ok_or_else(|| StatuslineError::git("Not a git repository"))?;
+
+    Ok((canonical_path, repo))
+}
+
+/// Reads the abbreviated commit SHA from `HEAD` when it is detached.
+///
+/// Returns `None` when `HEAD` is a symbolic ref (`ref: refs/heads/...`) or unreadable.
+fn read_detached_head(git_dir: &Path) -> Option<String> {
+    let head = std::fs::read_to_string(git_dir.join("HEAD")).ok()?;
+    let head = head.trim();
+    if head.len() < SHORT_SHA_LEN || !head.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    Some(head[..SHORT_SHA_LEN].to_string())
+}
+
+/// Resolves `HEAD` to an abbreviated commit SHA by reading the ref files directly.
+///
+/// Follows one level of `ref: refs/heads/...` through loose refs and `packed-refs`.
+/// Returns `None` before the first commit or when the ref cannot be resolved.
+pub fn read_head_commit_id(repo: &GitRepo) -> Option<String> {
+    let short = |sha: &str| {
+        (sha.len() >= SHORT_SHA_LEN && sha.chars().all(|c| c.is_ascii_hexdigit()))
+            .then(|| sha[..SHORT_SHA_LEN].to_string())
+    };
+
+    let head = std::fs::read_to_string(repo.git_dir.join("HEAD")).ok()?;
+    let Some(ref_name) = head.trim().strip_prefix("ref: ") else {
+        return short(head.trim());
+    };
+
+    if let Ok(sha) = std::fs::read_to_string(repo.common_dir.join(ref_name)) {
+        return short(sha.trim());
+    }
+    let packed = std::fs::read_to_string(repo.common_dir.join("packed-refs")).ok()?;
+    packed.lines().find_map(|line| {
+        let (sha, name) = line.split_once(' ')?;
+        (name == ref_name).then(|| short(sha)).flatten()
+    })
+}
+
+/// Detects an in-progress operation from the marker files git leaves in `git_dir`.
+///
+/// Checked in the same order as git's own prompt script: a rebase takes precedence
+/// over the merge/cherry-pick/revert it may be driving, and bisect comes last.
+/// For linked worktrees `git_dir` is the per-worktree directory, where these live.
+pub fn detect_operation(git_dir: &Path) -> Option<GitOperationState> {
+    let read_number = |path: PathBuf| -> Option<usize> {
+        std::fs::read_to_string(path).ok()?.trim().parse().ok()
+    };
+    let progress = |step: PathBuf, total: PathBuf| read_number(step).zip(read_number(total));
+
+    let rebase_merge = git_dir.join("rebase-merge");
+    if rebase_merge.is_dir() {
+        return Some(GitOperationState {
+            operation: GitOperation::Rebase,
+            progress: progress(rebase_merge.join("msgnum"), rebase_merge.join("end")),
+        });
+    }
+
+    let rebase_apply = git_dir.join("rebase-apply");
+    if rebase_apply.is_dir() {
+        let operation = if rebase_apply.join("applying").exists() {
+            GitOperation::Am
+        } else {
+            GitOperation::Rebase
+        };
+        return Some(GitOperationState {
+            operation,
+            progress: progress(rebase_apply.join("next"), rebase_apply.join("last")),
+        });
     }
 
-    Ok(canonical_path)
+    [
+        ("MERGE_HEAD", GitOperation::Merge),
+        ("CHERRY_PICK_HEAD", GitOperation::CherryPick),
+        ("REVERT_HEAD", GitOperation::Revert),
+        ("BISECT_LOG", GitOperation::Bisect),
+    ]
+    .into_iter()
+    .find(|(marker, _)| git_dir.join(marker).is_file())
+    .map(|(_, operation)| GitOperationState {
+        operation,
+        progress: None,
+    })
+}
+
+/// Counts stash entries by reading the stash reflog (one line per entry).
+///
+/// The stash lives in the common directory, so it is shared by all worktrees.
+fn count_stash_entries(common_dir: &Path) -> usize {
+    std::fs::read_to_string(common_dir.join("logs").join("refs").join("stash"))
+        .map(|log| log.lines().filter(|l| !l.trim().is_empty()).count())
+        .unwrap_or(0)
 }
 
 /// Gets the git status for the specified directory.
@@ -51,8 +347,9 @@ fn validate_git_directory(dir: &str) -> Result<PathBuf> {
 ///
 /// # Returns
 ///
-/// Returns `Some(GitStatus)` if the directory is a git repository,
-/// or `None` if it's not a git repository or an error occurs.
+/// Returns `Some(GitStatus)` if the directory is inside a git repository
+/// (at any depth below the repository root), or `None` if it's not a git
+/// repository or an error occurs.
 ///
 /// # Example
 ///
@@ -66,10 +363,41 @@ fn validate_git_directory(dir: &str) -> Result<PathBuf> {
 /// ```
 pub fn get_git_status(dir: &str) -> Option<GitStatus> {
     // Validate and canonicalize the directory path
-    let safe_dir = validate_git_directory(dir).ok()?;
+    let (safe_dir, repo) = validate_git_directory(dir).ok()?;
+
+    // Reuse the previous invocation's result while the repository looks unchanged
+    let max_age = Duration::from_secs(crate::config::get_config().git.cache_max_age_seconds);
+    let mut status = git_cache::get_or_compute(&repo, max_age, || read_status(&safe_dir, &repo))?;
+
+    // Porcelain output carries neither the stash nor (in v1) the detached commit,
+    // so read them straight from the git directory instead of spawning more processes
+    if status.detached_sha.is_none() {
+        status.detached_sha = read_detached_head(&repo.git_dir);
+    }
+    status.stash = count_stash_entries(&repo.common_dir);
+    status.operation = detect_operation(&repo.git_dir);
+
+    Some(status)
+}
+
+/// Reads the working tree status with the in-process backend when enabled, falling back
+/// to the git CLI.
+#[cfg_attr(not(feature = "git_native"), allow(unused_variables))]
+fn read_status(safe_dir: &Path, repo: &GitRepo) -> Option<GitStatus> {
+    #[cfg(feature = "git_native")]
+    match crate::git_native::read_status(repo) {
+        Ok(status) => return Some(status),
+        // Running the CLI after exhausting the budget would only double the wait
+        Err(crate::git_native::NativeStatusError::TimedOut) => return None,
+        Err(e) => log::debug!("Native git status unavailable ({}), using git CLI", e),
+    }
+
+    read_cli_status(safe_dir)
+}
 
-    // Get git status using the utility function
-    let status_text = git_utils::get_status_porcelain(&safe_dir)?;
+/// Runs `git status --porcelain` in `dir` and parses it with the configured parser.
+pub(crate) fn read_cli_status(dir: &Path) -> Option<GitStatus> {
+    let status_text = git_utils::get_status_porcelain(dir)?;
 
     #[cfg(feature = "git_porcelain_v2")]
     return parse_git_status_v2(&status_text);
@@ -78,6 +406,192 @@ pub fn get_git_status(dir: &str) -> Option<GitStatus> {
     return parse_git_status(&status_text);
 }
 
+/// Line counts for one side of the uncommitted changes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffCounts {
+    pub added: usize,
+    pub removed: usize,
+    /// Files with changes (binary files count here but add no lines)
+    pub files: usize,
+}
+
+/// Uncommitted changes relative to HEAD, split into staged and unstaged parts.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffStats {
+    /// Index vs HEAD
+    pub staged: DiffCounts,
+    /// Working tree vs index
+    pub unstaged: DiffCounts,
+    /// Distinct files changed in either part
+    pub files: usize,
+}
+
+impl DiffStats {
+    /// Lines added in total. A line staged and then edited again counts in both parts.
+    pub fn added(&self) -> usize {
+        self.staged.added + self.unstaged.added
+    }
+
+    /// Lines removed in total.
+    pub fn removed(&self) -> usize {
+        self.staged.removed + self.unstaged.removed
+    }
+}
+
+/// Returns line counts of uncommitted changes in the repository containing `dir`.
+///
+/// Untracked files are not included, matching `git diff`. Results are cached like
+/// [`get_git_status`]; `None` when either `git diff` fails or times out.
+pub fn get_diff_stats(dir: &str) -> Option<DiffStats> {
+    let (safe_dir, repo) = validate_git_directory(dir).ok()?;
+    let max_age = Duration::from_secs(crate::config::get_config().git.cache_max_age_seconds);
+
+    git_cache::get_or_compute(&repo, max_age, || {
+        let (staged, unstaged) = std::thread::scope(|scope| {
+            let staged = scope.spawn(|| git_utils::get_diff_numstat(&safe_dir, true));
+            let unstaged = git_utils::get_diff_numstat(&safe_dir, false);
+            (staged.join().ok().flatten(), unstaged)
+        });
+        Some(parse_diff_stats(&staged?, &unstaged?))
+    })
+}
+
+/// Combines `git diff --numstat` output for the staged and unstaged changes.
+fn parse_diff_stats(staged: &str, unstaged: &str) -> DiffStats {
+    let mut paths = std::collections::HashSet::new();
+    let mut count = |numstat: &str| {
+        let mut counts = DiffCounts::default();
+        for line in numstat.lines() {
+            let mut fields = line.splitn(3, '\t');
+            let (Some(added), Some(removed), Some(path)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                continue;
+            };
+            // Binary files report "-" for both counts
+            counts.added += added.parse::<usize>().unwrap_or(0);
+            counts.removed += removed.parse::<usize>().unwrap_or(0);
+            counts.files += 1;
+            paths.insert(path.to_string());
+        }
+        counts
+    };
+
+    let staged = count(staged);
+    let unstaged = count(unstaged);
+    DiffStats {
+        staged,
+        unstaged,
+        files: paths.len(),
+    }
+}
+
+/// The commit HEAD points at.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommitInfo {
+    /// Abbreviated commit hash
+    pub short_sha: String,
+    /// First line of the commit message
+    pub subject: String,
+    pub author: String,
+    /// Committer timestamp (Unix seconds)
+    pub timestamp: u64,
+}
+
+/// The nearest tag reachable from HEAD, cached even when there is none so that
+/// repositories without tags do not run `git describe` on every render.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NearestTag(pub Option<String>);
+
+/// Returns the HEAD commit of the repository containing `dir`, or `None` before the
+/// first commit.
+///
+/// Results are cached like [`get_git_status`], until HEAD or a ref changes.
+pub fn get_head_commit(dir: &str) -> Option<CommitInfo> {
+    let (safe_dir, repo) = validate_git_directory(dir).ok()?;
+    let max_age = Duration::from_secs(crate::config::get_config().git.cache_max_age_seconds);
+
+    git_cache::get_or_compute(&repo, max_age, || {
+        parse_commit_info(&git_utils::get_head_commit(&safe_dir)?)
+    })
+}
+
+/// Returns the nearest tag reachable from HEAD in the repository containing `dir`.
+///
+/// Results are cached like [`get_head_commit`].
+pub fn get_nearest_tag(dir: &str) -> Option<String> {
+    let (safe_dir, repo) = validate_git_directory(dir).ok()?;
+    let max_age = Duration::from_secs(crate::config::get_config().git.cache_max_age_seconds);
+
+    git_cache::get_or_compute(&repo, max_age, || {
+        Some(NearestTag(git_utils::get_nearest_tag(&safe_dir)))
+    })?
+    .0
+}
+
+/// Parses `%h%x00%s%x00%an%x00%ct` output from `git log -1`.
+fn parse_commit_info(output: &str) -> Option<CommitInfo> {
+    let mut fields = output.trim_end_matches('\n').split('\0');
+    let short_sha = fields.next().filter(|sha| !sha.is_empty())?.to_string();
+    let subject = fields.next()?.to_string();
+    let author = fields.next()?.to_string();
+    let timestamp = fields.next()?.trim().parse().ok()?;
+
+    Some(CommitInfo {
+        short_sha,
+        subject,
+        author,
+        timestamp,
+    })
+}
+
+/// Formats the time since a commit as a short relative age (e.g. "5m ago", "3d ago").
+pub fn format_commit_age(seconds: u64) -> String {
+    const MINUTE: u64 = 60;
+    const HOUR: u64 = 60 * MINUTE;
+    const DAY: u64 = 24 * HOUR;
+
+    let age = if seconds < MINUTE {
+        return "just now".to_string();
+    } else if seconds < HOUR {
+        format!("{}m", seconds / MINUTE)
+    } else if seconds < DAY {
+        format!("{}h", seconds / HOUR)
+    } else if seconds < 14 * DAY {
+        format!("{}d", seconds / DAY)
+    } else if seconds < 60 * DAY {
+        format!("{}w", seconds / (7 * DAY))
+    } else if seconds < 365 * DAY {
+        format!("{}mo", seconds / (30 * DAY))
+    } else {
+        format!("{}y", seconds / (365 * DAY))
+    };
+    format!("{} ago", age)
+}
+
+/// Parses the upstream part of a porcelain v1 branch line into `status`.
+///
+/// Accepts `origin/main`, `origin/main [ahead 1, behind 2]` and `origin/main [gone]`.
+#[cfg_attr(feature = "git_porcelain_v2", allow(dead_code))]
+fn parse_upstream_tracking(tracking: &str, status: &mut GitStatus) {
+    let (upstream, counts) = match tracking.find(" [") {
+        Some(idx) => (&tracking[..idx], tracking[idx + 2..].trim_end_matches(']')),
+        None => (tracking, ""),
+    };
+
+    if !upstream.is_empty() {
+        status.upstream = Some(upstream.to_string());
+    }
+
+    for part in counts.split(", ") {
+        if let Some(n) = part.strip_prefix("ahead ") {
+            status.ahead = n.trim().parse().unwrap_or(0);
+        } else if let Some(n) = part.strip_prefix("behind ") {
+            status.behind = n.trim().parse().unwrap_or(0);
+        }
+    }
+}
+
 /// Parses git status output in porcelain v1 format.
 ///
 /// This function implements comprehensive parsing of git's porcelain v1 status format,
@@ -89,6 +603,7 @@ pub fn get_git_status(dir: &str) -> Option<GitStatus> {
 /// ## Branch Line
 /// - Lines starting with `## ` indicate branch information
 /// - Format: `## <branch>...origin/<branch> [ahead N, behind M]`
+/// - The upstream name and ahead/behind counts are parsed when present
 /// - Special case: `## HEAD (no branch)` for detached HEAD state
 ///
 /// ## Status Codes (XY format)
@@ -142,6 +657,7 @@ fn parse_git_status(status_text: &str) -> Option<GitStatus> {
             } else if let Some(branch_end) = branch_info.find("...") {
                 // Branch with upstream tracking info
                 status.branch = branch_info[..branch_end].to_string();
+                parse_upstream_tracking(&branch_info[branch_end + 3..], &mut status);
             } else {
                 // Simple branch name without tracking
                 status.branch = branch_info.to_string();
@@ -169,7 +685,10 @@ fn parse_git_status(status_text: &str) -> Option<GitStatus> {
                 | ('U', 'A')
                 | ('D', 'U')
                 | ('A', 'A')
-                | ('U', 'U') => status.modified += 1,
+                | ('U', 'U') => {
+                    status.modified += 1;
+                    status.unmerged += 1;
+                }
                 // Regular status codes
                 _ => {
                     // Check X (index) status
@@ -224,12 +743,25 @@ fn parse_git_status(status_text: &str) -> Option<GitStatus> {
 #[cfg(feature = "git_porcelain_v2")]
 fn parse_git_status_v2(status_text: &str) -> Option<GitStatus> {
     let mut status = GitStatus::default();
+    let mut oid_header: Option<String> = None;
 
     for line in status_text.lines() {
         if let Some(header) = line.strip_prefix("# ") {
             // Parse header lines
             if let Some(branch_name) = header.strip_prefix("branch.head ") {
                 status.branch = branch_name.to_string();
+            } else if let Some(oid) = header.strip_prefix("branch.oid ") {
+                oid_header = Some(oid.trim().to_string());
+            } else if let Some(upstream) = header.strip_prefix("branch.upstream ") {
+                status.upstream = Some(upstream.to_string());
+            } else if let Some(ab) = header.strip_prefix("branch.ab ") {
+                for count in ab.split_whitespace() {
+                    if let Some(n) = count.strip_prefix('+') {
+                        status.ahead = n.parse().unwrap_or(0);
+                    } else if let Some(n) = count.strip_prefix('-') {
+                        status.behind = n.parse().unwrap_or(0);
+                    }
+                }
             }
         } else if let Some(first_char) = line.chars().next() {
             match first_char {
@@ -255,6 +787,7 @@ fn parse_git_status_v2(status_text: &str) -> Option<GitStatus> {
                 }
                 'u' => {
                     // Unmerged file: u <xy> ...
+                    status.unmerged += 1;
                     let parts: Vec<&str> = line.split_whitespace().collect();
                     if parts.len() >= 2 {
                         let xy = parts[1];
@@ -275,6 +808,13 @@ fn parse_git_status_v2(status_text: &str) -> Option<GitStatus> {
         }
     }
 
+    // `branch.oid` is "(initial)" before the first commit, which is not a usable SHA
+    if status.branch == "(detached)" {
+        status.detached_sha = oid_header
+            .filter(|oid| oid.len() >= SHORT_SHA_LEN && oid.chars().all(|c| c.is_ascii_hexdigit()))
+            .map(|oid| oid[..SHORT_SHA_LEN].to_string());
+    }
+
     Some(status)
 }
 
@@ -345,6 +885,17 @@ pub fn format_git_info(git_status: &GitStatus) -> String {
         ));
     }
 
+    // Add in-progress operation (e.g. "REBASE 3/7 !2")
+    let state = format_git_state(git_status);
+    if !state.is_empty() {
+        parts.push(format!(
+            "{}{}{}",
+            Colors::git_state(),
+            state,
+            Colors::reset()
+        ));
+    }
+
     // Add file status counts
     if git_status.added > 0 {
         parts.push(format!(
@@ -386,6 +937,71 @@ pub fn format_git_info(git_status: &GitStatus) -> String {
     }
 }
 
+/// Formats only the file change counts (e.g., "+2 ~1 ?3"), without branch or colors.
+///
+/// Returns an empty string for a clean working tree.
+pub fn format_git_counts(git_status: &GitStatus) -> String {
+    format_change_counts(
+        git_status.added,
+        git_status.modified,
+        git_status.deleted,
+        git_status.untracked,
+    )
+}
+
+/// Formats file change counts as "+added ~modified -deleted ?untracked", leaving out
+/// zeros. Shared by git and the other VCS backends (see [`crate::vcs`]).
+pub fn format_change_counts(
+    added: usize,
+    modified: usize,
+    deleted: usize,
+    untracked: usize,
+) -> String {
+    [
+        (added > 0).then(|| format!("+{}", added)),
+        (modified > 0).then(|| format!("~{}", modified)),
+        (deleted > 0).then(|| format!("-{}", deleted)),
+        (untracked > 0).then(|| format!("?{}", untracked)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+/// Formats the in-progress operation with its step progress and the unmerged path
+/// count (e.g., "REBASE 3/7 !2", "MERGE !1"), without colors.
+///
+/// Returns an empty string when no operation is in progress and nothing is unmerged.
+pub fn format_git_state(git_status: &GitStatus) -> String {
+    let operation = git_status.operation.as_ref().map(|op| match op.progress {
+        Some((step, total)) => format!("{} {}/{}", op.operation.label(), step, total),
+        None => op.operation.label().to_string(),
+    });
+    let unmerged = (git_status.unmerged > 0).then(|| format!("!{}", git_status.unmerged));
+
+    [operation, unmerged]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Formats upstream tracking and stash counts (e.g., "↑2 ↓1 ≡3"), without colors.
+///
+/// Zero counts are omitted; returns an empty string when in sync with no stash.
+pub fn format_git_tracking(ahead: usize, behind: usize, stash: usize) -> String {
+    [
+        (ahead > 0).then(|| format!("↑{}", ahead)),
+        (behind > 0).then(|| format!("↓{}", behind)),
+        (stash > 0).then(|| format!("≡{}", stash)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -443,6 +1059,19 @@ mod tests {
         let status_text = "## main...origin/main [ahead 1, behind 2]\n";
         let status = parse_git_status(status_text).unwrap();
         assert_eq!(status.branch, "main");
+        assert_eq!(status.upstream.as_deref(), Some("origin/main"));
+        assert_eq!(status.ahead, 1);
+        assert_eq!(status.behind, 2);
+
+        // Test upstream without divergence and with a deleted upstream
+        let status = parse_git_status("## main...origin/main\n").unwrap();
+        assert_eq!(status.upstream.as_deref(), Some("origin/main"));
+        assert_eq!((status.ahead, status.behind), (0, 0));
+        let status = parse_git_status("## topic...origin/topic [gone]\n").unwrap();
+        assert_eq!(status.upstream.as_deref(), Some("origin/topic"));
+        assert_eq!((status.ahead, status.behind), (0, 0));
+        let status = parse_git_status("## main...origin/main [behind 4]\n").unwrap();
+        assert_eq!((status.ahead, status.behind), (0, 4));
 
         // Test feature branch
         let status_text = "## feature/cool\n";
@@ -568,6 +1197,93 @@ mod tests {
         let status_text = "## main\nUU conflict.txt\n";
         let status = parse_git_status(status_text).unwrap();
         assert_eq!(status.modified, 1);
+        assert_eq!(status.unmerged, 1);
+
+        // Only unmerged paths count as conflicts
+        let status_text = "## main\nUU a.txt\nAA b.txt\nM  c.txt\n";
+        let status = parse_git_status(status_text).unwrap();
+        assert_eq!(status.unmerged, 2);
+    }
+
+    #[test]
+    fn test_detect_operation() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let git_dir = temp.path();
+        assert!(detect_operation(git_dir).is_none());
+
+        std::fs::write(git_dir.join("BISECT_LOG"), "").unwrap();
+        assert_eq!(
+            detect_operation(git_dir).map(|s| s.operation),
+            Some(GitOperation::Bisect)
+        );
+
+        std::fs::write(git_dir.join("MERGE_HEAD"), "abc\n").unwrap();
+        assert_eq!(
+            detect_operation(git_dir),
+            Some(GitOperationState {
+                operation: GitOperation::Merge,
+                progress: None,
+            })
+        );
+
+        // An interactive rebase wins over the cherry-pick/merge it is driving
+        let rebase = git_dir.join("rebase-merge");
+        std::fs::create_dir(&rebase).unwrap();
+        std::fs::write(rebase.join("msgnum"), "3\n").unwrap();
+        std::fs::write(rebase.join("end"), "7\n").unwrap();
+        assert_eq!(
+            detect_operation(git_dir),
+            Some(GitOperationState {
+                operation: GitOperation::Rebase,
+                progress: Some((3, 7)),
+            })
+        );
+    }
+
+    #[test]
+    fn test_detect_operation_rebase_apply() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let apply = temp.path().join("rebase-apply");
+        std::fs::create_dir(&apply).unwrap();
+        std::fs::write(apply.join("next"), "2").unwrap();
+        std::fs::write(apply.join("last"), "5").unwrap();
+        std::fs::write(apply.join("rebasing"), "").unwrap();
+        assert_eq!(
+            detect_operation(temp.path()),
+            Some(GitOperationState {
+                operation: GitOperation::Rebase,
+                progress: Some((2, 5)),
+            })
+        );
+
+        std::fs::remove_file(apply.join("rebasing")).unwrap();
+        std::fs::write(apply.join("applying"), "").unwrap();
+        assert_eq!(
+            detect_operation(temp.path()).map(|s| s.operation),
+            Some(GitOperation::Am)
+        );
+    }
+
+    #[test]
+    fn test_format_git_state() {
+        let mut status = GitStatus {
+            operation: Some(GitOperationState {
+                operation: GitOperation::Rebase,
+                progress: Some((3, 7)),
+            }),
+            unmerged: 2,
+            ..Default::default()
+        };
+        assert_eq!(format_git_state(&status), "REBASE 3/7 !2");
+
+        status.operation = Some(GitOperationState {
+            operation: GitOperation::CherryPick,
+            progress: None,
+        });
+        status.unmerged = 0;
+        assert_eq!(format_git_state(&status), "CHERRY-PICK");
+
+        assert_eq!(format_git_state(&GitStatus::default()), "");
     }
 
     #[test]
@@ -618,6 +1334,7 @@ mod tests {
             modified: 1,
             deleted: 0,
             untracked: 3,
+            ..Default::default()
         };
         let formatted = format_git_info(&status);
         assert!(formatted.contains("main"));
@@ -626,6 +1343,365 @@ mod tests {
         assert!(formatted.contains("?3"));
     }
 
+    /// Create a minimal git directory (just enough for `is_git_dir`)
+    fn fake_git_dir(path: &Path) {
+        std::fs::create_dir_all(path).unwrap();
+        std::fs::write(path.join("HEAD"), "ref: refs/heads/main\n").unwrap();
+    }
+
+    #[test]
+    fn test_discover_repository_from_subdirectory() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = std::fs::canonicalize(temp.path()).unwrap();
+        fake_git_dir(&root.join(".git"));
+        let nested = root.join("crates").join("foo");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        let repo = discover_repository_with_env(&nested, None, None, None).unwrap();
+        assert_eq!(repo.work_tree, root);
+        assert_eq!(repo.git_dir, root.join(".git"));
+        assert_eq!(repo.common_dir, root.join(".git"));
+        assert_eq!(repo.subdir, PathBuf::from("crates/foo"));
+
+        let at_root = discover_repository_with_env(&root, None, None, None).unwrap();
+        assert_eq!(at_root.subdir, PathBuf::new());
+    }
+
+    #[test]
+    fn test_discover_repository_follows_gitdir_file() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let base = std::fs::canonicalize(temp.path()).unwrap();
+
+        // Main repository with a linked worktree entry
+        let main_git = base.join("main").join(".git");
+        fake_git_dir(&main_git);
+        let wt_git = main_git.join("worktrees").join("feature");
+        fake_git_dir(&wt_git);
+        std::fs::write(wt_git.join("commondir"), "../..\n").unwrap();
+
+        // Linked worktree checkout: `.git` is a file pointing back (relative path)
+        let worktree = base.join("feature");
+        std::fs::create_dir_all(worktree.join("src")).unwrap();
+        std::fs::write(
+            worktree.join(".git"),
+            "gitdir: ../main/.git/worktrees/feature\n",
+        )
+        .unwrap();
+
+        let repo = discover_repository_with_env(&worktree.join("src"), None, None, None).unwrap();
+        assert_eq!(repo.work_tree, worktree);
+        assert_eq!(repo.git_dir, wt_git);
+        assert_eq!(repo.common_dir, main_git);
+        assert_eq!(repo.subdir, PathBuf::from("src"));
+
+        // A `.git` file pointing nowhere is not a repository
+        let broken = base.join("broken");
+        std::fs::create_dir_all(&broken).unwrap();
+        std::fs::write(broken.join(".git"), "gitdir: /does/not/exist\n").unwrap();
+        assert!(discover_repository_with_env(&broken, None, None, None).is_none());
+    }
+
+    #[test]
+    fn test_discover_repository_honours_git_dir_env() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let base = std::fs::canonicalize(temp.path()).unwrap();
+        let git_dir = base.join("store.git");
+        fake_git_dir(&git_dir);
+        let work_tree = base.join("checkout");
+        std::fs::create_dir_all(work_tree.join("docs")).unwrap();
+
+        // GIT_DIR + GIT_WORK_TREE: position is relative to the explicit work tree
+        let repo = discover_repository_with_env(
+            &work_tree.join("docs"),
+            Some(git_dir.clone().into_os_string()),
+            Some(work_tree.clone().into_os_string()),
+            None,
+        )
+        .unwrap();
+        assert_eq!(repo.git_dir, git_dir);
+        assert_eq!(repo.work_tree, work_tree);
+        assert_eq!(repo.subdir, PathBuf::from("docs"));
+
+        // GIT_DIR alone: the current directory is the top of the work tree
+        let repo = discover_repository_with_env(
+            &work_tree.join("docs"),
+            Some(OsString::from("../../store.git")),
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(repo.git_dir, git_dir);
+        assert_eq!(repo.work_tree, work_tree.join("docs"));
+        assert_eq!(repo.subdir, PathBuf::new());
+
+        // Invalid GIT_DIR is not silently replaced by a walk-up
+        assert!(discover_repository_with_env(
+            &work_tree,
+            Some(OsString::from("/does/not/exist")),
+            None,
+            None
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_discover_repository_stops_at_ceiling() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = std::fs::canonicalize(temp.path()).unwrap();
+        fake_git_dir(&root.join(".git"));
+        let ceiling = root.join("vendor");
+        let nested = ceiling.join("lib");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        assert!(discover_repository_with_env(
+            &nested,
+            None,
+            None,
+            Some(ceiling.clone().into_os_string())
+        )
+        .is_none());
+        assert!(discover_repository_with_env(&nested, None, None, None).is_some());
+    }
+
+    #[test]
+    fn test_format_git_counts() {
+        let status = GitStatus {
+            branch: "main".to_string(),
+            added: 2,
+            modified: 1,
+            deleted: 0,
+            untracked: 3,
+            ..Default::default()
+        };
+        assert_eq!(format_git_counts(&status), "+2 ~1 ?3");
+
+        let clean = GitStatus {
+            branch: "main".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(format_git_counts(&clean), "");
+    }
+
+    #[test]
+    fn test_format_git_tracking() {
+        assert_eq!(format_git_tracking(2, 1, 3), "↑2 ↓1 ≡3");
+        assert_eq!(format_git_tracking(0, 4, 0), "↓4");
+        assert_eq!(format_git_tracking(0, 0, 0), "");
+    }
+
+    #[test]
+    fn test_parse_diff_stats() {
+        let staged = "10\t2\tsrc/lib.rs\n-\t-\tassets/logo.png\n";
+        let unstaged = "3\t1\tsrc/lib.rs\n0\t7\tREADME.md\n";
+        let stats = parse_diff_stats(staged, unstaged);
+
+        assert_eq!(
+            stats.staged,
+            DiffCounts {
+                added: 10,
+                removed: 2,
+                files: 2
+            }
+        );
+        assert_eq!(
+            stats.unstaged,
+            DiffCounts {
+                added: 3,
+                removed: 8,
+                files: 2
+            }
+        );
+        assert_eq!(stats.files, 3, "src/lib.rs is counted once");
+        assert_eq!((stats.added(), stats.removed()), (13, 10));
+
+        assert_eq!(parse_diff_stats("", ""), DiffStats::default());
+    }
+
+    #[test]
+    fn test_get_diff_stats() {
+        use std::process::Command;
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = std::fs::canonicalize(temp.path()).unwrap();
+        let git = |args: &[&str]| {
+            Command::new("git")
+                .args(args)
+                .current_dir(&root)
+                .env("GIT_AUTHOR_NAME", "Test")
+                .env("GIT_AUTHOR_EMAIL", "test@example.com")
+                .env("GIT_COMMITTER_NAME", "Test")
+                .env("GIT_COMMITTER_EMAIL", "test@example.com")
+                .output()
+                .is_ok_and(|o| o.status.success())
+        };
+        if !git(&["init", "-q"]) {
+            return; // git not installed
+        }
+        std::fs::write(root.join("a.txt"), "one\ntwo\nthree\n").unwrap();
+        assert!(git(&["add", "a.txt"]));
+        assert!(git(&["commit", "-q", "-m", "initial"]));
+
+        std::fs::write(root.join("a.txt"), "one\nTWO\nthree\nfour\n").unwrap();
+        assert!(git(&["add", "a.txt"]));
+        std::fs::write(root.join("b.txt"), "new\n").unwrap();
+        assert!(git(&["add", "b.txt"]));
+        std::fs::write(root.join("b.txt"), "new\nmore\n").unwrap();
+
+        let stats = get_diff_stats(root.to_str().unwrap()).expect("diff stats");
+        assert_eq!((stats.staged.added, stats.staged.removed), (3, 1));
+        assert_eq!(stats.staged.files, 2);
+        assert_eq!((stats.unstaged.added, stats.unstaged.removed), (1, 0));
+        assert_eq!(stats.unstaged.files, 1);
+        assert_eq!(stats.files, 2);
+    }
+
+    #[test]
+    fn test_read_head_commit_id() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let git_dir = temp.path().join(".git");
+        std::fs::create_dir_all(git_dir.join("refs/heads")).unwrap();
+        let repo = GitRepo {
+            work_tree: temp.path().to_path_buf(),
+            git_dir: git_dir.clone(),
+            common_dir: git_dir.clone(),
+            subdir: PathBuf::new(),
+        };
+
+        // Unborn branch
+        std::fs::write(git_dir.join("HEAD"), "ref: refs/heads/main\n").unwrap();
+        assert!(read_head_commit_id(&repo).is_none());
+
+        // Packed ref
+        std::fs::write(
+            git_dir.join("packed-refs"),
+            "# pack-refs with: peeled fully-peeled sorted\n\
+             1111111111111111111111111111111111111111 refs/heads/dev\n\
+             2222222222222222222222222222222222222222 refs/heads/main\n",
+        )
+        .unwrap();
+        assert_eq!(read_head_commit_id(&repo), Some("2222222".to_string()));
+
+        // Loose ref takes precedence over packed-refs
+        std::fs::write(
+            git_dir.join("refs/heads/main"),
+            "3333333333333333333333333333333333333333\n",
+        )
+        .unwrap();
+        assert_eq!(read_head_commit_id(&repo), Some("3333333".to_string()));
+
+        // Detached HEAD
+        std::fs::write(
+            git_dir.join("HEAD"),
+            "a1b2c3d4e5f60718293a4b5c6d7e8f9012345678\n",
+        )
+        .unwrap();
+        assert_eq!(read_head_commit_id(&repo), Some("a1b2c3d".to_string()));
+    }
+
+    #[test]
+    fn test_parse_commit_info() {
+        let info = parse_commit_info(
+            "a1b2c3d\x00Fix parser: handle | and {x}\x00Jane Doe\x001700000000\n",
+        )
+        .unwrap();
+        assert_eq!(info.short_sha, "a1b2c3d");
+        assert_eq!(info.subject, "Fix parser: handle | and {x}");
+        assert_eq!(info.author, "Jane Doe");
+        assert_eq!(info.timestamp, 1_700_000_000);
+
+        assert!(parse_commit_info("").is_none());
+        assert!(parse_commit_info("a1b2c3d\0subject\0author\0not-a-number").is_none());
+    }
+
+    #[test]
+    fn test_format_commit_age() {
+        assert_eq!(format_commit_age(30), "just now");
+        assert_eq!(format_commit_age(5 * 60), "5m ago");
+        assert_eq!(format_commit_age(3 * 3600 + 59), "3h ago");
+        assert_eq!(format_commit_age(2 * 86400), "2d ago");
+        assert_eq!(format_commit_age(21 * 86400), "3w ago");
+        assert_eq!(format_commit_age(90 * 86400), "3mo ago");
+        assert_eq!(format_commit_age(800 * 86400), "2y ago");
+    }
+
+    #[test]
+    fn test_get_head_commit_and_tag() {
+        use std::process::Command;
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = std::fs::canonicalize(temp.path()).unwrap();
+        let git = |args: &[&str]| {
+            Command::new("git")
+                .args(args)
+                .current_dir(&root)
+                .env("GIT_AUTHOR_NAME", "Test Author")
+                .env("GIT_AUTHOR_EMAIL", "test@example.com")
+                .env("GIT_COMMITTER_NAME", "Test Author")
+                .env("GIT_COMMITTER_EMAIL", "test@example.com")
+                .output()
+                .is_ok_and(|o| o.status.success())
+        };
+        if !git(&["init", "-q"]) {
+            return; // git not installed
+        }
+        let dir = root.to_str().unwrap();
+        assert!(get_head_commit(dir).is_none(), "no commits yet");
+
+        assert!(git(&["commit", "-q", "--allow-empty", "-m", "first"]));
+        assert!(git(&["tag", "v1.0"]));
+        assert!(git(&[
+            "commit",
+            "-q",
+            "--allow-empty",
+            "-m",
+            "second commit"
+        ]));
+
+        let commit = get_head_commit(dir).expect("HEAD commit");
+        assert_eq!(commit.subject, "second commit");
+        assert_eq!(commit.author, "Test Author");
+        assert!(commit.short_sha.len() >= SHORT_SHA_LEN);
+        assert_eq!(get_nearest_tag(dir), Some("v1.0".to_string()));
+    }
+
+    #[test]
+    fn test_read_detached_head_and_stash() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let git_dir = temp.path();
+
+        std::fs::write(git_dir.join("HEAD"), "ref: refs/heads/main\n").unwrap();
+        assert!(read_detached_head(git_dir).is_none());
+        std::fs::write(
+            git_dir.join("HEAD"),
+            "a1b2c3d4e5f60718293a4b5c6d7e8f9012345678\n",
+        )
+        .unwrap();
+        assert_eq!(read_detached_head(git_dir).as_deref(), Some("a1b2c3d"));
+
+        assert_eq!(count_stash_entries(git_dir), 0);
+        let logs = git_dir.join("logs").join("refs");
+        std::fs::create_dir_all(&logs).unwrap();
+        std::fs::write(
+            logs.join("stash"),
+            "0000 1111 a <a> 1 +0000\tWIP\n1111 2222 a <a> 2 +0000\tWIP\n",
+        )
+        .unwrap();
+        assert_eq!(count_stash_entries(git_dir), 2);
+    }
+
+    #[test]
+    fn test_format_git_info_detached_head() {
+        let status = GitStatus {
+            branch: "HEAD (no branch)".to_string(),
+            detached_sha: Some("a1b2c3d".to_string()),
+            ..Default::default()
+        };
+        // The detached SHA is only exposed as {git_detached}
+        let formatted = format_git_info(&status);
+        assert!(formatted.contains("HEAD (no branch)"));
+        assert!(!formatted.contains("a1b2c3d"));
+    }
+
     #[cfg(feature = "git_porcelain_v2")]
     #[test]
     fn test_parse_git_status_v2_branch() {
@@ -638,6 +1714,21 @@ mod tests {
         let status_text = "# branch.oid 1234567890abcdef\n# branch.head (detached)\n";
         let status = parse_git_status_v2(status_text).unwrap();
         assert_eq!(status.branch, "(detached)");
+        assert_eq!(status.detached_sha.as_deref(), Some("1234567"));
+    }
+
+    #[cfg(feature = "git_porcelain_v2")]
+    #[test]
+    fn test_parse_git_status_v2_tracking() {
+        let status_text = "# branch.oid 1234567890abcdef\n\
+                          # branch.head main\n\
+                          # branch.upstream origin/main\n\
+                          # branch.ab +3 -1\n";
+        let status = parse_git_status_v2(status_text).unwrap();
+        assert_eq!(status.upstream.as_deref(), Some("origin/main"));
+        assert_eq!(status.ahead, 3);
+        assert_eq!(status.behind, 1);
+        assert!(status.detached_sha.is_none());
     }
 
     #[cfg(feature = "git_porcelain_v2")]
@@ -672,6 +1763,7 @@ mod tests {
 
         let status = parse_git_status_v2(status_text).unwrap();
         assert_eq!(status.modified, 2); // All unmerged states count as modified
+        assert_eq!(status.unmerged, 2);
     }
 
     #[test]
@@ -686,6 +1778,7 @@ mod tests {
             modified: 0,
             deleted: 0,
             untracked: 0,
+            ..Default::default()
         };
         let formatted = format_git_info(&status);
         // Should not contain control characters (the escape codes from the malicious input)
@@ -0,0 +1,487 @@
+//! Cross-invocation git status cache.
+//!
+//! The statusline runs as a fresh process on every refresh, so without a cache each
+//! render pays for a full `git status` (and `git diff`, `git log`, `git describe`).
+//! This module stores the last [`GitStatus`], [`DiffStats`], [`CommitInfo`] and
+//! [`NearestTag`] per repository and working directory under
+//! `<cache dir>/claudia-statusline/git-status/` together with a cheap fingerprint of
+//! the repository:
+//!
+//! - `HEAD` contents and the mtimes of the current branch and upstream refs,
+//!   `refs/tags`, `packed-refs` and `FETCH_HEAD` (new commits, tags, fetches, pushes)
+//! - `.git/index` mtime and size (staging, commits, checkouts)
+//! - mtimes of the work tree root and of each directory from the current
+//!   directory up to the root (files created, deleted or renamed there)
+//!
+//! The last two only apply to values read from the work tree (status and diff).
+//! Sessions in different subdirectories of one repository keep separate entries for
+//! those, so their directory mtimes do not invalidate each other's; the HEAD commit
+//! and nearest tag are shared by the whole repository.
+//!
+//! In-place edits to tracked files change none of these, so entries also expire
+//! after `[git] cache_max_age_seconds`. Setting it to `0` disables the cache.
+//!
+//! Stash, detached-HEAD SHA and in-progress operations are not cached; they are
+//! read from the git directory on every render.
+
+use crate::common::{cache_file_name, get_cache_dir, write_cache_file};
+use crate::git::{CommitInfo, DiffStats, GitRepo, GitStatus, NearestTag};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Bumped whenever the entry layout or fingerprint contents change.
+const CACHE_VERSION: u32 = 3;
+
+/// Entries not refreshed for this long are deleted when another entry is written.
+const PRUNE_AFTER: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// A value derived from the repository state that can be cached across invocations.
+pub trait CachedValue: Serialize + DeserializeOwned {
+    /// Distinguishes entry files of different value types for the same repository.
+    const KIND: &'static str;
+
+    /// Whether the value depends on the index and work tree rather than only on HEAD
+    /// and refs. Values that do not are cached once per repository.
+    const WORK_TREE: bool = true;
+
+    /// Upstream branch whose ref should be part of the fingerprint, if any.
+    fn upstream(&self) -> Option<&str> {
+        None
+    }
+}
+
+impl CachedValue for GitStatus {
+    const KIND: &'static str = "status";
+
+    fn upstream(&self) -> Option<&str> {
+        self.upstream.as_deref()
+    }
+}
+
+impl CachedValue for DiffStats {
+    const KIND: &'static str = "diff";
+}
+
+impl CachedValue for CommitInfo {
+    const KIND: &'static str = "commit";
+    const WORK_TREE: bool = false;
+}
+
+impl CachedValue for NearestTag {
+    const KIND: &'static str = "tag";
+    const WORK_TREE: bool = false;
+}
+
+/// Cheap-to-compute snapshot of the repository state a status depends on.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Fingerprint {
+    work_tree: PathBuf,
+    /// `(mtime in ns, size)` of the index file (work tree values only)
+    index: Option<(u64, u64)>,
+    head: String,
+    /// mtimes of ref files, in a fixed order (`None` when missing)
+    refs: Vec<(PathBuf, Option<u64>)>,
+    /// mtimes of work tree directories from the root down to the current directory
+    /// (work tree values only)
+    dirs: Vec<(PathBuf, Option<u64>)>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(bound = "T: CachedValue")]
+struct CacheEntry<T> {
+    version: u32,
+    /// Unix timestamp (seconds) when the value was computed
+    created_at: u64,
+    fingerprint: Fingerprint,
+    value: T,
+}
+
+/// Returns the cached value for `repo` when its fingerprint is unchanged and the entry
+/// is younger than `max_age`; otherwise runs `compute` and caches its result.
+///
+/// A zero `max_age` bypasses the cache entirely.
+pub fn get_or_compute<T: CachedValue>(
+    repo: &GitRepo,
+    max_age: Duration,
+    compute: impl FnOnce() -> Option<T>,
+) -> Option<T> {
+    if max_age.is_zero() {
+        return compute();
+    }
+    let Some(dir) = get_cache_dir("git-status") else {
+        return compute();
+    };
+    get_or_compute_in(&dir, repo, max_age, compute)
+}
+
+fn get_or_compute_in<T: CachedValue>(
+    dir: &Path,
+    repo: &GitRepo,
+    max_age: Duration,
+    compute: impl FnOnce() -> Option<T>,
+) -> Option<T> {
+    let path = dir.join(entry_file_name::<T>(repo));
+
+    if let Some(value) = load(&path, repo, max_age) {
+        log::debug!("git {} cache hit for {}", T::KIND, repo.work_tree.display());
+        return Some(value);
+    }
+
+    // Capture before computing so a change made while git runs invalidates the entry
+    let fingerprint = Fingerprint::capture(repo, T::WORK_TREE, None);
+    let created_at = unix_now();
+    let value = compute()?;
+
+    let entry = CacheEntry {
+        version: CACHE_VERSION,
+        created_at,
+        fingerprint: fingerprint.with_upstream(repo, value.upstream()),
+        value,
+    };
+    if let Err(e) = store(dir, &path, &entry) {
+        log::debug!("Failed to write git status cache {}: {}", path.display(), e);
+    }
+    Some(entry.value)
+}
+
+fn load<T: CachedValue>(path: &Path, repo: &GitRepo, max_age: Duration) -> Option<T> {
+    let contents = fs::read_to_string(path).ok()?;
+    let entry: CacheEntry<T> = serde_json::from_str(&contents).ok()?;
+    if entry.version != CACHE_VERSION {
+        return None;
+    }
+    let age = unix_now().saturating_sub(entry.created_at);
+    if age >= max_age.as_secs() {
+        return None;
+    }
+    let current = Fingerprint::capture(repo, T::WORK_TREE, entry.value.upstream());
+    (current == entry.fingerprint).then_some(entry.value)
+}
+
+/// Writes the entry atomically and prunes stale entries.
+fn store<T: CachedValue>(dir: &Path, path: &Path, entry: &CacheEntry<T>) -> std::io::Result<()> {
+    write_cache_file(path, &serde_json::to_vec(entry)?)?;
+    prune(dir, path);
+    Ok(())
+}
+
+/// Removes entries (and abandoned temp files) that have not been written for a week.
+fn prune(dir: &Path, keep: &Path) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let now = SystemTime::now();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let stale = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .is_some_and(|age| age > PRUNE_AFTER);
+        if stale && path != keep {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+impl Fingerprint {
+    /// Captures the current fingerprint, including the upstream ref when it is known.
+    ///
+    /// Without `work_tree` the index and directory mtimes are left out.
+    fn capture(repo: &GitRepo, work_tree: bool, upstream: Option<&str>) -> Self {
+        let head = fs::read_to_string(repo.git_dir.join("HEAD")).unwrap_or_default();
+
+        let mut refs = vec![
+            mtime_entry(&repo.common_dir.join("packed-refs")),
+            mtime_entry(&repo.common_dir.join("refs").join("tags")),
+            mtime_entry(&repo.git_dir.join("FETCH_HEAD")),
+        ];
+        if let Some(branch_ref) = head.trim().strip_prefix("ref: ") {
+            refs.push(mtime_entry(&repo.common_dir.join(branch_ref)));
+        }
+
+        let mut dirs = Vec::new();
+        let mut index = None;
+        if work_tree {
+            dirs.push(mtime_entry(&repo.work_tree));
+            let mut current = repo.work_tree.clone();
+            for component in repo.subdir.components() {
+                current.push(component);
+                dirs.push(mtime_entry(&current));
+            }
+
+            index = fs::metadata(repo.git_dir.join("index"))
+                .ok()
+                .and_then(|m| Some((mtime_nanos(&m)?, m.len())));
+        }
+
+        Fingerprint {
+            work_tree: repo.work_tree.clone(),
+            index,
+            head,
+            refs,
+            dirs,
+        }
+        .with_upstream(repo, upstream)
+    }
+
+    /// Adds the mtime of the upstream tracking ref (e.g. `origin/main`).
+    fn with_upstream(mut self, repo: &GitRepo, upstream: Option<&str>) -> Self {
+        if let Some(upstream) = upstream {
+            let ref_path = repo.common_dir.join("refs").join("remotes").join(upstream);
+            self.refs.push(mtime_entry(&ref_path));
+        }
+        self
+    }
+}
+
+fn mtime_entry(path: &Path) -> (PathBuf, Option<u64>) {
+    let mtime = fs::metadata(path).ok().and_then(|m| mtime_nanos(&m));
+    (path.to_path_buf(), mtime)
+}
+
+fn mtime_nanos(metadata: &fs::Metadata) -> Option<u64> {
+    let since_epoch = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+    u64::try_from(since_epoch.as_nanos()).ok()
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// One file per value kind and repository (per subdirectory for work tree values),
+/// named by a hash of the path.
+fn entry_file_name<T: CachedValue>(repo: &GitRepo) -> String {
+    let path = if T::WORK_TREE {
+        repo.work_tree.join(&repo.subdir)
+    } else {
+        repo.work_tree.clone()
+    };
+    cache_file_name(&path, &format!("-{}.json", T::KIND))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    /// Fake repository layout: `<root>/.git/{HEAD,index}` plus a `src` subdirectory.
+    fn fake_repo(root: &Path) -> GitRepo {
+        let git_dir = root.join(".git");
+        fs::create_dir_all(git_dir.join("refs/heads")).unwrap();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(git_dir.join("HEAD"), "ref: refs/heads/main\n").unwrap();
+        fs::write(git_dir.join("refs/heads/main"), "0000\n").unwrap();
+        fs::write(git_dir.join("index"), "DIRC").unwrap();
+        GitRepo {
+            work_tree: root.to_path_buf(),
+            git_dir: git_dir.clone(),
+            common_dir: git_dir,
+            subdir: PathBuf::from("src"),
+        }
+    }
+
+    fn status(branch: &str, modified: usize) -> GitStatus {
+        GitStatus {
+            branch: branch.to_string(),
+            modified,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_cache_hit_while_fingerprint_unchanged() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let cache = temp.path().join("cache");
+        let repo = fake_repo(&temp.path().join("repo"));
+        let max_age = Duration::from_secs(60);
+        let calls = Cell::new(0);
+        let compute = || {
+            calls.set(calls.get() + 1);
+            Some(status("main", 2))
+        };
+
+        let first = get_or_compute_in(&cache, &repo, max_age, compute);
+        let second = get_or_compute_in(&cache, &repo, max_age, compute);
+        assert_eq!(first, Some(status("main", 2)));
+        assert_eq!(second, first);
+        assert_eq!(calls.get(), 1, "second lookup should be served from cache");
+    }
+
+    #[test]
+    fn test_cache_invalidated_by_index_head_and_dirs() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let cache = temp.path().join("cache");
+        let root = temp.path().join("repo");
+        let repo = fake_repo(&root);
+        let max_age = Duration::from_secs(60);
+
+        let lookup = |modified: usize| {
+            get_or_compute_in(&cache, &repo, max_age, || Some(status("main", modified)))
+                .unwrap()
+                .modified
+        };
+        assert_eq!(lookup(1), 1);
+        assert_eq!(lookup(9), 1); // cached
+
+        // Index size change (e.g. `git add`)
+        fs::write(root.join(".git/index"), "DIRC with more entries").unwrap();
+        assert_eq!(lookup(2), 2);
+
+        // HEAD moved to another branch
+        fs::write(root.join(".git/HEAD"), "ref: refs/heads/topic\n").unwrap();
+        assert_eq!(lookup(3), 3);
+
+        // New file in the current directory changes its mtime
+        let before = Fingerprint::capture(&repo, true, None);
+        std::thread::sleep(Duration::from_millis(20));
+        fs::write(root.join("src/new.rs"), "").unwrap();
+        if Fingerprint::capture(&repo, true, None) != before {
+            assert_eq!(lookup(4), 4);
+        }
+    }
+
+    #[test]
+    fn test_cache_expires_and_can_be_disabled() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let cache = temp.path().join("cache");
+        let repo = fake_repo(&temp.path().join("repo"));
+
+        let path = cache.join(entry_file_name::<GitStatus>(&repo));
+        let lookup = |max_age: Duration, modified: usize| {
+            get_or_compute_in(&cache, &repo, max_age, || Some(status("main", modified)))
+                .unwrap()
+                .modified
+        };
+        assert_eq!(lookup(Duration::from_secs(60), 1), 1);
+
+        // Backdate the entry beyond max age
+        let mut entry: CacheEntry<GitStatus> =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        entry.created_at -= 120;
+        fs::write(&path, serde_json::to_vec(&entry).unwrap()).unwrap();
+        assert_eq!(lookup(Duration::from_secs(60), 2), 2);
+
+        // Zero max age never reads or writes the cache
+        assert_eq!(
+            get_or_compute(&repo, Duration::ZERO, || Some(status("main", 3))).map(|s| s.modified),
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn test_failed_compute_is_not_cached() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let cache = temp.path().join("cache");
+        let repo = fake_repo(&temp.path().join("repo"));
+        let max_age = Duration::from_secs(60);
+
+        assert!(get_or_compute_in(&cache, &repo, max_age, || None::<GitStatus>).is_none());
+        assert!(!cache.join(entry_file_name::<GitStatus>(&repo)).exists());
+    }
+
+    #[test]
+    fn test_upstream_ref_is_part_of_fingerprint() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path().join("repo");
+        let repo = fake_repo(&root);
+        let remote = root.join(".git/refs/remotes/origin");
+        fs::create_dir_all(&remote).unwrap();
+        fs::write(remote.join("main"), "1111\n").unwrap();
+
+        let with_upstream = Fingerprint::capture(&repo, true, Some("origin/main"));
+        assert_ne!(with_upstream, Fingerprint::capture(&repo, true, None));
+        assert!(with_upstream
+            .refs
+            .iter()
+            .any(|(path, mtime)| path.ends_with("refs/remotes/origin/main") && mtime.is_some()));
+    }
+
+    #[test]
+    fn test_subdirectories_are_cached_separately() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let cache = temp.path().join("cache");
+        let root = temp.path().join("repo");
+        let repo = fake_repo(&root);
+        fs::create_dir_all(root.join("docs")).unwrap();
+        let docs = GitRepo {
+            subdir: PathBuf::from("docs"),
+            ..repo.clone()
+        };
+        let max_age = Duration::from_secs(60);
+
+        get_or_compute_in(&cache, &repo, max_age, || Some(status("main", 1)));
+        get_or_compute_in(&cache, &docs, max_age, || Some(status("main", 2)));
+        assert_ne!(
+            entry_file_name::<GitStatus>(&repo),
+            entry_file_name::<GitStatus>(&docs)
+        );
+
+        // A file created in one session's directory leaves the other's entry valid
+        std::thread::sleep(Duration::from_millis(20));
+        fs::write(root.join("docs/new.md"), "").unwrap();
+        assert_eq!(
+            get_or_compute_in(&cache, &repo, max_age, || Some(status("main", 9)))
+                .map(|s| s.modified),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_value_kinds_are_cached_separately() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let cache = temp.path().join("cache");
+        let repo = fake_repo(&temp.path().join("repo"));
+        let max_age = Duration::from_secs(60);
+
+        get_or_compute_in(&cache, &repo, max_age, || Some(status("main", 1)));
+        let diff = DiffStats {
+            files: 4,
+            ..Default::default()
+        };
+        let cached = get_or_compute_in(&cache, &repo, max_age, || Some(diff.clone()));
+        assert_eq!(cached, Some(diff));
+        assert!(cache.join(entry_file_name::<DiffStats>(&repo)).exists());
+        assert_eq!(
+            get_or_compute_in(&cache, &repo, max_age, || Some(status("main", 9)))
+                .map(|s| s.modified),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_head_values_ignore_work_tree_and_follow_refs() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let cache = temp.path().join("cache");
+        let root = temp.path().join("repo");
+        let repo = fake_repo(&root);
+        let top = GitRepo {
+            subdir: PathBuf::new(),
+            ..repo.clone()
+        };
+        let max_age = Duration::from_secs(60);
+        let tag = |name: &str| Some(NearestTag(Some(name.to_string())));
+        let cached = |repo: &GitRepo, name: &str| {
+            get_or_compute_in(&cache, repo, max_age, || tag(name)).and_then(|t| t.0)
+        };
+
+        assert_eq!(cached(&repo, "v1"), Some("v1".to_string()));
+
+        // Edits, staging and other directories share the entry
+        std::thread::sleep(Duration::from_millis(20));
+        fs::write(root.join("src/new.rs"), "").unwrap();
+        fs::write(root.join(".git/index"), "DIRC changed").unwrap();
+        assert_eq!(cached(&top, "v2"), Some("v1".to_string()));
+
+        // A new tag invalidates it
+        fs::create_dir_all(root.join(".git/refs/tags")).unwrap();
+        assert_eq!(cached(&repo, "v2"), Some("v2".to_string()));
+    }
+}
@@ -0,0 +1,391 @@
+//! In-process git status using gitoxide (`git_native` feature).
+//!
+//! Reads HEAD, the index and the working tree without spawning `git`, producing the
+//! same [`GitStatus`] as parsing `git status --porcelain` (v1, or v2 with the
+//! `git_porcelain_v2` feature). Repositories gitoxide cannot handle (unknown
+//! extensions, sparse indices, `GIT_DIR` with a detached work tree, ...) report
+//! [`NativeStatusError::Unsupported`] so the caller can fall back to the git CLI.
+//!
+//! Stash, detached-HEAD SHA and in-progress operations are filled in by
+//! [`crate::git::get_git_status`] for both backends.
+
+use crate::git::{GitRepo, GitStatus};
+use gix::bstr::BString;
+use gix::diff::index::Change as TreeIndexChange;
+use gix::status::index_worktree::iter::Summary;
+use gix::status::Item;
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+
+/// Why the native backend could not produce a status.
+#[derive(Debug, Error)]
+pub enum NativeStatusError {
+    /// The repository or one of its features is not supported; use the git CLI instead
+    #[error("unsupported repository: {0}")]
+    Unsupported(String),
+
+    /// The status did not complete within the git timeout
+    #[error("timed out")]
+    TimedOut,
+}
+
+impl NativeStatusError {
+    fn unsupported(err: impl std::fmt::Display) -> Self {
+        NativeStatusError::Unsupported(err.to_string())
+    }
+}
+
+/// Reads the working tree status of `repo` in-process.
+///
+/// Gives up after the configured git timeout (`git.timeout_ms`), mirroring the
+/// limit applied to `git status` subprocesses.
+pub fn read_status(repo: &GitRepo) -> Result<GitStatus, NativeStatusError> {
+    let timeout = Duration::from_millis(crate::git_utils::git_timeout_ms() as u64);
+    read_status_with_timeout(repo, timeout)
+}
+
+fn read_status_with_timeout(
+    repo: &GitRepo,
+    timeout: Duration,
+) -> Result<GitStatus, NativeStatusError> {
+    let gix_repo = gix::open(&repo.work_tree).map_err(NativeStatusError::unsupported)?;
+
+    // GIT_DIR/GIT_WORK_TREE setups where the work tree does not contain the git dir
+    let canonical = |path: &Path| std::fs::canonicalize(path).ok();
+    let work_tree = gix_repo.workdir().and_then(canonical);
+    if work_tree.as_deref() != Some(repo.work_tree.as_path())
+        || canonical(gix_repo.git_dir()).as_deref() != Some(repo.git_dir.as_path())
+    {
+        return Err(NativeStatusError::Unsupported(
+            "work tree does not match discovered repository".to_string(),
+        ));
+    }
+
+    let interrupt = Arc::new(AtomicBool::new(false));
+    {
+        let interrupt = Arc::clone(&interrupt);
+        std::thread::spawn(move || {
+            std::thread::sleep(timeout);
+            interrupt.store(true, Ordering::Relaxed);
+        });
+    }
+    let timed_out = || interrupt.load(Ordering::Relaxed);
+
+    let mut status = GitStatus::default();
+    read_branch(&gix_repo, &mut status).map_err(|e| {
+        if timed_out() {
+            NativeStatusError::TimedOut
+        } else {
+            e
+        }
+    })?;
+
+    let items = gix_repo
+        .status(gix::progress::Discard)
+        .map_err(NativeStatusError::unsupported)?
+        .should_interrupt_owned(Arc::clone(&interrupt))
+        .into_iter(Vec::<BString>::new())
+        .map_err(NativeStatusError::unsupported)?
+        .collect::<Result<Vec<Item>, _>>();
+    let items = match items {
+        Ok(items) if !timed_out() => items,
+        Ok(_) => return Err(NativeStatusError::TimedOut),
+        Err(_) if timed_out() => return Err(NativeStatusError::TimedOut),
+        Err(e) => return Err(NativeStatusError::unsupported(e)),
+    };
+
+    count_changes(&items, &mut status);
+    Ok(status)
+}
+
+/// Fills in the branch name, upstream and ahead/behind counts.
+///
+/// Branch names follow the porcelain format of the active parser so both backends
+/// agree on detached and unborn HEADs.
+fn read_branch(repo: &gix::Repository, status: &mut GitStatus) -> Result<(), NativeStatusError> {
+    let head = repo.head().map_err(NativeStatusError::unsupported)?;
+
+    let Some(name) = head.referent_name().map(|n| n.to_owned()) else {
+        #[cfg(feature = "git_porcelain_v2")]
+        {
+            // Porcelain v2 carries the commit in `branch.oid`
+            status.branch = "(detached)".to_string();
+            status.detached_sha = head
+                .id()
+                .map(|id| id.to_hex_with_len(crate::git::SHORT_SHA_LEN).to_string());
+        }
+        #[cfg(not(feature = "git_porcelain_v2"))]
+        {
+            status.branch = "HEAD (no branch)".to_string();
+        }
+        return Ok(());
+    };
+
+    let short_name = name.as_ref().shorten().to_string();
+    if head.is_unborn() {
+        #[cfg(feature = "git_porcelain_v2")]
+        {
+            status.branch = short_name;
+        }
+        #[cfg(not(feature = "git_porcelain_v2"))]
+        {
+            status.branch = format!("No commits yet on {}", short_name);
+        }
+        return Ok(());
+    }
+    status.branch = short_name;
+
+    let Some(tracking) =
+        repo.branch_remote_tracking_ref_name(name.as_ref(), gix::remote::Direction::Fetch)
+    else {
+        return Ok(());
+    };
+    let tracking = tracking.map_err(NativeStatusError::unsupported)?;
+    status.upstream = Some(tracking.as_ref().shorten().to_string());
+
+    // A configured upstream whose ref no longer exists is reported as "[gone]"
+    let Some(mut upstream_ref) = repo
+        .try_find_reference(tracking.as_ref())
+        .map_err(NativeStatusError::unsupported)?
+    else {
+        return Ok(());
+    };
+    let upstream_id = upstream_ref
+        .peel_to_id()
+        .map_err(NativeStatusError::unsupported)?
+        .detach();
+    let head_id = repo
+        .head_id()
+        .map_err(NativeStatusError::unsupported)?
+        .detach();
+
+    let count_exclusive = |tip: gix::ObjectId, hidden: gix::ObjectId| {
+        repo.rev_walk([tip])
+            .with_hidden([hidden])
+            .all()
+            .map_err(NativeStatusError::unsupported)?
+            .try_fold(0usize, |n, info| {
+                info.map(|_| n + 1).map_err(NativeStatusError::unsupported)
+            })
+    };
+    status.ahead = count_exclusive(head_id, upstream_id)?;
+    status.behind = count_exclusive(upstream_id, head_id)?;
+
+    Ok(())
+}
+
+/// Counts changes the way the porcelain parsers count XY codes.
+///
+/// HEAD-to-index changes play the role of `X`, index-to-worktree changes of `Y`;
+/// conflicted paths count once as modified (and unmerged), like `UU`.
+fn count_changes(items: &[Item], status: &mut GitStatus) {
+    let conflicted: HashSet<&[u8]> = items
+        .iter()
+        .filter_map(|item| match item {
+            Item::IndexWorktree(change) => {
+                matches!(change.summary(), Some(Summary::Conflict)).then(|| change.rela_path())
+            }
+            Item::TreeIndex(_) => None,
+        })
+        .map(|path| path.as_ref())
+        .collect();
+
+    for item in items {
+        match item {
+            Item::TreeIndex(change) => {
+                if conflicted.contains(AsRef::<[u8]>::as_ref(change.location())) {
+                    continue;
+                }
+                match change {
+                    TreeIndexChange::Addition { .. } => status.added += 1,
+                    TreeIndexChange::Deletion { .. } => status.deleted += 1,
+                    // Mode, content and type changes, renames and copies
+                    TreeIndexChange::Modification { .. } | TreeIndexChange::Rewrite { .. } => {
+                        status.modified += 1
+                    }
+                }
+            }
+            Item::IndexWorktree(change) => match change.summary() {
+                Some(Summary::Conflict) => {
+                    status.modified += 1;
+                    status.unmerged += 1;
+                }
+                Some(Summary::Modified | Summary::TypeChange) => status.modified += 1,
+                Some(Summary::Removed) => status.deleted += 1,
+                // Untracked files (directories are collapsed, as with `git status`)
+                Some(Summary::Added) => status.untracked += 1,
+                // Index-to-worktree rename tracking is disabled by default; intent-to-add
+                // entries (` A`) are not counted by the porcelain parsers either
+                Some(Summary::Renamed | Summary::Copied | Summary::IntentToAdd) | None => {}
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::{discover_repository, read_cli_status};
+    use std::process::Command;
+
+    /// Runs git in `dir` with a fixed identity; returns false when git is unavailable.
+    fn git(dir: &Path, args: &[&str]) -> bool {
+        Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .env("GIT_AUTHOR_NAME", "Test")
+            .env("GIT_AUTHOR_EMAIL", "test@example.com")
+            .env("GIT_COMMITTER_NAME", "Test")
+            .env("GIT_COMMITTER_EMAIL", "test@example.com")
+            .env("GIT_CONFIG_NOSYSTEM", "1")
+            .env("HOME", dir)
+            .output()
+            .is_ok_and(|o| o.status.success())
+    }
+
+    /// Creates a fixture repository with one commit on `main`, or `None` without git.
+    fn fixture_repo(root: &Path) -> Option<()> {
+        let ok = git(root, &["init", "-q", "-b", "main"])
+            && std::fs::write(root.join("tracked.txt"), "one\n").is_ok()
+            && std::fs::write(root.join("other.txt"), "other\n").is_ok()
+            && git(root, &["add", "."])
+            && git(root, &["commit", "-q", "-m", "initial"]);
+        ok.then_some(())
+    }
+
+    /// Asserts that the native backend and the porcelain parser agree for `dir`.
+    fn assert_parity(dir: &Path) -> GitStatus {
+        let dir = std::fs::canonicalize(dir).unwrap();
+        let repo = discover_repository(&dir).expect("fixture should be a repository");
+        let native = read_status_with_timeout(&repo, Duration::from_secs(30))
+            .expect("native status should succeed");
+        let cli = read_cli_status(&dir).expect("git status should succeed");
+        assert_eq!(native, cli);
+        native
+    }
+
+    #[test]
+    fn test_parity_clean_and_dirty_worktree() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path();
+        if fixture_repo(root).is_none() {
+            return; // git not installed
+        }
+        assert_parity(root);
+
+        // Staged add, modified, deleted, untracked file and untracked directory
+        std::fs::write(root.join("new.txt"), "new\n").unwrap();
+        assert!(git(root, &["add", "new.txt"]));
+        std::fs::write(root.join("new.txt"), "changed after add\n").unwrap();
+        std::fs::write(root.join("tracked.txt"), "two\n").unwrap();
+        std::fs::remove_file(root.join("other.txt")).unwrap();
+        std::fs::write(root.join("scratch.txt"), "").unwrap();
+        std::fs::create_dir_all(root.join("notes/deep")).unwrap();
+        std::fs::write(root.join("notes/deep/a.md"), "a").unwrap();
+        std::fs::write(root.join("notes/b.md"), "b").unwrap();
+        let status = assert_parity(root);
+        assert_eq!(
+            (
+                status.added,
+                status.modified,
+                status.deleted,
+                status.untracked
+            ),
+            (1, 2, 1, 2)
+        );
+
+        // Staged rename counts once as modified
+        assert!(git(root, &["add", "-A"]));
+        assert!(git(root, &["commit", "-q", "-m", "second"]));
+        assert!(git(root, &["mv", "tracked.txt", "renamed.txt"]));
+        assert_eq!(assert_parity(root).modified, 1);
+    }
+
+    #[test]
+    fn test_parity_merge_conflict() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path();
+        if fixture_repo(root).is_none() {
+            return; // git not installed
+        }
+        assert!(git(root, &["checkout", "-q", "-b", "topic"]));
+        std::fs::write(root.join("tracked.txt"), "topic\n").unwrap();
+        assert!(git(root, &["commit", "-q", "-am", "topic"]));
+        assert!(git(root, &["checkout", "-q", "main"]));
+        std::fs::write(root.join("tracked.txt"), "main\n").unwrap();
+        assert!(git(root, &["commit", "-q", "-am", "main"]));
+        // The merge itself fails with a conflict
+        git(root, &["merge", "-q", "topic"]);
+        assert_eq!(assert_parity(root).unmerged, 1);
+    }
+
+    #[test]
+    fn test_parity_upstream_ahead_behind() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let origin = temp.path().join("origin");
+        std::fs::create_dir(&origin).unwrap();
+        if fixture_repo(&origin).is_none() {
+            return; // git not installed
+        }
+        let clone = temp.path().join("clone");
+        assert!(git(
+            temp.path(),
+            &["clone", "-q", origin.to_str().unwrap(), "clone"]
+        ));
+        assert_parity(&clone);
+
+        // One local commit and two upstream commits
+        assert!(git(
+            &clone,
+            &["commit", "-q", "--allow-empty", "-m", "local"]
+        ));
+        assert!(git(
+            &origin,
+            &["commit", "-q", "--allow-empty", "-m", "up1"]
+        ));
+        assert!(git(
+            &origin,
+            &["commit", "-q", "--allow-empty", "-m", "up2"]
+        ));
+        assert!(git(&clone, &["fetch", "-q"]));
+        let status = assert_parity(&clone);
+        assert_eq!(status.upstream.as_deref(), Some("origin/main"));
+        assert_eq!((status.ahead, status.behind), (1, 2));
+
+        // Detached HEAD has no upstream
+        assert!(git(&clone, &["checkout", "-q", "--detach"]));
+        assert_parity(&clone);
+    }
+
+    #[test]
+    fn test_parity_unborn_branch() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path();
+        if !git(root, &["init", "-q", "-b", "main"]) {
+            return; // git not installed
+        }
+        std::fs::write(root.join("first.txt"), "1").unwrap();
+        assert_parity(root);
+        assert!(git(root, &["add", "first.txt"]));
+        assert_parity(root);
+    }
+
+    #[test]
+    fn test_non_matching_work_tree_is_unsupported() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = std::fs::canonicalize(temp.path()).unwrap();
+        if fixture_repo(&root).is_none() {
+            return; // git not installed
+        }
+        let mut repo = discover_repository(&root).unwrap();
+        repo.work_tree = root.join("elsewhere");
+        assert!(matches!(
+            read_status_with_timeout(&repo, Duration::from_secs(30)),
+            Err(NativeStatusError::Unsupported(_))
+        ));
+    }
+}
@@ -1,15 +1,29 @@
-//! GitProvider -- wraps existing git module as a DataProvider.
+//! GitProvider -- wraps the git module as a DataProvider.
 //!
-//! Produces the same `git` and `git_branch` variables that
-//! `VariableBuilder::git_with_config()` currently produces, preserving
-//! backward compatibility. Colors are pre-applied (Phase 6 defers
-//! raw-value refactoring).
+//! Emits `git` and `git_branch` (as `VariableBuilder::git_with_config()` did),
+//! `git_status`, `git_root` / `git_subdir`, the raw `git_ahead`, `git_behind`,
+//! `git_stash` and `git_upstream`, `git_detached` and `git_state`, plus the
+//! `vcs_*` variables for plain git repositories (see [`crate::vcs`]). The HEAD
+//! commit (`git_commit*`), the nearest tag (`git_tag`) and `git diff` line counts
+//! (`git_diff*`) are only queried when the layout uses them.
 
-use crate::git::{format_git_info, get_git_status};
+use crate::common::validate_path_security;
+use crate::display::Colors;
+use crate::git::{
+    discover_repository, format_commit_age, format_git_counts, format_git_info, format_git_state,
+    get_diff_stats, get_git_status, get_head_commit, get_nearest_tag,
+};
 use crate::provider::{DataProvider, ProviderResult};
-use crate::utils::sanitize_for_terminal;
+use crate::utils::{sanitize_for_terminal, shorten_path};
+use crate::vcs::{self, VcsKind};
 use std::collections::HashMap;
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Time allowed beyond the git commands for repository discovery, the cache and polling.
+const OVERHEAD: Duration = Duration::from_millis(200);
+
+/// Commit subjects longer than this are truncated with an ellipsis.
+const COMMIT_SUBJECT_MAX_CHARS: usize = 50;
 
 /// Data provider that collects git repository status variables.
 ///
@@ -17,6 +31,12 @@ use std::time::Duration;
 /// and returns pre-formatted strings with ANSI color codes embedded.
 pub struct GitProvider {
     current_dir: String,
+    /// Run `git log` for the `git_commit*` variables
+    commit: bool,
+    /// Run `git describe` for `git_tag`
+    tag: bool,
+    /// Run `git diff --numstat` for the `git_diff*` variables
+    diff: bool,
 }
 
 impl GitProvider {
@@ -24,8 +44,20 @@ impl GitProvider {
     pub fn new(current_dir: &str) -> Self {
         Self {
             current_dir: current_dir.to_string(),
+            commit: true,
+            tag: true,
+            diff: true,
         }
     }
+
+    /// Skip the HEAD commit, tag and diff queries when `format` has none of their
+    /// variables; each costs git processes whenever its cache entry is stale.
+    pub fn only_for_format(mut self, format: &str) -> Self {
+        self.commit = format.contains("git_commit");
+        self.tag = format.contains("git_tag");
+        self.diff = format.contains("git_diff");
+        self
+    }
 }
 
 impl DataProvider for GitProvider {
@@ -37,20 +69,41 @@ impl DataProvider for GitProvider {
         50
     }
 
+    /// The status, commit, tag and diff queries run side by side, so the provider
+    /// takes as long as the slowest one, retries included. The orchestrator waits
+    /// for the provider either way; a shorter timeout would only discard the result.
     fn timeout(&self) -> Duration {
-        Duration::from_millis(100)
+        crate::git_utils::git_command_budget() + OVERHEAD
     }
 
+    /// Available anywhere inside a repository working tree (subdirectories,
+    /// linked worktrees, submodules, or an explicit `GIT_DIR`).
     fn is_available(&self) -> bool {
-        std::path::Path::new(&self.current_dir)
-            .join(".git")
-            .exists()
+        validate_path_security(&self.current_dir)
+            .ok()
+            .and_then(|dir| discover_repository(&dir))
+            .is_some()
     }
 
     fn collect(&self) -> ProviderResult {
         let mut vars = HashMap::new();
 
-        if let Some(status) = get_git_status(&self.current_dir) {
+        // The HEAD commit, nearest tag and diff need their own git processes; run them
+        // alongside the status so the provider stays within a single git timeout
+        let dir = self.current_dir.as_str();
+        let (status, commit, tag, diff) = std::thread::scope(|scope| {
+            let commit = scope.spawn(|| self.commit.then(|| get_head_commit(dir)).flatten());
+            let tag = scope.spawn(|| self.tag.then(|| get_nearest_tag(dir)).flatten());
+            let diff = scope.spawn(|| self.diff.then(|| get_diff_stats(dir)).flatten());
+            (
+                get_git_status(dir),
+                commit.join().ok().flatten(),
+                tag.join().ok().flatten(),
+                diff.join().ok().flatten(),
+            )
+        });
+
+        if let Some(status) = status {
             let git_info = format_git_info(&status);
             // Trim leading space from format_git_info() legacy format
             vars.insert("git".to_string(), git_info.trim_start().to_string());
@@ -58,12 +111,148 @@ impl DataProvider for GitProvider {
                 "git_branch".to_string(),
                 sanitize_for_terminal(&status.branch),
             );
+            if let Some(sha) = &status.detached_sha {
+                vars.insert("git_detached".to_string(), sanitize_for_terminal(sha));
+            }
+            let counts = format_git_counts(&status);
+            if !counts.is_empty() {
+                vars.insert("git_status".to_string(), counts);
+            }
+
+            // Upstream tracking and stash (raw values so templates can format them)
+            for (key, count) in [
+                ("git_ahead", status.ahead),
+                ("git_behind", status.behind),
+                ("git_stash", status.stash),
+            ] {
+                if count > 0 {
+                    vars.insert(key.to_string(), count.to_string());
+                }
+            }
+            if let Some(upstream) = &status.upstream {
+                vars.insert("git_upstream".to_string(), sanitize_for_terminal(upstream));
+            }
+
+            // In-progress operation, colored so it stands out from the branch
+            let state = format_git_state(&status);
+            if !state.is_empty() {
+                vars.insert(
+                    "git_state".to_string(),
+                    format!("{}{}{}", Colors::git_state(), state, Colors::reset()),
+                );
+            }
+
+            // Last commit, so long sessions show how long ago work was committed
+            if let Some(commit) = commit {
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0);
+                vars.insert(
+                    "git_commit".to_string(),
+                    sanitize_for_terminal(&commit.short_sha),
+                );
+                vars.insert(
+                    "git_commit_subject".to_string(),
+                    truncate_subject(&sanitize_for_terminal(&commit.subject)),
+                );
+                vars.insert(
+                    "git_commit_author".to_string(),
+                    sanitize_for_terminal(&commit.author),
+                );
+                vars.insert(
+                    "git_commit_age".to_string(),
+                    format_commit_age(now.saturating_sub(commit.timestamp)),
+                );
+            }
+            if let Some(tag) = tag {
+                vars.insert("git_tag".to_string(), sanitize_for_terminal(&tag));
+            }
+
+            // Pending line changes according to git, unlike `lines` which reports
+            // what Claude Code changed during the session
+            if let Some(diff) = diff.filter(|diff| diff.files > 0) {
+                vars.insert(
+                    "git_diff".to_string(),
+                    format_line_counts(diff.added(), diff.removed()),
+                );
+                vars.insert("git_diff_added".to_string(), diff.added().to_string());
+                vars.insert("git_diff_removed".to_string(), diff.removed().to_string());
+                vars.insert("git_diff_files".to_string(), diff.files.to_string());
+                for (key, counts) in [
+                    ("git_diff_staged", &diff.staged),
+                    ("git_diff_unstaged", &diff.unstaged),
+                ] {
+                    if counts.files > 0 {
+                        vars.insert(
+                            key.to_string(),
+                            format_line_counts(counts.added, counts.removed),
+                        );
+                    }
+                }
+            }
+
+            // Repository root and the current directory's position within it
+            let safe_dir = validate_path_security(&self.current_dir).ok();
+            if let Some(repo) = safe_dir.as_deref().and_then(discover_repository) {
+                vars.insert(
+                    "git_root".to_string(),
+                    sanitize_for_terminal(&shorten_path(&repo.work_tree.to_string_lossy())),
+                );
+                let subdir = repo.subdir.to_string_lossy();
+                if !subdir.is_empty() {
+                    vars.insert("git_subdir".to_string(), sanitize_for_terminal(&subdir));
+                }
+
+                // Backend-neutral variables, unless jj owns this working copy
+                // (VcsProvider reports colocated repositories as Jujutsu)
+                let is_git = safe_dir
+                    .as_deref()
+                    .and_then(vcs::detect_kind)
+                    .is_some_and(|(kind, _)| kind == VcsKind::Git);
+                if is_git {
+                    let vcs_status = vcs::git::to_vcs_status(&status, &repo);
+                    vcs::insert_vars(&vcs_status, &repo.work_tree, &mut vars);
+                }
+            }
         }
 
         Ok(vars)
     }
 }
 
+/// Formats added/removed line counts like `{lines}` (e.g. "+12 -3"), in the theme's
+/// line colors. Zero counts are omitted; a change of only binary files shows "+0".
+fn format_line_counts(added: usize, removed: usize) -> String {
+    let mut parts = Vec::new();
+    if added > 0 || removed == 0 {
+        parts.push(format!(
+            "{}+{}{}",
+            Colors::lines_added(),
+            added,
+            Colors::reset()
+        ));
+    }
+    if removed > 0 {
+        parts.push(format!(
+            "{}-{}{}",
+            Colors::lines_removed(),
+            removed,
+            Colors::reset()
+        ));
+    }
+    parts.join(" ")
+}
+
+/// Truncates a commit subject to [`COMMIT_SUBJECT_MAX_CHARS`] characters.
+fn truncate_subject(subject: &str) -> String {
+    if subject.chars().count() <= COMMIT_SUBJECT_MAX_CHARS {
+        return subject.to_string();
+    }
+    let truncated: String = subject.chars().take(COMMIT_SUBJECT_MAX_CHARS - 1).collect();
+    format!("{}…", truncated.trim_end())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -74,7 +263,11 @@ mod tests {
         let provider = GitProvider::new("/tmp/nonexistent");
         assert_eq!(provider.name(), "git");
         assert_eq!(provider.priority(), 50);
-        assert_eq!(provider.timeout(), Duration::from_millis(100));
+        // Room for a git command that times out once and is retried
+        assert!(
+            provider.timeout()
+                > Duration::from_millis(2 * crate::git_utils::git_timeout_ms() as u64)
+        );
     }
 
     #[test]
@@ -95,4 +288,279 @@ mod tests {
             "Non-git directory should produce empty vars"
         );
     }
+
+    #[test]
+    fn test_git_provider_subdirectory_of_repo() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = std::fs::canonicalize(temp_dir.path()).unwrap();
+        let status = std::process::Command::new("git")
+            .args(["init", "-q"])
+            .current_dir(&root)
+            .status();
+        if !matches!(status, Ok(s) if s.success()) {
+            return; // git not installed
+        }
+        let nested = root.join("crates").join("foo");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        let provider = GitProvider::new(nested.to_str().unwrap());
+        assert!(provider.is_available(), "Subdirectory should be detected");
+
+        let vars = provider.collect().expect("collect should succeed");
+        assert!(vars.contains_key("git_branch"));
+        assert_eq!(vars.get("git_subdir"), Some(&"crates/foo".to_string()));
+        assert!(vars.contains_key("git_root"));
+    }
+
+    #[test]
+    fn test_git_provider_stash_and_tracking_vars() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = std::fs::canonicalize(temp_dir.path()).unwrap();
+        let status = std::process::Command::new("git")
+            .args(["init", "-q"])
+            .current_dir(&root)
+            .status();
+        if !matches!(status, Ok(s) if s.success()) {
+            return; // git not installed
+        }
+        let logs = root.join(".git").join("logs").join("refs");
+        std::fs::create_dir_all(&logs).unwrap();
+        std::fs::write(logs.join("stash"), "0000 1111 a <a> 1 +0000\tWIP on main\n").unwrap();
+
+        let provider = GitProvider::new(root.to_str().unwrap());
+        let vars = provider.collect().expect("collect should succeed");
+        assert_eq!(vars.get("git_stash"), Some(&"1".to_string()));
+        // No upstream configured in a fresh repository
+        assert!(!vars.contains_key("git_upstream"));
+        assert!(!vars.contains_key("git_ahead"));
+        assert!(!vars.contains_key("git_behind"));
+        assert!(!vars.contains_key("git_state"));
+
+        std::fs::write(root.join(".git").join("MERGE_HEAD"), "0000\n").unwrap();
+        let vars = provider.collect().expect("collect should succeed");
+        assert!(vars
+            .get("git_state")
+            .is_some_and(|state| state.contains("MERGE")));
+    }
+
+    #[test]
+    fn test_truncate_subject() {
+        assert_eq!(truncate_subject("Short subject"), "Short subject");
+        let long = "é".repeat(COMMIT_SUBJECT_MAX_CHARS + 10);
+        let truncated = truncate_subject(&long);
+        assert_eq!(truncated.chars().count(), COMMIT_SUBJECT_MAX_CHARS);
+        assert!(truncated.ends_with('…'));
+    }
+
+    #[test]
+    fn test_git_provider_commit_vars() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = std::fs::canonicalize(temp_dir.path()).unwrap();
+        let git = |args: &[&str]| {
+            std::process::Command::new("git")
+                .args(args)
+                .current_dir(&root)
+                .env("GIT_AUTHOR_NAME", "Test")
+                .env("GIT_AUTHOR_EMAIL", "test@example.com")
+                .env("GIT_COMMITTER_NAME", "Test")
+                .env("GIT_COMMITTER_EMAIL", "test@example.com")
+                .status()
+                .is_ok_and(|s| s.success())
+        };
+        if !git(&["init", "-q"]) {
+            return; // git not installed
+        }
+
+        let provider = GitProvider::new(root.to_str().unwrap());
+        let vars = provider.collect().expect("collect should succeed");
+        assert!(!vars.contains_key("git_commit"), "no commits yet");
+        assert!(!vars.contains_key("git_tag"));
+
+        assert!(git(&[
+            "commit",
+            "-q",
+            "--allow-empty",
+            "-m",
+            "Add \x1b[31mred\x1b[0m"
+        ]));
+        assert!(git(&["tag", "v0.1.0"]));
+
+        let vars = provider.collect().expect("collect should succeed");
+        assert!(vars.get("git_commit").is_some_and(|sha| sha.len() >= 7));
+        assert_eq!(vars.get("git_commit_subject"), Some(&"Add red".to_string()));
+        assert_eq!(vars.get("git_commit_author"), Some(&"Test".to_string()));
+        assert_eq!(vars.get("git_commit_age"), Some(&"just now".to_string()));
+        assert_eq!(vars.get("git_tag"), Some(&"v0.1.0".to_string()));
+        assert!(!vars.contains_key("git_detached"));
+
+        // A detached HEAD keeps git's branch text; the SHA has its own variable
+        assert!(git(&["checkout", "-q", "--detach"]));
+        let vars = provider.collect().expect("collect should succeed");
+        assert_eq!(vars.get("git_detached"), vars.get("git_commit"));
+        let branch = vars.get("git_branch").expect("git_branch is set");
+        assert!(!branch.contains(vars["git_commit"].as_str()));
+
+        // Layouts without commit or tag variables skip those queries
+        let vars = GitProvider::new(root.to_str().unwrap())
+            .only_for_format("{git}{sep}{git_diff_files}")
+            .collect()
+            .expect("collect should succeed");
+        assert!(vars.contains_key("git_branch"));
+        assert!(!vars.contains_key("git_commit"));
+        assert!(!vars.contains_key("git_tag"));
+    }
+
+    #[test]
+    fn test_git_provider_diff_vars() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = std::fs::canonicalize(temp_dir.path()).unwrap();
+        let git = |args: &[&str]| {
+            std::process::Command::new("git")
+                .args(args)
+                .current_dir(&root)
+                .env("GIT_AUTHOR_NAME", "Test")
+                .env("GIT_AUTHOR_EMAIL", "test@example.com")
+                .env("GIT_COMMITTER_NAME", "Test")
+                .env("GIT_COMMITTER_EMAIL", "test@example.com")
+                .status()
+                .is_ok_and(|s| s.success())
+        };
+        if !git(&["init", "-q"]) {
+            return; // git not installed
+        }
+        std::fs::write(root.join("a.txt"), "one\ntwo\n").unwrap();
+        assert!(git(&["add", "a.txt"]));
+        assert!(git(&["commit", "-q", "-m", "initial"]));
+
+        let provider = GitProvider::new(root.to_str().unwrap());
+        let vars = provider.collect().expect("collect should succeed");
+        assert!(!vars.contains_key("git_diff"), "clean tree has no diff");
+
+        std::fs::write(root.join("a.txt"), "one\n2\nthree\n").unwrap();
+        // A new file in the root changes its mtime, so a cached clean result is not reused
+        std::fs::write(root.join("b.txt"), "").unwrap();
+        assert!(git(&["add", "b.txt"]));
+
+        let vars = provider.collect().expect("collect should succeed");
+        assert_eq!(vars.get("git_diff_added"), Some(&"2".to_string()));
+        assert_eq!(vars.get("git_diff_removed"), Some(&"1".to_string()));
+        assert_eq!(vars.get("git_diff_files"), Some(&"2".to_string()));
+        assert!(vars
+            .get("git_diff")
+            .is_some_and(|d| d.contains("+2") && d.contains("-1")));
+        assert!(vars
+            .get("git_diff_staged")
+            .is_some_and(|d| d.contains("+0")));
+        assert!(vars
+            .get("git_diff_unstaged")
+            .is_some_and(|d| d.contains("+2")));
+
+        // Layouts without diff variables skip `git diff`
+        let vars = GitProvider::new(root.to_str().unwrap())
+            .only_for_format("{git}{sep}{git_commit}")
+            .collect()
+            .expect("collect should succeed");
+        assert!(vars.contains_key("git_commit"));
+        assert!(!vars.contains_key("git_diff"));
+    }
+
+    #[test]
+    fn test_git_provider_diff_larger_than_pipe_buffer() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = std::fs::canonicalize(temp_dir.path()).unwrap();
+        let git = |args: &[&str]| {
+            std::process::Command::new("git")
+                .args(args)
+                .current_dir(&root)
+                .env("GIT_AUTHOR_NAME", "Test")
+                .env("GIT_AUTHOR_EMAIL", "test@example.com")
+                .env("GIT_COMMITTER_NAME", "Test")
+                .env("GIT_COMMITTER_EMAIL", "test@example.com")
+                .status()
+                .is_ok_and(|s| s.success())
+        };
+        if !git(&["init", "-q"]) {
+            return; // git not installed
+        }
+
+        // ~2000 files with long names put well over 64KB on `git diff --numstat`
+        let name = |i: usize| format!("{:0>60}.txt", i);
+        for i in 0..2000 {
+            std::fs::write(root.join(name(i)), "one\n").unwrap();
+        }
+        assert!(git(&["add", "-A"]));
+        assert!(git(&["commit", "-q", "-m", "initial"]));
+        for i in 0..2000 {
+            std::fs::write(root.join(name(i)), "two\n").unwrap();
+        }
+
+        let vars = GitProvider::new(root.to_str().unwrap())
+            .collect()
+            .expect("collect should succeed");
+        assert!(vars.contains_key("git"));
+        assert_eq!(vars.get("git_diff_files"), Some(&"2000".to_string()));
+        assert_eq!(vars.get("git_diff_added"), Some(&"2000".to_string()));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    #[serial_test::serial]
+    fn test_git_provider_survives_a_retried_git_command() {
+        use crate::provider::ProviderOrchestrator;
+        use std::os::unix::fs::PermissionsExt;
+
+        let Some(real_git) = std::process::Command::new("sh")
+            .args(["-c", "command -v git"])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        else {
+            return; // git not installed
+        };
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = std::fs::canonicalize(temp_dir.path()).unwrap();
+        let repo = root.join("repo");
+        let bin = root.join("bin");
+        std::fs::create_dir_all(&repo).unwrap();
+        std::fs::create_dir_all(&bin).unwrap();
+        std::process::Command::new(&real_git)
+            .args(["init", "-q"])
+            .current_dir(&repo)
+            .status()
+            .unwrap();
+
+        // The first git command hangs until it is killed; the retry answers at once
+        let marker = root.join("hung");
+        let fake_git = bin.join("git");
+        std::fs::write(
+            &fake_git,
+            format!(
+                "#!/bin/sh\nif [ ! -e '{}' ]; then : > '{}'; exec sleep 5; fi\nexec '{}' \"$@\"\n",
+                marker.display(),
+                marker.display(),
+                real_git
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&fake_git, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let old_path = std::env::var_os("PATH").unwrap_or_default();
+        let mut paths = vec![bin.clone()];
+        paths.extend(std::env::split_paths(&old_path));
+        std::env::set_var("PATH", std::env::join_paths(paths).unwrap());
+        std::env::set_var("STATUSLINE_GIT_TIMEOUT_MS", "300");
+
+        let mut orchestrator = ProviderOrchestrator::new();
+        orchestrator.register(Box::new(
+            GitProvider::new(repo.to_str().unwrap()).only_for_format("{git}"),
+        ));
+        let vars = orchestrator.collect_all();
+
+        std::env::set_var("PATH", old_path);
+        std::env::remove_var("STATUSLINE_GIT_TIMEOUT_MS");
+        #[cfg(not(feature = "git_native"))]
+        assert!(marker.exists(), "the fake git was not run");
+        assert!(vars.contains_key("git"), "{{git}} was dropped: {:?}", vars);
+    }
 }
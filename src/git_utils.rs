@@ -8,9 +8,15 @@ use crate::error::StatuslineError;
 use crate::retry::retry_simple;
 use std::io::Read;
 use std::path::Path;
-use std::process::{Command, Output, Stdio};
+use std::process::{Child, Command, ExitStatus, Output, Stdio};
 use std::time::{Duration, Instant};
 
+/// Attempts `execute_git_command` makes at a command (lock files are often transient)
+const GIT_ATTEMPTS: u32 = 2;
+
+/// Pause between attempts, in milliseconds
+const GIT_RETRY_DELAY_MS: u64 = 100;
+
 /// Executes a git command with the given arguments in a directory.
 ///
 /// This function handles:
@@ -28,46 +34,144 @@ use std::time::{Duration, Instant};
 ///
 /// Returns the command output if successful, or None if the command fails or times out.
 fn execute_git_command<P: AsRef<Path>>(dir: P, args: &[&str]) -> Option<Output> {
+    retry_git_command(dir.as_ref(), args, execute_with_timeout)
+}
+
+/// Like [`execute_git_command`], for commands whose output can outgrow the pipe
+/// buffer (see [`execute_reading_with_timeout`]).
+fn execute_git_command_large_output<P: AsRef<Path>>(dir: P, args: &[&str]) -> Option<Output> {
+    retry_git_command(dir.as_ref(), args, execute_reading_with_timeout)
+}
+
+/// Runs a git command with `run`, retrying it as described for [`execute_git_command`].
+fn retry_git_command(
+    dir: &Path,
+    args: &[&str],
+    run: fn(Command, u32) -> Option<Output>,
+) -> Option<Output> {
+    let timeout_ms = git_timeout_ms();
+
+    retry_simple(GIT_ATTEMPTS, GIT_RETRY_DELAY_MS, || {
+        execute_git_with_timeout(dir, args, timeout_ms, run)
+            .ok_or_else(|| StatuslineError::git("Git command timed out or failed"))
+    })
+    .ok()
+}
+
+/// Returns the per-command git timeout in milliseconds.
+///
+/// Uses `STATUSLINE_GIT_TIMEOUT_MS` when set, otherwise `[git] timeout_ms` from config.
+pub fn git_timeout_ms() -> u32 {
     let config = config::get_config();
 
     // Support environment variable override for timeout
-    let timeout_ms = std::env::var("STATUSLINE_GIT_TIMEOUT_MS")
+    std::env::var("STATUSLINE_GIT_TIMEOUT_MS")
         .ok()
         .and_then(|v| v.parse::<u32>().ok())
-        .unwrap_or(config.git.timeout_ms);
+        .unwrap_or(config.git.timeout_ms)
+}
 
-    retry_simple(2, 100, || {
-        execute_git_with_timeout(dir.as_ref(), args, timeout_ms)
-            .ok_or_else(|| StatuslineError::git("Git command timed out or failed"))
-    })
-    .ok()
+/// Returns the longest one git query can take: every attempt running into the
+/// per-command timeout, plus the pauses between attempts.
+pub fn git_command_budget() -> Duration {
+    let attempts = u64::from(GIT_ATTEMPTS);
+    Duration::from_millis(
+        attempts * u64::from(git_timeout_ms()) + (attempts - 1) * GIT_RETRY_DELAY_MS,
+    )
 }
 
 /// Internal function that executes a git command with proper timeout support.
 ///
 /// Returns the command output if successful, or None if timeout/failure occurs.
-///
-/// Note: This implementation avoids spawning threads to read stdout/stderr,
-/// which can fail on FreeBSD with EAGAIN. Instead, we wait for the process
-/// to complete and then read the pipes sequentially.
-///
-/// Safe commands: `git status --porcelain`, `git rev-parse`, `git branch` -
-/// these produce small output well under the ~64KB pipe buffer limit.
-/// Unsafe: commands like `git log` or `git diff` with large output could deadlock.
 fn execute_git_with_timeout<P: AsRef<Path>>(
     dir: P,
     args: &[&str],
     timeout_ms: u32,
+    run: fn(Command, u32) -> Option<Output>,
 ) -> Option<Output> {
     let mut cmd = Command::new("git");
     cmd.args(args)
         .current_dir(dir.as_ref())
-        .env("GIT_OPTIONAL_LOCKS", "0")
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped());
+        .env("GIT_OPTIONAL_LOCKS", "0");
+
+    run(cmd, timeout_ms)
+}
+
+/// Runs `cmd` with piped output, killing it if it does not finish within `timeout_ms`.
+///
+/// Shared by the git helpers above and the Jujutsu/Mercurial backends in [`crate::vcs`].
+///
+/// Note: This implementation avoids spawning threads to read stdout/stderr,
+/// which can fail on FreeBSD with EAGAIN. Instead, we wait for the process
+/// to complete and then read the pipes sequentially.
+///
+/// Safe commands: `git rev-parse`, `git log -1`, `git describe` -
+/// these produce small output well under the ~64KB pipe buffer limit.
+/// Unsafe: commands with a line per file (`git status`, `git diff --numstat`)
+/// could deadlock; run those with [`execute_reading_with_timeout`].
+pub(crate) fn execute_with_timeout(mut cmd: Command, timeout_ms: u32) -> Option<Output> {
+    cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+
+    let mut child = cmd.spawn().ok()?;
+    let status = wait_with_timeout(&mut child, &cmd, timeout_ms)?;
+
+    // Process finished, read output sequentially
+    // This is safe for small outputs (git status) and avoids
+    // thread spawning which fails on FreeBSD
+    let mut stdout_data = Vec::new();
+    let mut stderr_data = Vec::new();
+
+    if let Some(mut stdout) = child.stdout.take() {
+        let _ = stdout.read_to_end(&mut stdout_data);
+    }
+    if let Some(mut stderr) = child.stderr.take() {
+        let _ = stderr.read_to_end(&mut stderr_data);
+    }
+
+    Some(Output {
+        status,
+        stdout: stdout_data,
+        stderr: stderr_data,
+    })
+}
+
+/// Runs `cmd` like [`execute_with_timeout`], but reads stdout on a separate thread
+/// while it runs, so output larger than the pipe buffer (such as `git diff --numstat`
+/// of many files) cannot stall the command until it is killed. Stderr is discarded.
+///
+/// When the reader thread cannot be spawned (EAGAIN on FreeBSD) the command is not
+/// run and `None` is returned.
+pub(crate) fn execute_reading_with_timeout(mut cmd: Command, timeout_ms: u32) -> Option<Output> {
+    cmd.stdout(Stdio::piped()).stderr(Stdio::null());
 
     let mut child = cmd.spawn().ok()?;
+    let mut stdout = child.stdout.take()?;
+    let reader = std::thread::Builder::new().spawn(move || {
+        let mut data = Vec::new();
+        let _ = stdout.read_to_end(&mut data);
+        data
+    });
+    let reader = match reader {
+        Ok(reader) => reader,
+        Err(e) => {
+            log::debug!("Could not read {:?} output: {}", cmd.get_program(), e);
+            let _ = child.kill();
+            let _ = child.wait();
+            return None;
+        }
+    };
 
+    // On a timeout the reader is left to finish once the killed process's pipe closes
+    let status = wait_with_timeout(&mut child, &cmd, timeout_ms)?;
+    Some(Output {
+        status,
+        stdout: reader.join().ok()?,
+        stderr: Vec::new(),
+    })
+}
+
+/// Waits for `child` to exit, killing it if it does not finish within `timeout_ms`.
+fn wait_with_timeout(child: &mut Child, cmd: &Command, timeout_ms: u32) -> Option<ExitStatus> {
     // Wait for the timeout duration, polling for completion
     let timeout = Duration::from_millis(timeout_ms as u64);
     let start = Instant::now();
@@ -78,40 +182,25 @@ fn execute_git_with_timeout<P: AsRef<Path>>(
             let _ = child.kill();
             let _ = child.wait(); // Reap the process
             log::info!(
-                "Git command timed out after {}ms: git {}",
+                "Command timed out after {}ms: {} {}",
                 timeout_ms,
-                args.join(" ")
+                cmd.get_program().to_string_lossy(),
+                cmd.get_args()
+                    .map(|arg| arg.to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join(" ")
             );
             return None;
         }
 
         match child.try_wait() {
-            Ok(Some(status)) => {
-                // Process finished, read output sequentially
-                // This is safe for small outputs (git status) and avoids
-                // thread spawning which fails on FreeBSD
-                let mut stdout_data = Vec::new();
-                let mut stderr_data = Vec::new();
-
-                if let Some(mut stdout) = child.stdout.take() {
-                    let _ = stdout.read_to_end(&mut stdout_data);
-                }
-                if let Some(mut stderr) = child.stderr.take() {
-                    let _ = stderr.read_to_end(&mut stderr_data);
-                }
-
-                return Some(Output {
-                    status,
-                    stdout: stdout_data,
-                    stderr: stderr_data,
-                });
-            }
+            Ok(Some(status)) => return Some(status),
             Ok(None) => {
                 // Still running, continue waiting
                 std::thread::sleep(Duration::from_millis(10));
             }
             Err(e) => {
-                log::debug!("Error waiting for git process: {}", e);
+                log::debug!("Error waiting for {:?} process: {}", cmd.get_program(), e);
                 return None;
             }
         }
@@ -138,7 +227,34 @@ pub fn get_status_porcelain<P: AsRef<Path>>(dir: P) -> Option<String> {
     #[cfg(not(feature = "git_porcelain_v2"))]
     let args = &["status", "--porcelain=v1", "--branch"];
 
-    let output = execute_git_command(dir, args)?;
+    // One line per changed file, so a busy work tree can outgrow the pipe buffer
+    let output = execute_git_command_large_output(dir, args)?;
+
+    if output.status.success() {
+        Some(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        None
+    }
+}
+
+/// Gets per-file line counts (`git diff --numstat`) for unstaged or staged changes.
+///
+/// Unstaged changes compare the working tree with the index; staged changes compare
+/// the index with HEAD (or the empty tree before the first commit).
+pub fn get_diff_numstat<P: AsRef<Path>>(dir: P, staged: bool) -> Option<String> {
+    let args: &[&str] = if staged {
+        &[
+            "diff",
+            "--cached",
+            "--numstat",
+            "--no-renames",
+            "--no-ext-diff",
+        ]
+    } else {
+        &["diff", "--numstat", "--no-renames", "--no-ext-diff"]
+    };
+
+    let output = execute_git_command_large_output(dir, args)?;
 
     if output.status.success() {
         Some(String::from_utf8_lossy(&output.stdout).to_string())
@@ -147,6 +263,34 @@ pub fn get_status_porcelain<P: AsRef<Path>>(dir: P) -> Option<String> {
     }
 }
 
+/// Gets the HEAD commit's short hash, subject, author name and commit time.
+///
+/// Fields are NUL-separated (`%h%x00%s%x00%an%x00%ct`) so subjects containing any
+/// printable character parse unambiguously. Returns `None` before the first commit.
+pub fn get_head_commit<P: AsRef<Path>>(dir: P) -> Option<String> {
+    let output = execute_git_command(dir, &["log", "-1", "--format=%h%x00%s%x00%an%x00%ct"])?;
+
+    if output.status.success() {
+        Some(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        None
+    }
+}
+
+/// Gets the nearest tag reachable from HEAD (`git describe --tags --abbrev=0`).
+///
+/// Returns `None` when no tag is reachable.
+pub fn get_nearest_tag<P: AsRef<Path>>(dir: P) -> Option<String> {
+    let output = execute_git_command(dir, &["describe", "--tags", "--abbrev=0"])?;
+
+    if output.status.success() {
+        let tag = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        (!tag.is_empty()).then_some(tag)
+    } else {
+        None
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -201,10 +345,26 @@ mod tests {
             temp_dir.path(),
             &["--version"], // Quick command that should succeed
             200,            // 200ms timeout
+            execute_with_timeout,
         );
 
         // Should complete quickly and successfully
         assert!(result.is_some());
         assert!(start.elapsed() < Duration::from_millis(500));
     }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_reading_with_timeout_handles_large_output() {
+        // More than the ~64KB pipe buffer, which would stall until the timeout if
+        // stdout were only read after the process exits
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "head -c 200000 /dev/zero"]);
+
+        let start = Instant::now();
+        let output = execute_reading_with_timeout(cmd, 2000).expect("should not time out");
+        assert!(output.status.success());
+        assert_eq!(output.stdout.len(), 200_000);
+        assert!(start.elapsed() < Duration::from_millis(2000));
+    }
 }
@@ -0,0 +1,505 @@
+//! Pipe filters for template variables: `{var|filter:arg:arg|filter}`.
+//!
+//! Filters apply left to right to the variable's value. Arguments are separated
+//! by `:` and may be quoted (`"..."`, with `\"` for a quote) to contain `:`, `|`
+//! or `}`.
+//! Values in the legacy render path carry ANSI colors, so every filter works on
+//! the visible text and keeps the escape sequences around it.
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use super::format::resolve_color_override;
+
+/// Suffix `truncate` appends when it shortens a value
+const DEFAULT_ELLIPSIS: &str = "…";
+
+/// Largest width or precision a filter uses; larger ones are clamped to it
+const MAX_WIDTH: usize = 512;
+
+/// A filter applied to a variable's value.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Filter {
+    /// `upper`: uppercase the text
+    Upper,
+    /// `lower`: lowercase the text
+    Lower,
+    /// `truncate:N[:suffix]`: at most N terminal columns, ending in the suffix when cut
+    Truncate { width: usize, suffix: String },
+    /// `fmt:"spec"`: printf-style formatting of the value (or the number in it)
+    Format(FormatSpec),
+    /// `pad:N`: right-align to N terminal columns; `pad:-N` left-aligns
+    Pad { width: usize, left: bool },
+    /// `default:text`: replacement for an empty value
+    Default(String),
+    /// `color:name`: recolor the value (names, hex or 256 colors as in component colors)
+    Color(String),
+}
+
+/// A single printf conversion (`%s`, `%d`, `%f`) with its surrounding text.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct FormatSpec {
+    prefix: String,
+    suffix: String,
+    left: bool,
+    zero: bool,
+    plus: bool,
+    width: usize,
+    precision: Option<usize>,
+    conversion: char,
+}
+
+/// Split the inside of a `{...}` placeholder into the variable name and its filters.
+pub(super) fn parse_variable(inner: &str) -> Result<(String, Vec<Filter>), String> {
+    let parts = split_unquoted(inner, '|')?;
+    let name = parts[0].trim();
+    if name.is_empty() {
+        return Err(format!("missing variable name in {{{}}}", inner));
+    }
+    let filters = parts[1..]
+        .iter()
+        .map(|part| parse_filter(part.trim()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| format!("{} in {{{}}}", err, inner))?;
+    Ok((name.to_string(), filters))
+}
+
+fn parse_filter(spec: &str) -> Result<Filter, String> {
+    let parts = split_unquoted(spec, ':')?;
+    let name = parts[0].trim();
+    let args: Vec<String> = parts[1..].iter().map(|arg| unquote(arg)).collect();
+    let arity = |min: usize, max: usize| {
+        if args.len() < min || args.len() > max {
+            let expected = match (min, max) {
+                (0, 0) => "no arguments".to_string(),
+                (min, max) if min == max => format!("{} argument(s)", min),
+                (min, max) => format!("{} to {} arguments", min, max),
+            };
+            Err(format!(
+                "filter '{}' takes {}, got {}",
+                name,
+                expected,
+                args.len()
+            ))
+        } else {
+            Ok(())
+        }
+    };
+
+    match name {
+        "upper" => arity(0, 0).map(|_| Filter::Upper),
+        "lower" => arity(0, 0).map(|_| Filter::Lower),
+        "truncate" => {
+            arity(1, 2)?;
+            let width = args[0]
+                .trim()
+                .parse::<usize>()
+                .ok()
+                .filter(|w| *w > 0)
+                .ok_or_else(|| format!("truncate width '{}' is not a positive number", args[0]))?
+                .min(MAX_WIDTH);
+            let suffix = args
+                .get(1)
+                .cloned()
+                .unwrap_or_else(|| DEFAULT_ELLIPSIS.to_string());
+            Ok(Filter::Truncate { width, suffix })
+        }
+        "fmt" => {
+            arity(1, 1)?;
+            FormatSpec::parse(&args[0]).map(Filter::Format)
+        }
+        "pad" => {
+            arity(1, 1)?;
+            let width = args[0]
+                .trim()
+                .parse::<i64>()
+                .map_err(|_| format!("pad width '{}' is not a number", args[0]))?;
+            Ok(Filter::Pad {
+                width: (width.unsigned_abs() as usize).min(MAX_WIDTH),
+                left: width < 0,
+            })
+        }
+        "default" => {
+            arity(1, 1)?;
+            Ok(Filter::Default(args[0].clone()))
+        }
+        "color" => {
+            arity(1, 1)?;
+            let code = resolve_color_override(args[0].trim());
+            if code.is_empty() {
+                return Err(format!("unknown color '{}'", args[0]));
+            }
+            Ok(Filter::Color(code))
+        }
+        "" => Err("empty filter".to_string()),
+        other => Err(format!("unknown filter '{}'", other)),
+    }
+}
+
+/// Byte offset of the `}` closing the placeholder that `s` starts with, skipping
+/// any `}` inside quoted filter arguments. With a quote that is never closed it is
+/// the first `}`, so the placeholder still fails to parse with that error.
+pub(super) fn closing_brace(s: &str) -> Option<usize> {
+    let first = s.find('}')?;
+    let mut in_quotes = false;
+    let mut escaped = false;
+    for (i, c) in s.char_indices().skip(1) {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match c {
+            '\\' if in_quotes => escaped = true,
+            '"' => in_quotes = !in_quotes,
+            '}' if !in_quotes => return Some(i),
+            _ => {}
+        }
+    }
+    Some(first)
+}
+
+/// Split on `delimiter` outside double quotes.
+fn split_unquoted(s: &str, delimiter: char) -> Result<Vec<&str>, String> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut in_quotes = false;
+    let mut escaped = false;
+    for (i, c) in s.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match c {
+            '\\' if in_quotes => escaped = true,
+            '"' => in_quotes = !in_quotes,
+            c if c == delimiter && !in_quotes => {
+                parts.push(&s[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    if in_quotes {
+        return Err(format!("unterminated quote in '{}'", s));
+    }
+    parts.push(&s[start..]);
+    Ok(parts)
+}
+
+/// Strip surrounding quotes and unescape `\"` and `\\`; unquoted arguments are used as-is.
+fn unquote(arg: &str) -> String {
+    let Some(inner) = arg
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+    else {
+        return arg.to_string();
+    };
+    let mut out = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            if let Some(next) = chars.next() {
+                out.push(next);
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+impl FormatSpec {
+    /// Parse a printf format with exactly one `%s`, `%d` or `%f` conversion
+    /// (flags `-`, `0`, `+`, a width and a precision are allowed; `%%` is a literal `%`).
+    fn parse(spec: &str) -> Result<Self, String> {
+        let mut prefix = String::new();
+        let mut suffix = String::new();
+        let mut found: Option<FormatSpec> = None;
+        let mut chars = spec.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                match found {
+                    Some(_) => suffix.push(c),
+                    None => prefix.push(c),
+                }
+                continue;
+            }
+            if chars.peek() == Some(&'%') {
+                chars.next();
+                match found {
+                    Some(_) => suffix.push('%'),
+                    None => prefix.push('%'),
+                }
+                continue;
+            }
+            if found.is_some() {
+                return Err(format!("fmt '{}' has more than one conversion", spec));
+            }
+
+            let (mut left, mut zero, mut plus) = (false, false, false);
+            while let Some(&flag) = chars.peek() {
+                match flag {
+                    '-' => left = true,
+                    '0' => zero = true,
+                    '+' => plus = true,
+                    _ => break,
+                }
+                chars.next();
+            }
+            let mut width = String::new();
+            while let Some(&d) = chars.peek().filter(|d| d.is_ascii_digit()) {
+                width.push(d);
+                chars.next();
+            }
+            let mut precision = None;
+            if chars.peek() == Some(&'.') {
+                chars.next();
+                let mut digits = String::new();
+                while let Some(&d) = chars.peek().filter(|d| d.is_ascii_digit()) {
+                    digits.push(d);
+                    chars.next();
+                }
+                precision = Some(clamp_width(&digits));
+            }
+            let conversion = match chars.next() {
+                Some(c @ ('s' | 'd' | 'f')) => c,
+                Some(c) => {
+                    return Err(format!(
+                        "fmt '{}' has unsupported conversion '%{}'",
+                        spec, c
+                    ))
+                }
+                None => return Err(format!("fmt '{}' ends in the middle of a conversion", spec)),
+            };
+            found = Some(FormatSpec {
+                prefix: String::new(),
+                suffix: String::new(),
+                left,
+                zero,
+                plus,
+                width: clamp_width(&width),
+                precision,
+                conversion,
+            });
+        }
+
+        let mut spec_out =
+            found.ok_or_else(|| format!("fmt '{}' has no %s, %d or %f conversion", spec))?;
+        spec_out.prefix = prefix;
+        spec_out.suffix = suffix;
+        Ok(spec_out)
+    }
+
+    /// Format `text`; numeric conversions use the first number in it and leave
+    /// text without a number unchanged.
+    fn apply(&self, text: &str) -> String {
+        let body = match self.conversion {
+            's' => match self.precision {
+                Some(p) => truncate(text, p, ""),
+                None => text.to_string(),
+            },
+            _ => {
+                let Some(number) = first_number(text) else {
+                    return text.to_string();
+                };
+                let mut body = if self.conversion == 'd' {
+                    format!("{}", number.round() as i64)
+                } else {
+                    format!("{:.*}", self.precision.unwrap_or(6), number)
+                };
+                if self.plus && number >= 0.0 {
+                    body.insert(0, '+');
+                }
+                body
+            }
+        };
+
+        let len = body.width();
+        let padded = if len >= self.width {
+            body
+        } else if self.left {
+            format!("{}{}", body, " ".repeat(self.width - len))
+        } else if self.zero && self.conversion != 's' {
+            let sign_len = usize::from(body.starts_with(['-', '+']));
+            let (sign, digits) = body.split_at(sign_len);
+            format!("{}{}{}", sign, "0".repeat(self.width - len), digits)
+        } else {
+            format!("{}{}", " ".repeat(self.width - len), body)
+        };
+        format!("{}{}{}", self.prefix, padded, self.suffix)
+    }
+}
+
+/// A width or precision of a printf conversion (digits only; none is 0)
+fn clamp_width(digits: &str) -> usize {
+    if digits.is_empty() {
+        return 0;
+    }
+    digits
+        .parse::<usize>()
+        .map_or(MAX_WIDTH, |w| w.min(MAX_WIDTH))
+}
+
+/// The first decimal number in `text` (`"$12.50"` -> 12.5, `"-3%"` -> -3)
+pub(super) fn first_number(text: &str) -> Option<f64> {
+    let start = text.find(|c: char| c.is_ascii_digit())?;
+    let mut end = start;
+    let mut seen_dot = false;
+    for (i, c) in text[start..].char_indices() {
+        if c.is_ascii_digit() {
+            end = start + i + 1;
+        } else if c == '.' && !seen_dot {
+            seen_dot = true;
+        } else {
+            break;
+        }
+    }
+    let number: f64 = text[start..end].parse().ok()?;
+    Some(if text[..start].ends_with('-') {
+        -number
+    } else {
+        number
+    })
+}
+
+/// Apply `filters` to `value`, left to right.
+pub(super) fn apply_filters(value: &str, filters: &[Filter]) -> String {
+    filters.iter().fold(value.to_string(), |value, filter| {
+        apply_filter(&value, filter)
+    })
+}
+
+fn apply_filter(value: &str, filter: &Filter) -> String {
+    match filter {
+        Filter::Upper => map_text(value, str::to_uppercase),
+        Filter::Lower => map_text(value, str::to_lowercase),
+        Filter::Truncate { width, suffix } => truncate(value, *width, suffix),
+        Filter::Format(spec) => {
+            let (lead, text, trail) = split_escapes(value);
+            format!("{}{}{}", lead, spec.apply(&visible_text(text)), trail)
+        }
+        Filter::Pad { width, left } => {
+            let fill = " ".repeat(width.saturating_sub(visible_width(value)));
+            if *left {
+                format!("{}{}", value, fill)
+            } else {
+                format!("{}{}", fill, value)
+            }
+        }
+        Filter::Default(default) => {
+            if visible_width(value) == 0 {
+                default.clone()
+            } else {
+                value.to_string()
+            }
+        }
+        Filter::Color(code) => {
+            let text = visible_text(value);
+            if text.is_empty() || !crate::display::Colors::enabled() {
+                return value.to_string();
+            }
+            format!("{}{}{}", code, text, crate::display::Colors::reset())
+        }
+    }
+}
+
+/// Length of the ANSI escape sequence (`ESC [ ... m`) at the start of `s`, if any
+fn escape_len(s: &str) -> Option<usize> {
+    let rest = s.strip_prefix("\x1b[")?;
+    let end = rest.find(|c: char| !(c.is_ascii_digit() || c == ';'))?;
+    rest[end..].starts_with('m').then_some(2 + end + 1)
+}
+
+/// The value as runs of text and escape sequences (`true` for escapes)
+fn segments(value: &str) -> Vec<(bool, &str)> {
+    let mut segments = Vec::new();
+    let mut rest = value;
+    while !rest.is_empty() {
+        if let Some(len) = escape_len(rest) {
+            segments.push((true, &rest[..len]));
+            rest = &rest[len..];
+            continue;
+        }
+        let end = rest
+            .char_indices()
+            .skip(1)
+            .find(|(_, c)| *c == '\x1b')
+            .map_or(rest.len(), |(i, _)| i);
+        segments.push((false, &rest[..end]));
+        rest = &rest[end..];
+    }
+    segments
+}
+
+/// Apply `f` to each run of text between escape sequences
+fn map_text(value: &str, f: impl Fn(&str) -> String) -> String {
+    segments(value)
+        .into_iter()
+        .map(|(escape, run)| if escape { run.to_string() } else { f(run) })
+        .collect()
+}
+
+/// The value without escape sequences
+pub(super) fn visible_text(value: &str) -> String {
+    segments(value)
+        .into_iter()
+        .filter(|(escape, _)| !escape)
+        .map(|(_, run)| run)
+        .collect()
+}
+
+/// Terminal columns the value takes: none for escape sequences, two for
+/// wide characters such as emoji and CJK
+pub(super) fn visible_width(value: &str) -> usize {
+    visible_text(value).width()
+}
+
+/// Split a value into its leading escape sequences, its text and its trailing ones
+fn split_escapes(value: &str) -> (&str, &str, &str) {
+    let mut lead = 0;
+    while let Some(len) = escape_len(&value[lead..]) {
+        lead += len;
+    }
+    let mut trail = value.len();
+    while let Some(start) = value[lead..trail].rfind("\x1b[") {
+        let start = lead + start;
+        match escape_len(&value[start..trail]) {
+            Some(len) if start + len == trail => trail = start,
+            _ => break,
+        }
+    }
+    (&value[..lead], &value[lead..trail], &value[trail..])
+}
+
+/// Keep at most `width` terminal columns, ending in `suffix` when cut (or in as
+/// much of it as fits); escape sequences are all kept so colors still reset
+pub(super) fn truncate(value: &str, width: usize, suffix: &str) -> String {
+    if visible_width(value) <= width {
+        return value.to_string();
+    }
+    let suffix = if suffix.width() > width {
+        truncate(suffix, width, "")
+    } else {
+        suffix.to_string()
+    };
+    let mut keep = width - suffix.width();
+    let mut cut = false;
+    let mut out = String::with_capacity(value.len());
+    for (escape, run) in segments(value) {
+        if escape {
+            out.push_str(run);
+        } else if !cut {
+            for c in run.chars() {
+                let columns = c.width().unwrap_or(0);
+                if columns > keep {
+                    out.push_str(&suffix);
+                    cut = true;
+                    break;
+                }
+                keep -= columns;
+                out.push(c);
+            }
+        }
+    }
+    out
+}
@@ -0,0 +1,231 @@
+//! Fitting layout lines into the terminal width.
+//!
+//! A format line is split into segments at `{sep}`. Each segment takes the
+//! `priority` and `compact` template of the component its variables belong to
+//! (`[layout.components.*]`). While the rendered line is wider than the target,
+//! segments collapse to their compact template and then drop, lowest priority
+//! first (rightmost first among equals). A line that still does not fit is cut
+//! with an ellipsis.
+//!
+//! `{fill}` splits a line into a left group and a right group; the right group
+//! is aligned against the target width by repeating the fill between the two.
+
+use std::cmp::Reverse;
+
+use unicode_width::UnicodeWidthStr;
+
+use super::filters::{truncate, visible_width};
+use crate::config::ComponentsConfig;
+
+/// Priority of segments showing no component variables (`{duration}`, `{lines}`, ...)
+const DEFAULT_PRIORITY: u8 = 40;
+
+/// Suffix of a line cut to the target width
+const ELLIPSIS: &str = "…";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SegmentState {
+    Full,
+    Compact,
+    Dropped,
+}
+
+/// A `{sep}`-delimited part of a format line
+#[derive(Debug)]
+struct Segment<'a> {
+    template: &'a str,
+    /// Whether the segment is in the right-aligned group (after `{fill}`)
+    right: bool,
+    priority: u8,
+    /// Compact template, when all variables belong to one component that has one
+    compact: Option<&'a str>,
+    state: SegmentState,
+}
+
+impl<'a> Segment<'a> {
+    fn new(template: &'a str, right: bool, components: &'a ComponentsConfig) -> Self {
+        let mut priority = None;
+        let mut owner = None;
+        let mut compact = None;
+        let mut single_component = true;
+        for name in variable_names(template) {
+            match component_settings(name, components) {
+                Some((component, component_priority, component_compact)) => {
+                    single_component &= owner.is_none_or(|owner| owner == component);
+                    owner = Some(component);
+                    compact = Some(component_compact);
+                    priority = priority.max(Some(component_priority));
+                }
+                None => {
+                    single_component = false;
+                    priority = priority.max(Some(DEFAULT_PRIORITY));
+                }
+            }
+        }
+
+        Segment {
+            template,
+            right,
+            priority: priority.unwrap_or(DEFAULT_PRIORITY),
+            compact: compact.filter(|compact| single_component && !compact.is_empty()),
+            state: SegmentState::Full,
+        }
+    }
+}
+
+/// Names of the variables a template references, without filters
+pub(super) fn variable_names(template: &str) -> impl Iterator<Item = &str> {
+    template.split('{').skip(1).filter_map(|part| {
+        let inner = &part[..part.find('}')?];
+        let name = inner.split('|').next().unwrap_or_default().trim();
+        (!name.is_empty()).then_some(name)
+    })
+}
+
+/// The component a variable belongs to (`[layout.components.*]` name)
+pub(super) fn component_of(name: &str) -> Option<&'static str> {
+    match name {
+        "directory" | "dir_short" => Some("directory"),
+        _ if name == "git" || name.starts_with("git_") => Some("git"),
+        "context" | "context_pct" | "context_tokens" => Some("context"),
+        "cost" | "cost_short" | "burn_rate" | "daily_total" => Some("cost"),
+        "model" | "model_full" | "model_name" => Some("model"),
+        _ if name == "token_rate" || name.starts_with("token_") => Some("token_rate"),
+        _ => None,
+    }
+}
+
+/// The component a variable belongs to, with its `priority` and `compact` template
+fn component_settings<'c>(
+    name: &str,
+    components: &'c ComponentsConfig,
+) -> Option<(&'static str, u8, &'c str)> {
+    let component = component_of(name)?;
+    let (priority, compact) = match component {
+        "directory" => (components.directory.priority, &components.directory.compact),
+        "git" => (components.git.priority, &components.git.compact),
+        "context" => (components.context.priority, &components.context.compact),
+        "cost" => (components.cost.priority, &components.cost.compact),
+        "model" => (components.model.priority, &components.model.compact),
+        _ => (
+            components.token_rate.priority,
+            &components.token_rate.compact,
+        ),
+    };
+    Some((component, priority, compact.as_str()))
+}
+
+/// The format line of one group for the segments in their current state
+fn group_template(segments: &[Segment], right: bool) -> String {
+    segments
+        .iter()
+        .filter(|segment| segment.right == right)
+        .filter_map(|segment| match segment.state {
+            SegmentState::Full => Some(segment.template),
+            SegmentState::Compact => segment.compact,
+            SegmentState::Dropped => None,
+        })
+        .collect::<Vec<_>>()
+        .join("{sep}")
+}
+
+/// A rendered line: its left group and its right-aligned group
+struct Groups {
+    left: String,
+    right: String,
+}
+
+impl Groups {
+    fn render(segments: &[Segment], render: &impl Fn(&str, bool) -> String) -> Self {
+        let right = group_template(segments, true);
+        Groups {
+            left: render(&group_template(segments, false), false),
+            right: if right.is_empty() {
+                String::new()
+            } else {
+                render(&right, true)
+            },
+        }
+    }
+
+    /// Columns needed, with at least one column of fill between the groups
+    fn width(&self) -> usize {
+        let gap = usize::from(!self.left.is_empty() && !self.right.is_empty());
+        visible_width(&self.left) + gap + visible_width(&self.right)
+    }
+
+    /// The line, with the right group aligned against `width` columns
+    fn align(&self, width: usize, fill: &str) -> String {
+        if self.right.is_empty() {
+            return self.left.clone();
+        }
+        let gap = width.saturating_sub(self.width()) + usize::from(!self.left.is_empty());
+        format!("{}{}{}", self.left, fill_columns(fill, gap), self.right)
+    }
+}
+
+/// `columns` columns of `fill`, topped up with spaces when it is wide
+fn fill_columns(fill: &str, columns: usize) -> String {
+    let fill_width = fill.width();
+    if fill_width == 0 {
+        return " ".repeat(columns);
+    }
+    fill.repeat(columns / fill_width) + &" ".repeat(columns % fill_width)
+}
+
+/// Render one format line within `width` terminal columns
+///
+/// `render` turns the format line of a group into its output, told whether it
+/// is the right-aligned group; the line is rendered again after each segment
+/// collapses or drops, until it fits.
+pub(super) fn fit_line(
+    line: &str,
+    width: usize,
+    fill: &str,
+    components: &ComponentsConfig,
+    render: impl Fn(&str, bool) -> String,
+) -> String {
+    let (left, right) = line.split_once("{fill}").unwrap_or((line, ""));
+    let mut segments: Vec<Segment> = left
+        .split("{sep}")
+        .map(|template| Segment::new(template, false, components))
+        .chain(
+            (!right.is_empty())
+                .then(|| right.split("{sep}"))
+                .into_iter()
+                .flatten()
+                .map(|template| Segment::new(template, true, components)),
+        )
+        .collect();
+
+    let mut rendered = Groups::render(&segments, &render);
+    if rendered.width() <= width {
+        return rendered.align(width, fill);
+    }
+
+    let mut order: Vec<usize> = (0..segments.len()).collect();
+    order.sort_by_key(|&i| (segments[i].priority, Reverse(i)));
+
+    for i in order {
+        let mut attempts = vec![SegmentState::Dropped];
+        if segments[i].compact.is_some() {
+            attempts.insert(0, SegmentState::Compact);
+        }
+        for state in attempts {
+            let previous = segments[i].state;
+            segments[i].state = state;
+            let candidate = Groups::render(&segments, &render);
+            // Never drop the line down to nothing
+            if candidate.width() == 0 {
+                segments[i].state = previous;
+                continue;
+            }
+            rendered = candidate;
+            if rendered.width() <= width {
+                return rendered.align(width, fill);
+            }
+        }
+    }
+
+    truncate(&rendered.align(width, fill), width, ELLIPSIS)
+}
@@ -3,7 +3,10 @@
 //! This module provides template-based rendering of the statusline,
 //! allowing users to customize the format and order of components.
 
+mod filters;
+mod fit;
 mod format;
+mod powerline;
 mod presets;
 mod template;
 mod variables;
@@ -13,8 +16,11 @@ mod tests;
 
 // Re-exports: public API surface matches pre-split layout.rs
 // Note: allow(unused_imports) needed because these are used by lib consumers but not the binary target
+#[allow(unused_imports)] // Used by the binary's report commands
+pub(crate) use format::format_token_count;
+pub use powerline::Powerline;
 #[allow(unused_imports)]
-pub use presets::{get_preset_format, list_available_presets};
+pub use presets::{get_preset_format, layout_format, list_available_presets};
 #[allow(unused_imports)]
 pub use presets::{PRESET_COMPACT, PRESET_DEFAULT, PRESET_DETAILED, PRESET_MINIMAL, PRESET_POWER};
 pub use template::LayoutRenderer;
@@ -0,0 +1,190 @@
+//! Powerline rendering of layout lines.
+//!
+//! Each `{sep}`-delimited segment becomes a block of text on the background of
+//! the component its variables belong to (the theme's `*_bg` colors). Between
+//! two blocks a transition glyph is drawn in the color of the block before it,
+//! on the background of the block after it, so the blocks appear to flow into
+//! each other; neighbours sharing a background get a thin glyph instead.
+//! Segments that render empty are left out before the transitions are worked
+//! out. The right-aligned group of a line (after `{fill}`) uses left-pointing
+//! glyphs.
+
+use super::filters::visible_text;
+use super::fit::{component_of, variable_names};
+use crate::theme::{Theme, ThemeColors};
+
+/// Transition glyphs of a powerline style
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Glyphs {
+    /// Between blocks of the left group, and after its last block
+    right: &'static str,
+    /// Between left-group blocks sharing a background
+    right_thin: &'static str,
+    /// Between blocks of the right-aligned group, and before its first block
+    left: &'static str,
+    /// Between right-group blocks sharing a background
+    left_thin: &'static str,
+}
+
+const ARROW: Glyphs = Glyphs {
+    right: "\u{e0b0}",
+    right_thin: "\u{e0b1}",
+    left: "\u{e0b2}",
+    left_thin: "\u{e0b3}",
+};
+
+const ROUNDED: Glyphs = Glyphs {
+    right: "\u{e0b4}",
+    right_thin: "\u{e0b5}",
+    left: "\u{e0b6}",
+    left_thin: "\u{e0b7}",
+};
+
+const ASCII: Glyphs = Glyphs {
+    right: ">",
+    right_thin: "|",
+    left: "<",
+    left_thin: "|",
+};
+
+/// A block color, as a background and as the foreground of transition glyphs
+#[derive(Debug, Clone, Default, PartialEq)]
+struct BlockColor {
+    bg: String,
+    fg: String,
+}
+
+/// Glyphs and resolved theme colors of powerline mode
+#[derive(Debug, Clone)]
+pub struct Powerline {
+    glyphs: Glyphs,
+    /// Text color on every block
+    text: String,
+    reset: String,
+    directory: BlockColor,
+    git: BlockColor,
+    context: BlockColor,
+    model: BlockColor,
+    cost: BlockColor,
+    token_rate: BlockColor,
+    segment: BlockColor,
+}
+
+impl Powerline {
+    /// Powerline mode in `style` ("arrow", "rounded" or "ascii") with the
+    /// theme's block colors; without a theme blocks are plain text joined by
+    /// thin glyphs.
+    pub fn new(style: &str, theme: Option<&Theme>) -> Self {
+        let glyphs = match style.to_lowercase().as_str() {
+            "arrow" => ARROW,
+            "rounded" => ROUNDED,
+            "ascii" => ASCII,
+            _ => {
+                log::warn!("Unknown powerline style '{}', using arrow", style);
+                ARROW
+            }
+        };
+        let color = |name: fn(&ThemeColors) -> &String| {
+            theme.map_or_else(BlockColor::default, |theme| BlockColor {
+                bg: theme.resolve_background(name(&theme.colors)),
+                fg: theme.resolve_color(name(&theme.colors)),
+            })
+        };
+        Powerline {
+            glyphs,
+            text: theme.map_or_else(String::new, |theme| {
+                theme.resolve_color(&theme.colors.powerline_fg)
+            }),
+            reset: theme.map_or_else(String::new, |_| "\x1b[0m".to_string()),
+            directory: color(|colors| &colors.directory_bg),
+            git: color(|colors| &colors.git_bg),
+            context: color(|colors| &colors.context_bg),
+            model: color(|colors| &colors.model_bg),
+            cost: color(|colors| &colors.cost_bg),
+            token_rate: color(|colors| &colors.token_rate_bg),
+            segment: color(|colors| &colors.segment_bg),
+        }
+    }
+
+    /// The block color of a segment: that of the first component it shows
+    fn color(&self, template: &str) -> &BlockColor {
+        match variable_names(template).find_map(component_of) {
+            Some("directory") => &self.directory,
+            Some("git") => &self.git,
+            Some("context") => &self.context,
+            Some("model") => &self.model,
+            Some("cost") => &self.cost,
+            Some("token_rate") => &self.token_rate,
+            _ => &self.segment,
+        }
+    }
+
+    /// Render the format line of a group as blocks
+    ///
+    /// `render` turns one segment (a format line without `{sep}`) into its
+    /// output; its colors are replaced by the block colors. `right` renders the
+    /// right-aligned group, with left-pointing glyphs.
+    pub(super) fn render_group(
+        &self,
+        template: &str,
+        right: bool,
+        render: impl Fn(&str) -> String,
+    ) -> String {
+        let blocks: Vec<(&BlockColor, String)> = template
+            .split("{sep}")
+            .filter_map(|segment| {
+                let text = visible_text(&render(segment)).trim().to_string();
+                (!text.is_empty()).then(|| (self.color(segment), text))
+            })
+            .collect();
+        if blocks.is_empty() {
+            return String::new();
+        }
+
+        let colored = !self.reset.is_empty();
+        let mut out = String::new();
+        let mut previous: Option<&BlockColor> = None;
+        for (color, text) in &blocks {
+            match (previous, right) {
+                // Neighbours on one background are told apart by a thin glyph
+                (Some(previous), _) if previous == *color => {
+                    out.push_str(&self.text);
+                    out.push_str(if right {
+                        self.glyphs.left_thin
+                    } else {
+                        self.glyphs.right_thin
+                    });
+                }
+                (None, false) => out.push_str(&color.bg),
+                (Some(previous), false) => {
+                    out.push_str(&previous.fg);
+                    out.push_str(&color.bg);
+                    out.push_str(self.glyphs.right);
+                }
+                // The right group also opens with a glyph, on the terminal background
+                (_, true) => {
+                    if previous.is_some() || colored {
+                        out.push_str(&color.fg);
+                        out.push_str(self.glyphs.left);
+                    }
+                    out.push_str(&color.bg);
+                }
+            }
+            out.push_str(&self.text);
+            out.push(' ');
+            out.push_str(text);
+            out.push(' ');
+            previous = Some(color);
+        }
+
+        if colored {
+            out.push_str(&self.reset);
+            if let (Some(last), false) = (previous, right) {
+                out.push_str(&last.fg);
+                out.push_str(self.glyphs.right);
+                out.push_str(&self.reset);
+            }
+        }
+        out
+    }
+}
@@ -1,5 +1,7 @@
 //! Preset layout definitions and user preset loading.
 
+use crate::config::LayoutConfig;
+
 /// Built-in layout presets
 pub const PRESET_DEFAULT: &str = "{directory}{sep}{git}{sep}{context}{sep}{model}{sep}{cost}";
 pub const PRESET_COMPACT: &str = "{dir_short} {git_branch} {model} {cost_short}";
@@ -30,6 +32,18 @@ pub fn get_preset_format(preset: &str) -> String {
     }
 }
 
+/// The format string `[layout]` renders, or an empty string when it leaves the
+/// built-in statusline in place
+pub fn layout_format(config: &LayoutConfig) -> String {
+    if !config.format.is_empty() {
+        config.format.clone()
+    } else if config.is_custom() {
+        get_preset_format(&config.preset)
+    } else {
+        String::new()
+    }
+}
+
 /// Load a user-defined preset from the config directory
 fn load_user_preset(name: &str) -> Option<String> {
     let preset_dir = dirs::config_dir()?
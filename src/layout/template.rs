@@ -1,14 +1,23 @@
 //! Template parsing and rendering for the layout engine.
 //!
 //! Supports two rendering paths:
-//! - `render()`: Legacy string-replacement approach (backward compatible)
-//! - `render_template()`: AST-based conditional template engine with {if}/{else}/{endif}
+//! - `render()`: Legacy string-replacement approach (backward compatible), after
+//!   resolving any {if}/{elif}/{else}/{endif} conditionals of the format
+//! - `render_template()`: AST-based conditional template engine with {if}/{elif}/{else}/{endif}
+//!
+//! Both support pipe filters on variables (`{dir_short|upper}`); see `filters`.
 
+use std::borrow::Cow;
 use std::collections::HashMap;
 
+use super::filters::{
+    apply_filters, closing_brace, first_number, parse_variable, visible_text, Filter,
+};
+use super::fit::fit_line;
 use super::format::clean_separators;
+use super::powerline::Powerline;
 use super::presets::get_preset_format;
-use crate::config::LayoutConfig;
+use crate::config::{ComponentsConfig, LayoutConfig};
 use crate::utils::sanitize_for_terminal;
 
 // ---------------------------------------------------------------------------
@@ -20,9 +29,14 @@ use crate::utils::sanitize_for_terminal;
 enum TemplateNode {
     /// Literal text to output as-is.
     Literal(String),
-    /// Variable substitution: {var_name}
-    Variable(String),
-    /// Conditional block: {if condition}...{else}...{endif}
+    /// Variable substitution: {var_name}, with any pipe filters: {var_name|upper}
+    Variable {
+        name: String,
+        filters: Vec<Filter>,
+        /// The placeholder as written, for [`resolve_conditionals`]
+        placeholder: String,
+    },
+    /// Conditional block: {if condition}...{else}...{endif} ({elif} nests in the else-branch)
     Conditional {
         condition: Condition,
         if_branch: Vec<TemplateNode>,
@@ -31,16 +45,42 @@ enum TemplateNode {
 }
 
 /// A condition expression for conditional template blocks.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 enum Condition {
     /// Truthiness: non-empty string = true
     Truthy(String),
-    /// Negation: {if !var}
-    Negated(String),
     /// Equality: {if var == value}
     Equals(String, String),
     /// Inequality: {if var != value}
     NotEquals(String, String),
+    /// Numeric comparison: {if var >= 80}, using the first number in the value
+    Compare(String, CompareOp, f64),
+    /// Negation: {if !var}, {if !(a && b)}
+    Not(Box<Condition>),
+    /// Conjunction: {if a && b}
+    And(Box<Condition>, Box<Condition>),
+    /// Disjunction: {if a || b}
+    Or(Box<Condition>, Box<Condition>),
+}
+
+/// Operator of a numeric comparison.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CompareOp {
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+}
+
+impl CompareOp {
+    fn holds(self, left: f64, right: f64) -> bool {
+        match self {
+            CompareOp::Less => left < right,
+            CompareOp::LessEqual => left <= right,
+            CompareOp::Greater => left > right,
+            CompareOp::GreaterEqual => left >= right,
+        }
+    }
 }
 
 /// Maximum nesting depth for conditional blocks.
@@ -49,6 +89,8 @@ const MAX_NESTING_DEPTH: usize = 10;
 /// Terminator found when parsing a branch inside a conditional.
 #[derive(Debug, PartialEq)]
 enum BranchTerminator {
+    /// Hit {elif condition}
+    Elif(Condition),
     /// Hit {else}
     Else,
     /// Hit {endif}
@@ -65,14 +107,15 @@ enum BranchTerminator {
 ///
 /// Handles:
 /// - `{{` -> literal `{` (brace escaping)
-/// - `{if condition}...{else}...{endif}` with nesting
-/// - `{var_name}` variable references
+/// - `{if condition}...{elif condition}...{else}...{endif}` with nesting
+/// - `{var_name}` variable references, with `|filter` suffixes
 /// - Plain literal text
 fn parse_template(input: &str) -> Result<Vec<TemplateNode>, String> {
     let mut pos = 0;
     let (nodes, terminator) = parse_until_terminator(input, &mut pos, 0)?;
     match terminator {
         BranchTerminator::EndOfInput => Ok(nodes),
+        BranchTerminator::Elif(_) => Err("unexpected {elif} outside conditional".to_string()),
         BranchTerminator::Else => Err("unexpected {else} outside conditional".to_string()),
         BranchTerminator::EndIf => Err("unexpected {endif} outside conditional".to_string()),
     }
@@ -81,7 +124,7 @@ fn parse_template(input: &str) -> Result<Vec<TemplateNode>, String> {
 /// Parse template nodes until a terminator is found.
 ///
 /// Returns (nodes, terminator). At the top level, expects EndOfInput.
-/// Inside a conditional, expects Else or EndIf.
+/// Inside a conditional, expects Elif, Else or EndIf.
 fn parse_until_terminator(
     input: &str,
     pos: &mut usize,
@@ -101,7 +144,7 @@ fn parse_until_terminator(
             }
 
             // Try to find the closing }
-            if let Some(close_pos) = find_closing_brace(input, *pos) {
+            if let Some(close_pos) = closing_brace(&input[*pos..]).map(|close| *pos + close) {
                 let inner = &input[*pos + 1..close_pos];
 
                 // Check for {else} -- terminate this branch
@@ -116,14 +159,18 @@ fn parse_until_terminator(
                     return Ok((nodes, BranchTerminator::EndIf));
                 }
 
-                // Check for {if ...}
-                if inner.starts_with("if ") || inner.starts_with("if!") {
-                    let condition_str = if inner.starts_with("if!") {
-                        &inner[2..] // includes the ! prefix
-                    } else {
-                        &inner[3..] // skip "if "
-                    };
+                // Check for {elif ...} -- terminate this branch
+                if inner == "elif" {
+                    return Err("empty condition in {elif}".to_string());
+                }
+                if let Some(condition_str) = tag_condition(inner, "elif") {
+                    let condition = parse_condition(condition_str)?;
+                    *pos = close_pos + 1;
+                    return Ok((nodes, BranchTerminator::Elif(condition)));
+                }
 
+                // Check for {if ...}
+                if let Some(condition_str) = tag_condition(inner, "if") {
                     if depth >= MAX_NESTING_DEPTH {
                         return Err(format!(
                             "nesting depth exceeds maximum of {}",
@@ -131,43 +178,23 @@ fn parse_until_terminator(
                         ));
                     }
 
-                    let condition = parse_condition(condition_str.trim())?;
+                    let condition = parse_condition(condition_str)?;
                     *pos = close_pos + 1;
-
-                    // Parse the if-branch (stops at {else} or {endif})
-                    let (if_branch, terminator) = parse_until_terminator(input, pos, depth + 1)?;
-
-                    let else_branch = match terminator {
-                        BranchTerminator::Else => {
-                            // Parse the else-branch (stops at {endif})
-                            let (else_nodes, end_terminator) =
-                                parse_until_terminator(input, pos, depth + 1)?;
-                            match end_terminator {
-                                BranchTerminator::EndIf => else_nodes,
-                                BranchTerminator::Else => {
-                                    return Err("multiple {else} in single conditional".to_string());
-                                }
-                                BranchTerminator::EndOfInput => {
-                                    return Err("unclosed {if} block (missing {endif})".to_string());
-                                }
-                            }
-                        }
-                        BranchTerminator::EndIf => Vec::new(),
-                        BranchTerminator::EndOfInput => {
-                            return Err("unclosed {if} block (missing {endif})".to_string());
-                        }
-                    };
-
-                    nodes.push(TemplateNode::Conditional {
-                        condition,
-                        if_branch,
-                        else_branch,
-                    });
+                    nodes.extend(parse_conditional(input, pos, depth + 1, condition)?);
                     continue;
                 }
 
-                // It's a variable reference: {var_name}
-                nodes.push(TemplateNode::Variable(inner.to_string()));
+                // It's a variable reference: {var_name} or {var_name|filter...}
+                let (name, filters) = if inner.contains('|') {
+                    parse_variable(inner)?
+                } else {
+                    (inner.to_string(), Vec::new())
+                };
+                nodes.push(TemplateNode::Variable {
+                    name,
+                    filters,
+                    placeholder: input[*pos..=close_pos].to_string(),
+                });
                 *pos = close_pos + 1;
             } else {
                 // No closing brace found -- treat { as literal text
@@ -197,63 +224,250 @@ fn parse_until_terminator(
     Ok((nodes, BranchTerminator::EndOfInput))
 }
 
-/// Find the position of the closing `}` for a brace starting at `start`.
-fn find_closing_brace(input: &str, start: usize) -> Option<usize> {
-    let bytes = input.as_bytes();
-    let mut i = start + 1;
-    while i < bytes.len() {
-        if bytes[i] == b'}' {
-            return Some(i);
+/// Parse the branches of a conditional whose opening tag has been consumed.
+///
+/// An `{elif}` chain becomes conditionals nested in the else-branches, at the
+/// same depth, so `{if a}A{elif b}B{else}C{endif}` reads as
+/// `{if a}A{else}{if b}B{else}C{endif}{endif}`. Returns the outermost one.
+fn parse_conditional(
+    input: &str,
+    pos: &mut usize,
+    depth: usize,
+    condition: Condition,
+) -> Result<Vec<TemplateNode>, String> {
+    // Each branch stops at {elif}, {else} or {endif}
+    let mut branches = Vec::new();
+    let mut condition = condition;
+    let else_branch = loop {
+        let (nodes, terminator) = parse_until_terminator(input, pos, depth)?;
+        match terminator {
+            BranchTerminator::Elif(next) => {
+                branches.push((std::mem::replace(&mut condition, next), nodes));
+            }
+            BranchTerminator::Else => {
+                branches.push((condition, nodes));
+                // Parse the else-branch (stops at {endif})
+                let (else_nodes, end_terminator) = parse_until_terminator(input, pos, depth)?;
+                match end_terminator {
+                    BranchTerminator::EndIf => break else_nodes,
+                    BranchTerminator::Elif(_) => {
+                        return Err("{elif} after {else} in single conditional".to_string());
+                    }
+                    BranchTerminator::Else => {
+                        return Err("multiple {else} in single conditional".to_string());
+                    }
+                    BranchTerminator::EndOfInput => {
+                        return Err("unclosed {if} block (missing {endif})".to_string());
+                    }
+                }
+            }
+            BranchTerminator::EndIf => {
+                branches.push((condition, nodes));
+                break Vec::new();
+            }
+            BranchTerminator::EndOfInput => {
+                return Err("unclosed {if} block (missing {endif})".to_string());
+            }
         }
-        i += 1;
+    };
+
+    let mut node = else_branch;
+    while let Some((condition, if_branch)) = branches.pop() {
+        node = vec![TemplateNode::Conditional {
+            condition,
+            if_branch,
+            else_branch: node,
+        }];
     }
-    None
+    Ok(node)
+}
+
+/// The condition of a `{keyword condition}` tag: `{if var}`, `{if!var}`, `{elif(a || b)}`
+fn tag_condition<'a>(inner: &'a str, keyword: &str) -> Option<&'a str> {
+    let rest = inner.strip_prefix(keyword)?;
+    rest.starts_with([' ', '!', '(']).then_some(rest)
 }
 
-/// Parse a condition string from inside `{if ...}`.
+/// Parse a condition string from inside `{if ...}` or `{elif ...}`.
 ///
-/// Supports:
+/// Supports, loosest binding first:
+/// - `a || b` -> Or
+/// - `a && b` -> And
+/// - `!a` -> Not, `(a)` for grouping
+/// - `var == value` / `var != value` -> Equals / NotEquals (string comparison)
+/// - `var < n`, `<=`, `>`, `>=` -> Compare (numeric)
 /// - `var` -> Truthy(var)
-/// - `!var` -> Negated(var)
-/// - `var == value` -> Equals(var, value)
-/// - `var != value` -> NotEquals(var, value)
+///
+/// Values run to the next `&&`, `||` or closing parenthesis and may be
+/// double-quoted to contain those.
 fn parse_condition(s: &str) -> Result<Condition, String> {
     let s = s.trim();
     if s.is_empty() {
         return Err("empty condition in {if}".to_string());
     }
 
-    // Check for != (must check before == since both contain =)
-    if let Some(idx) = s.find("!=") {
-        let var = s[..idx].trim().to_string();
-        let val = s[idx + 2..].trim().to_string();
-        if var.is_empty() {
-            return Err("empty variable name in condition".to_string());
+    let mut parser = ConditionParser {
+        input: s,
+        pos: 0,
+        depth: 0,
+    };
+    let condition = parser.parse_or()?;
+    parser.skip_whitespace();
+    match parser.rest().chars().next() {
+        None => Ok(condition),
+        Some(')') => Err("unexpected ')' in condition".to_string()),
+        Some(_) => Err(format!("unexpected '{}' in condition", parser.rest())),
+    }
+}
+
+/// Recursive-descent parser over a single condition string.
+struct ConditionParser<'a> {
+    input: &'a str,
+    pos: usize,
+    /// Open parentheses, bounded by `MAX_NESTING_DEPTH`
+    depth: usize,
+}
+
+impl ConditionParser<'_> {
+    fn rest(&self) -> &str {
+        &self.input[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Consume `token` (after any whitespace) if it comes next.
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        if self.rest().starts_with(token) {
+            self.pos += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Condition, String> {
+        let mut condition = self.parse_and()?;
+        while self.eat("||") {
+            let right = self.parse_and()?;
+            condition = Condition::Or(Box::new(condition), Box::new(right));
         }
-        return Ok(Condition::NotEquals(var, val));
+        Ok(condition)
     }
 
-    // Check for ==
-    if let Some(idx) = s.find("==") {
-        let var = s[..idx].trim().to_string();
-        let val = s[idx + 2..].trim().to_string();
-        if var.is_empty() {
-            return Err("empty variable name in condition".to_string());
+    fn parse_and(&mut self) -> Result<Condition, String> {
+        let mut condition = self.parse_unary()?;
+        while self.eat("&&") {
+            let right = self.parse_unary()?;
+            condition = Condition::And(Box::new(condition), Box::new(right));
         }
-        return Ok(Condition::Equals(var, val));
+        Ok(condition)
     }
 
-    // Check for negation: !var
-    if let Some(rest) = s.strip_prefix('!') {
-        let var = rest.trim().to_string();
-        if var.is_empty() {
-            return Err("empty variable name after ! in condition".to_string());
+    fn parse_unary(&mut self) -> Result<Condition, String> {
+        self.skip_whitespace();
+        if self.rest().starts_with('!') && !self.rest().starts_with("!=") {
+            self.pos += 1;
+            self.skip_whitespace();
+            if self.at_operand_end() {
+                return Err("empty variable name after ! in condition".to_string());
+            }
+            return Ok(Condition::Not(Box::new(self.parse_unary()?)));
+        }
+        if self.eat("(") {
+            if self.depth >= MAX_NESTING_DEPTH {
+                return Err(format!(
+                    "nesting depth exceeds maximum of {}",
+                    MAX_NESTING_DEPTH
+                ));
+            }
+            self.depth += 1;
+            let condition = self.parse_or()?;
+            if !self.eat(")") {
+                return Err("missing ')' in condition".to_string());
+            }
+            self.depth -= 1;
+            return Ok(condition);
         }
-        return Ok(Condition::Negated(var));
+        self.parse_comparison()
     }
 
-    // Simple truthiness: var
-    Ok(Condition::Truthy(s.to_string()))
+    /// Whether the next token ends an operand: end of input, `&&`, `||` or `)`.
+    fn at_operand_end(&self) -> bool {
+        let rest = self.rest();
+        rest.is_empty() || rest.starts_with("&&") || rest.starts_with("||") || rest.starts_with(')')
+    }
+
+    fn parse_comparison(&mut self) -> Result<Condition, String> {
+        let rest = self.rest();
+        let name_len = rest
+            .find(|c: char| c.is_whitespace() || "=!<>()&|".contains(c))
+            .unwrap_or(rest.len());
+        let name = rest[..name_len].to_string();
+        if name.is_empty() {
+            return if rest.starts_with(')') {
+                Err("unexpected ')' in condition".to_string())
+            } else {
+                Err("empty variable name in condition".to_string())
+            };
+        }
+        self.pos += name_len;
+
+        // Two-character operators first, since `<=` starts with `<`
+        let operators = ["==", "!=", "<=", ">=", "<", ">"];
+        let Some(op) = operators.into_iter().find(|op| self.eat(op)) else {
+            return Ok(Condition::Truthy(name));
+        };
+        let value = self.parse_value()?;
+        let compare_op = match op {
+            "==" => return Ok(Condition::Equals(name, value)),
+            "!=" => return Ok(Condition::NotEquals(name, value)),
+            "<" => CompareOp::Less,
+            "<=" => CompareOp::LessEqual,
+            ">" => CompareOp::Greater,
+            _ => CompareOp::GreaterEqual,
+        };
+        match value.parse::<f64>() {
+            Ok(number) if number.is_finite() => Ok(Condition::Compare(name, compare_op, number)),
+            _ => Err(format!(
+                "expected a number after '{} {}' in condition, got '{}'",
+                name, op, value
+            )),
+        }
+    }
+
+    /// The value after a comparison operator: a double-quoted string, or the
+    /// trimmed text up to the next `&&`, `||` or (inside parentheses) `)`.
+    fn parse_value(&mut self) -> Result<String, String> {
+        self.skip_whitespace();
+        if let Some(quoted) = self.rest().strip_prefix('"') {
+            let Some(end) = quoted.find('"') else {
+                return Err("unterminated quote in condition".to_string());
+            };
+            let value = quoted[..end].to_string();
+            self.pos += end + 2;
+            return Ok(value);
+        }
+
+        let rest = self.rest();
+        let mut end = rest.len();
+        for (i, _) in rest.char_indices() {
+            let tail = &rest[i..];
+            if tail.starts_with("&&")
+                || tail.starts_with("||")
+                || (self.depth > 0 && tail.starts_with(')'))
+            {
+                end = i;
+                break;
+            }
+        }
+        let value = rest[..end].trim().to_string();
+        self.pos += end;
+        Ok(value)
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -269,13 +483,16 @@ fn evaluate(nodes: &[TemplateNode], vars: &HashMap<String, String>, show_unknown
     for node in nodes {
         match node {
             TemplateNode::Literal(text) => result.push_str(text),
-            TemplateNode::Variable(name) => {
+            TemplateNode::Variable { name, filters, .. } => {
                 if let Some(value) = vars.get(name.as_str()) {
-                    result.push_str(value);
-                } else if show_unknown {
+                    result.push_str(&apply_filters(value, filters));
+                } else if show_unknown && !filters.iter().any(|f| matches!(f, Filter::Default(_))) {
                     result.push('{');
                     result.push_str(name);
                     result.push('}');
+                } else if !filters.is_empty() {
+                    // Filters see unknown variables as empty, so `default` applies
+                    result.push_str(&apply_filters("", filters));
                 }
                 // else: unknown variable with show_unknown=false -> append nothing
             }
@@ -299,9 +516,43 @@ fn evaluate(nodes: &[TemplateNode], vars: &HashMap<String, String>, show_unknown
 fn eval_condition(condition: &Condition, vars: &HashMap<String, String>) -> bool {
     match condition {
         Condition::Truthy(var) => vars.get(var.as_str()).is_some_and(|v| !v.is_empty()),
-        Condition::Negated(var) => vars.get(var.as_str()).is_none_or(|v| v.is_empty()),
         Condition::Equals(var, value) => vars.get(var.as_str()) == Some(value),
         Condition::NotEquals(var, value) => vars.get(var.as_str()) != Some(value),
+        // Missing or non-numeric values ("--", "") satisfy no comparison
+        Condition::Compare(var, op, number) => vars
+            .get(var.as_str())
+            .and_then(|v| first_number(v))
+            .is_some_and(|value| op.holds(value, *number)),
+        Condition::Not(inner) => !eval_condition(inner, vars),
+        Condition::And(left, right) => eval_condition(left, vars) && eval_condition(right, vars),
+        Condition::Or(left, right) => eval_condition(left, vars) || eval_condition(right, vars),
+    }
+}
+
+/// Write the nodes back as template text, keeping only the branches whose
+/// conditions hold; variables are left as placeholders.
+///
+/// This lets the `{sep}`-based render paths of a layout `format` (segment
+/// fitting, powerline) use conditionals. Conditions see the visible text of
+/// the values, since values in those paths carry ANSI colors.
+fn resolve_conditionals(nodes: &[TemplateNode], vars: &HashMap<String, String>, out: &mut String) {
+    for node in nodes {
+        match node {
+            TemplateNode::Literal(text) => out.push_str(text),
+            TemplateNode::Variable { placeholder, .. } => out.push_str(placeholder),
+            TemplateNode::Conditional {
+                condition,
+                if_branch,
+                else_branch,
+            } => {
+                let branch = if eval_condition(condition, vars) {
+                    if_branch
+                } else {
+                    else_branch
+                };
+                resolve_conditionals(branch, vars, out);
+            }
+        }
     }
 }
 
@@ -333,7 +584,7 @@ fn load_user_template() -> Option<String> {
 ///
 /// Supports two rendering modes:
 /// - `render()`: Legacy string-replacement (backward compatible, strips unknown vars)
-/// - `render_template()`: AST-based with conditional support ({if}/{else}/{endif})
+/// - `render_template()`: AST-based with conditional support ({if}/{elif}/{else}/{endif})
 pub struct LayoutRenderer {
     /// The format template string
     pub(super) template: String,
@@ -342,8 +593,13 @@ pub struct LayoutRenderer {
     /// Pre-parsed AST for template rendering (None if parse failed)
     ast: Option<Vec<TemplateNode>>,
     /// Parse error message, if AST parsing failed
-    #[allow(dead_code)]
     parse_error: Option<String>,
+    /// Segment priorities and compact forms used by `render_to_width`
+    components: ComponentsConfig,
+    /// Padding before a line's right-aligned group (after `{fill}`)
+    fill: String,
+    /// Render segments as powerline blocks instead of joining them with the separator
+    powerline: Option<Powerline>,
 }
 
 impl LayoutRenderer {
@@ -356,7 +612,11 @@ impl LayoutRenderer {
         };
 
         let separator = config.separator.clone();
-        Self::new_with_ast(template, separator)
+        Self {
+            components: config.components.clone(),
+            fill: config.fill.clone(),
+            ..Self::new_with_ast(template, separator)
+        }
     }
 
     /// Create a renderer using the conditional default template.
@@ -393,6 +653,32 @@ impl LayoutRenderer {
             separator,
             ast,
             parse_error,
+            components: ComponentsConfig::default(),
+            fill: " ".to_string(),
+            powerline: None,
+        }
+    }
+
+    /// Render segments as powerline blocks (`[layout] powerline`)
+    pub fn with_powerline(self, powerline: Powerline) -> Self {
+        Self {
+            powerline: Some(powerline),
+            ..self
+        }
+    }
+
+    /// Render the format line of a group, as powerline blocks when enabled
+    fn render_group(
+        &self,
+        template: &str,
+        right: bool,
+        variables: &HashMap<String, String>,
+    ) -> String {
+        match &self.powerline {
+            Some(powerline) => powerline.render_group(template, right, |segment| {
+                render_format(segment, &self.separator, variables)
+            }),
+            None => render_format(template, &self.separator, variables),
         }
     }
 
@@ -403,34 +689,80 @@ impl LayoutRenderer {
     /// - Value: the rendered component string (with colors)
     ///
     /// Unknown variables are replaced with empty string.
-    /// The {sep} variable is replaced with the configured separator.
+    /// The {sep} variable is replaced with the configured separator, as is
+    /// `{fill}`, which only aligns when a width is known (`render_to_width`).
+    /// `{var|filter}` placeholders get their filters applied; a placeholder whose
+    /// filters do not parse is dropped like an unknown variable.
     ///
-    /// This method preserves exact backward compatibility with the pre-conditional
-    /// template engine. For conditional template support, use `render_template()`.
+    /// In powerline mode each line is rendered as blocks instead.
+    ///
+    /// Conditionals are resolved first (see [`resolve_conditionals`]); a format
+    /// without them renders exactly as in the pre-conditional template engine.
     pub fn render(&self, variables: &HashMap<String, String>) -> String {
-        let mut result = self.template.clone();
-
-        // Sanitize separator (user-provided, could contain control characters)
-        // but preserve valid ANSI colors in template output
-        let safe_separator = sanitize_for_terminal(&self.separator);
-
-        // Replace {sep} with sanitized separator
-        result = result.replace("{sep}", &safe_separator);
-
-        // Replace all variables
-        for (key, value) in variables {
-            let placeholder = format!("{{{}}}", key);
-            result = result.replace(&placeholder, value);
+        let template = self.resolved_template(variables).replace("{fill}", "{sep}");
+        if self.powerline.is_none() {
+            return render_format(&template, &self.separator, variables);
         }
+        template
+            .split('\n')
+            .map(|line| self.render_group(line, false, variables))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
 
-        // Remove any unreplaced variables (unknown or empty)
-        result = remove_unreplaced_variables(&result);
-
-        // Clean up multiple separators (when components are empty)
-        // Use same sanitized separator for consistent matching
-        result = clean_separators(&result, &safe_separator);
+    /// Render like [`render`](Self::render), fitting each line into `width` columns.
+    ///
+    /// Lines that are too wide collapse and then drop their `{sep}`-delimited
+    /// segments, lowest component priority first, and are cut with `…` as a
+    /// last resort. The part of a line after `{fill}` is right-aligned, padded
+    /// with the configured fill. `None` renders every segment, as `render` does.
+    pub fn render_to_width(
+        &self,
+        variables: &HashMap<String, String>,
+        width: Option<usize>,
+    ) -> String {
+        let Some(width) = width else {
+            return self.render(variables);
+        };
+        let fill = sanitize_for_terminal(&self.fill);
+        self.resolved_template(variables)
+            .split('\n')
+            .map(|line| {
+                fit_line(line, width, &fill, &self.components, |group, right| {
+                    self.render_group(group, right, variables)
+                })
+            })
+            .filter(|line| !line.trim().is_empty())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
 
-        result
+    /// The format with the branches of its conditionals chosen for `variables`
+    ///
+    /// A format that fails to parse is used as written, after logging why.
+    fn resolved_template(&self, variables: &HashMap<String, String>) -> Cow<'_, str> {
+        match &self.ast {
+            Some(nodes)
+                if nodes
+                    .iter()
+                    .any(|node| matches!(node, TemplateNode::Conditional { .. })) =>
+            {
+                let visible = variables
+                    .iter()
+                    .map(|(name, value)| (name.clone(), visible_text(value)))
+                    .collect();
+                let mut template = String::with_capacity(self.template.len());
+                resolve_conditionals(nodes, &visible, &mut template);
+                Cow::Owned(template)
+            }
+            Some(_) => Cow::Borrowed(&self.template),
+            None => {
+                if let Some(err) = &self.parse_error {
+                    log::warn!("Template error: {}", err);
+                }
+                Cow::Borrowed(&self.template)
+            }
+        }
     }
 
     /// Render using the conditional template engine (AST-based).
@@ -481,15 +813,27 @@ impl LayoutRenderer {
                 let result = evaluate(nodes, &sanitized, show_unknown);
                 clean_separators(&result, &safe_separator)
             }
-            None => "[tmpl err]".to_string(),
+            None => {
+                if let Some(err) = &self.parse_error {
+                    log::warn!("Template error: {}", err);
+                }
+                "[tmpl err]".to_string()
+            }
         }
     }
 
+    /// Why the template failed to parse, if it did
+    #[allow(dead_code)]
+    pub fn parse_error(&self) -> Option<&str> {
+        self.parse_error.as_deref()
+    }
+
     /// Check if the template uses a specific variable
     #[allow(dead_code)]
     pub fn uses_variable(&self, name: &str) -> bool {
         let placeholder = format!("{{{}}}", name);
-        self.template.contains(&placeholder)
+        let filtered = format!("{{{}|", name);
+        self.template.contains(&placeholder) || self.template.contains(&filtered)
     }
 
     /// Get list of variables used in the template
@@ -503,8 +847,10 @@ impl LayoutRenderer {
                 let mut var_name = String::new();
                 for c in chars.by_ref() {
                     if c == '}' {
-                        if !var_name.is_empty() && var_name != "sep" {
-                            variables.push(var_name);
+                        // Filters are not part of the name: {dir_short|upper}
+                        let name = var_name.split('|').next().unwrap_or_default().trim();
+                        if !name.is_empty() && name != "sep" && name != "fill" {
+                            variables.push(name.to_string());
                         }
                         break;
                     }
@@ -517,6 +863,62 @@ impl LayoutRenderer {
     }
 }
 
+/// Legacy string-replacement render of a format string (see [`LayoutRenderer::render`])
+fn render_format(template: &str, separator: &str, variables: &HashMap<String, String>) -> String {
+    // Filtered placeholders first, so filters see the values before any
+    // plain substitution (and a value can never become a filter expression)
+    let mut result = substitute_filtered_variables(template, variables);
+
+    // Sanitize separator (user-provided, could contain control characters)
+    // but preserve valid ANSI colors in template output
+    let safe_separator = sanitize_for_terminal(separator);
+
+    // Replace {sep} with sanitized separator
+    result = result.replace("{sep}", &safe_separator);
+
+    // Replace all variables
+    for (key, value) in variables {
+        let placeholder = format!("{{{}}}", key);
+        result = result.replace(&placeholder, value);
+    }
+
+    // Remove any unreplaced variables (unknown or empty)
+    result = remove_unreplaced_variables(&result);
+
+    // Clean up multiple separators (when components are empty)
+    // Use same sanitized separator for consistent matching
+    clean_separators(&result, &safe_separator)
+}
+
+/// Substitute the `{var|filter...}` placeholders of a template for the legacy
+/// render path, leaving plain `{var}` placeholders alone
+fn substitute_filtered_variables(template: &str, variables: &HashMap<String, String>) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        result.push_str(&rest[..open]);
+        let placeholder = &rest[open..];
+        match closing_brace(placeholder) {
+            Some(close) if placeholder[1..close].contains('|') => {
+                match parse_variable(&placeholder[1..close]) {
+                    Ok((name, filters)) => {
+                        let value = variables.get(&name).map_or("", String::as_str);
+                        result.push_str(&apply_filters(value, &filters));
+                    }
+                    Err(err) => log::warn!("Template error: {}", err),
+                }
+                rest = &placeholder[close + 1..];
+            }
+            _ => {
+                result.push('{');
+                rest = &placeholder[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
 /// Remove unreplaced {variable} placeholders from the string
 fn remove_unreplaced_variables(s: &str) -> String {
     let mut result = String::with_capacity(s.len());
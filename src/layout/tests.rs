@@ -5,8 +5,9 @@ use std::collections::HashMap;
 use super::*;
 use crate::config::{
     ContextComponentConfig, CostComponentConfig, DirectoryComponentConfig, GitComponentConfig,
-    LayoutConfig, ModelComponentConfig,
+    LayoutConfig, LayoutWidth, ModelComponentConfig,
 };
+use crate::theme::Theme;
 
 #[test]
 fn test_basic_substitution() {
@@ -236,6 +237,191 @@ fn test_variable_builder_lines_changed() {
     assert!(lines.contains("-50"));
 }
 
+#[test]
+fn test_variable_builder_claude_code_metadata() {
+    let vars = VariableBuilder::new()
+        .claude_code(
+            Some("1.0.80"),
+            Some("Explanatory"),
+            Some("claude-opus-4-1"),
+            "",
+            "",
+        )
+        .project("~/project", "project", "", "")
+        .api_timing(Some("45s"), Some("2s"), Some(5.1), "", "")
+        .exceeds_200k(true, "", "")
+        .build();
+
+    assert_eq!(vars.get("claude_version"), Some(&"v1.0.80".to_string()));
+    assert_eq!(vars.get("output_style"), Some(&"Explanatory".to_string()));
+    assert_eq!(vars.get("model_id"), Some(&"claude-opus-4-1".to_string()));
+    assert_eq!(vars.get("project_dir"), Some(&"~/project".to_string()));
+    assert_eq!(vars.get("project_name"), Some(&"project".to_string()));
+    assert_eq!(vars.get("wall_time"), Some(&"45s".to_string()));
+    assert_eq!(vars.get("api_time"), Some(&"2s".to_string()));
+    assert_eq!(vars.get("api_pct"), Some(&"5%".to_string()));
+    assert_eq!(vars.get("exceeds_200k"), Some(&">200k".to_string()));
+}
+
+#[test]
+fn test_variable_builder_claude_code_metadata_missing() {
+    let vars = VariableBuilder::new()
+        .claude_code(None, Some(""), None, "", "")
+        .api_timing(None, None, None, "", "")
+        .exceeds_200k(false, "", "")
+        .build();
+
+    assert!(vars.is_empty());
+}
+
+#[test]
+fn test_variable_builder_computed_cost() {
+    let vars = VariableBuilder::new()
+        .computed_cost(Some(1.234), Some(5.0), Some(0.0), Some(-18.4), "", "", "")
+        .build();
+
+    assert_eq!(vars.get("cost_computed"), Some(&"$1.23".to_string()));
+    assert_eq!(vars.get("cost_computed_today"), Some(&"$5.00".to_string()));
+    assert!(!vars.contains_key("cost_computed_month"));
+    assert_eq!(vars.get("cost_divergence"), Some(&"-18%".to_string()));
+
+    let vars = VariableBuilder::new()
+        .computed_cost(None, None, None, None, "", "", "")
+        .build();
+    assert!(vars.is_empty());
+}
+
+#[test]
+fn test_variable_builder_cache_saved() {
+    let vars = VariableBuilder::new()
+        .cache_saved(Some(2.5), Some(-0.4), "", "", "")
+        .build();
+
+    assert_eq!(vars.get("cache_saved"), Some(&"$2.50".to_string()));
+    assert_eq!(vars.get("cache_saved_today"), Some(&"-$0.40".to_string()));
+
+    let vars = VariableBuilder::new()
+        .cache_saved(None, None, "", "", "")
+        .build();
+    assert!(vars.is_empty());
+}
+
+#[test]
+fn test_variable_builder_budget() {
+    let vars = VariableBuilder::new()
+        .budget("monthly", 240.0, 300.0, "[==>]", "", "")
+        .build();
+
+    assert_eq!(vars.get("budget_remaining"), Some(&"$60.00".to_string()));
+    assert_eq!(vars.get("budget_pct"), Some(&"80%".to_string()));
+    assert_eq!(vars.get("budget_bar"), Some(&"[==>]".to_string()));
+    assert_eq!(vars.get("budget_limit"), Some(&"$300.00".to_string()));
+    assert_eq!(vars.get("budget_name"), Some(&"monthly".to_string()));
+
+    let vars = VariableBuilder::new()
+        .budget("", 12.5, 10.0, "[===]", "", "")
+        .build();
+    assert_eq!(vars.get("budget_remaining"), Some(&"-$2.50".to_string()));
+    assert_eq!(vars.get("budget_pct"), Some(&"125%".to_string()));
+    assert!(!vars.contains_key("budget_name"));
+}
+
+#[test]
+fn test_variable_builder_forecast() {
+    let vars = VariableBuilder::new()
+        .forecast(
+            Some(18.4),
+            Some(412.0),
+            Some(3_400_000),
+            None,
+            "",
+            "",
+            "",
+            "",
+        )
+        .build();
+
+    assert_eq!(vars.get("forecast_today"), Some(&"$18.40".to_string()));
+    assert_eq!(vars.get("forecast_month"), Some(&"$412.00".to_string()));
+    assert_eq!(vars.get("forecast_tokens_today"), Some(&"3.4M".to_string()));
+    assert!(!vars.contains_key("forecast_tokens_month"));
+}
+
+#[test]
+fn test_variable_builder_block() {
+    let vars = VariableBuilder::new()
+        .block(
+            "2h15m",
+            "2h45m",
+            3_400_000,
+            12.4,
+            Some((7_500_000, 27.35)),
+            Some("1h20m"),
+            "",
+            "",
+            "",
+            "",
+        )
+        .build();
+
+    assert_eq!(vars.get("block_elapsed"), Some(&"2h15m".to_string()));
+    assert_eq!(vars.get("block_remaining"), Some(&"2h45m".to_string()));
+    assert_eq!(vars.get("block_tokens"), Some(&"3.4M".to_string()));
+    assert_eq!(vars.get("block_cost"), Some(&"$12.40".to_string()));
+    assert_eq!(
+        vars.get("block_projected_tokens"),
+        Some(&"7.5M".to_string())
+    );
+    assert_eq!(
+        vars.get("block_projected_cost"),
+        Some(&"$27.35".to_string())
+    );
+    assert_eq!(vars.get("block_exhausts"), Some(&"1h20m".to_string()));
+
+    let vars = VariableBuilder::new()
+        .block("5m", "4h55m", 900, 0.05, None, None, "", "", "", "")
+        .build();
+    assert_eq!(vars.get("block_tokens"), Some(&"900".to_string()));
+    assert!(!vars.contains_key("block_projected_tokens"));
+    assert!(!vars.contains_key("block_exhausts"));
+}
+
+#[test]
+fn test_variable_builder_rate_limits() {
+    let vars = VariableBuilder::new()
+        .rate_limits(Some("1h56m"), Some("15:00"), Some(2), "<o>", "<r>", "</>")
+        .build();
+
+    assert_eq!(vars.get("limit_reset"), Some(&"<o>1h56m</>".to_string()));
+    assert_eq!(vars.get("limit_reset_at"), Some(&"<o>15:00</>".to_string()));
+    assert_eq!(vars.get("api_errors"), Some(&"<r>2</>".to_string()));
+
+    // Nothing to report leaves the variables unset
+    let vars = VariableBuilder::new()
+        .rate_limits(None, Some(""), Some(0), "", "", "")
+        .build();
+    assert!(!vars.contains_key("limit_reset"));
+    assert!(!vars.contains_key("limit_reset_at"));
+    assert!(!vars.contains_key("api_errors"));
+}
+
+#[test]
+fn test_variable_builder_extend_missing_keeps_existing() {
+    let mut provider_vars = HashMap::new();
+    provider_vars.insert("git_branch".to_string(), "raw-main".to_string());
+    provider_vars.insert("stats_cost".to_string(), "1.25".to_string());
+    provider_vars.insert("stats_burn_rate".to_string(), String::new());
+
+    let vars = VariableBuilder::new()
+        .set("git_branch", "colored-main".to_string())
+        .extend_missing(&provider_vars)
+        .build();
+
+    assert_eq!(vars.get("git_branch"), Some(&"colored-main".to_string()));
+    assert_eq!(vars.get("stats_cost"), Some(&"1.25".to_string()));
+    assert!(!vars.contains_key("stats_burn_rate"));
+}
+
 #[test]
 fn test_variable_builder_empty_values_ignored() {
     let vars = VariableBuilder::new()
@@ -268,6 +454,7 @@ fn test_directory_with_config_format_short() {
         format: "short".to_string(),
         max_length: 0,
         color: String::new(),
+        ..Default::default()
     };
     let vars = VariableBuilder::new()
         .directory_with_config(
@@ -289,6 +476,7 @@ fn test_directory_with_config_format_basename() {
         format: "basename".to_string(),
         max_length: 0,
         color: String::new(),
+        ..Default::default()
     };
     let vars = VariableBuilder::new()
         .directory_with_config(
@@ -310,6 +498,7 @@ fn test_directory_with_config_max_length() {
         format: "short".to_string(),
         max_length: 10,
         color: String::new(),
+        ..Default::default()
     };
     let vars = VariableBuilder::new()
         .directory_with_config(
@@ -334,6 +523,7 @@ fn test_directory_with_config_color_override() {
         format: "short".to_string(),
         max_length: 0,
         color: "red".to_string(),
+        ..Default::default()
     };
     let vars = VariableBuilder::new()
         .directory_with_config(
@@ -359,6 +549,7 @@ fn test_directory_with_config_utf8_truncation() {
         format: "short".to_string(),
         max_length: 8,
         color: String::new(),
+        ..Default::default()
     };
     let vars = VariableBuilder::new()
         .directory_with_config(
@@ -385,12 +576,14 @@ fn test_git_with_config_show_when_dirty_when_clean() {
         format: "full".to_string(),
         show_when: "dirty".to_string(),
         color: String::new(),
+        ..Default::default()
     };
     let vars = VariableBuilder::new()
         .git_with_config(
             "main",
             Some("main"),
             None,
+            None,
             false, // is_dirty = false
             "",
             "",
@@ -408,12 +601,14 @@ fn test_git_with_config_show_when_dirty_when_dirty() {
         format: "full".to_string(),
         show_when: "dirty".to_string(),
         color: String::new(),
+        ..Default::default()
     };
     let vars = VariableBuilder::new()
         .git_with_config(
             "main +2",
             Some("main"),
             Some("+2"),
+            None,
             true, // is_dirty = true
             "",
             "",
@@ -431,12 +626,14 @@ fn test_git_with_config_format_branch_only() {
         format: "branch".to_string(),
         show_when: "always".to_string(),
         color: String::new(),
+        ..Default::default()
     };
     let vars = VariableBuilder::new()
         .git_with_config(
             "main +2 ~1",
             Some("main"),
             Some("+2 ~1"),
+            None,
             true,
             "",
             "",
@@ -447,6 +644,42 @@ fn test_git_with_config_format_branch_only() {
     assert_eq!(vars.get("git"), Some(&"main".to_string()));
 }
 
+#[test]
+fn test_git_with_config_format_tracking() {
+    let mut config = GitComponentConfig {
+        format: "tracking".to_string(),
+        show_when: "always".to_string(),
+        color: String::new(),
+        ..Default::default()
+    };
+    let build = |config: &GitComponentConfig, tracking: Option<&str>| {
+        VariableBuilder::new()
+            .git_with_config(
+                "main +2 ~1",
+                Some("main"),
+                Some("+2 ~1"),
+                tracking,
+                true,
+                "",
+                "",
+                config,
+            )
+            .build()
+    };
+
+    let vars = build(&config, Some("↑2 ↓1 ≡1"));
+    assert_eq!(vars.get("git"), Some(&"main ↑2 ↓1 ≡1".to_string()));
+    // In sync: branch only, no trailing space
+    let vars = build(&config, None);
+    assert_eq!(vars.get("git"), Some(&"main".to_string()));
+
+    config.format = "full_tracking".to_string();
+    let vars = build(&config, Some("↑2"));
+    assert_eq!(vars.get("git"), Some(&"main +2 ~1 ↑2".to_string()));
+    let vars = build(&config, None);
+    assert_eq!(vars.get("git"), Some(&"main +2 ~1".to_string()));
+}
+
 // =============================================================================
 // P3 (issue #38): coverage for previously-untested VariableBuilder methods.
 // Pure string-map builders: deterministic, no DB/env, so NO #[serial] needed.
@@ -527,9 +760,19 @@ fn test_git_with_config_format_status() {
         format: "status".to_string(),
         show_when: "always".to_string(),
         color: String::new(),
+        ..Default::default()
     };
     let vars = VariableBuilder::new()
-        .git_with_config("main +2", Some("main"), Some("+2"), true, "", "", &config)
+        .git_with_config(
+            "main +2",
+            Some("main"),
+            Some("+2"),
+            None,
+            true,
+            "",
+            "",
+            &config,
+        )
         .build();
 
     // status format inserts the raw status_only string into "git".
@@ -558,6 +801,7 @@ fn test_token_rate_with_config_time_units() {
         show_session_total: false,
         show_daily_total: false,
         color: String::new(),
+        ..Default::default()
     };
     let minute_vars = VariableBuilder::new()
         .token_rate_with_config(rate, None, None, "", "", &minute_cfg)
@@ -583,6 +827,7 @@ fn test_token_rate_with_config_time_units() {
         show_session_total: false,
         show_daily_total: false,
         color: String::new(),
+        ..Default::default()
     };
     let hour_vars = VariableBuilder::new()
         .token_rate_with_config(rate, None, None, "", "", &hour_cfg)
@@ -608,6 +853,7 @@ fn test_model_with_config_format_full() {
     let config = ModelComponentConfig {
         format: "full".to_string(),
         color: String::new(),
+        ..Default::default()
     };
     let vars = VariableBuilder::new()
         .model_with_config(
@@ -630,6 +876,7 @@ fn test_model_with_config_format_version() {
     let config = ModelComponentConfig {
         format: "version".to_string(),
         color: String::new(),
+        ..Default::default()
     };
     let vars = VariableBuilder::new()
         .model_with_config(
@@ -657,6 +904,7 @@ fn test_model_with_config_format_name() {
     let config = ModelComponentConfig {
         format: "name".to_string(),
         color: String::new(),
+        ..Default::default()
     };
     let vars = VariableBuilder::new()
         .model_with_config("O4.5", "Claude Opus 4.5", "Opus", "4.5", "", "", &config)
@@ -672,6 +920,7 @@ fn test_cost_with_config_cost_only() {
     let config = CostComponentConfig {
         format: "cost_only".to_string(),
         color: String::new(),
+        ..Default::default()
     };
     let vars = VariableBuilder::new()
         .cost_with_config(Some(12.50), Some(3.25), Some(45.00), "", "", "", &config)
@@ -687,6 +936,7 @@ fn test_cost_with_config_rate_only() {
     let config = CostComponentConfig {
         format: "rate_only".to_string(),
         color: String::new(),
+        ..Default::default()
     };
     let vars = VariableBuilder::new()
         .cost_with_config(Some(12.50), Some(3.25), Some(45.00), "", "", "", &config)
@@ -702,6 +952,7 @@ fn test_cost_with_config_with_daily() {
     let config = CostComponentConfig {
         format: "with_daily".to_string(),
         color: String::new(),
+        ..Default::default()
     };
     let vars = VariableBuilder::new()
         .cost_with_config(Some(12.50), Some(3.25), Some(45.00), "", "", "", &config)
@@ -720,6 +971,7 @@ fn test_context_with_config_format_full() {
         format: "full".to_string(),
         bar_width: None,
         show_tokens: false,
+        ..Default::default()
     };
     let vars = VariableBuilder::new()
         .context_with_config("[=====>----]", Some(50), Some((100_000, 200_000)), &config)
@@ -738,6 +990,7 @@ fn test_context_with_config_format_bar() {
         format: "bar".to_string(),
         bar_width: None,
         show_tokens: true, // Should be ignored for bar format
+        ..Default::default()
     };
     let vars = VariableBuilder::new()
         .context_with_config("[=====>----]", Some(50), Some((100_000, 200_000)), &config)
@@ -754,6 +1007,7 @@ fn test_context_with_config_format_percent() {
         format: "percent".to_string(),
         bar_width: None,
         show_tokens: true, // Should be ignored for percent format
+        ..Default::default()
     };
     let vars = VariableBuilder::new()
         .context_with_config("[=====>----]", Some(75), Some((150_000, 200_000)), &config)
@@ -769,6 +1023,7 @@ fn test_context_with_config_format_tokens() {
         format: "tokens".to_string(),
         bar_width: None,
         show_tokens: true, // Should be ignored for tokens format
+        ..Default::default()
     };
     let vars = VariableBuilder::new()
         .context_with_config("[=====>----]", Some(50), Some((100_000, 200_000)), &config)
@@ -784,6 +1039,7 @@ fn test_context_with_config_full_with_tokens() {
         format: "full".to_string(),
         bar_width: None,
         show_tokens: true, // Enable tokens in full format
+        ..Default::default()
     };
     let vars = VariableBuilder::new()
         .context_with_config("[=====>----]", Some(50), Some((100_000, 200_000)), &config)
@@ -963,6 +1219,7 @@ fn test_token_rate_with_config_sets_all_variables() {
         show_session_total: true,
         show_daily_total: true,
         color: String::new(),
+        ..Default::default()
     };
 
     let vars = VariableBuilder::new()
@@ -1026,6 +1283,7 @@ fn test_token_rate_with_config_layout_integration() {
         show_session_total: true,
         show_daily_total: true,
         color: String::new(),
+        ..Default::default()
     };
 
     let vars = VariableBuilder::new()
@@ -1077,6 +1335,7 @@ fn test_token_rate_with_metrics_cache_metrics_disabled() {
         show_session_total: false,
         show_daily_total: false,
         color: String::new(),
+        ..Default::default()
     };
 
     // Cache metrics DISABLED
@@ -1140,6 +1399,7 @@ fn test_token_rate_with_metrics_cache_metrics_enabled() {
         show_session_total: false,
         show_daily_total: false,
         color: String::new(),
+        ..Default::default()
     };
 
     // Cache metrics ENABLED
@@ -1217,6 +1477,7 @@ fn test_token_rate_with_metrics_rate_display_options() {
         show_session_total: false,
         show_daily_total: false,
         color: String::new(),
+        ..Default::default()
     };
 
     // Test "output_only" (requires detailed mode)
@@ -2220,3 +2481,853 @@ fn test_template_sanitizes_provider_values() {
         out2
     );
 }
+
+// =========================================================================
+// Template filters ({var|filter:arg})
+// =========================================================================
+
+fn filter_vars() -> HashMap<String, String> {
+    let mut vars = HashMap::new();
+    vars.insert("dir_short".to_string(), "claudia-statusline".to_string());
+    vars.insert(
+        "git_branch".to_string(),
+        "feature/template-filters".to_string(),
+    );
+    vars.insert("stats_cost".to_string(), "$3.14159".to_string());
+    vars.insert("context_pct".to_string(), "7".to_string());
+    vars.insert("empty".to_string(), String::new());
+    vars
+}
+
+#[test]
+fn test_template_filter_case() {
+    let renderer = LayoutRenderer::with_format("{dir_short|upper} {git_branch|lower}", "");
+    assert_eq!(
+        renderer.render_template(&filter_vars(), false),
+        "CLAUDIA-STATUSLINE feature/template-filters"
+    );
+}
+
+#[test]
+fn test_template_filter_truncate() {
+    let vars = filter_vars();
+    let render =
+        |format: &str| LayoutRenderer::with_format(format, "").render_template(&vars, false);
+    assert_eq!(render("{git_branch|truncate:10}"), "feature/t…");
+    assert_eq!(render("{git_branch|truncate:10:...}"), "feature...");
+    assert_eq!(render("{git_branch|truncate:10:\"\"}"), "feature/te");
+    // A suffix wider than the width is cut too
+    assert_eq!(render("{git_branch|truncate:2:...}"), "..");
+    // Short values are left alone
+    assert_eq!(render("{context_pct|truncate:10}"), "7");
+}
+
+#[test]
+fn test_template_filter_fmt() {
+    let vars = filter_vars();
+    let render =
+        |format: &str| LayoutRenderer::with_format(format, "").render_template(&vars, false);
+    assert_eq!(render("{stats_cost|fmt:\"$%.2f\"}"), "$3.14");
+    assert_eq!(render("{stats_cost|fmt:%d}"), "3");
+    assert_eq!(render("{context_pct|fmt:\"%03d%%\"}"), "007%");
+    assert_eq!(render("{context_pct|fmt:%+.1f}"), "+7.0");
+    assert_eq!(render("[{dir_short|fmt:%-8.7s}]"), "[claudia ]");
+    // Values without a number are left as they are
+    assert_eq!(render("{git_branch|fmt:%d}"), "feature/template-filters");
+}
+
+#[test]
+fn test_template_filter_pad_and_default() {
+    let vars = filter_vars();
+    let render =
+        |format: &str| LayoutRenderer::with_format(format, "").render_template(&vars, false);
+    assert_eq!(render("[{context_pct|pad:3}]"), "[  7]");
+    assert_eq!(render("[{context_pct|pad:-3}]"), "[7  ]");
+    assert_eq!(render("[{dir_short|pad:3}]"), "[claudia-statusline]");
+    assert_eq!(render("{empty|default:\"-\"}"), "-");
+    assert_eq!(render("{missing|default:n/a}"), "n/a");
+    assert_eq!(render("{context_pct|default:-}"), "7");
+    // A default for an unknown variable wins over showing the placeholder
+    let renderer = LayoutRenderer::with_format("{missing|default:-} {other|upper}", "");
+    assert_eq!(renderer.render_template(&vars, true), "- {other}");
+}
+
+#[test]
+fn test_template_filter_quoted_arguments() {
+    let vars = filter_vars();
+    let render =
+        |format: &str| LayoutRenderer::with_format(format, "").render_template(&vars, false);
+    assert_eq!(render("{empty|default:\"a|b:c\"}"), "a|b:c");
+    assert_eq!(render("{empty|default:\"say \\\"hi\\\"\"}"), "say \"hi\"");
+    // A quoted closing brace does not end the placeholder, in either render path
+    assert_eq!(render("{empty|default:\"}\"}!"), "}!");
+    let renderer = LayoutRenderer::with_format("{empty|default:\"}\"}!", "");
+    assert_eq!(renderer.render(&vars), "}!");
+}
+
+#[test]
+fn test_template_filter_widths_are_clamped() {
+    let vars = filter_vars();
+    let render =
+        |format: &str| LayoutRenderer::with_format(format, "").render_template(&vars, false);
+    assert_eq!(render("{context_pct|pad:1000000}").len(), 512);
+    assert_eq!(
+        render("{context_pct|fmt:%99999999999999999999d}").len(),
+        512
+    );
+    assert_eq!(render("{context_pct|fmt:%.100000f}").len(), 514);
+    assert_eq!(render("{dir_short|truncate:100000}"), "claudia-statusline");
+}
+
+#[test]
+fn test_template_filter_chain() {
+    let renderer = LayoutRenderer::with_format(
+        "{git_branch|truncate:8|upper|pad:10}|{empty|default:x|upper}",
+        "",
+    );
+    assert_eq!(
+        renderer.render_template(&filter_vars(), false),
+        "  FEATURE…|X"
+    );
+}
+
+#[test]
+#[serial_test::serial]
+fn test_template_filter_color() {
+    crate::display::set_color_override(Some(true));
+    let vars = filter_vars();
+    let render =
+        |format: &str| LayoutRenderer::with_format(format, "").render_template(&vars, false);
+    assert_eq!(render("{context_pct|color:red}"), "\x1b[31m7\x1b[0m");
+    assert_eq!(
+        render("{context_pct|color:#FF0000}"),
+        "\x1b[38;2;255;0;0m7\x1b[0m"
+    );
+    // Empty values stay empty so conditionals and separators still collapse
+    assert_eq!(render("{empty|color:red}"), "");
+
+    crate::display::set_color_override(Some(false));
+    assert_eq!(render("{context_pct|color:red}"), "7");
+    crate::display::set_color_override(None);
+}
+
+#[test]
+fn test_template_filter_parse_errors() {
+    for (format, message) in [
+        (
+            "{dir_short|uper}",
+            "unknown filter 'uper' in {dir_short|uper}",
+        ),
+        (
+            "{dir_short|truncate}",
+            "filter 'truncate' takes 1 to 2 arguments, got 0",
+        ),
+        (
+            "{dir_short|truncate:abc}",
+            "truncate width 'abc' is not a positive number",
+        ),
+        (
+            "{dir_short|upper:1}",
+            "filter 'upper' takes no arguments, got 1",
+        ),
+        ("{dir_short|pad:wide}", "pad width 'wide' is not a number"),
+        ("{dir_short|color:bright}", "unknown color 'bright'"),
+        ("{dir_short|fmt:%q}", "unsupported conversion '%q'"),
+        ("{dir_short|fmt:\"%d %d\"}", "more than one conversion"),
+        ("{dir_short|fmt:plain}", "has no %s, %d or %f conversion"),
+        ("{dir_short|default:\"open}", "unterminated quote"),
+        ("{dir_short|}", "empty filter"),
+        ("{|upper}", "missing variable name"),
+    ] {
+        let renderer = LayoutRenderer::with_format(format, "");
+        assert_eq!(
+            renderer.render_template(&filter_vars(), false),
+            "[tmpl err]"
+        );
+        let error = renderer.parse_error().unwrap();
+        assert!(
+            error.contains(message),
+            "{}: expected {:?} in {:?}",
+            format,
+            message,
+            error
+        );
+    }
+}
+
+#[test]
+fn test_render_resolves_conditionals() {
+    let mut vars = HashMap::new();
+    vars.insert("model".to_string(), "\x1b[35mOpus\x1b[0m".to_string());
+    vars.insert("context_pct".to_string(), "\x1b[33m85\x1b[0m".to_string());
+    vars.insert("cost".to_string(), "$1.00".to_string());
+
+    let format = "{model}{if context_pct >= 90}{sep}FULL{elif context_pct >= 80}{sep}{context_pct|fmt:%d%%}{endif}{if !git_branch}{sep}no git{endif}{sep}{cost}";
+    let renderer = LayoutRenderer::with_format(format, " | ");
+    let expected = "\x1b[35mOpus\x1b[0m | \x1b[33m85%\x1b[0m | no git | $1.00";
+    assert_eq!(renderer.render(&vars), expected);
+    assert_eq!(renderer.render_to_width(&vars, Some(80)), expected);
+
+    // A condition that does not hold drops its text
+    let renderer = LayoutRenderer::with_format("{model}{if context_pct >= 90} HIGH{endif}", "");
+    assert_eq!(renderer.render(&vars), "\x1b[35mOpus\x1b[0m");
+}
+
+#[test]
+fn test_render_legacy_filters() {
+    // The legacy path sees colored values; filters keep the escape sequences
+    let renderer = LayoutRenderer::with_format(
+        "{directory|upper}{sep}{git_branch|truncate:6}{sep}{model|pad:6}{sep}{bogus|nope}{sep}{cost}",
+        " | ",
+    );
+    let mut vars = HashMap::new();
+    vars.insert("directory".to_string(), "\x1b[36m~/app\x1b[0m".to_string());
+    vars.insert(
+        "git_branch".to_string(),
+        "\x1b[32mfeature/x\x1b[0m".to_string(),
+    );
+    vars.insert("model".to_string(), "\x1b[35mS4.5\x1b[0m".to_string());
+    vars.insert("cost".to_string(), "$1.00".to_string());
+
+    assert_eq!(
+        renderer.render(&vars),
+        "\x1b[36m~/APP\x1b[0m | \x1b[32mfeatu…\x1b[0m |   \x1b[35mS4.5\x1b[0m | $1.00"
+    );
+}
+
+#[test]
+fn test_get_used_variables_with_filters() {
+    let renderer = LayoutRenderer::with_format("{dir_short|upper}{sep}{cost|fmt:%d}", " | ");
+    assert_eq!(
+        renderer.get_used_variables(),
+        vec!["dir_short".to_string(), "cost".to_string()]
+    );
+    assert!(renderer.uses_variable("dir_short"));
+    assert!(!renderer.uses_variable("dir"));
+}
+
+// =========================================================================
+// Condition expressions (comparisons, &&, ||, parentheses, {elif})
+// =========================================================================
+
+fn condition_vars(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+#[test]
+fn test_template_numeric_comparisons() {
+    let render = |format: &str, pct: &str| {
+        LayoutRenderer::with_format(format, "")
+            .render_template(&condition_vars(&[("context_pct", pct)]), false)
+    };
+
+    assert_eq!(render("{if context_pct >= 80}warn{endif}", "80"), "warn");
+    assert_eq!(render("{if context_pct >= 80}warn{endif}", "79.9"), "");
+    assert_eq!(render("{if context_pct > 80}warn{endif}", "80"), "");
+    assert_eq!(render("{if context_pct < 50}ok{endif}", "12"), "ok");
+    assert_eq!(render("{if context_pct <= 50}ok{endif}", "50"), "ok");
+    // Numbers are coerced from formatted values
+    assert_eq!(render("{if context_pct >= 80}warn{endif}", "85%"), "warn");
+    assert_eq!(
+        render("{if context_pct < 2.5}cheap{endif}", "$1.20"),
+        "cheap"
+    );
+    assert_eq!(render("{if context_pct < 0}neg{endif}", "-3"), "neg");
+    // Non-numeric and missing values satisfy no comparison
+    assert_eq!(
+        render("{if context_pct < 80}ok{else}none{endif}", "--"),
+        "none"
+    );
+    let renderer = LayoutRenderer::with_format("{if context_pct < 80}ok{endif}", "");
+    assert_eq!(renderer.render_template(&HashMap::new(), false), "");
+}
+
+#[test]
+fn test_template_boolean_operators() {
+    let renderer = LayoutRenderer::with_format("{if git_branch && git_dirty}*{endif}", "");
+    let vars = condition_vars(&[("git_branch", "main"), ("git_dirty", "1")]);
+    assert_eq!(renderer.render_template(&vars, false), "*");
+    let vars = condition_vars(&[("git_branch", "main")]);
+    assert_eq!(renderer.render_template(&vars, false), "");
+
+    let renderer = LayoutRenderer::with_format("{if a || b}any{endif}", "");
+    assert_eq!(
+        renderer.render_template(&condition_vars(&[("b", "x")]), false),
+        "any"
+    );
+    assert_eq!(renderer.render_template(&HashMap::new(), false), "");
+
+    // && binds tighter than ||
+    let renderer = LayoutRenderer::with_format("{if a || b && c}yes{endif}", "");
+    assert_eq!(
+        renderer.render_template(&condition_vars(&[("a", "1")]), false),
+        "yes"
+    );
+    assert_eq!(
+        renderer.render_template(&condition_vars(&[("b", "1")]), false),
+        ""
+    );
+
+    // Operators combine with comparisons
+    let renderer =
+        LayoutRenderer::with_format("{if model == Opus 4.1 && context_pct >= 80}hot{endif}", "");
+    let vars = condition_vars(&[("model", "Opus 4.1"), ("context_pct", "91")]);
+    assert_eq!(renderer.render_template(&vars, false), "hot");
+}
+
+#[test]
+fn test_template_parentheses_and_negation() {
+    let renderer = LayoutRenderer::with_format("{if (a || b) && c}yes{else}no{endif}", "");
+    assert_eq!(
+        renderer.render_template(&condition_vars(&[("a", "1")]), false),
+        "no"
+    );
+    assert_eq!(
+        renderer.render_template(&condition_vars(&[("b", "1"), ("c", "1")]), false),
+        "yes"
+    );
+
+    let renderer = LayoutRenderer::with_format("{if !(a && b)}partial{endif}", "");
+    assert_eq!(
+        renderer.render_template(&condition_vars(&[("a", "1")]), false),
+        "partial"
+    );
+    assert_eq!(
+        renderer.render_template(&condition_vars(&[("a", "1"), ("b", "1")]), false),
+        ""
+    );
+
+    // No space needed after `if`
+    let renderer = LayoutRenderer::with_format("{if(a)}A{endif}{if!b}!B{endif}", "");
+    assert_eq!(
+        renderer.render_template(&condition_vars(&[("a", "1")]), false),
+        "A!B"
+    );
+}
+
+#[test]
+fn test_template_quoted_condition_values() {
+    let renderer = LayoutRenderer::with_format("{if tag == \"a && b\"}match{endif}", "");
+    assert_eq!(
+        renderer.render_template(&condition_vars(&[("tag", "a && b")]), false),
+        "match"
+    );
+
+    // Unquoted values may contain ')' outside parentheses
+    let renderer = LayoutRenderer::with_format("{if model == Opus (beta)}b{endif}", "");
+    assert_eq!(
+        renderer.render_template(&condition_vars(&[("model", "Opus (beta)")]), false),
+        "b"
+    );
+}
+
+#[test]
+fn test_template_elif_chain() {
+    let renderer = LayoutRenderer::with_format(
+        "{if context_pct >= 90}crit{elif context_pct >= 70}warn{elif context_pct}ok{else}none{endif}",
+        "",
+    );
+    let render =
+        |pct: &str| renderer.render_template(&condition_vars(&[("context_pct", pct)]), false);
+    assert_eq!(render("95"), "crit");
+    assert_eq!(render("75"), "warn");
+    assert_eq!(render("10"), "ok");
+    assert_eq!(renderer.render_template(&HashMap::new(), false), "none");
+
+    // {elif} without {else}
+    let renderer = LayoutRenderer::with_format("[{if a}A{elif b}B{endif}]", "");
+    assert_eq!(renderer.render_template(&HashMap::new(), false), "[]");
+    assert_eq!(
+        renderer.render_template(&condition_vars(&[("b", "1")]), false),
+        "[B]"
+    );
+}
+
+#[test]
+fn test_template_elif_chain_does_not_count_as_nesting() {
+    // A long {elif} chain stays at one level of nesting
+    let mut template = String::from("{if v == 0}0");
+    for i in 1..20 {
+        template.push_str(&format!("{{elif v == {}}}{}", i, i));
+    }
+    template.push_str("{endif}");
+    let renderer = LayoutRenderer::with_format(&template, "");
+    assert_eq!(
+        renderer.render_template(&condition_vars(&[("v", "19")]), false),
+        "19"
+    );
+}
+
+#[test]
+fn test_template_condition_errors() {
+    let cases = [
+        ("{if a &&}x{endif}", "empty variable name in condition"),
+        ("{if (a || b}x{endif}", "missing ')' in condition"),
+        ("{if a)}x{endif}", "unexpected ')' in condition"),
+        ("{if !}x{endif}", "empty variable name after ! in condition"),
+        (
+            "{if pct >= high}x{endif}",
+            "expected a number after 'pct >=' in condition, got 'high'",
+        ),
+        ("{if a == \"b}x{endif}", "unterminated quote in condition"),
+        ("{if a b}x{endif}", "unexpected 'b' in condition"),
+        ("{elif a}x{endif}", "unexpected {elif} outside conditional"),
+        ("{if a}x{elif}y{endif}", "empty condition in {elif}"),
+        (
+            "{if a}x{else}y{elif b}z{endif}",
+            "{elif} after {else} in single conditional",
+        ),
+        ("{if a}x{elif b}y", "unclosed {if} block (missing {endif})"),
+    ];
+    for (template, error) in cases {
+        let renderer = LayoutRenderer::with_format(template, "");
+        assert_eq!(renderer.parse_error(), Some(error), "{}", template);
+        assert_eq!(
+            renderer.render_template(&HashMap::new(), false),
+            "[tmpl err]"
+        );
+    }
+
+    // Parentheses share the nesting limit
+    let template = format!("{{if {}a{}}}x{{endif}}", "(".repeat(11), ")".repeat(11));
+    let renderer = LayoutRenderer::with_format(&template, "");
+    assert_eq!(
+        renderer.parse_error(),
+        Some("nesting depth exceeds maximum of 10")
+    );
+    let template = format!("{{if {}a{}}}x{{endif}}", "(".repeat(10), ")".repeat(10));
+    let renderer = LayoutRenderer::with_format(&template, "");
+    assert_eq!(
+        renderer.render_template(&condition_vars(&[("a", "1")]), false),
+        "x"
+    );
+}
+
+// =========================================================================
+// Width-aware rendering (render_to_width)
+// =========================================================================
+
+fn width_vars() -> HashMap<String, String> {
+    [
+        ("directory", "~/projects/app"),
+        ("dir_short", "app"),
+        ("git", "main +2 ~1"),
+        ("git_branch", "main"),
+        ("context", "75% [=======>--]"),
+        ("context_pct", "75%"),
+        ("model", "S4.5"),
+        ("cost", "$12.50 ($3.00/hr)"),
+        ("cost_short", "$13"),
+        ("duration", "1h5m"),
+    ]
+    .iter()
+    .map(|(k, v)| (k.to_string(), v.to_string()))
+    .collect()
+}
+
+fn width_renderer(format: &str) -> LayoutRenderer {
+    LayoutRenderer::from_config(&LayoutConfig {
+        format: format.to_string(),
+        separator: " | ".to_string(),
+        ..Default::default()
+    })
+}
+
+#[test]
+fn test_render_to_width_fits_unchanged() {
+    let renderer = width_renderer("{directory}{sep}{git}{sep}{model}");
+    let vars = width_vars();
+    let full = renderer.render(&vars);
+    assert_eq!(full, "~/projects/app | main +2 ~1 | S4.5");
+    assert_eq!(renderer.render_to_width(&vars, None), full);
+    assert_eq!(renderer.render_to_width(&vars, Some(200)), full);
+    assert_eq!(renderer.render_to_width(&vars, Some(full.len())), full);
+}
+
+#[test]
+fn test_render_to_width_collapses_then_drops_by_priority() {
+    let renderer = width_renderer("{directory}{sep}{git}{sep}{context}{sep}{model}{sep}{cost}");
+    let vars = width_vars();
+    let render = |width| renderer.render_to_width(&vars, Some(width));
+
+    // cost (50) collapses, then drops, before git (60), context (70) and directory (80)
+    assert_eq!(
+        render(59),
+        "~/projects/app | main +2 ~1 | 75% [=======>--] | S4.5 | $13"
+    );
+    assert_eq!(
+        render(58),
+        "~/projects/app | main +2 ~1 | 75% [=======>--] | S4.5"
+    );
+    assert_eq!(
+        render(51),
+        "~/projects/app | main | 75% [=======>--] | S4.5"
+    );
+    assert_eq!(render(45), "~/projects/app | 75% [=======>--] | S4.5");
+    assert_eq!(render(38), "~/projects/app | 75% | S4.5");
+    assert_eq!(render(26), "~/projects/app | S4.5");
+    assert_eq!(render(20), "app | S4.5");
+    assert_eq!(render(9), "S4.5");
+    // The last segment is never dropped; the line is cut as a last resort
+    assert_eq!(render(3), "S4…");
+}
+
+#[test]
+fn test_render_to_width_other_segments_use_default_priority() {
+    // {duration} belongs to no component (priority 40), so it goes before cost (50)
+    let renderer = width_renderer("{model}{sep}{duration}{sep}{cost}");
+    let vars = width_vars();
+    assert_eq!(
+        renderer.render_to_width(&vars, Some(30)),
+        "S4.5 | $12.50 ($3.00/hr)"
+    );
+    // A segment mixing components keeps its highest priority but has no compact form
+    let renderer = width_renderer("{model} {cost}{sep}{directory}");
+    assert_eq!(
+        renderer.render_to_width(&vars, Some(28)),
+        "S4.5 $12.50 ($3.00/hr) | app"
+    );
+    assert_eq!(
+        renderer.render_to_width(&vars, Some(27)),
+        "S4.5 $12.50 ($3.00/hr)"
+    );
+    assert_eq!(
+        renderer.render_to_width(&vars, Some(20)),
+        "S4.5 $12.50 ($3.00/…"
+    );
+}
+
+#[test]
+fn test_render_to_width_component_settings() {
+    let mut config = LayoutConfig {
+        format: "{directory}{sep}{git}{sep}{model}".to_string(),
+        separator: " | ".to_string(),
+        ..Default::default()
+    };
+    config.components.directory.priority = 10;
+    config.components.directory.compact = "{dir_short|upper}".to_string();
+    config.components.model.priority = 5;
+    let renderer = LayoutRenderer::from_config(&config);
+    let vars = width_vars();
+
+    // The model (5) drops first, then the directory (10) collapses and drops
+    assert_eq!(
+        renderer.render_to_width(&vars, Some(27)),
+        "~/projects/app | main +2 ~1"
+    );
+    assert_eq!(
+        renderer.render_to_width(&vars, Some(26)),
+        "APP | main +2 ~1"
+    );
+    assert_eq!(renderer.render_to_width(&vars, Some(15)), "main +2 ~1");
+}
+
+#[test]
+fn test_render_to_width_multi_line() {
+    let renderer = width_renderer("{directory}{sep}{git}\n{model}{sep}{duration}{sep}{cost}");
+    let vars = width_vars();
+    assert_eq!(
+        renderer.render_to_width(&vars, Some(22)),
+        "~/projects/app | main\nS4.5 | $13"
+    );
+}
+
+#[test]
+fn test_render_to_width_measures_terminal_columns() {
+    let renderer = width_renderer("{directory}{sep}{model}");
+    let mut vars = width_vars();
+    // Each CJK character and the emoji take two columns
+    vars.insert("directory".to_string(), "📁 日本語".to_string());
+    vars.insert("dir_short".to_string(), "日本".to_string());
+    assert_eq!(
+        renderer.render_to_width(&vars, Some(16)),
+        "📁 日本語 | S4.5"
+    );
+    assert_eq!(renderer.render_to_width(&vars, Some(15)), "日本 | S4.5");
+
+    // Without a compact value the segment is cut
+    let renderer = width_renderer("{directory}");
+    vars.insert("directory".to_string(), "日本語テキスト".to_string());
+    vars.remove("dir_short");
+    assert_eq!(renderer.render_to_width(&vars, Some(9)), "日本語テ…");
+}
+
+#[test]
+fn test_render_to_width_keeps_colors() {
+    let renderer = width_renderer("{directory}{sep}{model}");
+    let mut vars = width_vars();
+    vars.insert(
+        "directory".to_string(),
+        "\x1b[36m~/projects/app\x1b[0m".to_string(),
+    );
+    vars.insert("dir_short".to_string(), "\x1b[36mapp\x1b[0m".to_string());
+    // Escape sequences take no columns
+    assert_eq!(
+        renderer.render_to_width(&vars, Some(21)),
+        "\x1b[36m~/projects/app\x1b[0m | S4.5"
+    );
+    assert_eq!(
+        renderer.render_to_width(&vars, Some(20)),
+        "\x1b[36mapp\x1b[0m | S4.5"
+    );
+}
+
+#[test]
+fn test_filter_widths_count_columns() {
+    let vars: HashMap<String, String> = [("name", "日本語テキスト")]
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    let render =
+        |format: &str| LayoutRenderer::with_format(format, "").render_template(&vars, false);
+    assert_eq!(render("{name|truncate:7}"), "日本語…");
+    assert_eq!(render("[{name|pad:16}]"), "[  日本語テキスト]");
+    assert_eq!(render("[{name|fmt:%16s}]"), "[  日本語テキスト]");
+    assert_eq!(render("[{name|fmt:%-.5s}]"), "[日本]");
+}
+
+#[test]
+#[serial_test::serial]
+fn test_layout_target_width() {
+    let prior = std::env::var("COLUMNS").ok();
+
+    // COLUMNS is only used when asked for
+    std::env::set_var("COLUMNS", "120");
+    let mut config = LayoutConfig::default();
+    assert_eq!(config.target_width(), None);
+    config.width = LayoutWidth::Columns(80);
+    assert_eq!(config.target_width(), Some(80));
+    config.width = LayoutWidth::Auto;
+    assert_eq!(config.target_width(), Some(120));
+
+    std::env::set_var("COLUMNS", "not a number");
+    assert_eq!(config.target_width(), None);
+    std::env::remove_var("COLUMNS");
+    assert_eq!(config.target_width(), None);
+
+    let parse = |toml: &str| toml::from_str::<LayoutConfig>(toml).map(|c| c.width);
+    assert_eq!(parse("width = 60").unwrap(), LayoutWidth::Columns(60));
+    assert_eq!(parse("width = \"auto\"").unwrap(), LayoutWidth::Auto);
+    assert!(parse("width = \"wide\"").is_err());
+
+    if let Some(columns) = prior {
+        std::env::set_var("COLUMNS", columns);
+    }
+}
+
+// =========================================================================
+// Left/right aligned groups ({fill})
+// =========================================================================
+
+fn fill_renderer(format: &str, fill: &str) -> LayoutRenderer {
+    LayoutRenderer::from_config(&LayoutConfig {
+        format: format.to_string(),
+        separator: " | ".to_string(),
+        fill: fill.to_string(),
+        ..Default::default()
+    })
+}
+
+#[test]
+fn test_fill_aligns_right_group() {
+    let renderer = fill_renderer("{directory}{fill}{model}{sep}{duration}", " ");
+    let vars = width_vars();
+    assert_eq!(
+        renderer.render_to_width(&vars, Some(30)),
+        "~/projects/app     S4.5 | 1h5m"
+    );
+    // Exactly one column of fill when the groups just fit
+    assert_eq!(
+        renderer.render_to_width(&vars, Some(26)),
+        "~/projects/app S4.5 | 1h5m"
+    );
+
+    let renderer = fill_renderer("{directory}{fill}{model}", "─");
+    assert_eq!(
+        renderer.render_to_width(&vars, Some(24)),
+        "~/projects/app──────S4.5"
+    );
+    // A two-column fill is topped up with a space
+    let renderer = fill_renderer("{directory}{fill}{model}", "＝");
+    assert_eq!(
+        renderer.render_to_width(&vars, Some(23)),
+        "~/projects/app＝＝ S4.5"
+    );
+}
+
+#[test]
+fn test_fill_without_width_acts_as_separator() {
+    let renderer = fill_renderer("{directory}{fill}{model}{sep}{duration}", "·");
+    let vars = width_vars();
+    assert_eq!(renderer.render(&vars), "~/projects/app | S4.5 | 1h5m");
+    assert_eq!(
+        renderer.render_to_width(&vars, None),
+        "~/projects/app | S4.5 | 1h5m"
+    );
+    assert_eq!(
+        renderer.get_used_variables(),
+        vec!["directory", "model", "duration"]
+    );
+}
+
+#[test]
+fn test_fill_with_empty_group() {
+    let vars = width_vars();
+    // An empty left group still right-aligns the right one
+    let renderer = fill_renderer("{missing}{fill}{model}", " ");
+    assert_eq!(renderer.render_to_width(&vars, Some(10)), "      S4.5");
+    // An empty right group leaves no trailing fill
+    let renderer = fill_renderer("{directory}{fill}{missing}", ".");
+    assert_eq!(renderer.render_to_width(&vars, Some(30)), "~/projects/app");
+}
+
+#[test]
+fn test_fill_fits_across_groups() {
+    let renderer = fill_renderer("{directory}{sep}{git}{fill}{cost}{sep}{model}", " ");
+    let vars = width_vars();
+    assert_eq!(
+        renderer.render_to_width(&vars, Some(60)),
+        "~/projects/app | main +2 ~1         $12.50 ($3.00/hr) | S4.5"
+    );
+    // cost (right) collapses and drops, then git (left) collapses
+    assert_eq!(
+        renderer.render_to_width(&vars, Some(40)),
+        "~/projects/app | main +2 ~1   $13 | S4.5"
+    );
+    assert_eq!(
+        renderer.render_to_width(&vars, Some(30)),
+        "~/projects/app | main     S4.5"
+    );
+}
+
+#[test]
+fn test_fill_multi_line() {
+    let renderer = fill_renderer("{directory}{fill}{model}\n{git}{fill}{cost_short}", " ");
+    let vars = width_vars();
+    assert_eq!(
+        renderer.render_to_width(&vars, Some(20)),
+        "~/projects/app  S4.5\nmain +2 ~1       $13"
+    );
+}
+
+// =========================================================================
+// Powerline mode
+// =========================================================================
+
+fn powerline_renderer(format: &str, style: &str, theme: Option<&Theme>) -> LayoutRenderer {
+    width_renderer(format).with_powerline(Powerline::new(style, theme))
+}
+
+#[test]
+fn test_powerline_blocks_and_transitions() {
+    let theme = Theme::default();
+    let renderer = powerline_renderer("{directory}{sep}{model}", "arrow", Some(&theme));
+    let mut vars = width_vars();
+    // Value colors are replaced by the block colors
+    vars.insert(
+        "directory".to_string(),
+        "\x1b[36m~/projects/app\x1b[0m".to_string(),
+    );
+    assert_eq!(
+        renderer.render(&vars),
+        "\x1b[104m\x1b[30m ~/projects/app \x1b[94m\x1b[105m\u{e0b0}\x1b[30m S4.5 \
+         \x1b[0m\x1b[95m\u{e0b0}\x1b[0m"
+    );
+}
+
+#[test]
+fn test_powerline_skips_empty_segments() {
+    let theme = Theme::default();
+    let renderer = powerline_renderer(
+        "{directory}{sep}{missing}{sep}{git}{sep}{context_tokens}",
+        "arrow",
+        Some(&theme),
+    );
+    // The directory block flows straight into the git block
+    assert_eq!(
+        renderer.render(&width_vars()),
+        "\x1b[104m\x1b[30m ~/projects/app \x1b[94m\x1b[102m\u{e0b0}\x1b[30m main +2 ~1 \
+         \x1b[0m\x1b[92m\u{e0b0}\x1b[0m"
+    );
+    let renderer = powerline_renderer("{missing}{sep}{context_tokens}", "arrow", Some(&theme));
+    assert_eq!(renderer.render(&width_vars()), "");
+}
+
+#[test]
+fn test_powerline_same_background_uses_thin_glyph() {
+    let theme = Theme::default();
+    let renderer = powerline_renderer("{duration}{sep}{lines}", "arrow", Some(&theme));
+    let mut vars = width_vars();
+    vars.insert("lines".to_string(), "+3 -1".to_string());
+    assert_eq!(
+        renderer.render(&vars),
+        "\x1b[47m\x1b[30m 1h5m \x1b[30m\u{e0b1}\x1b[30m +3 -1 \x1b[0m\x1b[37m\u{e0b0}\x1b[0m"
+    );
+}
+
+#[test]
+fn test_powerline_styles() {
+    let theme = Theme::default();
+    let vars = width_vars();
+    let rounded = powerline_renderer("{model}{sep}{cost_short}", "rounded", Some(&theme));
+    assert_eq!(
+        rounded.render(&vars),
+        "\x1b[105m\x1b[30m S4.5 \x1b[95m\x1b[103m\u{e0b4}\x1b[30m $13 \x1b[0m\x1b[93m\u{e0b4}\x1b[0m"
+    );
+    let ascii = powerline_renderer("{model}{sep}{cost_short}", "ASCII", Some(&theme));
+    assert_eq!(
+        ascii.render(&vars),
+        "\x1b[105m\x1b[30m S4.5 \x1b[95m\x1b[103m>\x1b[30m $13 \x1b[0m\x1b[93m>\x1b[0m"
+    );
+    // Unknown styles fall back to arrows
+    let unknown = powerline_renderer("{model}", "fancy", Some(&theme));
+    assert!(unknown.render(&vars).contains('\u{e0b0}'));
+}
+
+#[test]
+fn test_powerline_without_colors() {
+    let renderer = powerline_renderer("{directory}{sep}{model}\n{cost_short}", "arrow", None);
+    assert_eq!(
+        renderer.render(&width_vars()),
+        " ~/projects/app \u{e0b1} S4.5 \n $13 "
+    );
+}
+
+#[test]
+fn test_powerline_right_group() {
+    let theme = Theme::default();
+    let renderer = powerline_renderer("{directory}{fill}{git}{sep}{model}", "ascii", Some(&theme));
+    assert_eq!(
+        renderer.render_to_width(&width_vars(), Some(40)),
+        "\x1b[104m\x1b[30m ~/projects/app \x1b[0m\x1b[94m>\x1b[0m   \
+         \x1b[92m<\x1b[102m\x1b[30m main +2 ~1 \x1b[95m<\x1b[105m\x1b[30m S4.5 \x1b[0m"
+    );
+    // Without a width {fill} splits blocks like {sep}
+    assert_eq!(
+        renderer.render(&width_vars()),
+        "\x1b[104m\x1b[30m ~/projects/app \x1b[94m\x1b[102m>\x1b[30m main +2 ~1 \
+         \x1b[92m\x1b[105m>\x1b[30m S4.5 \x1b[0m\x1b[95m>\x1b[0m"
+    );
+}
+
+#[test]
+fn test_powerline_fits_width() {
+    let renderer = powerline_renderer("{directory}{sep}{git}{sep}{cost}", "ascii", None);
+    let vars = width_vars();
+    assert_eq!(
+        renderer.render_to_width(&vars, Some(60)),
+        " ~/projects/app | main +2 ~1 | $12.50 ($3.00/hr) "
+    );
+    // The cost block collapses to its compact form, then drops
+    assert_eq!(
+        renderer.render_to_width(&vars, Some(40)),
+        " ~/projects/app | main +2 ~1 | $13 "
+    );
+    assert_eq!(
+        renderer.render_to_width(&vars, Some(30)),
+        " ~/projects/app | main +2 ~1 "
+    );
+}
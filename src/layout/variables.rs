@@ -138,6 +138,8 @@ impl VariableBuilder {
     /// Set git variables with component configuration
     ///
     /// Applies format and show_when options from config.
+    /// format: "full" (default), "branch", "status", "tracking" (branch + ahead/behind/stash),
+    /// "full_tracking" (full + ahead/behind/stash)
     /// show_when: "always" (default), "dirty" (only when dirty), "never"
     #[allow(clippy::too_many_arguments)]
     pub fn git_with_config(
@@ -145,6 +147,7 @@ impl VariableBuilder {
         full_info: &str,
         branch: Option<&str>,
         status_only: Option<&str>,
+        tracking: Option<&str>,
         is_dirty: bool,
         default_color: &str,
         reset: &str,
@@ -185,6 +188,24 @@ impl VariableBuilder {
                     }
                 }
             }
+            "tracking" => {
+                if let Some(b) = branch.filter(|b| !b.is_empty()) {
+                    let value = match tracking.filter(|t| !t.is_empty()) {
+                        Some(t) => format!("{}{} {}{}", color, b, t, reset),
+                        None => format!("{}{}{}", color, b, reset),
+                    };
+                    self.variables.insert("git".to_string(), value);
+                }
+            }
+            "full_tracking" => {
+                if !full_info.is_empty() {
+                    let value = match tracking.filter(|t| !t.is_empty()) {
+                        Some(t) => format!("{} {}{}{}", full_info, color, t, reset),
+                        None => full_info.to_string(),
+                    };
+                    self.variables.insert("git".to_string(), value);
+                }
+            }
             _ => {
                 // "full" is default
                 if !full_info.is_empty() {
@@ -382,6 +403,318 @@ impl VariableBuilder {
         self
     }
 
+    /// Set the local clock variable ({time})
+    pub fn clock(mut self, time: &str, color: &str, reset: &str) -> Self {
+        if !time.is_empty() {
+            self.variables
+                .insert("time".to_string(), format!("{}{}{}", color, time, reset));
+        }
+        self
+    }
+
+    /// Set API timing variables ({wall_time}, {api_time}, {api_pct})
+    ///
+    /// `wall_time` and `api_time` are pre-formatted durations; `api_pct` is the
+    /// share of wall-clock time spent waiting on the API.
+    pub fn api_timing(
+        mut self,
+        wall_time: Option<&str>,
+        api_time: Option<&str>,
+        api_pct: Option<f64>,
+        color: &str,
+        reset: &str,
+    ) -> Self {
+        if let Some(wall) = wall_time.filter(|s| !s.is_empty()) {
+            self.variables.insert(
+                "wall_time".to_string(),
+                format!("{}{}{}", color, wall, reset),
+            );
+        }
+        if let Some(api) = api_time.filter(|s| !s.is_empty()) {
+            self.variables
+                .insert("api_time".to_string(), format!("{}{}{}", color, api, reset));
+        }
+        if let Some(pct) = api_pct {
+            self.variables.insert(
+                "api_pct".to_string(),
+                format!("{}{:.0}%{}", color, pct, reset),
+            );
+        }
+        self
+    }
+
+    /// Set Claude Code session variables ({claude_version}, {output_style}, {model_id})
+    pub fn claude_code(
+        mut self,
+        version: Option<&str>,
+        output_style: Option<&str>,
+        model_id: Option<&str>,
+        color: &str,
+        reset: &str,
+    ) -> Self {
+        if let Some(version) = version.filter(|s| !s.is_empty()) {
+            self.variables.insert(
+                "claude_version".to_string(),
+                format!("{}v{}{}", color, version.trim_start_matches('v'), reset),
+            );
+        }
+        if let Some(style) = output_style.filter(|s| !s.is_empty()) {
+            self.variables.insert(
+                "output_style".to_string(),
+                format!("{}{}{}", color, style, reset),
+            );
+        }
+        if let Some(id) = model_id.filter(|s| !s.is_empty()) {
+            self.variables
+                .insert("model_id".to_string(), format!("{}{}{}", color, id, reset));
+        }
+        self
+    }
+
+    /// Set token-derived cost variables ({cost_computed}, {cost_computed_today},
+    /// {cost_computed_month}, {cost_divergence})
+    ///
+    /// `divergence_pct` is the signed difference from the reported cost and should only
+    /// be passed when it exceeds the configured threshold.
+    #[allow(clippy::too_many_arguments)]
+    pub fn computed_cost(
+        mut self,
+        session: Option<f64>,
+        today: Option<f64>,
+        month: Option<f64>,
+        divergence_pct: Option<f64>,
+        cost_color: &str,
+        warn_color: &str,
+        reset: &str,
+    ) -> Self {
+        for (key, value) in [
+            ("cost_computed", session),
+            ("cost_computed_today", today),
+            ("cost_computed_month", month),
+        ] {
+            if let Some(value) = value.filter(|v| *v > 0.0) {
+                self.variables.insert(
+                    key.to_string(),
+                    format!("{}${:.2}{}", cost_color, value, reset),
+                );
+            }
+        }
+        if let Some(pct) = divergence_pct {
+            self.variables.insert(
+                "cost_divergence".to_string(),
+                format!("{}{:+.0}%{}", warn_color, pct, reset),
+            );
+        }
+        self
+    }
+
+    /// Set prompt-cache savings variables ({cache_saved}, {cache_saved_today})
+    ///
+    /// Negative values (cache writes that were never paid back by reads) are shown
+    /// with a leading minus in `loss_color`.
+    pub fn cache_saved(
+        mut self,
+        session: Option<f64>,
+        today: Option<f64>,
+        saved_color: &str,
+        loss_color: &str,
+        reset: &str,
+    ) -> Self {
+        for (key, value) in [("cache_saved", session), ("cache_saved_today", today)] {
+            if let Some(value) = value {
+                let formatted = if value < 0.0 {
+                    format!("{}-${:.2}{}", loss_color, -value, reset)
+                } else {
+                    format!("{}${:.2}{}", saved_color, value, reset)
+                };
+                self.variables.insert(key.to_string(), formatted);
+            }
+        }
+        self
+    }
+
+    /// Set spending budget variables ({budget_remaining}, {budget_pct}, {budget_bar},
+    /// {budget_spent}, {budget_limit}, {budget_name})
+    ///
+    /// An exceeded budget shows the overspend as a negative remaining amount.
+    pub fn budget(
+        mut self,
+        name: &str,
+        spent: f64,
+        limit: f64,
+        bar: &str,
+        color: &str,
+        reset: &str,
+    ) -> Self {
+        let remaining = limit - spent;
+        let remaining = if remaining < 0.0 {
+            format!("-${:.2}", -remaining)
+        } else {
+            format!("${:.2}", remaining)
+        };
+        for (key, value) in [
+            ("budget_remaining", remaining),
+            ("budget_pct", format!("{:.0}%", spent / limit * 100.0)),
+            ("budget_bar", bar.to_string()),
+            ("budget_spent", format!("${:.2}", spent)),
+            ("budget_limit", format!("${:.2}", limit)),
+        ] {
+            self.variables
+                .insert(key.to_string(), format!("{}{}{}", color, value, reset));
+        }
+        if !name.is_empty() {
+            self.variables
+                .insert("budget_name".to_string(), name.to_string());
+        }
+        self
+    }
+
+    /// Set forecast variables ({forecast_today}, {forecast_month},
+    /// {forecast_tokens_today}, {forecast_tokens_month})
+    #[allow(clippy::too_many_arguments)]
+    pub fn forecast(
+        mut self,
+        today: Option<f64>,
+        month: Option<f64>,
+        tokens_today: Option<u64>,
+        tokens_month: Option<u64>,
+        today_color: &str,
+        month_color: &str,
+        token_color: &str,
+        reset: &str,
+    ) -> Self {
+        for (key, value, color) in [
+            ("forecast_today", today, today_color),
+            ("forecast_month", month, month_color),
+        ] {
+            if let Some(value) = value {
+                self.variables
+                    .insert(key.to_string(), format!("{}${:.2}{}", color, value, reset));
+            }
+        }
+        for (key, value) in [
+            ("forecast_tokens_today", tokens_today),
+            ("forecast_tokens_month", tokens_month),
+        ] {
+            if let Some(value) = value {
+                self.variables.insert(
+                    key.to_string(),
+                    format!("{}{}{}", token_color, format_token_count(value), reset),
+                );
+            }
+        }
+        self
+    }
+
+    /// Set usage block variables ({block_elapsed}, {block_remaining}, {block_tokens},
+    /// {block_cost}, {block_projected_tokens}, {block_projected_cost}, {block_exhausts})
+    ///
+    /// `elapsed`, `remaining` and `exhausts` are pre-formatted durations; `exhausts`
+    /// should only be passed when the block is projected to run out before it resets.
+    #[allow(clippy::too_many_arguments)]
+    pub fn block(
+        mut self,
+        elapsed: &str,
+        remaining: &str,
+        tokens: u64,
+        cost: f64,
+        projected: Option<(u64, f64)>,
+        exhausts: Option<&str>,
+        time_color: &str,
+        usage_color: &str,
+        warn_color: &str,
+        reset: &str,
+    ) -> Self {
+        for (key, value, color) in [
+            ("block_elapsed", elapsed.to_string(), time_color),
+            ("block_remaining", remaining.to_string(), time_color),
+            ("block_tokens", format_token_count(tokens), usage_color),
+            ("block_cost", format!("${:.2}", cost), usage_color),
+        ] {
+            self.variables
+                .insert(key.to_string(), format!("{}{}{}", color, value, reset));
+        }
+        if let Some((tokens, cost)) = projected {
+            self.variables.insert(
+                "block_projected_tokens".to_string(),
+                format!("{}{}{}", usage_color, format_token_count(tokens), reset),
+            );
+            self.variables.insert(
+                "block_projected_cost".to_string(),
+                format!("{}${:.2}{}", usage_color, cost, reset),
+            );
+        }
+        if let Some(exhausts) = exhausts {
+            self.variables.insert(
+                "block_exhausts".to_string(),
+                format!("{}{}{}", warn_color, exhausts, reset),
+            );
+        }
+        self
+    }
+
+    /// Set usage limit and API error variables ({limit_reset}, {limit_reset_at},
+    /// {api_errors})
+    ///
+    /// `limit_reset` is the pre-formatted time until an active usage limit resets;
+    /// `api_errors` is only set when the session has any.
+    pub fn rate_limits(
+        mut self,
+        limit_reset: Option<&str>,
+        limit_reset_at: Option<&str>,
+        api_errors: Option<u64>,
+        limit_color: &str,
+        error_color: &str,
+        reset: &str,
+    ) -> Self {
+        for (key, value) in [
+            ("limit_reset", limit_reset),
+            ("limit_reset_at", limit_reset_at),
+        ] {
+            if let Some(value) = value.filter(|v| !v.is_empty()) {
+                self.variables.insert(
+                    key.to_string(),
+                    format!("{}{}{}", limit_color, value, reset),
+                );
+            }
+        }
+        if let Some(count) = api_errors.filter(|c| *c > 0) {
+            self.variables.insert(
+                "api_errors".to_string(),
+                format!("{}{}{}", error_color, count, reset),
+            );
+        }
+        self
+    }
+
+    /// Set project root variables ({project_dir}, {project_name})
+    pub fn project(mut self, short_path: &str, name: &str, color: &str, reset: &str) -> Self {
+        if !short_path.is_empty() {
+            self.variables.insert(
+                "project_dir".to_string(),
+                format!("{}{}{}", color, short_path, reset),
+            );
+        }
+        if !name.is_empty() {
+            self.variables.insert(
+                "project_name".to_string(),
+                format!("{}{}{}", color, name, reset),
+            );
+        }
+        self
+    }
+
+    /// Set the large-context marker ({exceeds_200k}) when the 200k token threshold is crossed
+    pub fn exceeds_200k(mut self, exceeded: bool, color: &str, reset: &str) -> Self {
+        if exceeded {
+            self.variables.insert(
+                "exceeds_200k".to_string(),
+                format!("{}>200k{}", color, reset),
+            );
+        }
+        self
+    }
+
     /// Set cost variables ({cost}, {burn_rate}, {daily_total}, {cost_short})
     #[allow(dead_code)]
     pub fn cost(
@@ -841,6 +1174,17 @@ impl VariableBuilder {
         self
     }
 
+    /// Add variables collected elsewhere (e.g. by data providers) without
+    /// overriding any variable already set on this builder. Empty values are skipped.
+    pub fn extend_missing(mut self, vars: &HashMap<String, String>) -> Self {
+        for (key, value) in vars {
+            if !value.is_empty() && !self.variables.contains_key(key) {
+                self.variables.insert(key.clone(), value.clone());
+            }
+        }
+        self
+    }
+
     /// Build the final HashMap
     pub fn build(self) -> HashMap<String, String> {
         self.variables
@@ -30,6 +30,12 @@
 // TODO: Re-enable html_root_url once the crate is published on docs.rs
 // #![doc(html_root_url = "https://docs.rs/statusline/2.7.0")]
 
+/// Backfill of session history from Claude Code transcripts
+pub mod backfill;
+/// Rolling 5-hour usage blocks rebuilt from timestamped transcript usage
+pub mod blocks;
+/// Spending budgets checked against recorded daily, monthly and workspace cost
+pub mod budget;
 pub mod common;
 /// Configuration management module for loading and saving settings
 pub mod config;
@@ -39,7 +45,16 @@ pub mod context_learning;
 pub mod database;
 pub mod display;
 pub mod error;
+/// Portable NDJSON, JSON and CSV exports of the stats database
+pub mod export;
+/// End-of-day and end-of-month spend forecasts from daily history
+pub mod forecast;
 pub mod git;
+/// Cross-invocation git status cache
+pub mod git_cache;
+/// In-process git status via gitoxide (requires git_native feature)
+#[cfg(feature = "git_native")]
+pub mod git_native;
 /// GitProvider wraps git module as a DataProvider
 pub mod git_provider;
 pub mod git_utils;
@@ -49,13 +64,19 @@ pub mod gsd;
 pub mod hook_handler;
 /// Layout rendering module for customizable statusline format
 pub mod layout;
+/// Usage limit and API error state recorded from session transcripts
+pub mod limits;
 /// Database schema migration system
 pub mod migrations;
 pub mod models;
+/// Model pricing table and cost computed from transcript token usage
+pub mod pricing;
 /// Data provider system for parallel variable collection
 pub mod provider;
 /// Shared statusline rendering logic (stats-update flow used by the binary and the embedding API)
 pub mod render;
+/// Usage reports grouped by day, week, month, model, project or device
+pub mod report;
 /// Retry logic with exponential backoff for transient failures
 pub mod retry;
 /// Hook-based state management for real-time event tracking
@@ -67,6 +88,8 @@ pub mod sync;
 /// Theme system for customizable statusline colors
 pub mod theme;
 pub mod utils;
+/// Version control abstraction (git, Jujutsu, Mercurial) and the VcsProvider
+pub mod vcs;
 pub mod version;
 
 pub use config::Config;
@@ -114,9 +137,11 @@ pub use version::{short_version, version_string};
 /// let input = StatuslineInput {
 ///     workspace: Some(Workspace {
 ///         current_dir: Some("/home/user/project".to_string()),
+///         ..Default::default()
 ///     }),
 ///     model: Some(Model {
 ///         display_name: Some("Claude 3.5 Sonnet".to_string()),
+///         ..Default::default()
 ///     }),
 ///     ..Default::default()
 /// };
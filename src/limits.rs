@@ -0,0 +1,188 @@
+//! Usage limit and API error state.
+//!
+//! When Claude Code hits a usage limit or a request fails, it writes an error
+//! entry into the transcript ("5-hour limit reached ∙ resets 3pm", "API Error:
+//! 529 ..."). [`crate::utils::TranscriptTail::errors`] finds those entries and the
+//! render flow records them per session in `session_errors`; this provider turns
+//! them into `{limit_reset}` and `{api_errors}`.
+
+use crate::database::SqliteDatabase;
+use crate::provider::{DataProvider, ProviderResult};
+use chrono::{DateTime, Local, SecondsFormat, Utc};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Provider for the usage limit and API error variables.
+pub struct LimitProvider {
+    /// Current session ID, for the API error count
+    session_id: Option<String>,
+    /// Path to the SQLite database (None if unavailable)
+    db_path: Option<PathBuf>,
+}
+
+impl LimitProvider {
+    pub fn new(session_id: Option<String>, db_path: Option<PathBuf>) -> Self {
+        Self {
+            session_id,
+            db_path,
+        }
+    }
+}
+
+/// Formats a reset time: the time of day when it is today, with the date otherwise.
+fn format_reset_at(reset_at: DateTime<Local>, now: DateTime<Local>) -> String {
+    if reset_at.date_naive() == now.date_naive() {
+        reset_at.format("%H:%M").to_string()
+    } else {
+        reset_at.format("%b %-d %H:%M").to_string()
+    }
+}
+
+impl DataProvider for LimitProvider {
+    fn name(&self) -> &str {
+        "limits"
+    }
+
+    fn priority(&self) -> u32 {
+        50
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_millis(200)
+    }
+
+    fn is_available(&self) -> bool {
+        true
+    }
+
+    fn collect(&self) -> ProviderResult {
+        let mut vars: HashMap<String, String> = ["limit_reset", "limit_reset_at", "api_errors"]
+            .into_iter()
+            .map(|key| (key.to_string(), String::new()))
+            .collect();
+
+        let Some(db) = self
+            .db_path
+            .as_deref()
+            .filter(|p| p.exists())
+            .and_then(|p| SqliteDatabase::new(p).ok())
+        else {
+            return Ok(vars);
+        };
+
+        let now = Utc::now();
+        let limit = db
+            .get_active_usage_limit(&now.to_rfc3339_opts(SecondsFormat::Millis, true))
+            .ok()
+            .flatten();
+        let reset_at = limit
+            .and_then(|limit| limit.reset_at)
+            .and_then(|t| DateTime::parse_from_rfc3339(&t).ok())
+            .map(|t| t.with_timezone(&Utc));
+        if let Some(reset_at) = reset_at {
+            vars.insert(
+                "limit_reset".into(),
+                (reset_at - now).num_seconds().max(0).to_string(),
+            );
+            vars.insert(
+                "limit_reset_at".into(),
+                format_reset_at(reset_at.with_timezone(&Local), now.with_timezone(&Local)),
+            );
+        }
+
+        if let Some(session_id) = self.session_id.as_deref() {
+            let errors = db
+                .count_session_errors(session_id, "api_error")
+                .unwrap_or(0);
+            if errors > 0 {
+                vars.insert("api_errors".into(), errors.to_string());
+            }
+        }
+
+        Ok(vars)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::SessionError;
+    use chrono::TimeDelta;
+    use tempfile::TempDir;
+
+    fn error(
+        session: &str,
+        kind: &str,
+        at: DateTime<Utc>,
+        reset: Option<DateTime<Utc>>,
+    ) -> SessionError {
+        SessionError {
+            session_id: session.to_string(),
+            timestamp: at.to_rfc3339_opts(SecondsFormat::Millis, true),
+            kind: kind.to_string(),
+            message: String::new(),
+            reset_at: reset.map(|t| t.to_rfc3339_opts(SecondsFormat::Millis, true)),
+        }
+    }
+
+    #[test]
+    fn test_limit_provider_variables() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("stats.db");
+        let db = SqliteDatabase::new(&db_path).unwrap();
+
+        let now = Utc::now();
+        db.record_session_errors(&[
+            // An expired limit from earlier is ignored
+            error(
+                "other",
+                "usage_limit",
+                now - TimeDelta::hours(8),
+                Some(now - TimeDelta::hours(3)),
+            ),
+            error(
+                "other",
+                "usage_limit",
+                now - TimeDelta::minutes(10),
+                Some(now + TimeDelta::minutes(90)),
+            ),
+            error("current", "api_error", now - TimeDelta::minutes(3), None),
+            error("current", "api_error", now - TimeDelta::minutes(2), None),
+            error("other", "api_error", now - TimeDelta::minutes(1), None),
+        ])
+        .unwrap();
+
+        let vars = LimitProvider::new(Some("current".to_string()), Some(db_path.clone()))
+            .collect()
+            .unwrap();
+        // The limit was hit in another session but applies to this one too
+        let remaining: i64 = vars["limit_reset"].parse().unwrap();
+        assert!((5390..=5400).contains(&remaining), "{}", remaining);
+        assert!(!vars["limit_reset_at"].is_empty());
+        assert_eq!(vars["api_errors"], "2");
+
+        let vars = LimitProvider::new(Some("quiet".to_string()), Some(db_path))
+            .collect()
+            .unwrap();
+        assert_eq!(vars["api_errors"], "");
+    }
+
+    #[test]
+    fn test_limit_provider_without_database() {
+        let vars = LimitProvider::new(None, None).collect().unwrap();
+        assert_eq!(vars.len(), 3);
+        assert!(vars.values().all(|v| v.is_empty()));
+    }
+
+    #[test]
+    fn test_format_reset_at() {
+        use chrono::TimeZone;
+        let now = Local.with_ymd_and_hms(2026, 10, 17, 12, 10, 0).unwrap();
+        let today = Local.with_ymd_and_hms(2026, 10, 17, 15, 0, 0).unwrap();
+        let later = Local.with_ymd_and_hms(2026, 10, 20, 9, 0, 0).unwrap();
+
+        assert_eq!(format_reset_at(today, now), "15:00");
+        assert_eq!(format_reset_at(later, now), "Oct 20 09:00");
+    }
+}
@@ -26,6 +26,9 @@ use std::env;
 use std::io::{self, Read};
 use std::path::PathBuf;
 
+mod backfill;
+mod blocks;
+mod budget;
 mod commands;
 mod common;
 mod config;
@@ -33,19 +36,25 @@ mod context_learning;
 mod database;
 mod display;
 mod error;
+mod export;
+mod forecast;
 mod git;
-#[allow(dead_code)]
+mod git_cache;
+#[cfg(feature = "git_native")]
+mod git_native;
 mod git_provider;
 mod git_utils;
-#[allow(dead_code)]
 mod gsd;
 mod hook_handler;
 mod layout;
+mod limits;
 mod migrations;
 mod models;
+mod pricing;
 #[allow(dead_code)]
 mod provider;
 mod render;
+mod report;
 mod retry;
 mod session_state;
 mod stats;
@@ -53,9 +62,10 @@ mod stats;
 mod sync;
 mod theme;
 mod utils;
+mod vcs;
 mod version;
 
-use display::{format_output, Colors};
+use display::{format_input, Colors};
 use error::Result;
 use models::StatuslineInput;
 use version::version_string;
@@ -85,6 +95,10 @@ pub(crate) struct Cli {
     #[arg(long, value_name = "PATH")]
     config: Option<PathBuf>,
 
+    /// Fit each statusline line into this many columns (overrides [layout] width)
+    #[arg(long, value_name = "COLUMNS")]
+    width: Option<usize>,
+
     /// Set log level
     #[arg(long, value_name = "LEVEL", value_parser = ["error", "warn", "info", "debug", "trace"])]
     log_level: Option<String>,
@@ -195,6 +209,178 @@ enum Commands {
         #[command(subcommand)]
         action: HookAction,
     },
+
+    /// Forecast today's and this month's spend and token usage
+    Forecast {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// List past and active 5-hour usage blocks
+    Blocks {
+        /// Number of days to list
+        #[arg(long, default_value_t = 7)]
+        days: u32,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Report usage totals by day, week, month, model, project or device
+    Report {
+        /// Group rows by
+        #[arg(long, default_value = "day", value_parser = ["day", "week", "month", "model", "project", "device"])]
+        by: String,
+
+        /// First date to include (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE")]
+        since: Option<chrono::NaiveDate>,
+
+        /// Last date to include (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE")]
+        until: Option<chrono::NaiveDate>,
+
+        /// Output format
+        #[arg(long, default_value = "table", value_parser = ["table", "json", "csv", "markdown"])]
+        format: String,
+    },
+
+    /// Browse live sessions and archived work periods
+    Sessions {
+        #[command(subcommand)]
+        action: SessionsAction,
+    },
+
+    /// Import session history from outside the statusline
+    Import {
+        #[command(subcommand)]
+        action: ImportAction,
+    },
+
+    /// Export the stats database as NDJSON, JSON or CSV
+    Export {
+        /// Output format (import-data reads ndjson and json)
+        #[arg(long, default_value = "ndjson", value_parser = ["ndjson", "json", "csv"])]
+        format: String,
+
+        /// Tables to export, comma-separated (default: all)
+        #[arg(long, value_delimiter = ',', value_parser = ["sessions", "session_archive", "daily_stats", "monthly_stats", "learned_context_windows", "usage_entries", "session_errors", "session_daily_costs", "meta"])]
+        tables: Vec<String>,
+
+        /// Write to this file instead of stdout
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+
+    /// Add the rows of an export to the stats database
+    ///
+    /// Rows already present are kept (or overwritten with --replace), so importing
+    /// an export twice changes nothing. Daily and monthly totals are not added up:
+    /// a day or month both databases recorded keeps one side's totals, and a
+    /// warning lists the ones whose totals differed.
+    ImportData {
+        /// NDJSON or JSON export ("-" reads stdin)
+        file: PathBuf,
+
+        /// Only import these tables, comma-separated
+        #[arg(long, value_delimiter = ',', value_parser = ["sessions", "session_archive", "daily_stats", "monthly_stats", "learned_context_windows", "usage_entries", "session_errors", "session_daily_costs", "meta"])]
+        tables: Vec<String>,
+
+        /// Overwrite rows the database already has instead of keeping them
+        #[arg(long)]
+        replace: bool,
+    },
+
+    /// Spending budgets configured in the [budget] config section
+    Budget {
+        #[command(subcommand)]
+        action: BudgetAction,
+    },
+}
+
+#[derive(Subcommand)]
+pub(crate) enum BudgetAction {
+    /// Show spend against every configured budget
+    Status {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub(crate) enum SessionsAction {
+    /// List sessions, most recently updated first
+    List {
+        /// Only sessions in this workspace directory or below it
+        #[arg(long, value_name = "DIR")]
+        workspace: Option<String>,
+
+        /// Only sessions whose model name contains this text (ignoring case)
+        #[arg(long)]
+        model: Option<String>,
+
+        /// Only sessions last updated on or after this date (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE")]
+        since: Option<chrono::NaiveDate>,
+
+        /// Only sessions last updated on or before this date (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE")]
+        until: Option<chrono::NaiveDate>,
+
+        /// Include work periods archived by auto_reset mode
+        #[arg(long)]
+        archived: bool,
+
+        /// Sessions per page
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+
+        /// Page to show, starting at 1
+        #[arg(long, default_value_t = 1)]
+        page: usize,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Show a session's times, totals, burn rate per mode and archived work periods
+    Show {
+        /// Session ID, or the start of one
+        session_id: String,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// List sessions updated within the burn rate inactivity threshold
+    Active {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub(crate) enum ImportAction {
+    /// Rebuild past sessions from the Claude Code transcripts in ~/.claude/projects
+    Transcripts {
+        /// Only sessions active on or after this date (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE")]
+        since: Option<chrono::NaiveDate>,
+
+        /// List the sessions that would be imported without writing anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Transcript directory (default: $CLAUDE_CONFIG_DIR/projects or ~/.claude/projects)
+        #[arg(long, value_name = "DIR")]
+        projects_dir: Option<PathBuf>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -257,6 +443,11 @@ fn main() -> Result<()> {
         env::set_var("STATUSLINE_THEME", theme);
     }
 
+    // Handle width with precedence: CLI > env > config
+    if let Some(width) = cli.width {
+        env::set_var("STATUSLINE_WIDTH", width.to_string());
+    }
+
     // Handle config path if provided
     if let Some(ref config_path) = cli.config {
         env::set_var("STATUSLINE_CONFIG_PATH", config_path.display().to_string());
@@ -390,6 +581,51 @@ fn main() -> Result<()> {
             Commands::Hook { action } => {
                 return commands::hooks::handle_hook_command(action);
             }
+
+            Commands::Forecast { json } => {
+                return commands::forecast::show_forecast_report(json);
+            }
+
+            Commands::Blocks { days, json } => {
+                return commands::blocks::show_blocks_report(days, json);
+            }
+
+            Commands::Report {
+                by,
+                since,
+                until,
+                format,
+            } => {
+                return commands::report::show_usage_report(&by, since, until, &format);
+            }
+
+            Commands::Sessions { action } => {
+                return commands::sessions::handle_sessions_command(action);
+            }
+
+            Commands::Import { action } => {
+                return commands::import::handle_import_command(action);
+            }
+
+            Commands::Export {
+                format,
+                tables,
+                output,
+            } => {
+                return commands::export::export_data(&format, &tables, output);
+            }
+
+            Commands::ImportData {
+                file,
+                tables,
+                replace,
+            } => {
+                return commands::export::import_data(&file, &tables, replace);
+            }
+
+            Commands::Budget { action } => {
+                return commands::budget::handle_budget_command(action);
+            }
         }
     }
 
@@ -430,15 +666,10 @@ fn main() -> Result<()> {
     // implementation (see src/render.rs). The binary always updates stats.
     let daily_total = render::update_stats_and_daily_total(&input, true);
 
-    // Format and print output
-    format_output(
-        &current_dir,
-        input.model.as_ref().and_then(|m| m.display_name.as_deref()),
-        input.transcript.as_deref(),
-        input.cost.as_ref(),
-        daily_total,
-        input.session_id.as_deref(),
-    );
+    // Collect git/stats/GSD variables in parallel (per-provider timeouts), then
+    // format and print output
+    let provider_vars = render::collect_provider_vars(&input, &current_dir, daily_total, false);
+    format_input(&input, &current_dir, daily_total, &provider_vars);
 
     Ok(())
 }
@@ -91,6 +91,12 @@ impl MigrationRunner {
             Box::new(AddAdaptiveLearning),
             Box::new(AddBurnRateTracking),
             Box::new(AddDailyTokenTracking),
+            Box::new(AddSessionMetadata),
+            Box::new(AddComputedCost),
+            Box::new(AddCacheSavings),
+            Box::new(AddUsageEntries),
+            Box::new(AddSessionErrors),
+            Box::new(AddSessionDailyCosts),
         ]
     }
 
@@ -661,6 +667,251 @@ impl Migration for AddDailyTokenTracking {
     }
 }
 
+/// Migration 007: Add Claude Code session metadata (model id, project root, version, timing)
+pub struct AddSessionMetadata;
+
+impl Migration for AddSessionMetadata {
+    fn version(&self) -> u32 {
+        7
+    }
+
+    fn description(&self) -> &str {
+        "Add Claude Code metadata columns (model_id, project_dir, version, output style, API/wall time) to sessions"
+    }
+
+    fn up(&self, tx: &Transaction) -> Result<()> {
+        // Identity of the session as reported by Claude Code
+        tx.execute("ALTER TABLE sessions ADD COLUMN model_id TEXT", [])?;
+        tx.execute("ALTER TABLE sessions ADD COLUMN project_dir TEXT", [])?;
+        tx.execute("ALTER TABLE sessions ADD COLUMN claude_version TEXT", [])?;
+        tx.execute("ALTER TABLE sessions ADD COLUMN output_style TEXT", [])?;
+
+        // Timing reported by Claude Code (cumulative for the session, in milliseconds)
+        tx.execute(
+            "ALTER TABLE sessions ADD COLUMN total_duration_ms INTEGER",
+            [],
+        )?;
+        tx.execute(
+            "ALTER TABLE sessions ADD COLUMN total_api_duration_ms INTEGER",
+            [],
+        )?;
+
+        // Project-level queries (e.g. per-project reports)
+        tx.execute(
+            "CREATE INDEX IF NOT EXISTS idx_sessions_project ON sessions(project_dir)",
+            [],
+        )?;
+
+        Ok(())
+    }
+
+    fn down(&self, tx: &Transaction) -> Result<()> {
+        tx.execute("DROP INDEX IF EXISTS idx_sessions_project", [])?;
+
+        // Note: SQLite doesn't support DROP COLUMN easily
+        // Metadata columns remain but that's acceptable for backward compatibility
+        Ok(())
+    }
+}
+
+/// Migration 008: Add token-derived cost to sessions and the daily/monthly aggregates
+pub struct AddComputedCost;
+
+impl Migration for AddComputedCost {
+    fn version(&self) -> u32 {
+        8
+    }
+
+    fn description(&self) -> &str {
+        "Add computed_cost (cost priced from transcript token usage) to sessions, daily_stats and monthly_stats"
+    }
+
+    fn up(&self, tx: &Transaction) -> Result<()> {
+        tx.execute(
+            "ALTER TABLE sessions ADD COLUMN computed_cost REAL DEFAULT 0.0",
+            [],
+        )?;
+        tx.execute(
+            "ALTER TABLE daily_stats ADD COLUMN computed_cost REAL DEFAULT 0.0",
+            [],
+        )?;
+        tx.execute(
+            "ALTER TABLE monthly_stats ADD COLUMN computed_cost REAL DEFAULT 0.0",
+            [],
+        )?;
+        Ok(())
+    }
+
+    fn down(&self, _tx: &Transaction) -> Result<()> {
+        // Note: SQLite doesn't support DROP COLUMN easily
+        // Cost columns remain but that's acceptable for backward compatibility
+        Ok(())
+    }
+}
+
+/// Migration 009: Add prompt-cache savings and cache write spend to sessions and the aggregates
+pub struct AddCacheSavings;
+
+impl Migration for AddCacheSavings {
+    fn version(&self) -> u32 {
+        9
+    }
+
+    fn description(&self) -> &str {
+        "Add cache_saved and cache_write_cost (dollar value of prompt caching) to sessions, daily_stats and monthly_stats"
+    }
+
+    fn up(&self, tx: &Transaction) -> Result<()> {
+        for table in ["sessions", "daily_stats", "monthly_stats"] {
+            tx.execute(
+                &format!(
+                    "ALTER TABLE {} ADD COLUMN cache_saved REAL DEFAULT 0.0",
+                    table
+                ),
+                [],
+            )?;
+            tx.execute(
+                &format!(
+                    "ALTER TABLE {} ADD COLUMN cache_write_cost REAL DEFAULT 0.0",
+                    table
+                ),
+                [],
+            )?;
+        }
+        Ok(())
+    }
+
+    fn down(&self, _tx: &Transaction) -> Result<()> {
+        // Note: SQLite doesn't support DROP COLUMN easily
+        // Savings columns remain but that's acceptable for backward compatibility
+        Ok(())
+    }
+}
+
+/// Migration 010: Add per-response usage entries for 5-hour usage blocks
+pub struct AddUsageEntries;
+
+impl Migration for AddUsageEntries {
+    fn version(&self) -> u32 {
+        10
+    }
+
+    fn description(&self) -> &str {
+        "Add usage_entries table (timestamped per-response token usage and cost) for usage blocks"
+    }
+
+    fn up(&self, tx: &Transaction) -> Result<()> {
+        tx.execute(
+            "CREATE TABLE IF NOT EXISTS usage_entries (
+                message_id TEXT PRIMARY KEY,
+                session_id TEXT NOT NULL,
+                timestamp TEXT NOT NULL,
+                model TEXT,
+                input_tokens INTEGER DEFAULT 0,
+                output_tokens INTEGER DEFAULT 0,
+                cache_write_tokens INTEGER DEFAULT 0,
+                cache_read_tokens INTEGER DEFAULT 0,
+                cost REAL DEFAULT 0.0
+            )",
+            [],
+        )?;
+        tx.execute(
+            "CREATE INDEX IF NOT EXISTS idx_usage_entries_timestamp ON usage_entries(timestamp)",
+            [],
+        )?;
+        Ok(())
+    }
+
+    fn down(&self, tx: &Transaction) -> Result<()> {
+        tx.execute("DROP INDEX IF EXISTS idx_usage_entries_timestamp", [])?;
+        tx.execute("DROP TABLE IF EXISTS usage_entries", [])?;
+        Ok(())
+    }
+}
+
+/// Migration 011: Add usage limits and API errors found in session transcripts
+pub struct AddSessionErrors;
+
+impl Migration for AddSessionErrors {
+    fn version(&self) -> u32 {
+        11
+    }
+
+    fn description(&self) -> &str {
+        "Add session_errors table (usage limits and API errors found in transcripts)"
+    }
+
+    fn up(&self, tx: &Transaction) -> Result<()> {
+        tx.execute(
+            "CREATE TABLE IF NOT EXISTS session_errors (
+                session_id TEXT NOT NULL,
+                timestamp TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                message TEXT,
+                reset_at TEXT,
+                PRIMARY KEY (session_id, timestamp, kind)
+            )",
+            [],
+        )?;
+        tx.execute(
+            "CREATE INDEX IF NOT EXISTS idx_session_errors_kind ON session_errors(kind, reset_at)",
+            [],
+        )?;
+        Ok(())
+    }
+
+    fn down(&self, tx: &Transaction) -> Result<()> {
+        tx.execute("DROP INDEX IF EXISTS idx_session_errors_kind", [])?;
+        tx.execute("DROP TABLE IF EXISTS session_errors", [])?;
+        Ok(())
+    }
+}
+
+/// Migration 012: Add the cost each session added per day
+pub struct AddSessionDailyCosts;
+
+impl Migration for AddSessionDailyCosts {
+    fn version(&self) -> u32 {
+        12
+    }
+
+    fn description(&self) -> &str {
+        "Add session_daily_costs table (per-session cost by day) for per-directory budgets"
+    }
+
+    fn up(&self, tx: &Transaction) -> Result<()> {
+        tx.execute(
+            "CREATE TABLE IF NOT EXISTS session_daily_costs (
+                session_id TEXT NOT NULL,
+                date TEXT NOT NULL,
+                cost REAL DEFAULT 0.0,
+                computed_cost REAL DEFAULT 0.0,
+                PRIMARY KEY (session_id, date)
+            )",
+            [],
+        )?;
+        tx.execute(
+            "CREATE INDEX IF NOT EXISTS idx_session_daily_costs_date ON session_daily_costs(date)",
+            [],
+        )?;
+        // Earlier days are unknown; attribute existing sessions to their last active day
+        tx.execute(
+            "INSERT OR IGNORE INTO session_daily_costs (session_id, date, cost, computed_cost)
+             SELECT session_id, substr(last_updated, 1, 10), COALESCE(cost, 0.0),
+                    COALESCE(computed_cost, 0.0)
+             FROM sessions",
+            [],
+        )?;
+        Ok(())
+    }
+
+    fn down(&self, tx: &Transaction) -> Result<()> {
+        tx.execute("DROP INDEX IF EXISTS idx_session_daily_costs_date", [])?;
+        tx.execute("DROP TABLE IF EXISTS session_daily_costs", [])?;
+        Ok(())
+    }
+}
+
 /// Run migrations on a specific database path
 /// Returns Err only on critical failures that prevent migrations from running
 pub fn run_migrations_on_db(db_path: &Path) -> Result<()> {
@@ -690,9 +941,11 @@ mod tests {
         assert_eq!(runner.current_version().unwrap(), 0);
 
         runner.migrate().unwrap();
-        // We now have 6 migrations: InitialJsonToSqlite (v1), AddMetaTable (v2), AddSyncMetadata (v3),
-        // AddAdaptiveLearning (v4), AddBurnRateTracking (v5), AddDailyTokenTracking (v6)
-        assert_eq!(runner.current_version().unwrap(), 6);
+        // We now have 12 migrations: InitialJsonToSqlite (v1), AddMetaTable (v2), AddSyncMetadata (v3),
+        // AddAdaptiveLearning (v4), AddBurnRateTracking (v5), AddDailyTokenTracking (v6),
+        // AddSessionMetadata (v7), AddComputedCost (v8), AddCacheSavings (v9),
+        // AddUsageEntries (v10), AddSessionErrors (v11), AddSessionDailyCosts (v12)
+        assert_eq!(runner.current_version().unwrap(), 12);
     }
 
     #[test]
@@ -795,8 +1048,8 @@ mod tests {
         let mut runner = MigrationRunner::new(&db_path).unwrap();
         runner.migrate().unwrap();
 
-        // Verify version is 6
-        assert_eq!(runner.current_version().unwrap(), 6);
+        // Verify all migrations ran (token tracking is v6)
+        assert_eq!(runner.current_version().unwrap(), 12);
 
         // Verify token columns were added to daily_stats
         let daily_columns: Vec<String> = runner
@@ -872,4 +1125,38 @@ mod tests {
             "idx_monthly_tokens index should exist"
         );
     }
+
+    #[test]
+    fn test_session_metadata_migration() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test_metadata.db");
+
+        let mut runner = MigrationRunner::new(&db_path).unwrap();
+        runner.migrate().unwrap();
+        assert_eq!(runner.current_version().unwrap(), 12);
+
+        let sessions_columns: Vec<String> = runner
+            .conn
+            .prepare("PRAGMA table_info(sessions)")
+            .unwrap()
+            .query_map([], |row| row.get::<_, String>(1))
+            .unwrap()
+            .filter_map(|r| r.ok())
+            .collect();
+
+        for column in [
+            "model_id",
+            "project_dir",
+            "claude_version",
+            "output_style",
+            "total_duration_ms",
+            "total_api_duration_ms",
+        ] {
+            assert!(
+                sessions_columns.contains(&column.to_string()),
+                "sessions table should have {} column",
+                column
+            );
+        }
+    }
 }
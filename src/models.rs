@@ -24,39 +24,75 @@ pub struct StatuslineInput {
     pub transcript: Option<String>,
     /// Cost and metrics information
     pub cost: Option<Cost>,
+    /// Hook event that triggered this render (always "Status" for statuslines)
+    #[allow(dead_code)] // Public API - used by library consumers
+    pub hook_event_name: Option<String>,
+    /// Claude Code version (e.g., "1.0.80")
+    pub version: Option<String>,
+    /// Active output style
+    pub output_style: Option<OutputStyle>,
+    /// Whether the last request exceeded 200k total tokens
+    pub exceeds_200k_tokens: Option<bool>,
 }
 
 /// Workspace information from Claude Code.
 ///
-/// Contains the current working directory path.
-#[derive(Debug, Deserialize)]
+/// Contains the current working directory and the project root Claude Code was launched in.
+#[derive(Debug, Default, Deserialize)]
 pub struct Workspace {
     /// Current working directory path
     pub current_dir: Option<String>,
+    /// Directory Claude Code was started in (project root)
+    pub project_dir: Option<String>,
 }
 
 /// Model information from Claude Code.
 ///
-/// Contains the display name of the current Claude model being used.
-#[derive(Debug, Deserialize)]
+/// Contains the identifier and display name of the current Claude model being used.
+#[derive(Debug, Default, Deserialize)]
 pub struct Model {
+    /// Model identifier (e.g., "claude-opus-4-1")
+    pub id: Option<String>,
     /// Display name of the Claude model (e.g., "Claude 3.5 Sonnet")
     pub display_name: Option<String>,
 }
 
 /// Cost and metrics information.
 ///
-/// Tracks the total cost in USD and code change metrics for the current session.
-#[derive(Debug, Deserialize)]
+/// Tracks the total cost in USD, timing, and code change metrics for the current session.
+#[derive(Debug, Default, Deserialize)]
 pub struct Cost {
     /// Total cost in USD for the session
     pub total_cost_usd: Option<f64>,
+    /// Wall-clock time since the session started, in milliseconds
+    pub total_duration_ms: Option<u64>,
+    /// Time spent waiting on API responses, in milliseconds
+    pub total_api_duration_ms: Option<u64>,
     /// Total lines of code added
     pub total_lines_added: Option<u64>,
     /// Total lines of code removed
     pub total_lines_removed: Option<u64>,
 }
 
+impl Cost {
+    /// Fraction of wall-clock time spent waiting on the API, as a percentage.
+    ///
+    /// Returns `None` when either duration is missing or the wall time is zero.
+    pub fn api_time_percentage(&self) -> Option<f64> {
+        match (self.total_api_duration_ms, self.total_duration_ms) {
+            (Some(api), Some(wall)) if wall > 0 => Some((api as f64 / wall as f64) * 100.0),
+            _ => None,
+        }
+    }
+}
+
+/// Output style information from Claude Code.
+#[derive(Debug, Default, Deserialize)]
+pub struct OutputStyle {
+    /// Output style name (e.g., "default", "Explanatory")
+    pub name: Option<String>,
+}
+
 /// Token usage breakdown from transcript.
 ///
 /// Contains detailed token counts for cost analysis and cache efficiency tracking.
@@ -231,6 +267,12 @@ pub struct TranscriptEntry {
 /// Message within a transcript entry
 #[derive(Debug, Deserialize)]
 pub struct TranscriptMessage {
+    /// API message id; repeated on every transcript line written for one response
+    #[serde(default)]
+    pub id: Option<String>,
+    /// Model that produced the message (e.g. "claude-sonnet-4-5-20250929")
+    #[serde(default)]
+    pub model: Option<String>,
     /// Role of the message sender (user, assistant, etc.)
     pub role: String,
     /// Message content (can be string or array)
@@ -242,6 +284,44 @@ pub struct TranscriptMessage {
     pub usage: Option<Usage>,
 }
 
+/// Any transcript line, read leniently to find usage limits and API errors
+///
+/// Unlike [`TranscriptEntry`], every field is optional: system entries carry their
+/// text in `content` and have no `message`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptRecord {
+    /// Entry type ("assistant", "user", "system", ...)
+    #[serde(rename = "type", default)]
+    pub kind: Option<String>,
+    /// ISO 8601 formatted timestamp
+    #[serde(default)]
+    pub timestamp: Option<String>,
+    /// Set by Claude Code on assistant entries it wrote for a failed API request
+    #[serde(default)]
+    pub is_api_error_message: bool,
+    /// Severity of system entries ("info", "warning", "error")
+    #[serde(default)]
+    pub level: Option<String>,
+    /// Text of system entries
+    #[serde(default)]
+    pub content: Option<serde_json::Value>,
+    /// Message of assistant and user entries
+    #[serde(default)]
+    pub message: Option<TranscriptRecordMessage>,
+}
+
+/// Message within a [`TranscriptRecord`]
+#[derive(Debug, Default, Deserialize)]
+pub struct TranscriptRecordMessage {
+    /// Model, "<synthetic>" for messages Claude Code wrote itself
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Message content (can be string or array)
+    #[serde(default)]
+    pub content: Option<serde_json::Value>,
+}
+
 /// Token usage information from Claude
 #[derive(Debug, Deserialize)]
 pub struct Usage {
@@ -253,6 +333,18 @@ pub struct Usage {
     pub cache_read_input_tokens: Option<u32>,
     /// Number of tokens used to create cache
     pub cache_creation_input_tokens: Option<u32>,
+    /// Cache writes split by cache lifetime (newer Claude Code versions)
+    #[serde(default)]
+    pub cache_creation: Option<CacheCreation>,
+}
+
+/// Cache write tokens by time-to-live, priced differently
+#[derive(Debug, Deserialize)]
+pub struct CacheCreation {
+    /// Tokens written to the 5-minute cache
+    pub ephemeral_5m_input_tokens: Option<u32>,
+    /// Tokens written to the 1-hour cache
+    pub ephemeral_1h_input_tokens: Option<u32>,
 }
 
 /// Context window usage information
@@ -329,6 +421,69 @@ mod tests {
         assert_eq!(input.cost.unwrap().total_cost_usd.unwrap(), 2.50);
     }
 
+    #[test]
+    fn test_parse_full_claude_code_schema() {
+        let json = r#"{
+            "hook_event_name": "Status",
+            "session_id": "abc123",
+            "transcript_path": "/tmp/transcript.jsonl",
+            "cwd": "/home/user/project/src",
+            "model": {"id": "claude-opus-4-1", "display_name": "Opus"},
+            "workspace": {
+                "current_dir": "/home/user/project/src",
+                "project_dir": "/home/user/project"
+            },
+            "version": "1.0.80",
+            "output_style": {"name": "Explanatory"},
+            "cost": {
+                "total_cost_usd": 0.01234,
+                "total_duration_ms": 45000,
+                "total_api_duration_ms": 2300,
+                "total_lines_added": 156,
+                "total_lines_removed": 23
+            },
+            "exceeds_200k_tokens": true
+        }"#;
+        let input: StatuslineInput = serde_json::from_str(json).unwrap();
+        assert_eq!(input.hook_event_name.as_deref(), Some("Status"));
+        assert_eq!(input.version.as_deref(), Some("1.0.80"));
+        assert_eq!(
+            input.output_style.unwrap().name.as_deref(),
+            Some("Explanatory")
+        );
+        assert_eq!(input.exceeds_200k_tokens, Some(true));
+        assert_eq!(input.transcript.as_deref(), Some("/tmp/transcript.jsonl"));
+
+        let workspace = input.workspace.unwrap();
+        assert_eq!(workspace.project_dir.as_deref(), Some("/home/user/project"));
+
+        let model = input.model.unwrap();
+        assert_eq!(model.id.as_deref(), Some("claude-opus-4-1"));
+        assert_eq!(model.display_name.as_deref(), Some("Opus"));
+
+        let cost = input.cost.unwrap();
+        assert_eq!(cost.total_duration_ms, Some(45000));
+        assert_eq!(cost.total_api_duration_ms, Some(2300));
+        let pct = cost.api_time_percentage().unwrap();
+        assert!((pct - 5.111).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_api_time_percentage_requires_wall_time() {
+        let cost = Cost {
+            total_api_duration_ms: Some(1000),
+            total_duration_ms: Some(0),
+            ..Default::default()
+        };
+        assert!(cost.api_time_percentage().is_none());
+
+        let cost = Cost {
+            total_api_duration_ms: Some(1000),
+            ..Default::default()
+        };
+        assert!(cost.api_time_percentage().is_none());
+    }
+
     #[test]
     fn test_model_type_detection() {
         // Test Opus detection
@@ -0,0 +1,632 @@
+//! Model pricing and token-derived cost.
+//!
+//! Claude Code reports a session cost (`cost.total_cost_usd`), but it is an
+//! estimate that subscription users cannot relate to anything and that cannot be
+//! broken down. This module prices the token usage recorded in the transcript
+//! instead, using a built-in per-model rate table that `[pricing.models]` in the
+//! config file can override or extend.
+//!
+//! Transcripts only grow, so usage is read incrementally: the byte offset of the
+//! last complete line and the per-model totals so far are kept under
+//! `<cache dir>/claudia-statusline/transcript-usage/`, and each render only parses
+//! the lines appended since. Costs are computed from those totals on demand, so
+//! rate changes apply retroactively.
+//!
+//! The same totals give the dollar value of prompt caching ([`CacheSavings`]):
+//! what the usage would have cost if every cached token had been billed as
+//! regular input, compared with what it did cost.
+
+use crate::common::{cache_file_name, get_cache_dir, write_cache_file};
+use crate::config;
+use crate::models::{TranscriptEntry, Usage};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::Path;
+
+/// Bumped whenever the cursor layout or the way usage is counted changes.
+const CURSOR_VERSION: u32 = 1;
+
+/// Message ids remembered for de-duplication. Claude Code writes one transcript
+/// line per content block, all carrying the same id and usage, and they are
+/// always adjacent, so a short window is enough.
+const RECENT_IDS: usize = 64;
+
+/// Cache write (5 minute TTL) price as a multiple of the input price.
+pub const CACHE_WRITE_5M_MULTIPLIER: f64 = 1.25;
+/// Cache write (1 hour TTL) price as a multiple of the input price.
+pub const CACHE_WRITE_1H_MULTIPLIER: f64 = 2.0;
+/// Cache read price as a multiple of the input price.
+pub const CACHE_READ_MULTIPLIER: f64 = 0.1;
+
+/// Built-in input and output prices in USD per million tokens.
+///
+/// Patterns are matched as substrings of the normalized model id or display
+/// name (see [`normalize_model`]), in order, so more specific entries come first.
+const BUILTIN_RATES: &[(&str, f64, f64)] = &[
+    ("opus-4-5", 5.0, 25.0),
+    ("opus", 15.0, 75.0),
+    ("sonnet", 3.0, 15.0),
+    ("haiku-4-5", 1.0, 5.0),
+    ("3-5-haiku", 0.8, 4.0),
+    ("haiku-3-5", 0.8, 4.0),
+    ("haiku", 0.25, 1.25),
+];
+
+/// Prices for one model, in USD per million tokens.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModelRates {
+    pub input: f64,
+    pub output: f64,
+    pub cache_write_5m: f64,
+    pub cache_write_1h: f64,
+    pub cache_read: f64,
+}
+
+impl ModelRates {
+    /// Rates with the standard cache multipliers applied to the input price.
+    pub fn from_base(input: f64, output: f64) -> Self {
+        ModelRates {
+            input,
+            output,
+            cache_write_5m: input * CACHE_WRITE_5M_MULTIPLIER,
+            cache_write_1h: input * CACHE_WRITE_1H_MULTIPLIER,
+            cache_read: input * CACHE_READ_MULTIPLIER,
+        }
+    }
+}
+
+impl From<&config::ModelPricing> for ModelRates {
+    fn from(pricing: &config::ModelPricing) -> Self {
+        let base = ModelRates::from_base(pricing.input, pricing.output);
+        ModelRates {
+            cache_write_5m: pricing.cache_write_5m.unwrap_or(base.cache_write_5m),
+            cache_write_1h: pricing.cache_write_1h.unwrap_or(base.cache_write_1h),
+            cache_read: pricing.cache_read.unwrap_or(base.cache_read),
+            ..base
+        }
+    }
+}
+
+/// Token counts by billing category.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub input: u64,
+    pub output: u64,
+    pub cache_write_5m: u64,
+    pub cache_write_1h: u64,
+    pub cache_read: u64,
+}
+
+impl TokenUsage {
+    /// Adds one API response's usage.
+    ///
+    /// Older transcripts only carry `cache_creation_input_tokens`; those writes
+    /// are counted as 5-minute writes, the Claude Code default.
+    pub fn add(&mut self, usage: &Usage) {
+        self.input += usage.input_tokens.unwrap_or(0) as u64;
+        self.output += usage.output_tokens.unwrap_or(0) as u64;
+        self.cache_read += usage.cache_read_input_tokens.unwrap_or(0) as u64;
+        match &usage.cache_creation {
+            Some(split) => {
+                self.cache_write_5m += split.ephemeral_5m_input_tokens.unwrap_or(0) as u64;
+                self.cache_write_1h += split.ephemeral_1h_input_tokens.unwrap_or(0) as u64;
+            }
+            None => {
+                self.cache_write_5m += usage.cache_creation_input_tokens.unwrap_or(0) as u64;
+            }
+        }
+    }
+
+    /// Cost of this usage in USD.
+    pub fn cost(&self, rates: &ModelRates) -> f64 {
+        (self.input as f64 * rates.input
+            + self.output as f64 * rates.output
+            + self.cache_read as f64 * rates.cache_read)
+            / 1_000_000.0
+            + self.cache_write_cost(rates)
+    }
+
+    /// Part of the cost spent writing to the prompt cache, in USD.
+    pub fn cache_write_cost(&self, rates: &ModelRates) -> f64 {
+        (self.cache_write_5m as f64 * rates.cache_write_5m
+            + self.cache_write_1h as f64 * rates.cache_write_1h)
+            / 1_000_000.0
+    }
+
+    /// Cost in USD had nothing been cached: cache reads and writes billed as input.
+    pub fn uncached_cost(&self, rates: &ModelRates) -> f64 {
+        let input = self.input + self.cache_write_5m + self.cache_write_1h + self.cache_read;
+        (input as f64 * rates.input + self.output as f64 * rates.output) / 1_000_000.0
+    }
+
+    /// Dollar effect of prompt caching on this usage.
+    pub fn cache_savings(&self, rates: &ModelRates) -> CacheSavings {
+        CacheSavings {
+            saved: self.uncached_cost(rates) - self.cost(rates),
+            write_cost: self.cache_write_cost(rates),
+        }
+    }
+}
+
+/// Dollar effect of prompt caching.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CacheSavings {
+    /// Uncached cost minus actual cost. Cache reads save 90% of the input price,
+    /// while the write premium is subtracted, so this is negative when a cache
+    /// is written but rarely read.
+    pub saved: f64,
+    /// Spent on cache writes (5-minute and 1-hour)
+    pub write_cost: f64,
+}
+
+impl std::ops::Add for CacheSavings {
+    type Output = CacheSavings;
+
+    fn add(self, other: CacheSavings) -> CacheSavings {
+        CacheSavings {
+            saved: self.saved + other.saved,
+            write_cost: self.write_cost + other.write_cost,
+        }
+    }
+}
+
+/// Token usage of a transcript, per model.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptUsage {
+    pub by_model: BTreeMap<String, TokenUsage>,
+}
+
+impl TranscriptUsage {
+    /// Cost of all usage in USD, using the configured rates.
+    ///
+    /// Usage of models without a known price is left out.
+    pub fn cost(&self) -> f64 {
+        self.cost_with(rates_for_model)
+    }
+
+    /// Dollar effect of prompt caching on all usage, using the configured rates.
+    pub fn cache_savings(&self) -> CacheSavings {
+        self.cache_savings_with(rates_for_model)
+    }
+
+    fn cost_with(&self, rates_for: impl Fn(&str) -> Option<ModelRates>) -> f64 {
+        self.priced(rates_for)
+            .map(|(usage, rates)| usage.cost(&rates))
+            .sum()
+    }
+
+    fn cache_savings_with(&self, rates_for: impl Fn(&str) -> Option<ModelRates>) -> CacheSavings {
+        self.priced(rates_for)
+            .map(|(usage, rates)| usage.cache_savings(&rates))
+            .fold(CacheSavings::default(), |total, savings| total + savings)
+    }
+
+    /// Usage of each model that has a price, with its rates.
+    fn priced<'a>(
+        &'a self,
+        rates_for: impl Fn(&str) -> Option<ModelRates> + 'a,
+    ) -> impl Iterator<Item = (&'a TokenUsage, ModelRates)> + 'a {
+        self.by_model
+            .iter()
+            .filter_map(move |(model, usage)| match rates_for(model) {
+                Some(rates) => Some((usage, rates)),
+                None => {
+                    log::debug!("No pricing for model '{}', usage not costed", model);
+                    None
+                }
+            })
+    }
+}
+
+/// Normalizes a model id or display name for matching: lowercase, with dots
+/// and spaces replaced by dashes ("Opus 4.5" and "claude-opus-4-5-20251101"
+/// both contain "opus-4-5").
+pub fn normalize_model(model: &str) -> String {
+    model
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| if c == '.' || c == ' ' { '-' } else { c })
+        .collect()
+}
+
+/// Looks up the rates for a model id or display name.
+///
+/// Entries in `[pricing.models]` take precedence over the built-in table; among
+/// them the longest matching key wins.
+pub fn rates_for_model(model: &str) -> Option<ModelRates> {
+    lookup_rates(model, &config::get_config().pricing.models)
+}
+
+fn lookup_rates(
+    model: &str,
+    overrides: &HashMap<String, config::ModelPricing>,
+) -> Option<ModelRates> {
+    let normalized = normalize_model(model);
+    if normalized.is_empty() {
+        return None;
+    }
+
+    let configured = overrides
+        .iter()
+        .map(|(key, pricing)| (normalize_model(key), pricing))
+        .filter(|(key, _)| !key.is_empty() && normalized.contains(key.as_str()))
+        .max_by_key(|(key, _)| key.len());
+    if let Some((_, pricing)) = configured {
+        return Some(ModelRates::from(pricing));
+    }
+
+    BUILTIN_RATES
+        .iter()
+        .find(|(pattern, _, _)| normalized.contains(pattern))
+        .map(|&(_, input, output)| ModelRates::from_base(input, output))
+}
+
+/// Relative difference of `computed` from `reported`, in percent.
+///
+/// `None` when there is no meaningful reported cost to compare against.
+pub fn divergence_pct(computed: f64, reported: f64) -> Option<f64> {
+    (reported >= 0.01).then(|| (computed - reported) / reported * 100.0)
+}
+
+/// Reads the token usage of a transcript, parsing only lines appended since
+/// the previous call.
+pub fn transcript_usage(transcript_path: &str) -> Option<TranscriptUsage> {
+    let path = crate::utils::validate_transcript_file(transcript_path).ok()?;
+    match get_cache_dir("transcript-usage") {
+        Some(dir) => read_usage_in(&dir, &path),
+        None => read_usage(&path, UsageCursor::default()).map(|cursor| cursor.usage),
+    }
+}
+
+/// Position reached in a transcript and the usage counted up to it.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct UsageCursor {
+    version: u32,
+    /// Byte offset just past the last complete line consumed
+    offset: u64,
+    /// Most recently counted message ids, oldest first
+    recent_ids: VecDeque<String>,
+    usage: TranscriptUsage,
+}
+
+fn read_usage_in(dir: &Path, path: &Path) -> Option<TranscriptUsage> {
+    let cursor_path = dir.join(cursor_file_name(path));
+    let cursor = fs::read_to_string(&cursor_path)
+        .ok()
+        .and_then(|contents| serde_json::from_str::<UsageCursor>(&contents).ok())
+        .filter(|cursor| cursor.version == CURSOR_VERSION)
+        .unwrap_or_default();
+    let start = cursor.offset;
+
+    let cursor = read_usage(path, cursor)?;
+    if cursor.offset != start {
+        if let Err(e) = store(&cursor_path, &cursor) {
+            log::debug!(
+                "Failed to write transcript usage cursor {}: {}",
+                cursor_path.display(),
+                e
+            );
+        }
+    }
+    Some(cursor.usage)
+}
+
+/// Advances `cursor` over the complete lines appended to the transcript.
+///
+/// A transcript shorter than the cursor offset was replaced, so it is re-read
+/// from the start. A trailing line without a newline is still being written and
+/// is left for the next call.
+fn read_usage(path: &Path, mut cursor: UsageCursor) -> Option<UsageCursor> {
+    let mut file = File::open(path).ok()?;
+    let len = file.metadata().ok()?.len();
+    if len < cursor.offset {
+        cursor = UsageCursor::default();
+    }
+    cursor.version = CURSOR_VERSION;
+    file.seek(SeekFrom::Start(cursor.offset)).ok()?;
+
+    let mut reader = BufReader::new(file);
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        let read = reader.read_until(b'\n', &mut line).ok()?;
+        if read == 0 || line.last() != Some(&b'\n') {
+            break;
+        }
+        cursor.offset += read as u64;
+        count_line(&line, &mut cursor);
+    }
+
+    Some(cursor)
+}
+
+fn count_line(line: &[u8], cursor: &mut UsageCursor) {
+    let Ok(entry) = serde_json::from_slice::<TranscriptEntry>(line) else {
+        return;
+    };
+    let message = entry.message;
+    if message.role != "assistant" {
+        return;
+    }
+    let Some(usage) = message.usage else {
+        return;
+    };
+
+    if let Some(id) = message.id {
+        if cursor.recent_ids.contains(&id) {
+            return;
+        }
+        if cursor.recent_ids.len() == RECENT_IDS {
+            cursor.recent_ids.pop_front();
+        }
+        cursor.recent_ids.push_back(id);
+    }
+
+    let model = message.model.unwrap_or_else(|| "unknown".to_string());
+    cursor.usage.by_model.entry(model).or_default().add(&usage);
+}
+
+/// Writes the cursor atomically.
+fn store(path: &Path, cursor: &UsageCursor) -> std::io::Result<()> {
+    write_cache_file(path, &serde_json::to_vec(cursor)?)
+}
+
+/// One file per transcript, named by a hash of its canonical path.
+fn cursor_file_name(path: &Path) -> String {
+    cache_file_name(path, ".json")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn assistant_line(id: &str, model: &str, usage: &str) -> String {
+        format!(
+            r#"{{"message":{{"id":"{}","model":"{}","role":"assistant","content":[],"usage":{}}},"timestamp":"2025-11-01T10:00:00.000Z"}}"#,
+            id, model, usage
+        )
+    }
+
+    fn approx(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn test_builtin_rates_most_specific_first() {
+        let none = HashMap::new();
+        let opus45 = lookup_rates("claude-opus-4-5-20251101", &none).unwrap();
+        assert_eq!((opus45.input, opus45.output), (5.0, 25.0));
+        let opus41 = lookup_rates("claude-opus-4-1-20250805", &none).unwrap();
+        assert_eq!((opus41.input, opus41.output), (15.0, 75.0));
+        let haiku35 = lookup_rates("claude-3-5-haiku-20241022", &none).unwrap();
+        assert_eq!(haiku35.input, 0.8);
+        let haiku45 = lookup_rates("Haiku 4.5", &none).unwrap();
+        assert_eq!(haiku45.input, 1.0);
+
+        let sonnet = lookup_rates("Sonnet 4.5", &none).unwrap();
+        assert!(approx(sonnet.cache_write_5m, 3.75));
+        assert!(approx(sonnet.cache_write_1h, 6.0));
+        assert!(approx(sonnet.cache_read, 0.3));
+
+        assert!(lookup_rates("gpt-4o", &none).is_none());
+        assert!(lookup_rates("", &none).is_none());
+    }
+
+    #[test]
+    fn test_configured_rates_override_builtin() {
+        let mut overrides = HashMap::new();
+        overrides.insert(
+            "sonnet".to_string(),
+            config::ModelPricing {
+                input: 6.0,
+                output: 22.5,
+                cache_write_5m: None,
+                cache_write_1h: None,
+                cache_read: Some(0.5),
+            },
+        );
+        overrides.insert(
+            "sonnet-4-5".to_string(),
+            config::ModelPricing {
+                input: 4.0,
+                output: 20.0,
+                cache_write_5m: None,
+                cache_write_1h: None,
+                cache_read: None,
+            },
+        );
+
+        // Longest matching key wins
+        let rates = lookup_rates("claude-sonnet-4-5-20250929", &overrides).unwrap();
+        assert_eq!((rates.input, rates.output), (4.0, 20.0));
+
+        let rates = lookup_rates("claude-sonnet-4-20250514", &overrides).unwrap();
+        assert_eq!((rates.input, rates.output), (6.0, 22.5));
+        assert!(approx(rates.cache_write_5m, 7.5));
+        assert!(approx(rates.cache_read, 0.5));
+
+        // Models without an override still use the built-in table
+        assert_eq!(
+            lookup_rates("claude-opus-4-5", &overrides).unwrap().input,
+            5.0
+        );
+    }
+
+    #[test]
+    fn test_token_usage_cost() {
+        let usage = TokenUsage {
+            input: 1_000_000,
+            output: 100_000,
+            cache_write_5m: 200_000,
+            cache_write_1h: 100_000,
+            cache_read: 2_000_000,
+        };
+        let rates = ModelRates::from_base(3.0, 15.0);
+        // 3.00 + 1.50 + 0.75 + 0.60 + 0.60
+        assert!(approx(usage.cost(&rates), 6.45));
+        assert_eq!(TokenUsage::default().cost(&rates), 0.0);
+    }
+
+    #[test]
+    fn test_cache_savings() {
+        let rates = ModelRates::from_base(3.0, 15.0);
+        let usage = TokenUsage {
+            input: 0,
+            output: 0,
+            cache_write_5m: 100_000,
+            cache_write_1h: 0,
+            cache_read: 1_000_000,
+        };
+        // Uncached: 1.1M input at $3 = 3.30. Actual: 0.375 writes + 0.30 reads
+        let savings = usage.cache_savings(&rates);
+        assert!(approx(savings.write_cost, 0.375));
+        assert!(approx(savings.saved, 3.30 - 0.675));
+
+        // A cache that is written but never read costs money
+        let unused = TokenUsage {
+            cache_write_1h: 1_000_000,
+            ..Default::default()
+        };
+        assert!(approx(unused.cache_savings(&rates).saved, -3.0));
+
+        let mut transcript = TranscriptUsage::default();
+        transcript
+            .by_model
+            .insert("claude-sonnet-4-5".into(), usage);
+        transcript.by_model.insert("claude-sonnet-4".into(), unused);
+        transcript.by_model.insert("mystery-model".into(), unused);
+        let total = transcript.cache_savings_with(|m| lookup_rates(m, &HashMap::new()));
+        assert!(approx(total.saved, 3.30 - 0.675 - 3.0));
+        assert!(approx(total.write_cost, 0.375 + 6.0));
+    }
+
+    #[test]
+    fn test_usage_cache_write_split() {
+        let split: Usage = serde_json::from_str(
+            r#"{"input_tokens":10,"output_tokens":5,"cache_read_input_tokens":100,
+                "cache_creation_input_tokens":30,
+                "cache_creation":{"ephemeral_5m_input_tokens":10,"ephemeral_1h_input_tokens":20}}"#,
+        )
+        .unwrap();
+        let legacy: Usage =
+            serde_json::from_str(r#"{"input_tokens":1,"cache_creation_input_tokens":30}"#).unwrap();
+
+        let mut usage = TokenUsage::default();
+        usage.add(&split);
+        assert_eq!((usage.cache_write_5m, usage.cache_write_1h), (10, 20));
+        usage.add(&legacy);
+        assert_eq!((usage.cache_write_5m, usage.cache_write_1h), (40, 20));
+        assert_eq!((usage.input, usage.output, usage.cache_read), (11, 5, 100));
+    }
+
+    #[test]
+    fn test_transcript_usage_dedup_and_incremental() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let cache = temp.path().join("cache");
+        let path = temp.path().join("transcript.jsonl");
+
+        let usage = r#"{"input_tokens":100,"output_tokens":50,"cache_read_input_tokens":1000,"cache_creation_input_tokens":0}"#;
+        let mut file = File::create(&path).unwrap();
+        // Two content blocks of one response share the id and usage
+        writeln!(
+            file,
+            "{}",
+            assistant_line("msg_1", "claude-sonnet-4-5", usage)
+        )
+        .unwrap();
+        writeln!(
+            file,
+            "{}",
+            assistant_line("msg_1", "claude-sonnet-4-5", usage)
+        )
+        .unwrap();
+        writeln!(
+            file,
+            r#"{{"message":{{"role":"user","content":"hi"}},"timestamp":"2025-11-01T10:00:01.000Z"}}"#
+        )
+        .unwrap();
+        writeln!(file, r#"{{"type":"summary","summary":"x"}}"#).unwrap();
+        file.flush().unwrap();
+
+        let first = read_usage_in(&cache, &path).unwrap();
+        let sonnet = first.by_model["claude-sonnet-4-5"];
+        assert_eq!(
+            (sonnet.input, sonnet.output, sonnet.cache_read),
+            (100, 50, 1000)
+        );
+
+        // Appended lines are added to the stored totals; a partial line waits
+        writeln!(
+            file,
+            "{}",
+            assistant_line("msg_1", "claude-sonnet-4-5", usage)
+        )
+        .unwrap();
+        writeln!(
+            file,
+            "{}",
+            assistant_line("msg_2", "claude-opus-4-5", usage)
+        )
+        .unwrap();
+        write!(
+            file,
+            "{}",
+            &assistant_line("msg_3", "claude-opus-4-5", usage)[..40]
+        )
+        .unwrap();
+        file.flush().unwrap();
+
+        let second = read_usage_in(&cache, &path).unwrap();
+        assert_eq!(second.by_model["claude-sonnet-4-5"].input, 100);
+        assert_eq!(second.by_model["claude-opus-4-5"].input, 100);
+
+        let cost = second.cost_with(|model| lookup_rates(model, &HashMap::new()));
+        let expected = sonnet.cost(&ModelRates::from_base(3.0, 15.0))
+            + sonnet.cost(&ModelRates::from_base(5.0, 25.0));
+        assert!(approx(cost, expected));
+    }
+
+    #[test]
+    fn test_transcript_usage_resets_when_file_shrinks() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let cache = temp.path().join("cache");
+        let path = temp.path().join("transcript.jsonl");
+        let usage = r#"{"input_tokens":7,"output_tokens":1}"#;
+
+        let lines = [
+            assistant_line("msg_1", "claude-haiku-4-5", usage),
+            assistant_line("msg_2", "claude-haiku-4-5", usage),
+        ];
+        fs::write(&path, format!("{}\n{}\n", lines[0], lines[1])).unwrap();
+        assert_eq!(
+            read_usage_in(&cache, &path).unwrap().by_model["claude-haiku-4-5"].input,
+            14
+        );
+
+        fs::write(&path, format!("{}\n", lines[0])).unwrap();
+        assert_eq!(
+            read_usage_in(&cache, &path).unwrap().by_model["claude-haiku-4-5"].input,
+            7
+        );
+    }
+
+    #[test]
+    fn test_divergence_pct() {
+        assert!(approx(divergence_pct(1.15, 1.0).unwrap(), 15.0));
+        assert!(approx(divergence_pct(0.5, 1.0).unwrap(), -50.0));
+        assert!(divergence_pct(1.0, 0.0).is_none());
+    }
+
+    #[test]
+    fn test_normalize_model() {
+        assert_eq!(normalize_model("Opus 4.5"), "opus-4-5");
+        assert_eq!(
+            normalize_model("claude-opus-4-5-20251101"),
+            "claude-opus-4-5-20251101"
+        );
+    }
+}
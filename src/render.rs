@@ -9,13 +9,18 @@
 //! (`src/main.rs` and `src/lib.rs`) compile the same module files, so the binary and the
 //! library share this function directly.
 
+use std::collections::HashMap;
+
 use crate::models::StatuslineInput;
 
 /// Apply persistent stats updates for `input` and return today's daily cost total.
 ///
 /// When `update_stats` is `true` and the input carries a session id, this:
 /// 1. records the session's cost / lines / token breakdown (when `cost.total_cost_usd`
-///    is present),
+///    is present), along with Claude Code metadata such as the model id, project root,
+///    version and API/wall time, the cost and cache savings computed from the
+///    transcript's token usage, the timestamped usage of its recent responses
+///    (for usage blocks) and any usage limits or API errors it reports,
 /// 2. tracks `max_tokens_observed` for compaction detection whenever a transcript is
 ///    available (independent of cost), and
 /// 3. runs adaptive context learning when it is enabled in config.
@@ -33,6 +38,18 @@ pub fn update_stats_and_daily_total(input: &StatuslineInput, update_stats: bool)
     let transcript_path = input.transcript.as_deref();
     let session_id = input.session_id.as_deref();
 
+    // The transcript tail is read and parsed once; the token breakdown, context size,
+    // usage entries and errors below all come from it. One database connection is
+    // likewise shared by the metadata writes and adaptive learning.
+    let transcript = transcript_path
+        .filter(|_| update_stats)
+        .and_then(utils::TranscriptTail::read);
+    let token_breakdown = transcript
+        .as_ref()
+        .and_then(utils::TranscriptTail::token_breakdown);
+    let open_db = || crate::database::SqliteDatabase::new(&common::get_data_dir().join("stats.db"));
+    let mut db = None;
+
     // 1. Session cost update (or read-back of the existing daily total).
     let daily_total = if update_stats {
         if let (Some(session_id), Some(cost)) = (session_id, input.cost.as_ref()) {
@@ -42,14 +59,10 @@ pub fn update_stats_and_daily_total(input: &StatuslineInput, update_stats: bool)
                     .as_ref()
                     .and_then(|w| w.current_dir.as_deref());
 
-                // Extract token breakdown from transcript if available.
-                let token_breakdown =
-                    transcript_path.and_then(utils::get_token_breakdown_from_transcript);
-
                 // Device ID for the audit trail.
                 let device_id = common::get_device_id();
 
-                use crate::database::SessionUpdate;
+                use crate::database::{ComputedCost, SessionError, SessionMetadata, SessionUpdate};
                 let (daily_total, _monthly_total) = stats::update_stats_data(|data| {
                     data.update_session(
                         session_id,
@@ -60,7 +73,7 @@ pub fn update_stats_and_daily_total(input: &StatuslineInput, update_stats: bool)
                             model_name: model_name.map(|s| s.to_string()),
                             workspace_dir: workspace_dir.map(|s| s.to_string()),
                             device_id: Some(device_id),
-                            token_breakdown,
+                            token_breakdown: token_breakdown.clone(),
                             max_tokens_observed: None, // updated separately below
                             // active_time_seconds / last_activity are owned and computed by
                             // SqliteDatabase::update_session (src/database/session.rs); not available
@@ -70,6 +83,54 @@ pub fn update_stats_and_daily_total(input: &StatuslineInput, update_stats: bool)
                         },
                     )
                 });
+
+                // Claude Code metadata (model id, project root, version, API/wall time).
+                // Best effort: the session row exists now, so a failure here only loses
+                // descriptive columns and must never block the statusline.
+                // The same applies to the cost and cache savings priced from the
+                // transcript's token usage, and the timestamped usage of its recent
+                // responses kept for usage blocks.
+                let metadata = SessionMetadata::from_input(input);
+                let computed_cost = transcript_path
+                    .and_then(crate::pricing::transcript_usage)
+                    .map(|usage| ComputedCost::from_usage(&usage));
+                let usage_entries = match &transcript {
+                    Some(transcript) if config::get_config().blocks.enabled => {
+                        crate::blocks::transcript_entries(transcript, session_id)
+                    }
+                    _ => Vec::new(),
+                };
+                // Usage limits and API errors Claude Code wrote into the transcript
+                let session_errors: Vec<_> = transcript
+                    .as_ref()
+                    .map(utils::TranscriptTail::errors)
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|error| SessionError::from_transcript(session_id, error))
+                    .collect();
+                if !metadata.is_empty()
+                    || computed_cost.is_some()
+                    || !usage_entries.is_empty()
+                    || !session_errors.is_empty()
+                {
+                    db = open_db().ok();
+                    if let Some(db) = &db {
+                        if let Err(e) = db.update_session_metadata(session_id, &metadata) {
+                            log::debug!("Failed to record session metadata: {}", e);
+                        }
+                        if let Some(computed_cost) = computed_cost {
+                            if let Err(e) = db.record_computed_cost(session_id, &computed_cost) {
+                                log::debug!("Failed to record computed cost: {}", e);
+                            }
+                        }
+                        if let Err(e) = db.record_usage_entries(&usage_entries) {
+                            log::debug!("Failed to record usage entries: {}", e);
+                        }
+                        if let Err(e) = db.record_session_errors(&session_errors) {
+                            log::debug!("Failed to record session errors: {}", e);
+                        }
+                    }
+                }
                 daily_total
             } else {
                 // Session present but no cost figure — read back existing daily total.
@@ -88,7 +149,7 @@ pub fn update_stats_and_daily_total(input: &StatuslineInput, update_stats: bool)
     //    Runs whenever a transcript + session are present, regardless of cost.
     if update_stats {
         if let (Some(transcript), Some(session)) = (transcript_path, session_id) {
-            if let Some(current_tokens) = utils::get_token_count_from_transcript(transcript) {
+            if let Some(current_tokens) = token_breakdown.as_ref().map(|b| b.context_size()) {
                 // Updates both in-memory stats and the SQLite database.
                 stats::update_stats_data(|data| {
                     data.update_max_tokens(session, current_tokens);
@@ -116,12 +177,9 @@ pub fn update_stats_and_daily_total(input: &StatuslineInput, update_stats: bool)
                             .and_then(|s| s.max_tokens_observed)
                             .map(|t| t as usize);
 
-                        use crate::common::get_data_dir;
                         use crate::context_learning::ContextLearner;
-                        use crate::database::SqliteDatabase;
 
-                        let db_path = get_data_dir().join("stats.db");
-                        if let Ok(db) = SqliteDatabase::new(&db_path) {
+                        if let Some(db) = db.take().or_else(|| open_db().ok()) {
                             let learner = ContextLearner::new(db);
                             let workspace_dir = input
                                 .workspace
@@ -147,3 +205,77 @@ pub fn update_stats_and_daily_total(input: &StatuslineInput, update_stats: bool)
 
     daily_total
 }
+
+/// Run the data providers (git, jj/hg, stats, budget, forecast, blocks, limits, GSD)
+/// for `input` in parallel and return their merged variables.
+///
+/// Each provider runs in its own scoped thread under [`crate::provider::ProviderOrchestrator`],
+/// so the sources are queried side by side and the render waits for the slowest one.
+/// A provider's timeout does not stop it; it only discards a result that arrives later,
+/// so each timeout covers the provider's worst case (e.g. retried git commands).
+/// Providers that are unavailable, time out or fail simply contribute no variables.
+///
+/// `daily_total` should be the value returned by [`update_stats_and_daily_total`].
+/// Unless `all_variables` is set, optional queries for variables the configured
+/// layout does not use (e.g. the HEAD commit) are skipped.
+pub fn collect_provider_vars(
+    input: &StatuslineInput,
+    current_dir: &str,
+    daily_total: f64,
+    all_variables: bool,
+) -> HashMap<String, String> {
+    use crate::provider::ProviderOrchestrator;
+
+    let config = crate::config::get_config();
+    let cost = input.cost.as_ref();
+
+    let mut orchestrator = ProviderOrchestrator::new();
+
+    let mut git = crate::git_provider::GitProvider::new(current_dir);
+    if !all_variables {
+        git = git.only_for_format(&crate::layout::layout_format(&config.layout));
+    }
+    orchestrator.register(Box::new(git));
+    orchestrator.register(Box::new(crate::vcs::VcsProvider::new(current_dir)));
+
+    let db_path = crate::stats::StatsData::get_sqlite_path()
+        .ok()
+        .map(|p| p.display().to_string());
+    orchestrator.register(Box::new(crate::stats::StatsProvider::new(
+        input.session_id.clone(),
+        cost.and_then(|c| c.total_cost_usd).unwrap_or(0.0),
+        daily_total,
+        cost.and_then(|c| c.total_lines_added).unwrap_or(0),
+        cost.and_then(|c| c.total_lines_removed).unwrap_or(0),
+        input.transcript.clone(),
+        db_path.clone(),
+    )));
+
+    let db_path = db_path.map(std::path::PathBuf::from);
+    orchestrator.register(Box::new(crate::budget::BudgetProvider::new(
+        current_dir,
+        db_path.clone(),
+        &config.budget,
+    )));
+    orchestrator.register(Box::new(crate::forecast::ForecastProvider::new(
+        input.session_id.clone(),
+        cost.and_then(|c| c.total_cost_usd).unwrap_or(0.0),
+        db_path.clone(),
+        &config.forecast,
+    )));
+    orchestrator.register(Box::new(crate::blocks::BlockProvider::new(
+        db_path.clone(),
+        &config.blocks,
+    )));
+    orchestrator.register(Box::new(crate::limits::LimitProvider::new(
+        input.session_id.clone(),
+        db_path,
+    )));
+
+    orchestrator.register(Box::new(crate::gsd::GsdProvider::new(
+        &config.gsd,
+        std::path::Path::new(current_dir),
+    )));
+
+    orchestrator.collect_all()
+}
@@ -0,0 +1,477 @@
+//! Usage reports grouped by period, model, project or device.
+//!
+//! Days and months come from the `daily_stats` and `monthly_stats` aggregates, so
+//! they cover the full retention of those tables; weeks add up the days of each
+//! ISO week. Models, projects and devices group the rows of the `sessions` table by
+//! the session's `model_name`, `workspace_dir` or `device_id`, which only go back
+//! as far as session retention.
+
+use crate::database::{SessionFilter, SessionSummary, SqliteDatabase, UsageTotals};
+use chrono::NaiveDate;
+use serde_json::json;
+use std::collections::BTreeMap;
+
+/// Key used for sessions without a model, workspace or device
+const UNKNOWN: &str = "(unknown)";
+
+/// What a report groups its rows by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Grouping {
+    Day,
+    Week,
+    Month,
+    Model,
+    Project,
+    Device,
+}
+
+impl Grouping {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "day" => Some(Grouping::Day),
+            "week" => Some(Grouping::Week),
+            "month" => Some(Grouping::Month),
+            "model" => Some(Grouping::Model),
+            "project" => Some(Grouping::Project),
+            "device" => Some(Grouping::Device),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Grouping::Day => "day",
+            Grouping::Week => "week",
+            Grouping::Month => "month",
+            Grouping::Model => "model",
+            Grouping::Project => "project",
+            Grouping::Device => "device",
+        }
+    }
+
+    /// Column heading for the group key
+    pub fn label(&self) -> &'static str {
+        match self {
+            Grouping::Day => "Date",
+            Grouping::Week => "Week",
+            Grouping::Month => "Month",
+            Grouping::Model => "Model",
+            Grouping::Project => "Project",
+            Grouping::Device => "Device",
+        }
+    }
+
+    /// Whether rows are time periods (sorted oldest first) rather than groups of
+    /// sessions (sorted by cost)
+    pub fn is_period(&self) -> bool {
+        matches!(self, Grouping::Day | Grouping::Week | Grouping::Month)
+    }
+}
+
+/// One row of a report.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportRow {
+    pub key: String,
+    pub totals: UsageTotals,
+}
+
+/// Usage totals grouped by [`Grouping`], with the grand total.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub grouping: Grouping,
+    pub since: Option<NaiveDate>,
+    pub until: Option<NaiveDate>,
+    pub rows: Vec<ReportRow>,
+    pub total: UsageTotals,
+}
+
+/// Build a report of the activity between `since` and `until` (inclusive)
+///
+/// Monthly rows cover whole months, so a range starting or ending mid-month
+/// includes the full month.
+pub fn build_report(
+    db: &SqliteDatabase,
+    grouping: Grouping,
+    since: Option<NaiveDate>,
+    until: Option<NaiveDate>,
+) -> rusqlite::Result<Report> {
+    let day = |d: Option<NaiveDate>| d.map(|d| d.format("%Y-%m-%d").to_string());
+    let month = |d: Option<NaiveDate>| d.map(|d| d.format("%Y-%m").to_string());
+
+    let rows = match grouping {
+        Grouping::Day => db.get_daily_totals(day(since).as_deref(), day(until).as_deref())?,
+        Grouping::Week => {
+            group_weeks(db.get_daily_totals(day(since).as_deref(), day(until).as_deref())?)
+        }
+        Grouping::Month => {
+            db.get_monthly_totals(month(since).as_deref(), month(until).as_deref())?
+        }
+        Grouping::Model | Grouping::Project | Grouping::Device => {
+            let filter = SessionFilter {
+                since: day(since),
+                until: day(until),
+                ..SessionFilter::default()
+            };
+            group_sessions(&db.find_sessions(&filter, None, 0)?, grouping)
+        }
+    };
+
+    Ok(Report::new(grouping, since, until, rows))
+}
+
+/// Add up daily totals per ISO week ("2026-W42")
+fn group_weeks(days: Vec<(String, UsageTotals)>) -> Vec<(String, UsageTotals)> {
+    let mut weeks: BTreeMap<String, UsageTotals> = BTreeMap::new();
+    for (date, totals) in days {
+        let Ok(date) = NaiveDate::parse_from_str(&date, "%Y-%m-%d") else {
+            continue;
+        };
+        weeks
+            .entry(date.format("%G-W%V").to_string())
+            .or_default()
+            .add(&totals);
+    }
+    weeks.into_iter().collect()
+}
+
+/// Add up session totals per model, workspace or device
+fn group_sessions(sessions: &[SessionSummary], grouping: Grouping) -> Vec<(String, UsageTotals)> {
+    let mut groups: BTreeMap<String, UsageTotals> = BTreeMap::new();
+    for session in sessions {
+        let key = match grouping {
+            Grouping::Model => session.model_name.as_deref(),
+            Grouping::Project => session.workspace_dir.as_deref(),
+            _ => session.device_id.as_deref(),
+        };
+        let key = key.filter(|k| !k.is_empty()).unwrap_or(UNKNOWN);
+        groups
+            .entry(key.to_string())
+            .or_default()
+            .add(&session.totals);
+    }
+    groups.into_iter().collect()
+}
+
+impl Report {
+    pub fn new(
+        grouping: Grouping,
+        since: Option<NaiveDate>,
+        until: Option<NaiveDate>,
+        rows: Vec<(String, UsageTotals)>,
+    ) -> Self {
+        let mut rows: Vec<ReportRow> = rows
+            .into_iter()
+            .map(|(key, totals)| ReportRow { key, totals })
+            .collect();
+        if !grouping.is_period() {
+            rows.sort_by(|a, b| b.totals.cost.total_cmp(&a.totals.cost));
+        }
+        let mut total = UsageTotals::default();
+        for row in &rows {
+            total.add(&row.totals);
+        }
+        Self {
+            grouping,
+            since,
+            until,
+            rows,
+            total,
+        }
+    }
+
+    /// Key as shown in the table and markdown output (home directory shortened)
+    fn display_key(&self, key: &str) -> String {
+        match self.grouping {
+            Grouping::Project if key != UNKNOWN => crate::utils::shorten_path(key),
+            _ => key.to_string(),
+        }
+    }
+
+    /// Column headings and formatted cells of every row, followed by the total
+    fn cells(&self) -> (Vec<&'static str>, Vec<Vec<String>>) {
+        use crate::layout::format_token_count;
+
+        let headers = vec![
+            self.grouping.label(),
+            "Sessions",
+            "Cost",
+            "Input",
+            "Output",
+            "Cache Read",
+            "Cache Write",
+            "Tokens",
+            "Lines",
+        ];
+        let row = |key: String, t: &UsageTotals| {
+            vec![
+                key,
+                t.sessions.to_string(),
+                format!("${:.2}", t.cost),
+                format_token_count(t.input_tokens),
+                format_token_count(t.output_tokens),
+                format_token_count(t.cache_read_tokens),
+                format_token_count(t.cache_creation_tokens),
+                format_token_count(t.tokens()),
+                format!("+{} -{}", t.lines_added, t.lines_removed),
+            ]
+        };
+        let mut rows: Vec<_> = self
+            .rows
+            .iter()
+            .map(|r| row(self.display_key(&r.key), &r.totals))
+            .collect();
+        rows.push(row("Total".to_string(), &self.total));
+        (headers, rows)
+    }
+
+    /// Aligned plain-text table with a total row
+    pub fn to_table(&self) -> String {
+        let (headers, rows) = self.cells();
+        let widths: Vec<usize> = (0..headers.len())
+            .map(|i| {
+                rows.iter()
+                    .map(|r| r[i].chars().count())
+                    .chain([headers[i].len()])
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let line = |cells: &[&str]| {
+            cells
+                .iter()
+                .zip(&widths)
+                .enumerate()
+                .map(|(i, (cell, width))| match i {
+                    // The key is left-aligned, the figures right-aligned
+                    0 => format!("{:<width$}", cell, width = width),
+                    _ => format!("{:>width$}", cell, width = width),
+                })
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        };
+
+        let rule = "-".repeat(widths.iter().sum::<usize>() + 2 * (widths.len() - 1));
+        let mut out = vec![line(&headers), rule.clone()];
+        let (total, rows) = rows.split_last().expect("cells always adds a total row");
+        for row in rows {
+            out.push(line(&row.iter().map(String::as_str).collect::<Vec<_>>()));
+        }
+        out.push(rule);
+        out.push(line(&total.iter().map(String::as_str).collect::<Vec<_>>()));
+        out.join("\n")
+    }
+
+    /// GitHub-flavored markdown table with a bold total row
+    pub fn to_markdown(&self) -> String {
+        let (headers, mut rows) = self.cells();
+        if let Some(total) = rows.last_mut() {
+            for cell in total.iter_mut() {
+                *cell = format!("**{}**", cell);
+            }
+        }
+        let escape = |cell: &str| cell.replace('|', "\\|");
+        let mut out = vec![
+            format!("| {} |", headers.join(" | ")),
+            format!(
+                "|{}|",
+                (0..headers.len())
+                    .map(|i| if i == 0 { "---" } else { "---:" })
+                    .collect::<Vec<_>>()
+                    .join("|")
+            ),
+        ];
+        for row in rows {
+            let cells: Vec<_> = row.iter().map(|c| escape(c)).collect();
+            out.push(format!("| {} |", cells.join(" | ")));
+        }
+        out.join("\n")
+    }
+
+    /// CSV with raw numbers and a header row, one line per row (no total)
+    pub fn to_csv(&self) -> String {
+        let mut out = vec![format!(
+            "{},sessions,cost,computed_cost,input_tokens,output_tokens,cache_read_tokens,cache_creation_tokens,total_tokens,lines_added,lines_removed",
+            self.grouping.label().to_lowercase()
+        )];
+        for row in &self.rows {
+            let t = &row.totals;
+            out.push(format!(
+                "{},{},{:.4},{:.4},{},{},{},{},{},{},{}",
+                csv_field(&row.key),
+                t.sessions,
+                t.cost,
+                t.computed_cost,
+                t.input_tokens,
+                t.output_tokens,
+                t.cache_read_tokens,
+                t.cache_creation_tokens,
+                t.tokens(),
+                t.lines_added,
+                t.lines_removed
+            ));
+        }
+        out.join("\n")
+    }
+
+    /// JSON object with the rows and the total
+    pub fn to_json(&self) -> serde_json::Value {
+        let totals = |t: &UsageTotals| {
+            json!({
+                "sessions": t.sessions,
+                "cost": t.cost,
+                "computed_cost": t.computed_cost,
+                "input_tokens": t.input_tokens,
+                "output_tokens": t.output_tokens,
+                "cache_read_tokens": t.cache_read_tokens,
+                "cache_creation_tokens": t.cache_creation_tokens,
+                "total_tokens": t.tokens(),
+                "lines_added": t.lines_added,
+                "lines_removed": t.lines_removed,
+            })
+        };
+        let rows: Vec<_> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let mut value = totals(&row.totals);
+                value["key"] = json!(row.key);
+                value
+            })
+            .collect();
+        json!({
+            "by": self.grouping.as_str(),
+            "since": self.since.map(|d| d.to_string()),
+            "until": self.until.map(|d| d.to_string()),
+            "rows": rows,
+            "total": totals(&self.total),
+        })
+    }
+}
+
+/// Quote a CSV field when it contains a comma, quote or line break
+pub(crate) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn totals(sessions: u64, cost: f64, input: u64) -> UsageTotals {
+        UsageTotals {
+            sessions,
+            cost,
+            input_tokens: input,
+            output_tokens: input / 10,
+            lines_added: 5,
+            ..UsageTotals::default()
+        }
+    }
+
+    fn session(model: Option<&str>, dir: Option<&str>, cost: f64) -> SessionSummary {
+        SessionSummary {
+            model_name: model.map(str::to_string),
+            workspace_dir: dir.map(str::to_string),
+            totals: totals(1, cost, 1000),
+            ..SessionSummary::default()
+        }
+    }
+
+    #[test]
+    fn test_group_weeks() {
+        let weeks = group_weeks(vec![
+            ("2026-10-11".to_string(), totals(1, 1.0, 100)),
+            ("2026-10-12".to_string(), totals(2, 2.0, 200)),
+            ("2026-10-18".to_string(), totals(1, 4.0, 400)),
+        ]);
+        // Sunday the 11th closes week 41, Monday the 12th opens week 42
+        assert_eq!(weeks.len(), 2);
+        assert_eq!(weeks[0].0, "2026-W41");
+        assert_eq!(weeks[1].0, "2026-W42");
+        assert_eq!(weeks[1].1.cost, 6.0);
+        assert_eq!(weeks[1].1.sessions, 3);
+        assert_eq!(weeks[1].1.input_tokens, 600);
+    }
+
+    #[test]
+    fn test_group_sessions() {
+        let sessions = [
+            session(Some("Sonnet 4.5"), Some("/src/app"), 1.0),
+            session(Some("Opus 4.1"), Some("/src/app"), 5.0),
+            session(Some("Sonnet 4.5"), None, 2.0),
+        ];
+
+        let report = Report::new(
+            Grouping::Model,
+            None,
+            None,
+            group_sessions(&sessions, Grouping::Model),
+        );
+        let keys: Vec<_> = report.rows.iter().map(|r| r.key.as_str()).collect();
+        // Groups are sorted by cost, most expensive first
+        assert_eq!(keys, vec!["Opus 4.1", "Sonnet 4.5"]);
+        assert_eq!(report.rows[1].totals.sessions, 2);
+        assert_eq!(report.total.cost, 8.0);
+        assert_eq!(report.total.sessions, 3);
+
+        let projects = group_sessions(&sessions, Grouping::Project);
+        assert_eq!(projects[0].0, "(unknown)");
+        assert_eq!(projects[1].0, "/src/app");
+        assert_eq!(projects[1].1.cost, 6.0);
+    }
+
+    #[test]
+    fn test_report_formats() {
+        let report = Report::new(
+            Grouping::Day,
+            NaiveDate::from_ymd_opt(2026, 10, 1),
+            None,
+            vec![
+                ("2026-10-01".to_string(), totals(2, 12.5, 1_500_000)),
+                ("2026-10-02".to_string(), totals(1, 0.5, 900)),
+            ],
+        );
+
+        let table = report.to_table();
+        let lines: Vec<_> = table.lines().collect();
+        assert!(lines[0].starts_with("Date        Sessions"));
+        assert!(lines[2].contains("$12.50"));
+        assert!(lines[2].contains("1.5M"));
+        assert!(lines[5].starts_with("Total"));
+        assert!(lines[5].contains("$13.00"));
+
+        let markdown = report.to_markdown();
+        assert!(markdown.starts_with("| Date | Sessions | Cost |"));
+        assert!(markdown.contains("|---|---:|"));
+        assert!(markdown.ends_with("| **Total** | **3** | **$13.00** | **1.5M** | **150.1K** | **0** | **0** | **1.7M** | **+10 -0** |"));
+
+        let csv = report.to_csv();
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("date,sessions,cost,computed_cost,input_tokens"));
+        assert_eq!(
+            lines[1],
+            "2026-10-01,2,12.5000,0.0000,1500000,150000,0,0,1650000,5,0"
+        );
+
+        let json = report.to_json();
+        assert_eq!(json["by"], "day");
+        assert_eq!(json["since"], "2026-10-01");
+        assert!(json["until"].is_null());
+        assert_eq!(json["rows"][1]["key"], "2026-10-02");
+        assert_eq!(json["total"]["total_tokens"], 1_650_990);
+    }
+
+    #[test]
+    fn test_csv_field() {
+        assert_eq!(csv_field("/src/app"), "/src/app");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
@@ -210,6 +210,69 @@ impl StatsProvider {
             );
         }
     }
+
+    /// Fill the token-derived cost and cache savings variables.
+    ///
+    /// The session figures are priced from the transcript directly; today's and this
+    /// month's totals are the aggregates recorded in the database.
+    fn fill_computed_cost(&self, vars: &mut HashMap<String, String>) {
+        use crate::database::ComputedCost;
+
+        let config = crate::config::get_config();
+
+        let session = self
+            .transcript_path
+            .as_deref()
+            .and_then(crate::pricing::transcript_usage)
+            .map(|usage| ComputedCost::from_usage(&usage))
+            .filter(|c| c.cost > 0.0);
+        if let Some(session) = session {
+            vars.insert("stats_cost_computed".into(), format!("{:.2}", session.cost));
+            vars.insert(
+                "stats_cache_saved".into(),
+                format!("{:.2}", session.cache_saved),
+            );
+            vars.insert(
+                "stats_cache_write_cost".into(),
+                format!("{:.2}", session.cache_write_cost),
+            );
+
+            if let Some(pct) = crate::pricing::divergence_pct(session.cost, self.total_cost) {
+                if pct.abs() >= config.pricing.divergence_threshold_pct {
+                    vars.insert("stats_cost_divergence".into(), format!("{:+.0}", pct));
+                }
+            }
+        }
+
+        let db = self
+            .db_path
+            .as_deref()
+            .map(std::path::Path::new)
+            .filter(|p| p.exists())
+            .and_then(|p| crate::database::SqliteDatabase::new(p).ok());
+        if let Some(db) = db {
+            if let Some(today) = db.get_today_computed_cost().ok().filter(|c| c.cost > 0.0) {
+                vars.insert(
+                    "stats_cost_computed_today".into(),
+                    format!("{:.2}", today.cost),
+                );
+                vars.insert(
+                    "stats_cache_saved_today".into(),
+                    format!("{:.2}", today.cache_saved),
+                );
+                vars.insert(
+                    "stats_cache_write_cost_today".into(),
+                    format!("{:.2}", today.cache_write_cost),
+                );
+            }
+            if let Some(month) = db.get_month_computed_cost().ok().filter(|c| c.cost > 0.0) {
+                vars.insert(
+                    "stats_cost_computed_month".into(),
+                    format!("{:.2}", month.cost),
+                );
+            }
+        }
+    }
 }
 
 impl DataProvider for StatsProvider {
@@ -324,6 +387,17 @@ impl DataProvider for StatsProvider {
         // Fill in token rate values if database and session are available
         self.fill_token_rates(&mut vars);
 
+        // -- Token-derived cost and cache savings (all default to empty, filled if available) --
+        vars.insert("stats_cost_computed".into(), String::new());
+        vars.insert("stats_cost_computed_today".into(), String::new());
+        vars.insert("stats_cost_computed_month".into(), String::new());
+        vars.insert("stats_cost_divergence".into(), String::new());
+        vars.insert("stats_cache_saved".into(), String::new());
+        vars.insert("stats_cache_saved_today".into(), String::new());
+        vars.insert("stats_cache_write_cost".into(), String::new());
+        vars.insert("stats_cache_write_cost_today".into(), String::new());
+        self.fill_computed_cost(&mut vars);
+
         Ok(vars)
     }
 }
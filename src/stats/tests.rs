@@ -803,9 +803,17 @@ const EXPECTED_KEYS: &[&str] = &[
     "stats_token_cache_roi",
     "stats_token_session_total",
     "stats_token_daily_total",
+    "stats_cost_computed",
+    "stats_cost_computed_today",
+    "stats_cost_computed_month",
+    "stats_cost_divergence",
+    "stats_cache_saved",
+    "stats_cache_saved_today",
+    "stats_cache_write_cost",
+    "stats_cache_write_cost_today",
 ];
 
-/// All 17 expected keys are always present in collect() output,
+/// All 25 expected keys are always present in collect() output,
 /// even when the provider has no data.
 #[test]
 fn test_stats_provider_all_keys_present() {
@@ -900,6 +908,16 @@ fn test_stats_provider_empty_state() {
     assert_eq!(result.get("stats_token_cache_roi").unwrap(), "");
     assert_eq!(result.get("stats_token_session_total").unwrap(), "");
     assert_eq!(result.get("stats_token_daily_total").unwrap(), "");
+
+    // Token-derived cost and cache savings should be empty
+    assert_eq!(result.get("stats_cost_computed").unwrap(), "");
+    assert_eq!(result.get("stats_cost_computed_today").unwrap(), "");
+    assert_eq!(result.get("stats_cost_computed_month").unwrap(), "");
+    assert_eq!(result.get("stats_cost_divergence").unwrap(), "");
+    assert_eq!(result.get("stats_cache_saved").unwrap(), "");
+    assert_eq!(result.get("stats_cache_saved_today").unwrap(), "");
+    assert_eq!(result.get("stats_cache_write_cost").unwrap(), "");
+    assert_eq!(result.get("stats_cache_write_cost_today").unwrap(), "");
 }
 
 /// StatsProvider can be boxed as DataProvider and registered with orchestrator.
@@ -75,6 +75,10 @@ pub struct ThemeColors {
     #[serde(default = "default_green")]
     pub git_branch: String,
 
+    /// In-progress git operation color (e.g., "REBASE 3/7")
+    #[serde(default = "default_magenta")]
+    pub git_state: String,
+
     /// Model name color (e.g., "S4.5")
     #[serde(default = "default_cyan")]
     pub model: String,
@@ -125,6 +129,61 @@ pub struct ThemeColors {
     /// Context >= 90% color
     #[serde(default = "default_red")]
     pub context_critical: String,
+
+    // ===== Budget Threshold Colors =====
+    /// Budget used below `budget.warning_pct`
+    #[serde(default = "default_green")]
+    pub budget_normal: String,
+
+    /// Budget used past `budget.warning_pct`
+    #[serde(default = "default_yellow")]
+    pub budget_warning: String,
+
+    /// Budget used past `budget.critical_pct`, or exceeded
+    #[serde(default = "default_red")]
+    pub budget_critical: String,
+
+    // ===== Rate Limit Colors =====
+    /// Usage limit reached, time until it resets
+    #[serde(default = "default_orange")]
+    pub limit_reset: String,
+
+    /// API errors (overloaded, rate limited) in the session
+    #[serde(default = "default_red")]
+    pub api_error: String,
+
+    // ===== Powerline Colors =====
+    /// Text color on powerline blocks
+    #[serde(default = "default_black")]
+    pub powerline_fg: String,
+
+    /// Directory block background
+    #[serde(default = "default_bright_blue")]
+    pub directory_bg: String,
+
+    /// Git block background
+    #[serde(default = "default_bright_green")]
+    pub git_bg: String,
+
+    /// Context usage block background
+    #[serde(default = "default_bright_cyan")]
+    pub context_bg: String,
+
+    /// Model block background
+    #[serde(default = "default_bright_magenta")]
+    pub model_bg: String,
+
+    /// Cost block background
+    #[serde(default = "default_bright_yellow")]
+    pub cost_bg: String,
+
+    /// Token rate block background
+    #[serde(default = "default_white")]
+    pub token_rate_bg: String,
+
+    /// Background of blocks without a component (duration, lines, time, ...)
+    #[serde(default = "default_white")]
+    pub segment_bg: String,
 }
 
 /// Optional custom color palette for advanced theme customization.
@@ -155,6 +214,10 @@ fn default_yellow() -> String {
     "yellow".to_string()
 }
 
+fn default_magenta() -> String {
+    "magenta".to_string()
+}
+
 fn default_orange() -> String {
     "orange".to_string()
 }
@@ -167,6 +230,30 @@ fn default_light_gray() -> String {
     "light_gray".to_string()
 }
 
+fn default_black() -> String {
+    "black".to_string()
+}
+
+fn default_bright_blue() -> String {
+    "bright_blue".to_string()
+}
+
+fn default_bright_green() -> String {
+    "bright_green".to_string()
+}
+
+fn default_bright_cyan() -> String {
+    "bright_cyan".to_string()
+}
+
+fn default_bright_magenta() -> String {
+    "bright_magenta".to_string()
+}
+
+fn default_bright_yellow() -> String {
+    "bright_yellow".to_string()
+}
+
 // ===== Theme Manager =====
 
 /// Theme manager for discovering and loading themes.
@@ -532,6 +619,43 @@ impl Theme {
             }
         }
     }
+
+    /// Resolves a color name to the ANSI escape code setting it as background.
+    ///
+    /// Accepts everything [`resolve_color`](Self::resolve_color) does; the
+    /// foreground code is turned into its background counterpart. Codes that
+    /// are not a foreground color are returned unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use statusline::theme::Theme;
+    ///
+    /// let theme = Theme::default();
+    /// assert_eq!(theme.resolve_background("cyan"), "\x1b[46m");
+    /// assert_eq!(theme.resolve_background("bright_blue"), "\x1b[104m");
+    /// assert_eq!(theme.resolve_background("#FF5733"), "\x1b[48;2;255;87;51m");
+    /// ```
+    pub fn resolve_background(&self, name: &str) -> String {
+        let foreground = self.resolve_color(name);
+        let Some(params) = foreground
+            .strip_prefix("\x1b[")
+            .and_then(|rest| rest.strip_suffix('m'))
+        else {
+            return foreground;
+        };
+        let (first, rest) = params.split_once(';').unwrap_or((params, ""));
+        let background = match first.parse::<u8>() {
+            // 30-37 and 90-97 are 16-color foregrounds, 38 an extended (256/RGB) one
+            Ok(code @ (30..=38 | 90..=97)) => code + 10,
+            _ => return foreground,
+        };
+        if rest.is_empty() {
+            format!("\x1b[{}m", background)
+        } else {
+            format!("\x1b[{};{}m", background, rest)
+        }
+    }
 }
 
 impl Default for Theme {
@@ -545,6 +669,7 @@ impl Default for Theme {
             colors: ThemeColors {
                 directory: "cyan".to_string(),
                 git_branch: "green".to_string(),
+                git_state: "magenta".to_string(),
                 model: "cyan".to_string(),
                 duration: "light_gray".to_string(),
                 separator: "light_gray".to_string(),
@@ -557,6 +682,19 @@ impl Default for Theme {
                 context_caution: "yellow".to_string(),
                 context_warning: "orange".to_string(),
                 context_critical: "red".to_string(),
+                budget_normal: "green".to_string(),
+                budget_warning: "yellow".to_string(),
+                budget_critical: "red".to_string(),
+                limit_reset: "orange".to_string(),
+                api_error: "red".to_string(),
+                powerline_fg: "black".to_string(),
+                directory_bg: "bright_blue".to_string(),
+                git_bg: "bright_green".to_string(),
+                context_bg: "bright_cyan".to_string(),
+                model_bg: "bright_magenta".to_string(),
+                cost_bg: "bright_yellow".to_string(),
+                token_rate_bg: "white".to_string(),
+                segment_bg: "white".to_string(),
             },
             palette: None,
         }
@@ -578,6 +716,7 @@ impl Default for ThemeColors {
         Self {
             directory: default_cyan(),
             git_branch: default_green(),
+            git_state: default_magenta(),
             model: default_cyan(),
             duration: default_light_gray(),
             separator: default_light_gray(),
@@ -590,6 +729,19 @@ impl Default for ThemeColors {
             context_caution: default_yellow(),
             context_warning: default_orange(),
             context_critical: default_red(),
+            budget_normal: default_green(),
+            budget_warning: default_yellow(),
+            budget_critical: default_red(),
+            limit_reset: default_orange(),
+            api_error: default_red(),
+            powerline_fg: default_black(),
+            directory_bg: default_bright_blue(),
+            git_bg: default_bright_green(),
+            context_bg: default_bright_cyan(),
+            model_bg: default_bright_magenta(),
+            cost_bg: default_bright_yellow(),
+            token_rate_bg: default_white(),
+            segment_bg: default_white(),
         }
     }
 }
@@ -781,6 +933,118 @@ mod tests {
         assert_eq!(theme.name, "minimal");
         assert_eq!(theme.colors.directory, "cyan"); // Default
         assert_eq!(theme.colors.cost_high, "red"); // Default
+        assert_eq!(theme.colors.git_state, "magenta"); // Default
+        assert_eq!(theme.colors.budget_warning, "yellow"); // Default
+        assert_eq!(theme.colors.limit_reset, "orange"); // Default
+    }
+
+    #[test]
+    fn test_embedded_themes_define_git_state_budget_and_limits() {
+        // Every bundled theme picks its own git operation, budget and rate limit colors explicitly
+        for content in [
+            EMBEDDED_DARK_THEME,
+            EMBEDDED_LIGHT_THEME,
+            EMBEDDED_MONOKAI_THEME,
+            EMBEDDED_SOLARIZED_THEME,
+            EMBEDDED_HIGH_CONTRAST_THEME,
+            EMBEDDED_GRUVBOX_THEME,
+            EMBEDDED_NORD_THEME,
+            EMBEDDED_DRACULA_THEME,
+            EMBEDDED_ONE_DARK_THEME,
+            EMBEDDED_TOKYO_NIGHT_THEME,
+            EMBEDDED_CATPPUCCIN_THEME,
+        ] {
+            let theme = Theme::from_toml(content).unwrap();
+            assert!(
+                content.contains("\ngit_state = "),
+                "{} should define git_state",
+                theme.name
+            );
+            for key in [
+                "budget_normal",
+                "budget_warning",
+                "budget_critical",
+                "limit_reset",
+                "api_error",
+            ] {
+                assert!(
+                    content.contains(&format!("\n{} = ", key)),
+                    "{} should define {}",
+                    theme.name,
+                    key
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_embedded_themes_define_powerline_colors() {
+        for content in [
+            EMBEDDED_DARK_THEME,
+            EMBEDDED_LIGHT_THEME,
+            EMBEDDED_MONOKAI_THEME,
+            EMBEDDED_SOLARIZED_THEME,
+            EMBEDDED_HIGH_CONTRAST_THEME,
+            EMBEDDED_GRUVBOX_THEME,
+            EMBEDDED_NORD_THEME,
+            EMBEDDED_DRACULA_THEME,
+            EMBEDDED_ONE_DARK_THEME,
+            EMBEDDED_TOKYO_NIGHT_THEME,
+            EMBEDDED_CATPPUCCIN_THEME,
+        ] {
+            let theme = Theme::from_toml(content).unwrap();
+            for key in [
+                "powerline_fg",
+                "directory_bg",
+                "git_bg",
+                "context_bg",
+                "model_bg",
+                "cost_bg",
+                "token_rate_bg",
+                "segment_bg",
+            ] {
+                assert!(
+                    content.contains(&format!("\n{} = ", key)),
+                    "{} should define {}",
+                    theme.name,
+                    key
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_theme_powerline_defaults() {
+        let toml = r#"
+            name = "minimal"
+            [colors]
+            directory = "blue"
+        "#;
+
+        let theme = Theme::from_toml(toml).unwrap();
+        assert_eq!(theme.colors.powerline_fg, "black");
+        assert_eq!(theme.colors.directory_bg, "bright_blue");
+        assert_eq!(theme.colors.segment_bg, "white");
+    }
+
+    #[test]
+    fn test_resolve_background() {
+        let theme = Theme::default();
+        assert_eq!(theme.resolve_background("black"), "\x1b[40m");
+        assert_eq!(theme.resolve_background("cyan"), "\x1b[46m");
+        assert_eq!(theme.resolve_background("bright_yellow"), "\x1b[103m");
+        assert_eq!(theme.resolve_background("gray"), "\x1b[100m");
+        assert_eq!(theme.resolve_background("orange"), "\x1b[48;5;208m");
+        assert_eq!(theme.resolve_background("#1E1E2E"), "\x1b[48;2;30;30;46m");
+        assert_eq!(theme.resolve_background("\\x1b[38;5;39m"), "\x1b[48;5;39m");
+    }
+
+    #[test]
+    fn test_resolve_background_leaves_other_codes() {
+        let theme = Theme::default();
+        // Already a background, or not a color at all
+        assert_eq!(theme.resolve_background("\x1b[44m"), "\x1b[44m");
+        assert_eq!(theme.resolve_background("\x1b[1m"), "\x1b[1m");
     }
 
     #[test]
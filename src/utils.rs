@@ -17,6 +17,9 @@ use std::sync::OnceLock;
 /// Static ANSI regex pattern, initialized once
 static ANSI_REGEX: OnceLock<regex::Regex> = OnceLock::new();
 
+/// Static usage limit reset time pattern ("resets 3pm", "reset at Oct 20, 11:30am")
+static LIMIT_RESET_REGEX: OnceLock<regex::Regex> = OnceLock::new();
+
 /// Sanitizes a string for safe terminal output by removing control characters
 /// and ANSI escape sequences. This prevents malicious strings from manipulating
 /// terminal state or executing unintended commands.
@@ -250,7 +253,7 @@ pub fn get_context_window_for_model(model_name: Option<&str>, config: &config::C
 }
 
 /// Validates that a path is a valid transcript file
-fn validate_transcript_file(path: &str) -> Result<PathBuf> {
+pub(crate) fn validate_transcript_file(path: &str) -> Result<PathBuf> {
     // Use common validation first
     let canonical_path = validate_path_security(path)?;
 
@@ -294,30 +297,20 @@ pub fn get_token_count_from_transcript(transcript_path: &str) -> Option<u32> {
     get_token_breakdown_from_transcript(transcript_path).map(|breakdown| breakdown.context_size())
 }
 
-/// Extracts detailed token breakdown from transcript file.
+/// Reads the last `buffer_size` lines of a validated transcript file.
 ///
-/// Returns a TokenBreakdown with separate counts for input, output, cache read, and cache creation tokens.
-/// This data is used for cost analysis, cache efficiency tracking, and per-model analytics.
-///
-/// Implementation: Reads from the end of the file for efficiency with large transcripts.
-/// Only processes the last N lines (configured via transcript.buffer_lines).
-pub fn get_token_breakdown_from_transcript(
-    transcript_path: &str,
-) -> Option<crate::models::TokenBreakdown> {
-    use crate::models::TokenBreakdown;
+/// Small files are read in full through a circular buffer; files over 1MB are read
+/// from the end so that large transcripts don't have to be processed entirely.
+pub(crate) fn read_transcript_tail(
+    safe_path: &std::path::Path,
+    buffer_size: usize,
+) -> Option<Vec<String>> {
     use std::io::{Seek, SeekFrom};
 
-    // Validate and canonicalize the file path
-    let safe_path = validate_transcript_file(transcript_path).ok()?;
-
     // Open file and get size
-    let mut file = File::open(&safe_path).ok()?;
+    let mut file = File::open(safe_path).ok()?;
     let file_size = file.metadata().ok()?.len();
 
-    // Load config once to avoid repeated TOML parsing
-    let config = config::get_config();
-    let buffer_size = config.transcript.buffer_lines;
-
     // For small files, read normally from start
     // For large files (>1MB), read from end to avoid processing entire file
     let lines: Vec<String> = if file_size < 1024 * 1024 {
@@ -356,24 +349,69 @@ pub fn get_token_breakdown_from_transcript(
             .rev()
             .collect()
     };
+    Some(lines)
+}
 
-    // Process all assistant messages:
-    // - LAST for context-related tokens (input, cache_read) - represents CURRENT context usage
-    //   This is what users see on the statusline (e.g., "9%" after compaction, not "64%")
-    // - SUM for generated tokens (output, cache_creation) - represents total work done
-    //
-    // IMPORTANT: For compaction detection heuristics (Phase 2), the database tracks
-    // max_tokens_observed separately. This function returns CURRENT values for display.
-    let mut last_input = 0u32;
-    let mut last_cache_read = 0u32;
-    let mut sum_output = 0u32;
-    let mut sum_cache_creation = 0u32;
-    let mut has_data = false;
+/// Extracts detailed token breakdown from transcript file.
+///
+/// Returns a TokenBreakdown with separate counts for input, output, cache read, and cache creation tokens.
+/// This data is used for cost analysis, cache efficiency tracking, and per-model analytics.
+///
+/// Implementation: Reads from the end of the file for efficiency with large transcripts.
+/// Only processes the last N lines (configured via transcript.buffer_lines).
+pub fn get_token_breakdown_from_transcript(
+    transcript_path: &str,
+) -> Option<crate::models::TokenBreakdown> {
+    TranscriptTail::read(transcript_path)?.token_breakdown()
+}
 
-    for line in lines {
-        if let Ok(entry) = serde_json::from_str::<TranscriptEntry>(&line) {
+/// The last N lines of a transcript (configured via transcript.buffer_lines).
+///
+/// Read and parsed once per render; the token breakdown, usage limits and API errors
+/// and the usage of recent responses are all derived from it.
+pub struct TranscriptTail {
+    lines: Vec<String>,
+    /// Lines that parse as message entries, in file order
+    entries: Vec<TranscriptEntry>,
+}
+
+impl TranscriptTail {
+    /// Reads the end of a validated transcript file.
+    pub fn read(transcript_path: &str) -> Option<Self> {
+        let safe_path = validate_transcript_file(transcript_path).ok()?;
+        let lines = read_transcript_tail(&safe_path, config::get_config().transcript.buffer_lines)?;
+        let entries = lines
+            .iter()
+            .filter_map(|line| serde_json::from_str::<TranscriptEntry>(line).ok())
+            .collect();
+        Some(TranscriptTail { lines, entries })
+    }
+
+    /// The message entries, in file order.
+    pub fn entries(&self) -> &[TranscriptEntry] {
+        &self.entries
+    }
+
+    /// Token breakdown of the assistant messages (see [`get_token_breakdown_from_transcript`]).
+    pub fn token_breakdown(&self) -> Option<crate::models::TokenBreakdown> {
+        use crate::models::TokenBreakdown;
+
+        // Process all assistant messages:
+        // - LAST for context-related tokens (input, cache_read) - represents CURRENT context usage
+        //   This is what users see on the statusline (e.g., "9%" after compaction, not "64%")
+        // - SUM for generated tokens (output, cache_creation) - represents total work done
+        //
+        // IMPORTANT: For compaction detection heuristics (Phase 2), the database tracks
+        // max_tokens_observed separately. This function returns CURRENT values for display.
+        let mut last_input = 0u32;
+        let mut last_cache_read = 0u32;
+        let mut sum_output = 0u32;
+        let mut sum_cache_creation = 0u32;
+        let mut has_data = false;
+
+        for entry in &self.entries {
             if entry.message.role == "assistant" {
-                if let Some(usage) = entry.message.usage {
+                if let Some(usage) = &entry.message.usage {
                     has_data = true;
 
                     // Extract individual token counts
@@ -394,20 +432,205 @@ pub fn get_token_breakdown_from_transcript(
                 }
             }
         }
+
+        if has_data {
+            Some(TokenBreakdown {
+                input_tokens: last_input,
+                output_tokens: sum_output,
+                cache_read_tokens: last_cache_read,
+                cache_creation_tokens: sum_cache_creation,
+            })
+        } else {
+            None
+        }
     }
 
-    if has_data {
-        Some(TokenBreakdown {
-            input_tokens: last_input,
-            output_tokens: sum_output,
-            cache_read_tokens: last_cache_read,
-            cache_creation_tokens: sum_cache_creation,
-        })
+    /// Usage limits and API errors among the lines (see [`parse_transcript_error`]).
+    ///
+    /// Only entries Claude Code wrote itself are considered (API error messages,
+    /// "<synthetic>" assistant messages and error-level system entries), so a
+    /// conversation *about* usage limits is not mistaken for one.
+    pub fn errors(&self) -> Vec<TranscriptError> {
+        self.lines
+            .iter()
+            .filter_map(|line| parse_transcript_error(line, &chrono::Local))
+            .collect()
+    }
+}
+
+/// Kind of problem Claude Code reported in a transcript entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TranscriptErrorKind {
+    /// Usage limit reached; requests fail until it resets
+    UsageLimit,
+    /// Failed API request (overloaded, rate limited, server error, ...)
+    ApiError,
+}
+
+impl TranscriptErrorKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TranscriptErrorKind::UsageLimit => "usage_limit",
+            TranscriptErrorKind::ApiError => "api_error",
+        }
+    }
+}
+
+/// A usage limit or API error found in a transcript
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranscriptError {
+    pub kind: TranscriptErrorKind,
+    pub timestamp: DateTime<chrono::Utc>,
+    /// The message shown to the user (first line, sanitized)
+    pub message: String,
+    /// When a usage limit resets, if the message says
+    pub reset_at: Option<DateTime<chrono::Utc>>,
+}
+
+/// Recognizes a usage limit or API error entry; reset times without a date or
+/// time zone are read in `tz`.
+fn parse_transcript_error<Tz: chrono::TimeZone>(line: &str, tz: &Tz) -> Option<TranscriptError> {
+    use crate::models::TranscriptRecord;
+
+    // Cheap pre-check: most lines are ordinary messages
+    if !line.contains("isApiErrorMessage")
+        && !line.contains("<synthetic>")
+        && !line.contains("\"system\"")
+    {
+        return None;
+    }
+    let record = serde_json::from_str::<TranscriptRecord>(line).ok()?;
+    let timestamp = DateTime::parse_from_rfc3339(record.timestamp.as_deref()?)
+        .ok()?
+        .with_timezone(&chrono::Utc);
+
+    let synthetic = record
+        .message
+        .as_ref()
+        .and_then(|m| m.model.as_deref())
+        .is_some_and(|model| model == "<synthetic>");
+    let system_error =
+        record.kind.as_deref() == Some("system") && record.level.as_deref() == Some("error");
+    if !record.is_api_error_message && !synthetic && !system_error {
+        return None;
+    }
+
+    let content = match &record.message {
+        Some(message) => message.content.as_ref(),
+        None => record.content.as_ref(),
+    };
+    let text = content.map(content_text).unwrap_or_default();
+    let lower = text.to_lowercase();
+    let kind = if lower.contains("limit reached")
+        || lower.contains("hit your limit")
+        || lower.contains("usage limit")
+    {
+        TranscriptErrorKind::UsageLimit
+    } else if record.is_api_error_message || (system_error && lower.contains("api error")) {
+        TranscriptErrorKind::ApiError
     } else {
-        None
+        return None;
+    };
+
+    let reset_at = match kind {
+        TranscriptErrorKind::UsageLimit => parse_limit_reset(&text, timestamp, tz),
+        TranscriptErrorKind::ApiError => None,
+    };
+    // "Claude AI usage limit reached|<epoch>" carries the reset time after the bar
+    let message = text.split('|').next().unwrap_or_default();
+    let message = sanitize_for_terminal(message.lines().next().unwrap_or_default().trim());
+
+    Some(TranscriptError {
+        kind,
+        timestamp,
+        message,
+        reset_at,
+    })
+}
+
+/// Text of a message content value (a string, or an array of text blocks)
+fn content_text(content: &serde_json::Value) -> String {
+    match content {
+        serde_json::Value::String(text) => text.clone(),
+        serde_json::Value::Array(blocks) => blocks
+            .iter()
+            .filter_map(|block| block.get("text").and_then(|t| t.as_str()))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
     }
 }
 
+/// Reads when a usage limit resets from its message.
+///
+/// Understands a Unix timestamp after a bar ("...limit reached|1760450400") and a
+/// time of day, optionally with a date ("resets 3pm", "will reset at 11:30am",
+/// "resets Oct 20, 9am"). Times are in `tz` and are the next such time after the
+/// message was written.
+fn parse_limit_reset<Tz: chrono::TimeZone>(
+    text: &str,
+    at: DateTime<chrono::Utc>,
+    tz: &Tz,
+) -> Option<DateTime<chrono::Utc>> {
+    use chrono::{Datelike, NaiveDate, NaiveTime, Utc};
+
+    if let Some((_, epoch)) = text.split_once('|') {
+        if let Ok(epoch) = epoch.trim().parse::<i64>() {
+            return DateTime::<Utc>::from_timestamp(epoch, 0);
+        }
+    }
+
+    let regex = LIMIT_RESET_REGEX.get_or_init(|| {
+        regex::Regex::new(
+            r"(?i)\bresets?(?:\s+at)?\s+(?:([a-z]{3})[a-z]*\.?\s+(\d{1,2}),?\s+(?:at\s+)?)?(\d{1,2})(?::(\d{2}))?\s*([ap])\.?m\b",
+        )
+        .expect("limit reset regex pattern should be valid")
+    });
+    let caps = regex.captures(text)?;
+    let mut hour: u32 = caps[3].parse().ok()?;
+    let minute: u32 = caps.get(4).map_or(Some(0), |m| m.as_str().parse().ok())?;
+    if hour == 0 || hour > 12 {
+        return None;
+    }
+    hour %= 12;
+    if caps[5].eq_ignore_ascii_case("p") {
+        hour += 12;
+    }
+    let time = NaiveTime::from_hms_opt(hour, minute, 0)?;
+
+    let local_at = at.with_timezone(tz).naive_local();
+    let reset = match (caps.get(1), caps.get(2)) {
+        (Some(month), Some(day)) => {
+            const MONTHS: [&str; 12] = [
+                "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+            ];
+            let month = MONTHS
+                .iter()
+                .position(|m| m.eq_ignore_ascii_case(month.as_str()))?
+                as u32
+                + 1;
+            let day: u32 = day.as_str().parse().ok()?;
+            let this_year = NaiveDate::from_ymd_opt(local_at.year(), month, day)?.and_time(time);
+            if this_year < local_at - chrono::TimeDelta::days(1) {
+                NaiveDate::from_ymd_opt(local_at.year() + 1, month, day)?.and_time(time)
+            } else {
+                this_year
+            }
+        }
+        _ => {
+            let today = local_at.date().and_time(time);
+            if today <= local_at {
+                today + chrono::TimeDelta::days(1)
+            } else {
+                today
+            }
+        }
+    };
+    tz.from_local_datetime(&reset)
+        .earliest()
+        .map(|reset| reset.with_timezone(&Utc))
+}
+
 /// Detect compaction state based on token count changes and file modification time
 fn detect_compaction_state(
     transcript_path: &str,
@@ -789,6 +1012,71 @@ mod tests {
     }
     use std::fs;
 
+    #[test]
+    fn test_parse_transcript_error_usage_limit() {
+        use chrono::{TimeZone, Utc};
+        let tz = chrono::FixedOffset::east_opt(2 * 3600).unwrap();
+
+        // Synthetic assistant message with the reset time of day (14:10 local)
+        let line = r#"{"type":"assistant","timestamp":"2026-10-17T12:10:00Z","isApiErrorMessage":true,"message":{"model":"<synthetic>","role":"assistant","content":[{"type":"text","text":"5-hour limit reached ∙ resets 3pm"}]}}"#;
+        let error = parse_transcript_error(line, &tz).unwrap();
+        assert_eq!(error.kind, TranscriptErrorKind::UsageLimit);
+        assert_eq!(error.message, "5-hour limit reached ∙ resets 3pm");
+        assert_eq!(
+            error.reset_at,
+            Some(Utc.with_ymd_and_hms(2026, 10, 17, 13, 0, 0).unwrap())
+        );
+
+        // A time already past today means tomorrow
+        let line = line.replace("resets 3pm", "Your limit will reset at 11:30am");
+        let error = parse_transcript_error(&line, &tz).unwrap();
+        assert_eq!(
+            error.reset_at,
+            Some(Utc.with_ymd_and_hms(2026, 10, 18, 9, 30, 0).unwrap())
+        );
+
+        // Older format with a Unix timestamp
+        let line = r#"{"type":"assistant","timestamp":"2026-10-17T12:10:00Z","message":{"model":"<synthetic>","role":"assistant","content":[{"type":"text","text":"Claude AI usage limit reached|1760709600"}]}}"#;
+        let error = parse_transcript_error(line, &tz).unwrap();
+        assert_eq!(error.message, "Claude AI usage limit reached");
+        assert_eq!(
+            error.reset_at,
+            DateTime::<Utc>::from_timestamp(1_760_709_600, 0)
+        );
+
+        // With a date
+        let line = line.replace(
+            "Claude AI usage limit reached|1760709600",
+            "Weekly limit reached ∙ resets Oct 20, 9am",
+        );
+        let error = parse_transcript_error(&line, &tz).unwrap();
+        assert_eq!(
+            error.reset_at,
+            Some(Utc.with_ymd_and_hms(2026, 10, 20, 7, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_parse_transcript_error_api_errors() {
+        let tz = chrono::Utc;
+
+        let line = r#"{"type":"assistant","timestamp":"2026-10-17T12:10:00Z","isApiErrorMessage":true,"message":{"model":"<synthetic>","role":"assistant","content":[{"type":"text","text":"API Error: 529 {\"type\":\"error\",\"error\":{\"type\":\"overloaded_error\"}}"}]}}"#;
+        let error = parse_transcript_error(line, &tz).unwrap();
+        assert_eq!(error.kind, TranscriptErrorKind::ApiError);
+        assert_eq!(error.reset_at, None);
+
+        let line = r#"{"type":"system","timestamp":"2026-10-17T12:10:00Z","level":"error","content":"API Error (Request timed out.) · Retrying in 1 seconds… (attempt 1/10)"}"#;
+        let error = parse_transcript_error(line, &tz).unwrap();
+        assert_eq!(error.kind, TranscriptErrorKind::ApiError);
+
+        // Informational system entries and ordinary messages are ignored, even
+        // when they talk about limits
+        let info = r#"{"type":"system","timestamp":"2026-10-17T12:10:00Z","level":"info","content":"Conversation compacted"}"#;
+        assert_eq!(parse_transcript_error(info, &tz), None);
+        let chat = r#"{"type":"assistant","timestamp":"2026-10-17T12:10:00Z","message":{"model":"claude-sonnet-4-5","role":"assistant","content":[{"type":"text","text":"When the usage limit reached message appears, it resets 3pm"}]}}"#;
+        assert_eq!(parse_transcript_error(chat, &tz), None);
+    }
+
     #[test]
     fn test_validate_transcript_file_security() {
         // Test null byte injection
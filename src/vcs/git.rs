@@ -0,0 +1,85 @@
+//! Git backend, adapting [`crate::git`] to [`VcsBackend`].
+
+use super::{VcsBackend, VcsKind, VcsStatus};
+use crate::git::{discover_repository, get_git_status, read_head_commit_id, GitRepo, GitStatus};
+use std::path::Path;
+
+/// Git repository discovered from a working directory.
+pub struct GitBackend {
+    dir: String,
+    repo: GitRepo,
+}
+
+impl GitBackend {
+    /// Creates a backend for the repository containing `dir`, if any.
+    pub fn new(dir: &Path) -> Option<Self> {
+        Some(GitBackend {
+            dir: dir.to_string_lossy().into_owned(),
+            repo: discover_repository(dir)?,
+        })
+    }
+}
+
+impl VcsBackend for GitBackend {
+    fn kind(&self) -> VcsKind {
+        VcsKind::Git
+    }
+
+    fn root(&self) -> &Path {
+        &self.repo.work_tree
+    }
+
+    fn status(&self) -> Option<VcsStatus> {
+        get_git_status(&self.dir).map(|status| to_vcs_status(&status, &self.repo))
+    }
+}
+
+/// Converts a git status into the common VCS status.
+///
+/// The change id is the abbreviated HEAD commit, resolved from the ref files.
+pub fn to_vcs_status(status: &GitStatus, repo: &GitRepo) -> VcsStatus {
+    VcsStatus {
+        branch: status.detached_sha.is_none().then(|| status.branch.clone()),
+        change_id: read_head_commit_id(repo),
+        added: status.added,
+        modified: status.modified,
+        deleted: status.deleted,
+        untracked: status.untracked,
+        conflicts: status.unmerged,
+        ..VcsStatus::new(VcsKind::Git)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_to_vcs_status() {
+        let repo = GitRepo {
+            work_tree: PathBuf::from("/nonexistent"),
+            git_dir: PathBuf::from("/nonexistent/.git"),
+            common_dir: PathBuf::from("/nonexistent/.git"),
+            subdir: PathBuf::new(),
+        };
+        let status = GitStatus {
+            branch: "main".to_string(),
+            added: 1,
+            untracked: 2,
+            unmerged: 3,
+            ..Default::default()
+        };
+        let vcs = to_vcs_status(&status, &repo);
+        assert_eq!(vcs.kind, VcsKind::Git);
+        assert_eq!(vcs.branch.as_deref(), Some("main"));
+        assert_eq!(vcs.change_id, None);
+        assert_eq!((vcs.added, vcs.untracked, vcs.conflicts), (1, 2, 3));
+
+        let detached = GitStatus {
+            detached_sha: Some("a1b2c3d".to_string()),
+            ..status
+        };
+        assert_eq!(to_vcs_status(&detached, &repo).branch, None);
+    }
+}
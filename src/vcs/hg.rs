@@ -0,0 +1,139 @@
+//! Mercurial backend, using the `hg` CLI.
+//!
+//! Commands run with `HGPLAIN=1` so user aliases, color and localization
+//! cannot change the output format.
+
+use super::{VcsBackend, VcsKind, VcsStatus};
+use crate::git_utils::{execute_with_timeout, git_timeout_ms};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Tab-separated short node, branch and active bookmark of the working directory parent.
+const LOG_TEMPLATE: &str = "{node|short}\\t{branch}\\t{activebookmark}\\n";
+
+/// Mercurial working directory rooted at a directory containing `.hg`.
+pub struct HgBackend {
+    dir: PathBuf,
+    root: PathBuf,
+}
+
+impl HgBackend {
+    /// Creates a backend that runs `hg` in `dir`, inside the repository at `root`.
+    pub fn new(dir: &Path, root: PathBuf) -> Self {
+        HgBackend {
+            dir: dir.to_path_buf(),
+            root,
+        }
+    }
+
+    fn run(&self, args: &[&str]) -> Option<String> {
+        let mut cmd = Command::new("hg");
+        cmd.args(args).current_dir(&self.dir).env("HGPLAIN", "1");
+
+        let output = execute_with_timeout(cmd, git_timeout_ms())?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+impl VcsBackend for HgBackend {
+    fn kind(&self) -> VcsKind {
+        VcsKind::Mercurial
+    }
+
+    fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn status(&self) -> Option<VcsStatus> {
+        // Both commands only read the repository, so they can run side by side
+        let (log, files) = std::thread::scope(|scope| {
+            let log = scope.spawn(|| self.run(&["log", "-r", ".", "-T", LOG_TEMPLATE]));
+            let files = self.run(&["status"]);
+            (log.join().ok().flatten(), files)
+        });
+
+        let mut status = parse_log(&log?);
+        apply_status(&files?, &mut status);
+
+        // Only consult the merge state when an interrupted merge left one behind
+        if self.root.join(".hg").join("merge").join("state").exists() {
+            if let Some(list) = self.run(&["resolve", "--list"]) {
+                status.conflicts = list.lines().filter(|l| l.starts_with("U ")).count();
+            }
+        }
+
+        Some(status)
+    }
+}
+
+/// Parses [`LOG_TEMPLATE`] output. The active bookmark is preferred over the branch.
+fn parse_log(output: &str) -> VcsStatus {
+    let mut status = VcsStatus::new(VcsKind::Mercurial);
+    let mut fields = output.trim_end_matches('\n').split('\t');
+
+    // The null revision (empty repository) has no meaningful id
+    status.change_id = fields
+        .next()
+        .filter(|node| !node.is_empty() && !node.chars().all(|c| c == '0'))
+        .map(str::to_string);
+    let branch = fields.next().filter(|b| !b.is_empty());
+    let bookmark = fields.next().filter(|b| !b.is_empty());
+    status.branch = bookmark.or(branch).map(str::to_string);
+
+    status
+}
+
+/// Counts `hg status` lines: `A` added, `M` modified, `R`/`!` removed or missing,
+/// `?` untracked.
+fn apply_status(output: &str, status: &mut VcsStatus) {
+    for line in output.lines() {
+        match line.split_once(' ').map(|(code, _)| code) {
+            Some("A") => status.added += 1,
+            Some("M") => status.modified += 1,
+            Some("R") | Some("!") => status.deleted += 1,
+            Some("?") => status.untracked += 1,
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_log() {
+        let status = parse_log("1a2b3c4d5e6f\tdefault\t\n");
+        assert_eq!(status.kind, VcsKind::Mercurial);
+        assert_eq!(status.change_id.as_deref(), Some("1a2b3c4d5e6f"));
+        assert_eq!(status.branch.as_deref(), Some("default"));
+
+        let status = parse_log("1a2b3c4d5e6f\tstable\tmy-feature\n");
+        assert_eq!(status.branch.as_deref(), Some("my-feature"));
+
+        // Empty repository: the parent is the null revision
+        let status = parse_log("000000000000\tdefault\t\n");
+        assert!(status.change_id.is_none());
+    }
+
+    #[test]
+    fn test_apply_status() {
+        let mut status = VcsStatus::new(VcsKind::Mercurial);
+        apply_status(
+            "M src/lib.rs\nA new.rs\nR gone.rs\n! missing.rs\n? scratch.txt\n? notes.md\n",
+            &mut status,
+        );
+        assert_eq!(
+            (
+                status.added,
+                status.modified,
+                status.deleted,
+                status.untracked
+            ),
+            (1, 1, 2, 2)
+        );
+    }
+}
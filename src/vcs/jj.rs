@@ -0,0 +1,161 @@
+//! Jujutsu backend (standalone and colocated repositories), using the `jj` CLI.
+//!
+//! The first command snapshots the working copy; later ones pass
+//! `--ignore-working-copy` so they neither snapshot again nor contend for the
+//! working copy lock.
+
+use super::{VcsBackend, VcsKind, VcsStatus};
+use crate::git_utils::{execute_with_timeout, git_timeout_ms};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// The working copy commit plus the nearest ancestor with a bookmark.
+const LOG_REVSET: &str = "@ | latest(::@ & bookmarks())";
+/// Tab-separated working copy marker, change id, bookmarks and conflict flag.
+const LOG_TEMPLATE: &str = concat!(
+    r#"if(current_working_copy, "@", "-") ++ "\t" ++ change_id.shortest(8) ++ "\t" ++ "#,
+    r#"local_bookmarks.map(|b| b.name()).join(",") ++ "\t" ++ if(conflict, "1", "0") ++ "\n""#
+);
+
+/// Jujutsu working copy rooted at a directory containing `.jj`.
+pub struct JjBackend {
+    dir: PathBuf,
+    root: PathBuf,
+}
+
+impl JjBackend {
+    /// Creates a backend that runs `jj` in `dir`, inside the repository at `root`.
+    pub fn new(dir: &Path, root: PathBuf) -> Self {
+        JjBackend {
+            dir: dir.to_path_buf(),
+            root,
+        }
+    }
+
+    fn run(&self, args: &[&str]) -> Option<String> {
+        let mut cmd = Command::new("jj");
+        cmd.args(["--no-pager", "--color=never"])
+            .args(args)
+            .current_dir(&self.dir);
+
+        let output = execute_with_timeout(cmd, git_timeout_ms())?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+impl VcsBackend for JjBackend {
+    fn kind(&self) -> VcsKind {
+        VcsKind::Jujutsu
+    }
+
+    fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn status(&self) -> Option<VcsStatus> {
+        let log = self.run(&["log", "--no-graph", "-r", LOG_REVSET, "-T", LOG_TEMPLATE])?;
+        let (mut status, conflicted) = parse_log(&log)?;
+
+        let summary = self.run(&["diff", "--summary", "--ignore-working-copy", "-r", "@"])?;
+        apply_summary(&summary, &mut status);
+
+        if conflicted {
+            // A conflicted commit has at least one conflicted path
+            status.conflicts = self
+                .run(&["resolve", "--list", "--ignore-working-copy", "-r", "@"])
+                .map(|list| list.lines().filter(|l| !l.trim().is_empty()).count())
+                .unwrap_or(0)
+                .max(1);
+        }
+
+        Some(status)
+    }
+}
+
+/// Parses [`LOG_TEMPLATE`] output into the change id and nearest bookmark, and
+/// whether the working copy commit has conflicts.
+fn parse_log(output: &str) -> Option<(VcsStatus, bool)> {
+    let mut status = VcsStatus::new(VcsKind::Jujutsu);
+    let mut conflicted = false;
+    let mut found_working_copy = false;
+
+    for line in output.lines() {
+        let fields: Vec<&str> = line.split('\t').collect();
+        let [marker, change_id, bookmarks, conflict] = fields[..] else {
+            continue;
+        };
+        if marker == "@" {
+            found_working_copy = true;
+            status.change_id = Some(change_id.to_string());
+            conflicted = conflict == "1";
+        }
+        // Lines are newest first, so the first bookmark found is the nearest
+        if status.branch.is_none() && !bookmarks.is_empty() {
+            status.branch = Some(bookmarks.to_string());
+        }
+    }
+
+    found_working_copy.then_some((status, conflicted))
+}
+
+/// Counts `jj diff --summary` lines (`A path`, `M path`, `D path`, `R {a => b}`).
+fn apply_summary(summary: &str, status: &mut VcsStatus) {
+    for line in summary.lines() {
+        match line.split_once(' ').map(|(code, _)| code) {
+            Some("A") | Some("C") => status.added += 1,
+            Some("M") | Some("R") => status.modified += 1,
+            Some("D") => status.deleted += 1,
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_log_working_copy_and_ancestor_bookmark() {
+        let output = "@\tkxqzvoyl\t\t0\n-\tmnwlptsr\tmain,release\t0\n";
+        let (status, conflicted) = parse_log(output).unwrap();
+        assert_eq!(status.kind, VcsKind::Jujutsu);
+        assert_eq!(status.change_id.as_deref(), Some("kxqzvoyl"));
+        assert_eq!(status.branch.as_deref(), Some("main,release"));
+        assert!(!conflicted);
+    }
+
+    #[test]
+    fn test_parse_log_bookmark_on_working_copy_and_conflict() {
+        let (status, conflicted) = parse_log("@\tyq\tfeature\t1\n").unwrap();
+        assert_eq!(status.branch.as_deref(), Some("feature"));
+        assert!(conflicted);
+
+        // No bookmark anywhere in the ancestry
+        let (status, _) = parse_log("@\tzz\t\t0\n").unwrap();
+        assert!(status.branch.is_none());
+
+        assert!(parse_log("").is_none());
+        assert!(parse_log("Error: no jj repo\n").is_none());
+    }
+
+    #[test]
+    fn test_apply_summary() {
+        let mut status = VcsStatus::new(VcsKind::Jujutsu);
+        apply_summary(
+            "A src/new.rs\nM src/lib.rs\nM README.md\nD old.txt\nR {a.rs => b.rs}\n",
+            &mut status,
+        );
+        assert_eq!(
+            (
+                status.added,
+                status.modified,
+                status.deleted,
+                status.untracked
+            ),
+            (1, 3, 1, 0)
+        );
+    }
+}
@@ -0,0 +1,383 @@
+//! Version control abstraction over git, Jujutsu and Mercurial.
+//!
+//! Each backend implements [`VcsBackend`] and reports a common [`VcsStatus`]
+//! (branch or bookmark, change id, dirty counts, conflicts), which is exposed
+//! to layouts as the `vcs_*` variables:
+//!
+//! ```text
+//! .jj/  ──> JjBackend  ──┐
+//! .hg/  ──> HgBackend  ──┼──> VcsStatus ──> vcs, vcs_branch, vcs_change, vcs_status, ...
+//! .git  ──> GitBackend ──┘
+//! ```
+//!
+//! [`detect`] walks up from the current directory and picks the nearest
+//! repository. A colocated Jujutsu repository (`.jj` next to `.git`) is treated
+//! as Jujutsu. In git repositories the `vcs_*` variables are produced by
+//! [`GitProvider`](crate::git_provider::GitProvider) from the status it already
+//! collects, so [`VcsProvider`] only runs for Jujutsu and Mercurial.
+
+use crate::common::validate_path_security;
+use crate::provider::{DataProvider, ProviderResult};
+use crate::utils::{sanitize_for_terminal, shorten_path};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub mod git;
+pub mod hg;
+pub mod jj;
+
+pub use git::GitBackend;
+pub use hg::HgBackend;
+pub use jj::JjBackend;
+
+/// Supported version control systems.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VcsKind {
+    Git,
+    Jujutsu,
+    Mercurial,
+}
+
+impl VcsKind {
+    /// Command name, used as the `{vcs}` variable.
+    pub fn label(&self) -> &'static str {
+        match self {
+            VcsKind::Git => "git",
+            VcsKind::Jujutsu => "jj",
+            VcsKind::Mercurial => "hg",
+        }
+    }
+}
+
+/// Working copy status reported by every backend.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VcsStatus {
+    pub kind: VcsKind,
+    /// Branch (git, hg) or nearest bookmark (jj); `None` when detached or unset
+    pub branch: Option<String>,
+    /// Short id of the working revision: jj change id, git/hg commit hash
+    pub change_id: Option<String>,
+    pub added: usize,
+    pub modified: usize,
+    pub deleted: usize,
+    /// Files not tracked by the VCS (always 0 for jj, which tracks new files)
+    pub untracked: usize,
+    /// Files with unresolved conflicts
+    pub conflicts: usize,
+}
+
+impl VcsStatus {
+    /// Creates an empty (clean) status for `kind`.
+    pub fn new(kind: VcsKind) -> Self {
+        VcsStatus {
+            kind,
+            branch: None,
+            change_id: None,
+            added: 0,
+            modified: 0,
+            deleted: 0,
+            untracked: 0,
+            conflicts: 0,
+        }
+    }
+
+    /// Formats the change counts like `{git_status}` (e.g. "+2 ~1 ?3").
+    pub fn format_counts(&self) -> String {
+        crate::git::format_change_counts(self.added, self.modified, self.deleted, self.untracked)
+    }
+}
+
+/// A version control system that can report the status of a working copy.
+pub trait VcsBackend: Send + Sync {
+    /// Which VCS this backend talks to.
+    fn kind(&self) -> VcsKind;
+
+    /// Root directory of the working copy.
+    fn root(&self) -> &Path;
+
+    /// Reads the current status; `None` when the VCS command fails or times out.
+    fn status(&self) -> Option<VcsStatus>;
+}
+
+/// Finds the nearest repository containing `dir` and returns its kind and root.
+///
+/// At each level `.jj` wins over `.hg` and `.git`, so colocated Jujutsu
+/// repositories are reported as Jujutsu. Falls back to git discovery (which
+/// honours `GIT_DIR`) when no marker is found.
+pub fn detect_kind(dir: &Path) -> Option<(VcsKind, PathBuf)> {
+    for ancestor in dir.ancestors() {
+        if ancestor.join(".jj").is_dir() {
+            return Some((VcsKind::Jujutsu, ancestor.to_path_buf()));
+        }
+        if ancestor.join(".hg").is_dir() {
+            return Some((VcsKind::Mercurial, ancestor.to_path_buf()));
+        }
+        if ancestor.join(".git").exists() {
+            break;
+        }
+    }
+
+    crate::git::discover_repository(dir).map(|repo| (VcsKind::Git, repo.work_tree))
+}
+
+/// Detects the repository containing `dir` and returns a backend for it.
+pub fn detect(dir: &Path) -> Option<Box<dyn VcsBackend>> {
+    let (kind, root) = detect_kind(dir)?;
+    Some(match kind {
+        VcsKind::Git => Box::new(GitBackend::new(dir)?),
+        VcsKind::Jujutsu => Box::new(JjBackend::new(dir, root)),
+        VcsKind::Mercurial => Box::new(HgBackend::new(dir, root)),
+    })
+}
+
+/// Inserts the `vcs_*` variables for `status` of the repository at `root`.
+pub fn insert_vars(status: &VcsStatus, root: &Path, vars: &mut HashMap<String, String>) {
+    vars.insert("vcs".to_string(), status.kind.label().to_string());
+    if let Some(branch) = &status.branch {
+        vars.insert("vcs_branch".to_string(), sanitize_for_terminal(branch));
+    }
+    if let Some(change_id) = &status.change_id {
+        vars.insert("vcs_change".to_string(), sanitize_for_terminal(change_id));
+    }
+    let counts = status.format_counts();
+    if !counts.is_empty() {
+        vars.insert("vcs_status".to_string(), counts);
+    }
+    if status.conflicts > 0 {
+        vars.insert("vcs_conflicts".to_string(), status.conflicts.to_string());
+    }
+    vars.insert(
+        "vcs_root".to_string(),
+        sanitize_for_terminal(&shorten_path(&root.to_string_lossy())),
+    );
+}
+
+/// Data provider for the `vcs_*` variables in Jujutsu and Mercurial repositories.
+///
+/// Git repositories are left to `GitProvider`, which emits the same variables.
+pub struct VcsProvider {
+    current_dir: String,
+}
+
+impl VcsProvider {
+    /// Create a new VcsProvider for the given directory.
+    pub fn new(current_dir: &str) -> Self {
+        Self {
+            current_dir: current_dir.to_string(),
+        }
+    }
+}
+
+impl DataProvider for VcsProvider {
+    fn name(&self) -> &str {
+        "vcs"
+    }
+
+    fn priority(&self) -> u32 {
+        50
+    }
+
+    /// A status can take up to three sequential commands (log, diff, conflicts),
+    /// each bounded by the git timeout.
+    fn timeout(&self) -> Duration {
+        Duration::from_millis(3 * crate::git_utils::git_timeout_ms() as u64)
+    }
+
+    fn is_available(&self) -> bool {
+        validate_path_security(&self.current_dir)
+            .ok()
+            .and_then(|dir| detect_kind(&dir))
+            .is_some_and(|(kind, _)| kind != VcsKind::Git)
+    }
+
+    fn collect(&self) -> ProviderResult {
+        let mut vars = HashMap::new();
+
+        let backend = validate_path_security(&self.current_dir)
+            .ok()
+            .and_then(|dir| detect(&dir))
+            .filter(|backend| backend.kind() != VcsKind::Git);
+        if let Some(backend) = backend {
+            if let Some(status) = backend.status() {
+                insert_vars(&status, backend.root(), &mut vars);
+            }
+        }
+
+        Ok(vars)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_kind_prefers_nearest_marker() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = std::fs::canonicalize(temp.path()).unwrap();
+
+        // hg repository with a jj repository nested inside it
+        std::fs::create_dir_all(root.join(".hg")).unwrap();
+        let nested = root.join("vendor").join("lib");
+        std::fs::create_dir_all(nested.join(".jj")).unwrap();
+        let deep = nested.join("src");
+        std::fs::create_dir_all(&deep).unwrap();
+
+        assert_eq!(detect_kind(&deep), Some((VcsKind::Jujutsu, nested.clone())));
+        assert_eq!(
+            detect_kind(&root.join("vendor")),
+            Some((VcsKind::Mercurial, root.clone()))
+        );
+    }
+
+    #[test]
+    fn test_detect_kind_colocated_jj_wins_over_git() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = std::fs::canonicalize(temp.path()).unwrap();
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        std::fs::create_dir_all(root.join(".jj")).unwrap();
+
+        assert_eq!(detect_kind(&root), Some((VcsKind::Jujutsu, root.clone())));
+    }
+
+    #[test]
+    fn test_detect_kind_git() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = std::fs::canonicalize(temp.path()).unwrap();
+        let status = std::process::Command::new("git")
+            .args(["init", "-q"])
+            .current_dir(&root)
+            .status();
+        if !matches!(status, Ok(s) if s.success()) {
+            return; // git not installed
+        }
+        let sub = root.join("sub");
+        std::fs::create_dir_all(&sub).unwrap();
+
+        assert_eq!(detect_kind(&sub), Some((VcsKind::Git, root.clone())));
+        let backend = detect(&sub).expect("git backend");
+        assert_eq!(backend.kind(), VcsKind::Git);
+        assert_eq!(backend.root(), root.as_path());
+    }
+
+    #[test]
+    fn test_insert_vars() {
+        let status = VcsStatus {
+            branch: Some("main".to_string()),
+            change_id: Some("kxqzvoyl".to_string()),
+            added: 1,
+            modified: 2,
+            conflicts: 1,
+            ..VcsStatus::new(VcsKind::Jujutsu)
+        };
+        let mut vars = HashMap::new();
+        insert_vars(&status, Path::new("/tmp/repo"), &mut vars);
+
+        assert_eq!(vars.get("vcs"), Some(&"jj".to_string()));
+        assert_eq!(vars.get("vcs_branch"), Some(&"main".to_string()));
+        assert_eq!(vars.get("vcs_change"), Some(&"kxqzvoyl".to_string()));
+        assert_eq!(vars.get("vcs_status"), Some(&"+1 ~2".to_string()));
+        assert_eq!(vars.get("vcs_conflicts"), Some(&"1".to_string()));
+        assert!(vars.contains_key("vcs_root"));
+
+        let mut vars = HashMap::new();
+        insert_vars(
+            &VcsStatus::new(VcsKind::Mercurial),
+            Path::new("/r"),
+            &mut vars,
+        );
+        assert!(!vars.contains_key("vcs_branch"));
+        assert!(!vars.contains_key("vcs_status"));
+        assert!(!vars.contains_key("vcs_conflicts"));
+    }
+
+    #[test]
+    fn test_vcs_provider_skips_git_and_plain_dirs() {
+        let provider = VcsProvider::new("/tmp");
+        assert_eq!(provider.name(), "vcs");
+        assert!(!provider.is_available());
+        assert!(provider.collect().unwrap().is_empty());
+    }
+
+    /// Runs `collect` with fake `jj` and `hg` executables first on `PATH`.
+    #[cfg(unix)]
+    fn with_fake_vcs_commands<T>(bin: &Path, collect: impl FnOnce() -> T) -> T {
+        use std::os::unix::fs::PermissionsExt;
+
+        let scripts = [
+            (
+                "jj",
+                r#"case "$*" in
+  *" log "*) printf '@\tkxqzvoyl\t\t0\n-\tmnwlptsr\tmain\t0\n' ;;
+  *" diff "*) printf 'A src/new.rs\nM src/lib.rs\n' ;;
+esac"#,
+            ),
+            (
+                "hg",
+                r#"case "$1" in
+  log) printf '1a2b3c4d5e6f\tdefault\tfeature' ;;
+  status) printf 'M a.txt\n? b.txt\n' ;;
+esac"#,
+            ),
+        ];
+        for (name, body) in scripts {
+            let path = bin.join(name);
+            std::fs::write(&path, format!("#!/bin/sh\n{}\n", body)).unwrap();
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+
+        let old_path = std::env::var_os("PATH").unwrap_or_default();
+        let mut paths = vec![bin.to_path_buf()];
+        paths.extend(std::env::split_paths(&old_path));
+        std::env::set_var("PATH", std::env::join_paths(paths).unwrap());
+        let result = collect();
+        std::env::set_var("PATH", old_path);
+        result
+    }
+
+    #[test]
+    #[cfg(unix)]
+    #[serial_test::serial]
+    fn test_vcs_provider_vars_for_jj_and_hg() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = std::fs::canonicalize(temp.path()).unwrap();
+        let bin = root.join("bin");
+        let jj_repo = root.join("jj-repo");
+        let hg_repo = root.join("hg-repo");
+        std::fs::create_dir_all(&bin).unwrap();
+        std::fs::create_dir_all(jj_repo.join(".jj")).unwrap();
+        std::fs::create_dir_all(hg_repo.join(".hg")).unwrap();
+
+        let collect = |dir: &Path| {
+            let provider = VcsProvider::new(dir.to_str().unwrap());
+            assert!(provider.is_available());
+            provider.collect().expect("collect should succeed")
+        };
+        let (jj_vars, hg_vars) =
+            with_fake_vcs_commands(&bin, || (collect(&jj_repo), collect(&hg_repo)));
+        let keys = |vars: &HashMap<String, String>| {
+            let mut keys: Vec<String> = vars.keys().cloned().collect();
+            keys.sort();
+            keys
+        };
+
+        assert_eq!(
+            keys(&jj_vars),
+            ["vcs", "vcs_branch", "vcs_change", "vcs_root", "vcs_status"]
+        );
+        assert_eq!(jj_vars["vcs"], "jj");
+        assert_eq!(jj_vars["vcs_branch"], "main");
+        assert_eq!(jj_vars["vcs_change"], "kxqzvoyl");
+        assert_eq!(jj_vars["vcs_status"], "+1 ~1");
+
+        assert_eq!(
+            keys(&hg_vars),
+            ["vcs", "vcs_branch", "vcs_change", "vcs_root", "vcs_status"]
+        );
+        assert_eq!(hg_vars["vcs"], "hg");
+        assert_eq!(hg_vars["vcs_branch"], "feature");
+        assert_eq!(hg_vars["vcs_change"], "1a2b3c4d5e6f");
+        assert_eq!(hg_vars["vcs_status"], "~1 ?1");
+    }
+}
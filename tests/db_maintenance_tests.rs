@@ -423,3 +423,176 @@ fn test_db_maintain_pruning() {
     // Check that pruning section exists in output
     assert!(stdout.contains("Pruning"), "Output should mention pruning");
 }
+
+#[test]
+fn test_db_maintain_prunes_usage_entries() {
+    let _guard = test_support::init();
+    let (temp_dir, db_path) = setup_test_database();
+
+    use chrono::{Duration, Utc};
+    use rusqlite::Connection;
+
+    let old = (Utc::now() - Duration::days(100)).to_rfc3339();
+    let recent = (Utc::now() - Duration::days(1)).to_rfc3339();
+    {
+        let conn = Connection::open(&db_path).expect("Failed to open database");
+        // The hand-made fallback schema predates usage entries (migration v10)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS usage_entries (
+                message_id TEXT PRIMARY KEY,
+                session_id TEXT NOT NULL,
+                timestamp TEXT NOT NULL
+            )",
+            [],
+        )
+        .unwrap();
+        for (message_id, timestamp) in [("old", &old), ("recent", &recent)] {
+            conn.execute(
+                "INSERT INTO usage_entries (message_id, session_id, timestamp) VALUES (?1, 's', ?2)",
+                [message_id, timestamp.as_str()],
+            )
+            .unwrap();
+        }
+    }
+
+    let output = Command::new(test_support::test_binary())
+        .env("XDG_DATA_HOME", temp_dir.path())
+        .env("XDG_CONFIG_HOME", temp_dir.path())
+        .arg("db-maintain")
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "Maintenance should succeed");
+
+    let conn = Connection::open(&db_path).expect("Failed to open database");
+    let remaining: Vec<String> = conn
+        .prepare("SELECT message_id FROM usage_entries ORDER BY message_id")
+        .unwrap()
+        .query_map([], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(remaining, vec!["recent".to_string()]);
+}
+
+#[test]
+fn test_db_maintain_prunes_session_errors() {
+    let _guard = test_support::init();
+    let (temp_dir, db_path) = setup_test_database();
+
+    use chrono::{Duration, Utc};
+    use rusqlite::Connection;
+
+    let old = (Utc::now() - Duration::days(100)).to_rfc3339();
+    let recent = (Utc::now() - Duration::days(1)).to_rfc3339();
+    {
+        let conn = Connection::open(&db_path).expect("Failed to open database");
+        // The hand-made fallback schema predates session errors (migration v11)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS session_errors (
+                session_id TEXT NOT NULL,
+                timestamp TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                message TEXT,
+                reset_at TEXT,
+                PRIMARY KEY (session_id, timestamp, kind)
+            )",
+            [],
+        )
+        .unwrap();
+        for (session_id, timestamp) in [("old", &old), ("recent", &recent)] {
+            conn.execute(
+                "INSERT INTO session_errors (session_id, timestamp, kind) VALUES (?1, ?2, 'api_error')",
+                [session_id, timestamp.as_str()],
+            )
+            .unwrap();
+        }
+    }
+
+    let output = Command::new(test_support::test_binary())
+        .env("XDG_DATA_HOME", temp_dir.path())
+        .env("XDG_CONFIG_HOME", temp_dir.path())
+        .arg("db-maintain")
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "Maintenance should succeed");
+
+    let conn = Connection::open(&db_path).expect("Failed to open database");
+    let remaining: Vec<String> = conn
+        .prepare("SELECT session_id FROM session_errors ORDER BY session_id")
+        .unwrap()
+        .query_map([], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(remaining, vec!["recent".to_string()]);
+}
+
+#[test]
+fn test_reimport_after_pruning_adds_nothing() {
+    let _guard = test_support::init();
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+
+    use chrono::{Duration, Utc};
+    use rusqlite::Connection;
+
+    // A transcript older than the default 90 days of session retention
+    let at = (Utc::now() - Duration::days(100)).format("%Y-%m-%dT12:00:00.000Z");
+    let project = temp_dir.path().join("projects").join("-src-app");
+    fs::create_dir_all(&project).unwrap();
+    fs::write(
+        project.join("old-session.jsonl"),
+        format!(
+            r#"{{"type":"assistant","sessionId":"old-session","timestamp":"{}","cwd":"/src/app","message":{{"id":"msg_1","role":"assistant","model":"claude-sonnet-4-5-20250929","usage":{{"input_tokens":1000,"output_tokens":100}}}}}}"#,
+            at
+        ),
+    )
+    .unwrap();
+
+    let run = |args: &[&str]| {
+        let output = Command::new(test_support::test_binary())
+            .env("XDG_DATA_HOME", temp_dir.path())
+            .env("XDG_CONFIG_HOME", temp_dir.path())
+            .args(args)
+            .output()
+            .expect("Failed to execute command");
+        assert!(
+            output.status.success(),
+            "{:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).to_string()
+    };
+    let projects_dir = temp_dir.path().join("projects");
+    let import = [
+        "import",
+        "transcripts",
+        "--projects-dir",
+        projects_dir.to_str().unwrap(),
+    ];
+    let db_path = temp_dir.path().join("claudia-statusline").join("stats.db");
+    let totals = || -> (i64, f64) {
+        let conn = Connection::open(&db_path).expect("Failed to open database");
+        conn.query_row(
+            "SELECT SUM(session_count), SUM(computed_cost) FROM daily_stats",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap()
+    };
+
+    assert!(run(&import).contains("Imported 1 new sessions"));
+    let imported = totals();
+    assert_eq!(imported.0, 1);
+
+    // Maintenance prunes the session but keeps its day
+    run(&["db-maintain"]);
+    let conn = Connection::open(&db_path).expect("Failed to open database");
+    let sessions: i64 = conn
+        .query_row("SELECT COUNT(*) FROM sessions", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(sessions, 0);
+
+    assert!(run(&import).contains("Imported 0 new sessions"));
+    assert_eq!(totals(), imported);
+}
@@ -140,6 +140,7 @@ fn test_lines_changed_display() {
         total_cost_usd: Some(1.50),
         total_lines_added: Some(123),
         total_lines_removed: Some(45),
+        ..Default::default()
     };
 
     let output = format_output_to_string("/test", Some("Claude"), None, Some(&cost), 0.0, None);
@@ -164,6 +165,7 @@ fn test_cost_display() {
         total_cost_usd: Some(5.75),
         total_lines_added: None,
         total_lines_removed: None,
+        ..Default::default()
     };
 
     let output = format_output_to_string("/test", Some("Claude"), None, Some(&cost), 0.0, None);
@@ -233,6 +235,7 @@ fn test_multiple_components() {
         total_cost_usd: Some(2.50),
         total_lines_added: Some(50),
         total_lines_removed: Some(10),
+        ..Default::default()
     };
 
     let output = format_output_to_string(
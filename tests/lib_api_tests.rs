@@ -18,9 +18,11 @@ fn test_render_statusline_basic() {
     let input = StatuslineInput {
         workspace: Some(Workspace {
             current_dir: Some(test_dir),
+            ..Default::default()
         }),
         model: Some(Model {
             display_name: Some("Claude 3.5 Sonnet".to_string()),
+            ..Default::default()
         }),
         ..Default::default()
     };
@@ -143,9 +145,11 @@ fn test_render_with_git_repo() {
     let input = StatuslineInput {
         workspace: Some(Workspace {
             current_dir: Some(repo_path.to_string()),
+            ..Default::default()
         }),
         model: Some(Model {
             display_name: Some("Claude 3.5 Sonnet".to_string()),
+            ..Default::default()
         }),
         ..Default::default()
     };
@@ -226,6 +226,7 @@ fn test_zero_cost() {
         total_cost_usd: Some(0.0),
         total_lines_added: None,
         total_lines_removed: None,
+        ..Default::default()
     };
 
     let output = format_output_to_string("/test", Some("Claude"), None, Some(&cost), 0.0, None);